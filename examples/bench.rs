@@ -0,0 +1,73 @@
+use inevitable::{
+    config::Config,
+    microbench::{self, MicroBenchOptions},
+};
+fn parse_count(args: &[String], flag: &str, default: usize) -> usize {
+    let Some(position) = args.iter().position(|arg| arg == flag) else {
+        return default;
+    };
+    let Some(value) = args.get(position.saturating_add(1)) else {
+        eprintln!("{flag} 缺少参数值。");
+        return default;
+    };
+    value.parse().unwrap_or_else(|err| {
+        eprintln!("{flag} 参数无效: {value}, 错误: {err}");
+        default
+    })
+}
+fn format_ns(value: f64) -> String {
+    if value >= 1_000_000.0_f64 {
+        format!("{:.3}ms", value / 1_000_000.0_f64)
+    } else if value >= 1_000.0_f64 {
+        format!("{:.3}µs", value / 1_000.0_f64)
+    } else {
+        format!("{value:.1}ns")
+    }
+}
+fn main() {
+    let config = Config::load();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let defaults = MicroBenchOptions::default();
+    let options = MicroBenchOptions {
+        warmup_samples: parse_count(&args, "--warmup", defaults.warmup_samples),
+        samples: parse_count(&args, "--samples", defaults.samples),
+        iterations: parse_count(&args, "--iterations", defaults.iterations),
+    };
+    let filter = args
+        .iter()
+        .position(|arg| arg == "--filter")
+        .and_then(|position| args.get(position.saturating_add(1)))
+        .map(String::as_str);
+    println!(
+        "微基准: {size}x{size} 棋盘, 预热 {warmup} 组, 采样 {samples} 组, 每组 {iterations} 次",
+        size = config.board_size,
+        warmup = options.warmup_samples,
+        samples = options.samples,
+        iterations = options.iterations
+    );
+    let results = microbench::run(
+        config.board_size,
+        config.win_len,
+        config.evaluation,
+        options,
+        filter,
+    );
+    if results.is_empty() {
+        println!("没有匹配的基准项。");
+        return;
+    }
+    println!(
+        "{:<28} {:>12} {:>12} {:>12} {:>12}",
+        "基准项", "平均", "标准差", "最小", "最大"
+    );
+    for result in &results {
+        println!(
+            "{:<28} {:>12} {:>12} {:>12} {:>12}",
+            result.name,
+            format_ns(result.mean_ns),
+            format_ns(result.std_dev_ns),
+            format_ns(result.min_ns),
+            format_ns(result.max_ns)
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use crate::game_state::{GameState, MAX_WIN_LEN, MIN_WIN_LEN};
+use core::fmt;
+const STANDARD_WIN_LEN: usize = 5;
+const RULE_VARIANTS: [&str; 3] = ["freestyle", "renju", "points"];
+const BUILD_FEATURES: [(&str, bool); 6] = [
+    ("global-allocator", cfg!(feature = "global-allocator")),
+    ("mimalloc", cfg!(feature = "mimalloc")),
+    ("jemalloc", cfg!(feature = "jemalloc")),
+    ("system-allocator", cfg!(feature = "system-allocator")),
+    ("gpu-scoring", cfg!(feature = "gpu-scoring")),
+    ("overflow-checked", cfg!(feature = "overflow-checked")),
+];
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EngineInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub features: Vec<&'static str>,
+    pub min_board_size: usize,
+    pub max_board_size: usize,
+    pub min_win_len: usize,
+    pub max_win_len: usize,
+    pub rule_variants: Vec<&'static str>,
+}
+impl fmt::Display for EngineInfo {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let features = if self.features.is_empty() {
+            String::from("无")
+        } else {
+            self.features.join(", ")
+        };
+        write!(
+            f,
+            "{name} {version}（特性: {features}；棋盘 {min}-{max}（{STANDARD_WIN_LEN} 子连珠）；连珠长度 {min_win_len}-{max_win_len}；规则: {rules}）",
+            name = self.name,
+            version = self.version,
+            min = self.min_board_size,
+            max = self.max_board_size,
+            min_win_len = self.min_win_len,
+            max_win_len = self.max_win_len,
+            rules = self.rule_variants.join(", ")
+        )
+    }
+}
+fn max_board_size(win_len: usize) -> usize {
+    (win_len..=usize::from(u16::MAX))
+        .take_while(|&board_size| GameState::validate_shape(board_size, win_len).is_ok())
+        .last()
+        .unwrap_or(win_len)
+}
+#[inline]
+#[must_use]
+pub fn engine_info() -> EngineInfo {
+    EngineInfo {
+        name: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+        features: BUILD_FEATURES
+            .into_iter()
+            .filter_map(|(feature, enabled)| enabled.then_some(feature))
+            .collect(),
+        min_board_size: MIN_WIN_LEN,
+        max_board_size: max_board_size(STANDARD_WIN_LEN),
+        min_win_len: MIN_WIN_LEN,
+        max_win_len: MAX_WIN_LEN,
+        rule_variants: RULE_VARIANTS.to_vec(),
+    }
+}
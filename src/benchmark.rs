@@ -0,0 +1,226 @@
+use crate::{
+    checked,
+    game_state::Position,
+    interrupt::CancellationToken,
+    pns::{
+        BenchmarkResult, DepthReport, DepthSample, ParallelSolver, SearchParams, TreeStatsSnapshot,
+    },
+};
+use core::iter;
+use std::{fs, path::Path};
+const FIRST_PLAYER: u8 = 1;
+const SECOND_PLAYER: u8 = 2;
+const NAME_PREFIX: char = '#';
+#[derive(Clone)]
+pub struct Report {
+    pub runs: usize,
+    pub elapsed_secs: f64,
+    pub stats: TreeStatsSnapshot,
+    pub tt_size: usize,
+    pub node_table_size: usize,
+    pub peak_live_bytes: u64,
+    pub depths: Vec<DepthReport>,
+    pub samples: Vec<DepthSample>,
+}
+#[derive(Clone)]
+pub struct SuiteEntry {
+    pub name: String,
+    pub position: Position,
+}
+#[derive(Clone, Default)]
+pub struct Suite {
+    pub entries: Vec<SuiteEntry>,
+}
+#[derive(Clone)]
+pub struct SuiteRun {
+    pub name: String,
+    pub report: Report,
+}
+#[derive(Clone, Default)]
+pub struct SuiteReport {
+    pub runs: Vec<SuiteRun>,
+}
+#[inline]
+pub fn run(position: &Position, params: &SearchParams, runs: usize) -> Result<Report, String> {
+    run_with_stop(position, params, runs, &CancellationToken::new())
+}
+#[inline]
+pub fn run_with_stop(
+    position: &Position,
+    params: &SearchParams,
+    runs: usize,
+    cancel: &CancellationToken,
+) -> Result<Report, String> {
+    let board = attacker_board(position, params, runs)?;
+    let result = ParallelSolver::measure_next_move(&board, params, runs, cancel)
+        .ok_or_else(|| String::from("基准测试被中断，或搜索预算内未找到必胜着法"))?;
+    Ok(Report::new(runs, result))
+}
+fn attacker_board(
+    position: &Position,
+    params: &SearchParams,
+    runs: usize,
+) -> Result<Vec<u8>, String> {
+    if runs == 0 {
+        return Err(String::from("基准测试轮数必须大于 0"));
+    }
+    if position.board_size() != params.board_size || position.win_len() != params.win_len {
+        return Err(format!(
+            "局面为 {size}x{size} 棋盘、{win_len} 子连珠，与搜索参数的 {params_size}x{params_size} 棋盘、{params_win_len} 子连珠不一致",
+            size = position.board_size(),
+            win_len = position.win_len(),
+            params_size = params.board_size,
+            params_win_len = params.win_len
+        ));
+    }
+    if position.winner().is_some() {
+        return Err(String::from("局面已分出胜负，无法用于基准测试"));
+    }
+    let swapped = position.side_to_move() != params.root_player;
+    Ok(position
+        .board()
+        .iter()
+        .map(|&cell| match cell {
+            FIRST_PLAYER if swapped => SECOND_PLAYER,
+            SECOND_PLAYER if swapped => FIRST_PLAYER,
+            _ => cell,
+        })
+        .collect())
+}
+impl Report {
+    fn new(runs: usize, result: BenchmarkResult) -> Self {
+        Self {
+            runs,
+            elapsed_secs: result.elapsed_secs,
+            stats: result.stats,
+            tt_size: result.tt_size,
+            node_table_size: result.node_table_size,
+            peak_live_bytes: result.peak_live_bytes,
+            depths: result.depth_reports,
+            samples: result.depth_samples,
+        }
+    }
+}
+impl Suite {
+    #[inline]
+    pub fn load(path: &Path, win_len: usize) -> Result<Self, String> {
+        let read_err = |err| format!("读取基准测试集 {path} 失败: {err}", path = path.display());
+        let mut files = if path.is_dir() {
+            fs::read_dir(path)
+                .map_err(read_err)?
+                .map(|entry| entry.map(|dir_entry| dir_entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(read_err)?
+                .into_iter()
+                .filter(|file| file.is_file())
+                .collect()
+        } else {
+            vec![path.to_path_buf()]
+        };
+        files.sort();
+        let mut suite = Self::default();
+        for file in &files {
+            let text = fs::read_to_string(file).map_err(|err| {
+                format!("读取基准局面文件 {file} 失败: {err}", file = file.display())
+            })?;
+            let source = file
+                .file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+            suite.extend_from_text(&text, &source, win_len)?;
+        }
+        if suite.entries.is_empty() {
+            return Err(format!(
+                "基准测试集 {path} 中没有局面",
+                path = path.display()
+            ));
+        }
+        Ok(suite)
+    }
+    #[inline]
+    pub fn extend_from_text(
+        &mut self,
+        text: &str,
+        source: &str,
+        win_len: usize,
+    ) -> Result<(), String> {
+        let mut name = None;
+        let mut rows = Vec::new();
+        let mut index = 0_usize;
+        for line in text.lines().map(str::trim).chain(iter::once("")) {
+            if let Some(label) = line.strip_prefix(NAME_PREFIX) {
+                name = Some(String::from(label.trim()));
+            } else if !line.is_empty() {
+                rows.push(line);
+            } else if !rows.is_empty() {
+                index = checked::add_usize(index, 1, "Suite::extend_from_text::index");
+                let entry_name = name.take().unwrap_or_else(|| format!("{source}#{index}"));
+                let position = Position::parse(&rows.join("/"), win_len)
+                    .map_err(|err| format!("基准局面 {entry_name} 无效: {err}"))?;
+                rows.clear();
+                self.entries.push(SuiteEntry {
+                    name: entry_name,
+                    position,
+                });
+            }
+        }
+        Ok(())
+    }
+    #[inline]
+    pub fn run<F: FnMut(&SuiteRun)>(
+        &self,
+        params: &SearchParams,
+        runs: usize,
+        cancel: &CancellationToken,
+        mut on_entry: F,
+    ) -> Result<SuiteReport, String> {
+        let mut report = SuiteReport::default();
+        for entry in &self.entries {
+            let mut entry_params = params.clone();
+            entry_params.board_size = entry.position.board_size();
+            entry_params.win_len = entry.position.win_len();
+            let board = attacker_board(&entry.position, &entry_params, runs)
+                .map_err(|err| format!("基准局面 {name}: {err}", name = entry.name))?;
+            let result = ParallelSolver::benchmark_next_move(&board, &entry_params, runs, cancel)
+                .ok_or_else(|| {
+                format!(
+                    "基准局面 {name} 被中断，或搜索预算内未找到必胜着法",
+                    name = entry.name
+                )
+            })?;
+            let run = SuiteRun {
+                name: entry.name.clone(),
+                report: Report::new(runs, result),
+            };
+            on_entry(&run);
+            report.runs.push(run);
+        }
+        Ok(report)
+    }
+}
+impl SuiteReport {
+    #[inline]
+    #[must_use]
+    pub fn total_elapsed_secs(&self) -> f64 {
+        self.runs.iter().map(|run| run.report.elapsed_secs).sum()
+    }
+    #[inline]
+    #[must_use]
+    pub fn total_nodes(&self) -> u64 {
+        self.runs.iter().fold(0, |total, run| {
+            checked::add_u64(
+                total,
+                run.report.stats.nodes_created,
+                "SuiteReport::total_nodes",
+            )
+        })
+    }
+    #[inline]
+    #[must_use]
+    pub fn peak_live_bytes(&self) -> u64 {
+        self.runs
+            .iter()
+            .map(|run| run.report.peak_live_bytes)
+            .max()
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    checked,
+    config::Config,
+    game_state::Position,
+    interrupt::CancellationToken,
+    pns::{Budget, ParallelSolver, to_f64},
+    utils::available_memory_bytes,
+};
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write as _},
+    path::Path,
+    thread,
+};
+pub const CALIBRATION_FILE: &str = "calibration.json";
+const PROBE_NODES: u64 = 2_000_000;
+const PROBE_TIME: Duration = Duration::from_secs(1);
+const MEMORY_BUDGET_PERCENT: u64 = 80;
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HostProfile {
+    pub logical_threads: usize,
+    pub recommended_threads: usize,
+    pub nodes_per_sec: f64,
+    pub parallel_efficiency: f64,
+    pub bytes_per_node: Option<u64>,
+}
+struct Probe {
+    threads: usize,
+    nodes_per_sec: f64,
+    bytes_per_node: Option<u64>,
+}
+impl HostProfile {
+    #[inline]
+    #[must_use]
+    pub fn measure(config: &Config, cancel: &CancellationToken) -> Option<Self> {
+        let logical_threads = logical_threads();
+        let position = probe_position(config)?;
+        let mut thread_counts = vec![
+            1,
+            checked::div_usize(logical_threads, 2, "HostProfile::measure::half").max(1),
+            logical_threads,
+        ];
+        thread_counts.dedup();
+        let mut probes = Vec::with_capacity(thread_counts.len());
+        for threads in thread_counts {
+            probes.push(probe(config, &position, threads, cancel)?);
+        }
+        let single = probes.first()?;
+        let best = probes
+            .iter()
+            .max_by(|left, right| left.nodes_per_sec.total_cmp(&right.nodes_per_sec))?;
+        let ideal = single.nodes_per_sec
+            * to_f64(checked::usize_to_u64(
+                best.threads,
+                "HostProfile::measure::threads",
+            ));
+        Some(Self {
+            logical_threads,
+            recommended_threads: best.threads,
+            nodes_per_sec: single.nodes_per_sec,
+            parallel_efficiency: (best.nodes_per_sec / ideal).clamp(0.0_f64, 1.0_f64),
+            bytes_per_node: single.bytes_per_node,
+        })
+    }
+    #[inline]
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        serde_json::from_reader(BufReader::new(file))
+            .map(Some)
+            .map_err(io::Error::other)
+    }
+    #[inline]
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self).map_err(io::Error::other)?;
+        writer.flush()
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_current(&self) -> bool {
+        self.logical_threads == logical_threads()
+    }
+    #[inline]
+    #[must_use]
+    pub fn expected_nodes_per_sec(&self, threads: usize) -> f64 {
+        if threads <= 1 {
+            return self.nodes_per_sec;
+        }
+        self.nodes_per_sec
+            * self.parallel_efficiency
+            * to_f64(checked::usize_to_u64(
+                threads,
+                "HostProfile::expected_nodes_per_sec",
+            ))
+    }
+    #[inline]
+    #[must_use]
+    pub fn eta(&self, remaining_nodes: u64, threads: usize) -> Option<Duration> {
+        let rate = self.expected_nodes_per_sec(threads);
+        if rate <= 0.0_f64 {
+            return None;
+        }
+        Duration::try_from_secs_f64(to_f64(remaining_nodes) / rate).ok()
+    }
+    #[inline]
+    #[must_use]
+    pub fn memory_node_budget(&self, reserve_bytes: u64) -> Option<u64> {
+        let bytes_per_node = self.bytes_per_node.filter(|&bytes| bytes > 0)?;
+        let usable = available_memory_bytes()?.saturating_sub(reserve_bytes);
+        let budgeted = checked::div_u64(usable, 100, "HostProfile::memory_node_budget::percent")
+            .saturating_mul(MEMORY_BUDGET_PERCENT);
+        Some(checked::div_u64(
+            budgeted,
+            bytes_per_node,
+            "HostProfile::memory_node_budget",
+        ))
+    }
+}
+fn logical_threads() -> usize {
+    thread::available_parallelism().map_or(1, core::num::NonZero::get)
+}
+fn probe_position(config: &Config) -> Option<Position> {
+    let mut position = Position::new(config.board_size, config.win_len);
+    let center = checked::div_usize(config.board_size, 2, "calibration::probe_position::center");
+    position.play((center, center)).ok()?;
+    position
+        .play((center.saturating_sub(1), center.saturating_sub(1)))
+        .ok()?;
+    Some(position)
+}
+fn probe(
+    config: &Config,
+    position: &Position,
+    threads: usize,
+    cancel: &CancellationToken,
+) -> Option<Probe> {
+    let mut params = config
+        .search_params()
+        .with_budget(Budget {
+            nodes: Some(PROBE_NODES),
+            time: Some(PROBE_TIME),
+            ..Budget::unlimited()
+        })
+        .with_root_player(position.side_to_move());
+    params.num_threads = threads;
+    params.overlay_stream = None;
+    let result =
+        ParallelSolver::search(position.board().to_vec(), params, false, cancel, None, None);
+    if cancel.is_cancelled() {
+        return None;
+    }
+    let totals = &result.report.totals;
+    if totals.elapsed_secs <= 0.0_f64 || totals.stats.nodes_created == 0 {
+        return None;
+    }
+    let node_count = checked::usize_to_u64(totals.node_table_size, "calibration::probe::nodes");
+    Some(Probe {
+        threads,
+        nodes_per_sec: to_f64(totals.stats.nodes_created) / totals.elapsed_secs,
+        bytes_per_node: (totals.peak_live_bytes > 0 && node_count > 0).then(|| {
+            checked::div_u64(
+                totals.peak_live_bytes,
+                node_count,
+                "calibration::probe::bytes_per_node",
+            )
+        }),
+    })
+}
@@ -1,3 +1,5 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+static OVERFLOW_VIOLATIONS: AtomicU64 = AtomicU64::new(0);
 #[must_use]
 pub(crate) fn add_u64(left: u64, right: u64, context: &str) -> u64 {
     let Some(value) = left.checked_add(right) else {
@@ -23,6 +25,14 @@ pub(crate) fn div_u64(left: u64, right: u64, context: &str) -> u64 {
     value
 }
 #[must_use]
+pub(crate) fn rem_u64(left: u64, right: u64, context: &str) -> u64 {
+    let Some(value) = left.checked_rem(right) else {
+        eprintln!("{context} 发生 u64 取余错误: {left} % {right}");
+        panic!("{context} 发生 u64 取余错误");
+    };
+    value
+}
+#[must_use]
 pub(crate) fn rounded_div_u64(total: u64, count: u64, context: &str) -> u64 {
     if count == 0_u64 {
         eprintln!("{context} 的计数不能为 0。");
@@ -40,6 +50,14 @@ pub(crate) fn add_u32(left: u32, right: u32, context: &str) -> u32 {
     value
 }
 #[must_use]
+pub(crate) fn sub_u32(left: u32, right: u32, context: &str) -> u32 {
+    let Some(value) = left.checked_sub(right) else {
+        eprintln!("{context} 发生 u32 减法下溢: {left} - {right}");
+        panic!("{context} 发生 u32 减法下溢");
+    };
+    value
+}
+#[must_use]
 pub(crate) fn div_u32(left: u32, right: u32, context: &str) -> u32 {
     let Some(value) = left.checked_div(right) else {
         eprintln!("{context} 发生 u32 除法错误: {left} / {right}");
@@ -164,3 +182,56 @@ pub(crate) fn opponent_player(player: u8, context: &str) -> u8 {
         }
     }
 }
+#[must_use]
+pub(crate) fn overflow_violations() -> u64 {
+    OVERFLOW_VIOLATIONS.load(Ordering::Relaxed)
+}
+#[cfg(feature = "overflow-checked")]
+fn record_violation(context: &str, detail: &str) {
+    if OVERFLOW_VIOLATIONS.fetch_add(1, Ordering::Relaxed) == 0 {
+        eprintln!("{context} 发生算术溢出（已饱和处理）: {detail}");
+    }
+}
+#[cfg(feature = "overflow-checked")]
+#[must_use]
+pub(crate) fn saturating_add_u64(left: u64, right: u64, context: &str) -> u64 {
+    left.checked_add(right).unwrap_or_else(|| {
+        record_violation(context, &format!("{left} + {right}"));
+        u64::MAX
+    })
+}
+#[cfg(not(feature = "overflow-checked"))]
+#[must_use]
+pub(crate) const fn saturating_add_u64(left: u64, right: u64, _context: &str) -> u64 {
+    left.saturating_add(right)
+}
+#[cfg(feature = "overflow-checked")]
+pub(crate) fn atomic_add_u64(target: &AtomicU64, amount: u64, ordering: Ordering, context: &str) {
+    let updated = target.fetch_update(ordering, Ordering::Relaxed, |current| {
+        Some(current.saturating_add(amount))
+    });
+    if let Ok(previous) = updated
+        && previous.checked_add(amount).is_none()
+    {
+        record_violation(context, &format!("{previous} + {amount}"));
+    }
+}
+#[cfg(not(feature = "overflow-checked"))]
+pub(crate) fn atomic_add_u64(target: &AtomicU64, amount: u64, ordering: Ordering, _context: &str) {
+    target.fetch_add(amount, ordering);
+}
+#[cfg(feature = "overflow-checked")]
+pub(crate) fn atomic_sub_u64(target: &AtomicU64, amount: u64, ordering: Ordering, context: &str) {
+    let updated = target.fetch_update(ordering, Ordering::Relaxed, |current| {
+        Some(current.saturating_sub(amount))
+    });
+    if let Ok(previous) = updated
+        && previous < amount
+    {
+        record_violation(context, &format!("{previous} - {amount}"));
+    }
+}
+#[cfg(not(feature = "overflow-checked"))]
+pub(crate) fn atomic_sub_u64(target: &AtomicU64, amount: u64, ordering: Ordering, _context: &str) {
+    target.fetch_sub(amount, ordering);
+}
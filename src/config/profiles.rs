@@ -0,0 +1,104 @@
+use super::EvaluationWeights;
+use serde::Serialize;
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SearchPreset {
+    Solve7x7,
+    Play15x15,
+    Analyze19x19,
+}
+#[derive(Serialize)]
+struct PresetValues {
+    board_size: usize,
+    win_len: usize,
+    verbose: bool,
+    num_threads: usize,
+    max_children: Option<usize>,
+    node_budget: Option<u64>,
+    time_budget_secs: Option<f64>,
+    memory_budget_mb: Option<u64>,
+    soft_memory_percent: Option<u64>,
+    evaluation: EvaluationWeights,
+}
+const COMPACT_EVALUATION: EvaluationWeights = EvaluationWeights {
+    proximity_kernel_size: 7,
+    proximity_scale: 60.0,
+    positional_bonus_scale: 0.1,
+    score_win: 10_000_000.0,
+    score_live_four: 500_000.0,
+    score_blocked_four: 15_000.0,
+    score_live_three: 10_000.0,
+    score_live_two: 200.0,
+    score_block_win: 8_000_000.0,
+    score_block_live_four: 400_000.0,
+    score_block_blocked_four: 12_000.0,
+    score_block_live_three: 8_000.0,
+    fixed_point: false,
+    bitboard_ordering: false,
+};
+const OPEN_BOARD_EVALUATION: EvaluationWeights = EvaluationWeights {
+    proximity_kernel_size: 5,
+    proximity_scale: 80.0,
+    positional_bonus_scale: 0.02,
+    bitboard_ordering: true,
+    ..COMPACT_EVALUATION
+};
+impl SearchPreset {
+    pub const ALL: [Self; 3] = [Self::Solve7x7, Self::Play15x15, Self::Analyze19x19];
+    #[inline]
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Solve7x7 => "7x7-solve",
+            Self::Play15x15 => "15x15-play",
+            Self::Analyze19x19 => "19x19-analyze",
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+    const fn values(self) -> PresetValues {
+        match self {
+            Self::Solve7x7 => PresetValues {
+                board_size: 7,
+                win_len: 5,
+                verbose: false,
+                num_threads: 0,
+                max_children: None,
+                node_budget: None,
+                time_budget_secs: None,
+                memory_budget_mb: None,
+                soft_memory_percent: None,
+                evaluation: COMPACT_EVALUATION,
+            },
+            Self::Play15x15 => PresetValues {
+                board_size: 15,
+                win_len: 5,
+                verbose: false,
+                num_threads: 0,
+                max_children: Some(12),
+                node_budget: None,
+                time_budget_secs: Some(10.0),
+                memory_budget_mb: Some(4096),
+                soft_memory_percent: Some(80),
+                evaluation: OPEN_BOARD_EVALUATION,
+            },
+            Self::Analyze19x19 => PresetValues {
+                board_size: 19,
+                win_len: 5,
+                verbose: false,
+                num_threads: 0,
+                max_children: Some(16),
+                node_budget: Some(200_000_000),
+                time_budget_secs: Some(120.0),
+                memory_budget_mb: Some(8192),
+                soft_memory_percent: Some(75),
+                evaluation: OPEN_BOARD_EVALUATION,
+            },
+        }
+    }
+    pub(super) fn defaults(self) -> Result<serde_yaml::Value, serde_yaml::Error> {
+        serde_yaml::to_value(self.values())
+    }
+}
@@ -1,21 +1,57 @@
-use crate::checked;
 use crate::config::EvaluationWeights;
-use crate::utils::duration_to_ns;
 use alloc::sync::Arc;
+use bitboard::KernelShift;
 use smallvec::SmallVec;
-use std::time::Instant;
 mod bitboard;
+mod board;
+mod bulk_scoring;
 mod evaluation;
+mod forbidden;
+mod geometry;
+mod hashes;
 mod moves;
+mod packed_move;
+mod position;
+mod region;
+mod session;
 mod state;
+mod sync;
+mod terminal;
 mod threat_index;
+mod threats;
 pub type Bitboard = bitboard::Bitboard;
 pub type BitboardWorkspace = bitboard::BitboardWorkspace;
+pub type ProximityPlanes = bitboard::ProximityPlanes;
+pub type Board = board::Board;
+pub type CpuScorer = bulk_scoring::CpuScorer;
+#[cfg(feature = "gpu-scoring")]
+pub type GpuScorer = bulk_scoring::GpuScorer;
+pub type GameSession = session::GameSession;
+pub type ShapeError = geometry::ShapeError;
+pub type Position = position::Position;
+pub type BoardRegion = region::BoardRegion;
+pub type Move = packed_move::Move;
+pub type MoveRangeError = packed_move::MoveRangeError;
+pub type FirstLine = terminal::FirstLine;
+pub type GameOutcome = terminal::GameOutcome;
+pub type PointTarget = terminal::PointTarget;
+pub type ProximityScores<'buffers> = evaluation::ProximityScores<'buffers>;
+pub type PositionKey = session::PositionKey;
 pub type ZobristHasher = state::ZobristHasher;
+pub type CanonicalHash = hashes::CanonicalHash;
+pub type PositionHash = hashes::PositionHash;
 pub type ThreatIndex = threat_index::ThreatIndex;
+pub type PatternCounts = threat_index::PatternCounts;
 pub type Coord = (usize, usize);
+pub type BoardChange = (Coord, u8, u8);
 pub type MoveHistory = Vec<(Coord, SmallVec<[Coord; 8]>)>;
-pub type ForcingMoves = (Vec<Coord>, Vec<Coord>);
+pub type ForcingMoves = threats::ForcingMoves;
+pub type Rule = forbidden::Rule;
+pub(crate) use forbidden::is_forbidden;
+pub const MIN_WIN_LEN: usize = 3;
+pub const MAX_WIN_LEN: usize = 64;
+pub const RENJU_WIN_LEN: usize = 5;
+pub const MAX_BOARD_SIZE: usize = 256;
 macro_rules ! define_move_apply_timing { ($ ($ field : ident => $ stat_field : ident) ,* $ (,) ?) => { pub struct MoveApplyTiming { $ (pub $ field : u64 ,) * } impl MoveApplyTiming { # [inline] # [must_use] pub const fn zero () -> Self { Self { $ ($ field : 0 ,) * } } } } ; }
 crate::for_each_move_apply_timing!(define_move_apply_timing);
 #[derive(Clone, Copy, Default)]
@@ -28,38 +64,59 @@ pub struct MoveGenBuffers<'buffers> {
     pub scored_moves: &'buffers mut Vec<(Coord, f32)>,
     pub out_moves: &'buffers mut Vec<Coord>,
     pub candidate_moves: Option<&'buffers [u64]>,
-    pub proximity_scores: Option<&'buffers [f32]>,
+    pub proximity_scores: Option<ProximityScores<'buffers>>,
+    pub proximity_planes: Option<&'buffers mut ProximityPlanes>,
 }
-fn record_duration_ns<F: FnOnce()>(field: &mut u64, operation: F) {
-    let start = Instant::now();
-    operation();
-    *field = duration_to_ns(start.elapsed());
+pub struct ScoringJob<'batch> {
+    pub position: &'batch GomokuPosition,
+    pub player: u8,
+    pub moves: &'batch [Coord],
 }
-fn record_duration_add_ns<F: FnOnce()>(field: &mut u64, operation: F) {
-    let start = Instant::now();
-    operation();
-    *field = checked::add_u64(
-        *field,
-        duration_to_ns(start.elapsed()),
-        "game_state::record_duration_add_ns",
-    );
+pub trait BulkScorer: Send + Sync {
+    fn score_batch(&self, jobs: &[ScoringJob<'_>], scores: &mut Vec<f32>);
+}
+pub trait TerminalCondition {
+    fn name(&self) -> &'static str;
+    fn describe(&self, win_len: usize) -> String;
+    fn continues_after_line(&self) -> bool;
+    fn outcome(&self, scores: [u32; 2], board_full: bool) -> GameOutcome;
+    fn solver_support(&self, scores: [u32; 2]) -> Result<(), String>;
 }
 pub(crate) struct GomokuRules;
 #[derive(Clone)]
 pub struct GomokuPosition {
-    pub board: Vec<u8>,
+    pub board: Board,
     pub bitboard: Bitboard,
     pub board_size: usize,
     pub win_len: usize,
     pub hasher: Arc<ZobristHasher>,
     pub hash: u64,
     pub threat_index: ThreatIndex,
+    pub region: Option<BoardRegion>,
+    pub region_bits: Vec<u64>,
+    pub forbidden_player: Option<u8>,
 }
 #[derive(Clone)]
 pub struct GomokuEvaluator {
     pub(crate) config: EvaluationWeights,
     pub(crate) proximity_kernel: Vec<(usize, usize, f32)>,
     pub(crate) positional_bonus: Vec<f32>,
+    pub(crate) fixed_point: Option<FixedPointTables>,
+    pub(crate) bitboard_ordering: Option<BitboardOrderingTables>,
+}
+#[derive(Clone)]
+pub(crate) struct FixedPointTables {
+    pub(crate) proximity_kernel: Vec<i32>,
+    pub(crate) positional_bonus: Vec<i32>,
+    pub(crate) pattern_weights: [i32; 9],
+}
+#[derive(Clone)]
+pub(crate) struct BitboardOrderingTables {
+    pub(crate) static_weights: Vec<i32>,
+    pub(crate) plane_weights: Vec<i32>,
+    pub(crate) pattern_weights: [i32; 9],
+    pub(crate) kernel_shifts: Vec<KernelShift>,
+    pub(crate) plane_layout: (usize, usize),
 }
 #[derive(Clone)]
 pub(crate) struct GomokuMoveCache {
@@ -2,7 +2,9 @@ use crate::checked;
 use smallvec::SmallVec;
 mod core;
 mod iter;
+mod material;
 mod neighborhood;
+mod proximity;
 mod shift;
 mod workspace;
 const WORD_BITS: usize = 64;
@@ -17,6 +19,21 @@ pub struct Bitboard {
 pub struct BitboardWorkspace {
     scratch_pad: [Vec<u64>; 5],
 }
+#[derive(Default)]
+pub struct ProximityPlanes {
+    counters: Vec<u64>,
+    source: Vec<u64>,
+    shifted: Vec<u64>,
+    counter_bits: usize,
+    plane_count: usize,
+}
+#[derive(Clone)]
+pub(crate) struct KernelShift {
+    ring: usize,
+    distance: usize,
+    left: bool,
+    keep: SmallVec<[u64; 8]>,
+}
 type ScratchPads<'workspace> = [&'workspace mut Vec<u64>; 5];
 fn bit_mask(bit_index: usize, context: &str) -> u64 {
     checked::shl_u64(1_u64, bit_index, context)
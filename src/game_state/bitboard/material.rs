@@ -0,0 +1,50 @@
+use super::Bitboard;
+use crate::checked;
+impl Bitboard {
+    fn popcount(words: &[u64], context: &str) -> usize {
+        let total = words.iter().fold(0_u64, |count, &word| {
+            checked::add_u64(count, u64::from(word.count_ones()), context)
+        });
+        checked::u64_to_usize(total, context)
+    }
+    #[inline]
+    #[must_use]
+    pub fn stone_count(&self, player: u8) -> usize {
+        match player {
+            1 => Self::popcount(&self.black, "Bitboard::stone_count::black"),
+            2 => Self::popcount(&self.white, "Bitboard::stone_count::white"),
+            _ => {
+                eprintln!("Bitboard::stone_count 收到非法玩家编号: {player}");
+                panic!("Bitboard::stone_count 收到非法玩家编号");
+            }
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn total_stones(&self) -> usize {
+        checked::add_usize(
+            self.stone_count(1),
+            self.stone_count(2),
+            "Bitboard::total_stones",
+        )
+    }
+    #[inline]
+    #[must_use]
+    pub fn side_to_move(&self) -> u8 {
+        if self.stone_count(1) > self.stone_count(2) {
+            2
+        } else {
+            1
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn has_even_parity(&self) -> bool {
+        self.total_stones().is_multiple_of(2)
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_balanced(&self) -> bool {
+        self.stone_count(1) == self.stone_count(2)
+    }
+}
@@ -0,0 +1,180 @@
+use super::{Bitboard, KernelShift, ProximityPlanes, word_at};
+use crate::{checked, game_state::Coord};
+impl ProximityPlanes {
+    fn prepare(&mut self, rings: usize, counter_bits: usize, num_words: usize) {
+        let plane_count = checked::mul_usize(rings, counter_bits, "ProximityPlanes::prepare");
+        self.plane_count = plane_count;
+        let counters_len =
+            checked::mul_usize(plane_count, num_words, "ProximityPlanes::prepare::len");
+        if self.counters.len() != counters_len {
+            self.counters.resize(counters_len, 0);
+        }
+        self.counters.fill(0);
+        if self.source.len() != num_words {
+            self.source.resize(num_words, 0);
+        }
+        if self.shifted.len() != num_words {
+            self.shifted.resize(num_words, 0);
+        }
+        self.counter_bits = counter_bits;
+    }
+    fn word_planes(&self, word_index: usize) -> &[u64] {
+        let start =
+            checked::mul_usize(word_index, self.plane_count, "ProximityPlanes::word_planes");
+        let end = checked::add_usize(start, self.plane_count, "ProximityPlanes::word_planes");
+        let Some(planes) = self.counters.get(start..end) else {
+            eprintln!("ProximityPlanes::word_planes 计数平面索引越界: {word_index}");
+            panic!("ProximityPlanes::word_planes 计数平面索引越界");
+        };
+        planes
+    }
+    fn add_shifted_to_ring(&mut self, ring_index: usize) {
+        let ring_offset = checked::mul_usize(
+            ring_index,
+            self.counter_bits,
+            "ProximityPlanes::add_shifted_to_ring",
+        );
+        for (word_index, &shifted_word) in self.shifted.iter().enumerate() {
+            let start = checked::add_usize(
+                checked::mul_usize(
+                    word_index,
+                    self.plane_count,
+                    "ProximityPlanes::add_shifted_to_ring::word",
+                ),
+                ring_offset,
+                "ProximityPlanes::add_shifted_to_ring::start",
+            );
+            let end = checked::add_usize(
+                start,
+                self.counter_bits,
+                "ProximityPlanes::add_shifted_to_ring::end",
+            );
+            let Some(ring_counter) = self.counters.get_mut(start..end) else {
+                eprintln!("ProximityPlanes::add_shifted_to_ring 计数平面索引越界: 环 {ring_index}");
+                panic!("ProximityPlanes::add_shifted_to_ring 计数平面索引越界");
+            };
+            let mut carry = shifted_word;
+            for counter in ring_counter {
+                if carry == 0 {
+                    break;
+                }
+                let sum = *counter ^ carry;
+                carry &= *counter;
+                *counter = sum;
+            }
+            if carry != 0 {
+                eprintln!("ProximityPlanes::add_shifted_to_ring 计数位宽不足: 环 {ring_index}");
+                panic!("ProximityPlanes::add_shifted_to_ring 计数位宽不足");
+            }
+        }
+    }
+    #[inline]
+    pub(crate) fn weighted_sum(
+        &self,
+        plane_weights: &[i32],
+        (word_index, mask): (usize, u64),
+    ) -> i32 {
+        self.word_planes(word_index)
+            .iter()
+            .zip(plane_weights)
+            .filter(|&(&plane, _)| plane & mask != 0)
+            .fold(0_i32, |total, (_, &weight)| total.saturating_add(weight))
+    }
+}
+impl Bitboard {
+    pub(in crate::game_state) fn kernel_shift(
+        &self,
+        (row_offset, column_offset): Coord,
+        kernel_center: usize,
+        ring: usize,
+    ) -> KernelShift {
+        let size = self.size;
+        let forward = checked::mul_usize(
+            kernel_center,
+            checked::add_usize(size, 1_usize, "Bitboard::kernel_shift::stride"),
+            "Bitboard::kernel_shift::forward",
+        );
+        let backward = checked::add_usize(
+            checked::mul_usize(row_offset, size, "Bitboard::kernel_shift::row"),
+            column_offset,
+            "Bitboard::kernel_shift::backward",
+        );
+        let (left, distance) = if forward >= backward {
+            (
+                true,
+                checked::sub_usize(forward, backward, "Bitboard::kernel_shift::left"),
+            )
+        } else {
+            (
+                false,
+                checked::sub_usize(backward, forward, "Bitboard::kernel_shift::right"),
+            )
+        };
+        let upper_bound = checked::add_usize(size, column_offset, "Bitboard::kernel_shift::upper");
+        let mut keep = self.empty_mask();
+        for column_index in 0..size {
+            let shifted_column = checked::add_usize(
+                column_index,
+                kernel_center,
+                "Bitboard::kernel_shift::column",
+            );
+            if shifted_column < column_offset || shifted_column >= upper_bound {
+                continue;
+            }
+            for row_index in 0..size {
+                self.set_in(&mut keep, row_index, column_index);
+            }
+        }
+        KernelShift {
+            ring,
+            distance,
+            left,
+            keep,
+        }
+    }
+    pub(in crate::game_state) fn accumulate_proximity(
+        &self,
+        player: u8,
+        region: Option<&[u64]>,
+        shifts: &[KernelShift],
+        (rings, counter_bits): (usize, usize),
+        planes: &mut ProximityPlanes,
+    ) {
+        let stones = match player {
+            1 => &self.black,
+            2 => &self.white,
+            _ => {
+                eprintln!("Bitboard::accumulate_proximity 收到非法玩家编号: {player}");
+                panic!("Bitboard::accumulate_proximity 收到非法玩家编号");
+            }
+        };
+        planes.prepare(rings, counter_bits, self.num_words);
+        for shift in shifts {
+            let ring_index =
+                checked::sub_usize(shift.ring, 1_usize, "Bitboard::accumulate_proximity::ring");
+            for (word_index, source_word) in planes.source.iter_mut().enumerate() {
+                let region_word = region.map_or(u64::MAX, |bits| {
+                    word_at(bits, word_index, "Bitboard::accumulate_proximity::region")
+                });
+                *source_word =
+                    word_at(stones, word_index, "Bitboard::accumulate_proximity::stones")
+                        & word_at(
+                            &shift.keep,
+                            word_index,
+                            "Bitboard::accumulate_proximity::keep",
+                        )
+                        & region_word;
+            }
+            if Self::is_all_zeros(&planes.source) {
+                continue;
+            }
+            if shift.left {
+                self.shift_left_into(&planes.source, &mut planes.shifted, shift.distance);
+            } else {
+                self.shift_right_into(&planes.source, &mut planes.shifted, shift.distance);
+            }
+            self.apply_mask(&mut planes.shifted);
+            planes.add_shifted_to_ring(ring_index);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use super::{Coord, ShapeError};
+use crate::checked;
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Board {
+    rows: usize,
+    columns: usize,
+    cells: Vec<u8>,
+}
+impl Board {
+    #[inline]
+    #[must_use]
+    pub fn new(size: usize) -> Self {
+        Self {
+            rows: size,
+            columns: size,
+            cells: vec![0_u8; checked::mul_usize(size, size, "Board::new::cells")],
+        }
+    }
+    #[inline]
+    pub fn from_cells(size: usize, cells: Vec<u8>) -> Result<Self, ShapeError> {
+        let expected = checked::mul_usize(size, size, "Board::from_cells::cells");
+        if cells.len() != expected {
+            return Err(ShapeError::BoardLengthMismatch {
+                actual: cells.len(),
+                expected,
+            });
+        }
+        Ok(Self {
+            rows: size,
+            columns: size,
+            cells,
+        })
+    }
+    #[inline]
+    #[must_use]
+    pub const fn rows(&self) -> usize {
+        self.rows
+    }
+    #[inline]
+    #[must_use]
+    pub const fn columns(&self) -> usize {
+        self.columns
+    }
+    #[inline]
+    #[must_use]
+    pub const fn contains(&self, (row, column): Coord) -> bool {
+        row < self.rows && column < self.columns
+    }
+    #[inline]
+    #[must_use]
+    pub fn get(&self, coord: Coord) -> Option<u8> {
+        self.index(coord)
+            .and_then(|index| self.cells.get(index))
+            .copied()
+    }
+    #[inline]
+    pub fn set(&mut self, coord: Coord, value: u8) -> Option<u8> {
+        let index = self.index(coord)?;
+        let cell = self.cells.get_mut(index)?;
+        Some(core::mem::replace(cell, value))
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_empty_at(&self, coord: Coord) -> bool {
+        self.get(coord) == Some(0)
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.cells.iter().all(|&cell| cell != 0)
+    }
+    #[inline]
+    #[must_use]
+    pub fn has_stones(&self) -> bool {
+        self.cells.iter().any(|&cell| cell != 0)
+    }
+    #[inline]
+    #[must_use]
+    pub fn stone_count(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell != 0).count()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+    }
+    #[inline]
+    pub fn iter_cells(&self) -> impl Iterator<Item = (Coord, u8)> + '_ {
+        let columns = self.columns.max(1);
+        self.cells.iter().enumerate().map(move |(index, &cell)| {
+            (
+                (
+                    checked::div_usize(index, columns, "Board::iter_cells::row"),
+                    checked::rem_usize(index, columns, "Board::iter_cells::column"),
+                ),
+                cell,
+            )
+        })
+    }
+    #[inline]
+    pub fn row(&self, row: usize) -> impl Iterator<Item = u8> + '_ {
+        self.cells
+            .chunks(self.columns.max(1))
+            .nth(row)
+            .unwrap_or_default()
+            .iter()
+            .copied()
+    }
+    #[inline]
+    pub fn column(&self, column: usize) -> impl Iterator<Item = u8> + '_ {
+        self.cells
+            .chunks(self.columns.max(1))
+            .filter_map(move |row| row.get(column).copied())
+    }
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.cells
+    }
+    #[inline]
+    #[must_use]
+    pub fn into_cells(self) -> Vec<u8> {
+        self.cells
+    }
+    fn index(&self, coord: Coord) -> Option<usize> {
+        let (row, column) = coord;
+        if !self.contains(coord) {
+            return None;
+        }
+        Some(checked::add_usize(
+            checked::mul_usize(row, self.columns, "Board::index::row_offset"),
+            column,
+            "Board::index",
+        ))
+    }
+}
+impl AsRef<[u8]> for Board {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.cells
+    }
+}
@@ -0,0 +1,33 @@
+use super::{BulkScorer, GomokuEvaluator, ScoringJob};
+use crate::config::EvaluationWeights;
+#[cfg(feature = "gpu-scoring")]
+mod gpu;
+#[cfg(feature = "gpu-scoring")]
+pub type GpuScorer = gpu::GpuScorer;
+pub struct CpuScorer {
+    evaluator: GomokuEvaluator,
+}
+impl CpuScorer {
+    #[inline]
+    #[must_use]
+    pub fn new(board_size: usize, evaluation: EvaluationWeights) -> Self {
+        Self::from_evaluator(GomokuEvaluator::new(board_size, evaluation))
+    }
+    #[inline]
+    #[must_use]
+    pub const fn from_evaluator(evaluator: GomokuEvaluator) -> Self {
+        Self { evaluator }
+    }
+}
+impl BulkScorer for CpuScorer {
+    #[inline]
+    fn score_batch(&self, jobs: &[ScoringJob<'_>], scores: &mut Vec<f32>) {
+        scores.clear();
+        let mut scored_moves = Vec::new();
+        for job in jobs {
+            self.evaluator
+                .score_moves_into(job.position, job.player, job.moves, &mut scored_moves);
+            scores.extend(scored_moves.iter().map(|scored| scored.1));
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use super::{BulkScorer, CpuScorer, GomokuEvaluator, ScoringJob};
+use crate::{checked, config::EvaluationWeights, game_state::evaluation::pattern_weights};
+use alloc::borrow::Cow;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use wgpu::util::DeviceExt as _;
+const SHADER_SOURCE: &str = include_str!("score.wgsl");
+const WORKGROUP_SIZE: u32 = 64;
+pub struct GpuScorer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    tables: Vec<f32>,
+    weights: [f32; 9],
+    board_size: usize,
+    kernel_size: usize,
+    fixed_point: bool,
+    fallback: CpuScorer,
+    fallback_reported: AtomicBool,
+}
+struct BatchUpload {
+    params: Vec<u32>,
+    boards: Vec<u32>,
+    queries: Vec<u32>,
+    move_count: usize,
+}
+impl GpuScorer {
+    #[inline]
+    pub fn new(board_size: usize, evaluation: EvaluationWeights) -> Result<Self, String> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle());
+        let adapter =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+                .map_err(|err| format!("未找到可用的 GPU 适配器: {err}"))?;
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+                .map_err(|err| format!("无法创建 GPU 设备: {err}"))?;
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("bulk-scoring"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER_SOURCE)),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("bulk-scoring"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+        let evaluator = GomokuEvaluator::new(board_size, evaluation);
+        let mut tables = evaluator.positional_bonus.clone();
+        tables.extend(
+            evaluator
+                .proximity_kernel
+                .iter()
+                .map(|&(_, _, kernel_value)| kernel_value * evaluation.proximity_scale),
+        );
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+            tables,
+            weights: pattern_weights(evaluation),
+            board_size,
+            kernel_size: evaluation.proximity_kernel_size,
+            fixed_point: evaluation.fixed_point,
+            fallback: CpuScorer::from_evaluator(evaluator),
+            fallback_reported: AtomicBool::new(false),
+        })
+    }
+    fn upload(&self, jobs: &[ScoringJob<'_>]) -> Option<BatchUpload> {
+        let win_len = jobs.first()?.position.win_len;
+        let mut boards = Vec::new();
+        let mut queries = Vec::new();
+        let mut move_count = 0_usize;
+        for (job_index, job) in jobs.iter().enumerate() {
+            if job.position.board_size != self.board_size || job.position.win_len != win_len {
+                return None;
+            }
+            boards.extend(job.position.board.iter().copied().map(u32::from));
+            for &(row, column) in job.moves {
+                queries.extend([
+                    u32::try_from(job_index).ok()?,
+                    u32::try_from(job.position.board_index(row, column)).ok()?,
+                    u32::from(job.player),
+                ]);
+            }
+            move_count =
+                checked::add_usize(move_count, job.moves.len(), "GpuScorer::upload::move_count");
+        }
+        if move_count == 0 {
+            return None;
+        }
+        let mut params = vec![
+            u32::try_from(self.board_size).ok()?,
+            u32::try_from(win_len).ok()?,
+            u32::try_from(self.kernel_size).ok()?,
+            u32::try_from(move_count).ok()?,
+        ];
+        params.extend(self.weights.map(f32::to_bits));
+        Some(BatchUpload {
+            params,
+            boards,
+            queries,
+            move_count,
+        })
+    }
+    fn storage_buffer(&self, label: &str, contents: &[u8]) -> wgpu::Buffer {
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents,
+                usage: wgpu::BufferUsages::STORAGE,
+            })
+    }
+    fn dispatch(&self, upload: &BatchUpload) -> Result<Vec<f32>, String> {
+        let output_size = checked::usize_to_u64(
+            checked::mul_usize(
+                upload.move_count,
+                size_of::<f32>(),
+                "GpuScorer::dispatch::output_size",
+            ),
+            "GpuScorer::dispatch::output_size_u64",
+        );
+        let workgroups =
+            checked::usize_to_u32(upload.move_count, "GpuScorer::dispatch::workgroups")
+                .div_ceil(WORKGROUP_SIZE);
+        let params =
+            self.storage_buffer("bulk-scoring-params", bytemuck::cast_slice(&upload.params));
+        let tables = self.storage_buffer("bulk-scoring-tables", bytemuck::cast_slice(&self.tables));
+        let boards =
+            self.storage_buffer("bulk-scoring-boards", bytemuck::cast_slice(&upload.boards));
+        let queries = self.storage_buffer(
+            "bulk-scoring-queries",
+            bytemuck::cast_slice(&upload.queries),
+        );
+        let output = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("bulk-scoring-output"),
+            size: output_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("bulk-scoring-staging"),
+            size: output_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("bulk-scoring"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: tables.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: boards.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: queries.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: output.as_entire_binding(),
+                },
+            ],
+        });
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("bulk-scoring"),
+            });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("bulk-scoring"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(workgroups, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&output, 0, &staging, 0, output_size);
+        self.queue.submit([encoder.finish()]);
+        let slice = staging.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _sent = sender.send(result);
+        });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|err| format!("等待 GPU 完成失败: {err}"))?;
+        receiver
+            .recv()
+            .map_err(|err| format!("GPU 回读通道中断: {err}"))?
+            .map_err(|err| format!("GPU 结果映射失败: {err}"))?;
+        let scores = {
+            let view = slice
+                .get_mapped_range()
+                .map_err(|err| format!("GPU 结果读取失败: {err}"))?;
+            view.chunks_exact(size_of::<f32>())
+                .filter_map(|bytes| <[u8; 4]>::try_from(bytes).ok())
+                .map(f32::from_le_bytes)
+                .collect()
+        };
+        staging.unmap();
+        Ok(scores)
+    }
+}
+impl BulkScorer for GpuScorer {
+    #[inline]
+    fn score_batch(&self, jobs: &[ScoringJob<'_>], scores: &mut Vec<f32>) {
+        let pending = if self.fixed_point {
+            None
+        } else {
+            self.upload(jobs)
+        };
+        let Some(upload) = pending else {
+            self.fallback.score_batch(jobs, scores);
+            return;
+        };
+        match self.dispatch(&upload) {
+            Ok(gpu_scores) => {
+                scores.clear();
+                scores.extend(gpu_scores);
+            }
+            Err(err) => {
+                if !self.fallback_reported.swap(true, Ordering::Relaxed) {
+                    eprintln!("GPU 评分失败，回退到 CPU: {err}");
+                }
+                self.fallback.score_batch(jobs, scores);
+            }
+        }
+    }
+}
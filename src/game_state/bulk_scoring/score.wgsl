@@ -0,0 +1,82 @@
+struct Params {
+    board_size: u32,
+    win_len: u32,
+    kernel_size: u32,
+    move_count: u32,
+    weights: array<f32, 9>,
+}
+
+@group(0) @binding(0) var<storage, read> params: Params;
+@group(0) @binding(1) var<storage, read> tables: array<f32>;
+@group(0) @binding(2) var<storage, read> boards: array<u32>;
+@group(0) @binding(3) var<storage, read> queries: array<u32>;
+@group(0) @binding(4) var<storage, read_write> scores: array<f32>;
+
+fn cell_at(base: u32, row: i32, column: i32) -> u32 {
+    return boards[base + u32(row) * params.board_size + u32(column)];
+}
+
+fn in_board(row: i32, column: i32) -> bool {
+    let size = i32(params.board_size);
+    return row >= 0 && row < size && column >= 0 && column < size;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if index >= params.move_count {
+        return;
+    }
+    let job = queries[index * 3u];
+    let cell = queries[index * 3u + 1u];
+    let player = queries[index * 3u + 2u];
+    let cells = params.board_size * params.board_size;
+    let base = job * cells;
+    let row = i32(cell / params.board_size);
+    let column = i32(cell % params.board_size);
+    var score = tables[cell];
+
+    let kernel_size = i32(params.kernel_size);
+    let center = kernel_size / 2;
+    for (var kernel_row = 0; kernel_row < kernel_size; kernel_row++) {
+        for (var kernel_column = 0; kernel_column < kernel_size; kernel_column++) {
+            let source_row = row + kernel_row - center;
+            let source_column = column + kernel_column - center;
+            if in_board(source_row, source_column) && cell_at(base, source_row, source_column) == player {
+                score += tables[cells + u32(kernel_row * kernel_size + kernel_column)];
+            }
+        }
+    }
+
+    let win = i32(params.win_len);
+    var player_counts = array<i32, 9>(win - 1, win - 2, win - 3, win - 4, win - 2, 0, 0, 0, 1);
+    var opponent_counts = array<i32, 9>(0, 0, 0, 0, 1, win - 1, win - 2, win - 3, win - 2);
+    var directions = array<vec2<i32>, 4>(vec2(0, 1), vec2(1, 0), vec2(1, 1), vec2(1, -1));
+    for (var direction = 0; direction < 4; direction++) {
+        let step = directions[direction];
+        for (var shift = 0; shift < win; shift++) {
+            let start = vec2(row, column) - step * shift;
+            let end = start + step * (win - 1);
+            if !in_board(start.x, start.y) || !in_board(end.x, end.y) {
+                continue;
+            }
+            var own = 0;
+            var other = 0;
+            for (var offset = 0; offset < win; offset++) {
+                let point = start + step * offset;
+                let piece = cell_at(base, point.x, point.y);
+                if piece == player {
+                    own++;
+                } else if piece != 0u {
+                    other++;
+                }
+            }
+            for (var pattern = 0; pattern < 9; pattern++) {
+                if own == player_counts[pattern] && other == opponent_counts[pattern] {
+                    score += params.weights[pattern];
+                }
+            }
+        }
+    }
+    scores[index] = score;
+}
@@ -1,5 +1,15 @@
-use super::{Coord, GomokuEvaluator, GomokuPosition};
+use super::{
+    Bitboard, BitboardOrderingTables, Coord, FixedPointTables, GomokuEvaluator, GomokuPosition,
+    ProximityPlanes,
+};
 use crate::{checked, config::EvaluationWeights};
+const FIXED_POINT_SCALE: f32 = 16.0;
+const FIXED_POINT_SPLIT: i32 = 0x0001_0000;
+#[derive(Clone, Copy)]
+pub enum ProximityScores<'buffers> {
+    Float(&'buffers [f32]),
+    Fixed(&'buffers [i32]),
+}
 fn board_area(board_size: usize, context: &str) -> usize {
     checked::mul_usize(board_size, board_size, context)
 }
@@ -7,27 +17,198 @@ fn score_index(board_size: usize, row_index: usize, column_index: usize, context
     let row_offset = checked::mul_usize(row_index, board_size, context);
     checked::add_usize(row_offset, column_index, context)
 }
-fn score_slot_mut<'buffer>(
-    score_buffer: &'buffer mut [f32],
+fn score_slot_mut<'buffer, T>(
+    score_buffer: &'buffer mut [T],
     board_index: usize,
     context: &str,
-) -> &'buffer mut f32 {
+) -> &'buffer mut T {
     let Some(score) = score_buffer.get_mut(board_index) else {
         eprintln!("{context} 评分缓冲区索引越界: {board_index}");
         panic!("{context} 评分缓冲区索引越界");
     };
     score
 }
+fn ensure_buffer_len(actual: usize, board_size: usize, context: &str) {
+    let required_len = board_area(board_size, context);
+    if actual != required_len {
+        eprintln!("{context} 缓冲区长度不匹配: 实际 {actual}, 期望 {required_len}");
+        panic!("{context} 缓冲区长度不匹配");
+    }
+}
+fn buffered_score<T: Copy>(
+    board_size: usize,
+    board_index: usize,
+    scores: &[T],
+    context: &str,
+) -> Option<T> {
+    if scores.is_empty() {
+        return None;
+    }
+    ensure_buffer_len(scores.len(), board_size, context);
+    let Some(&score) = scores.get(board_index) else {
+        eprintln!("{context} 邻近度评分索引越界: {board_index}");
+        panic!("{context} 邻近度评分索引越界");
+    };
+    Some(score)
+}
+fn point_coord(board_size: usize, board_index: usize) -> Coord {
+    (
+        checked::div_usize(board_index, board_size, "GomokuEvaluator::point_coord::row"),
+        checked::rem_usize(
+            board_index,
+            board_size,
+            "GomokuEvaluator::point_coord::column",
+        ),
+    )
+}
+fn kernel_offset(
+    origin: usize,
+    kernel_index: usize,
+    kernel_center: usize,
+    board_size: usize,
+    mirrored: bool,
+) -> Option<usize> {
+    let offset = kernel_index.abs_diff(kernel_center);
+    if (kernel_index <= kernel_center) == mirrored {
+        origin.checked_sub(offset)
+    } else {
+        origin
+            .checked_add(offset)
+            .filter(|&target| target < board_size)
+    }
+}
+fn fixed_kernel_value(tables: &FixedPointTables, kernel_index: usize) -> i32 {
+    let Some(&value) = tables.proximity_kernel.get(kernel_index) else {
+        eprintln!("GomokuEvaluator::fixed_kernel_value 定点邻近核索引越界: {kernel_index}");
+        panic!("GomokuEvaluator::fixed_kernel_value 定点邻近核索引越界");
+    };
+    value
+}
+fn to_fixed(value: f32) -> i32 {
+    let scaled = (value * FIXED_POINT_SCALE).round();
+    if scaled.is_nan() {
+        return 0_i32;
+    }
+    let saturated = if scaled.is_sign_negative() {
+        i32::MIN
+    } else {
+        i32::MAX
+    };
+    format!("{scaled:.0}").parse().unwrap_or(saturated)
+}
+fn fixed_to_score(value: i32) -> f32 {
+    let (Ok(high), Ok(low)) = (
+        i16::try_from(value.div_euclid(FIXED_POINT_SPLIT)),
+        u16::try_from(value.rem_euclid(FIXED_POINT_SPLIT)),
+    ) else {
+        eprintln!("GomokuEvaluator::fixed_to_score 定点评分拆分失败: {value}");
+        panic!("GomokuEvaluator::fixed_to_score 定点评分拆分失败");
+    };
+    f32::from(high).mul_add(65_536.0_f32, f32::from(low)) / FIXED_POINT_SCALE
+}
+pub(crate) const fn pattern_weights(evaluation: EvaluationWeights) -> [f32; 9] {
+    [
+        evaluation.score_win,
+        evaluation.score_live_four,
+        evaluation.score_live_three,
+        evaluation.score_live_two,
+        evaluation.score_blocked_four,
+        evaluation.score_block_win,
+        evaluation.score_block_live_four,
+        evaluation.score_block_live_three,
+        evaluation.score_block_blocked_four,
+    ]
+}
 impl GomokuEvaluator {
     #[inline]
     #[must_use]
     pub fn new(board_size: usize, config: EvaluationWeights) -> Self {
         let proximity_kernel = Self::init_proximity_kernel(config);
         let positional_bonus = Self::init_positional_bonus(board_size, config);
+        let fixed_point = config.fixed_point.then(|| FixedPointTables {
+            proximity_kernel: proximity_kernel
+                .iter()
+                .map(|&(_, _, kernel_value)| to_fixed(kernel_value * config.proximity_scale))
+                .collect(),
+            positional_bonus: positional_bonus.iter().copied().map(to_fixed).collect(),
+            pattern_weights: pattern_weights(config).map(to_fixed),
+        });
+        let bitboard_ordering = config.bitboard_ordering.then(|| {
+            Self::init_bitboard_ordering(board_size, config, &proximity_kernel, &positional_bonus)
+        });
         Self {
             config,
             proximity_kernel,
             positional_bonus,
+            fixed_point,
+            bitboard_ordering,
+        }
+    }
+    fn init_bitboard_ordering(
+        board_size: usize,
+        config: EvaluationWeights,
+        proximity_kernel: &[(usize, usize, f32)],
+        positional_bonus: &[f32],
+    ) -> BitboardOrderingTables {
+        let kernel_center = checked::div_usize(
+            config.proximity_kernel_size,
+            2_usize,
+            "GomokuEvaluator::init_bitboard_ordering",
+        );
+        let bitboard = Bitboard::new(board_size);
+        let mut ring_weights: Vec<(i32, usize)> = Vec::new();
+        let mut kernel_shifts = Vec::with_capacity(proximity_kernel.len());
+        for &(kernel_row_index, kernel_column_index, kernel_value) in proximity_kernel {
+            let ring = checked::add_usize(
+                kernel_row_index.abs_diff(kernel_center),
+                kernel_column_index.abs_diff(kernel_center),
+                "GomokuEvaluator::init_bitboard_ordering::ring",
+            );
+            let Some(ring_index) = ring.checked_sub(1) else {
+                continue;
+            };
+            if ring_weights.len() < ring {
+                ring_weights.resize(ring, (0_i32, 0_usize));
+            }
+            if let Some(slot) = ring_weights.get_mut(ring_index) {
+                *slot = (
+                    to_fixed(kernel_value * config.proximity_scale),
+                    slot.1.saturating_add(1),
+                );
+            }
+            kernel_shifts.push(bitboard.kernel_shift(
+                (kernel_row_index, kernel_column_index),
+                kernel_center,
+                ring,
+            ));
+        }
+        let max_population = ring_weights
+            .iter()
+            .map(|&(_, population)| population)
+            .max()
+            .unwrap_or(0);
+        let mut counter_bits = 1_u32;
+        while 2_usize.saturating_pow(counter_bits) <= max_population {
+            counter_bits = counter_bits.saturating_add(1);
+        }
+        let plane_weights = ring_weights
+            .iter()
+            .flat_map(|&(weight, _)| {
+                (0..counter_bits).map(move |bit| weight.saturating_mul(2_i32.saturating_pow(bit)))
+            })
+            .collect();
+        BitboardOrderingTables {
+            static_weights: positional_bonus.iter().copied().map(to_fixed).collect(),
+            plane_weights,
+            pattern_weights: pattern_weights(config).map(to_fixed),
+            kernel_shifts,
+            plane_layout: (
+                ring_weights.len(),
+                checked::u64_to_usize(
+                    u64::from(counter_bits),
+                    "GomokuEvaluator::init_bitboard_ordering::counter_bits",
+                ),
+            ),
         }
     }
     fn init_proximity_kernel(config: EvaluationWeights) -> Vec<(usize, usize, f32)> {
@@ -121,7 +302,9 @@ impl GomokuEvaluator {
         let scale = self.config.proximity_scale;
         for row_index in 0..board_size {
             for column_index in 0..board_size {
-                if position.cell(row_index, column_index) == player {
+                if position.cell(row_index, column_index) == player
+                    && position.in_region((row_index, column_index))
+                {
                     self.apply_proximity_kernel_scaled(
                         board_size,
                         (row_index, column_index),
@@ -175,93 +358,61 @@ impl GomokuEvaluator {
         let scale = self.config.proximity_scale * delta;
         self.apply_proximity_kernel_scaled(position.board_size, mov, scale, target);
     }
-    fn apply_proximity_kernel_scaled(
+    fn for_each_kernel_cell<F>(
         &self,
         board_size: usize,
-        mov: Coord,
-        scale: f32,
-        target: &mut [f32],
-    ) {
-        let required_len = board_area(board_size, "GomokuEvaluator::apply_proximity_kernel_scaled");
-        if target.len() != required_len {
-            eprintln!(
-                "GomokuEvaluator::apply_proximity_kernel_scaled 缓冲区长度不匹配: 实际 {}, 期望 {}",
-                target.len(),
-                required_len
-            );
-            panic!("GomokuEvaluator::apply_proximity_kernel_scaled 缓冲区长度不匹配");
-        }
-        let (row_index, column_index) = mov;
+        (row_index, column_index): Coord,
+        mirrored: bool,
+        mut visit: F,
+    ) where
+        F: FnMut(usize, f32, Coord),
+    {
         let kernel_center = checked::div_usize(
             self.config.proximity_kernel_size,
             2_usize,
-            "GomokuEvaluator::apply_proximity_kernel_scaled::kernel_center",
+            "GomokuEvaluator::for_each_kernel_cell::kernel_center",
         );
-        for &(kernel_row_index, kernel_column_index, kernel_value) in &self.proximity_kernel {
-            let target_row = if kernel_row_index <= kernel_center {
-                let offset = checked::sub_usize(
-                    kernel_center,
-                    kernel_row_index,
-                    "GomokuEvaluator::apply_proximity_kernel_scaled::target_row_forward_offset",
-                );
-                let target_row = checked::add_usize(
-                    row_index,
-                    offset,
-                    "GomokuEvaluator::apply_proximity_kernel_scaled::target_row_forward",
-                );
-                if target_row >= board_size {
-                    continue;
-                }
-                target_row
-            } else {
-                let offset = checked::sub_usize(
-                    kernel_row_index,
-                    kernel_center,
-                    "GomokuEvaluator::apply_proximity_kernel_scaled::target_row_backward_offset",
-                );
-                if offset > row_index {
-                    continue;
-                }
-                checked::sub_usize(
-                    row_index,
-                    offset,
-                    "GomokuEvaluator::apply_proximity_kernel_scaled::target_row_backward",
-                )
+        for (kernel_index, &(kernel_row_index, kernel_column_index, kernel_value)) in
+            self.proximity_kernel.iter().enumerate()
+        {
+            let Some(row) = kernel_offset(
+                row_index,
+                kernel_row_index,
+                kernel_center,
+                board_size,
+                mirrored,
+            ) else {
+                continue;
             };
-            let target_column = if kernel_column_index <= kernel_center {
-                let offset = checked::sub_usize(
-                    kernel_center,
-                    kernel_column_index,
-                    "GomokuEvaluator::apply_proximity_kernel_scaled::target_column_forward_offset",
-                );
-                let target_column = checked::add_usize(
-                    column_index,
-                    offset,
-                    "GomokuEvaluator::apply_proximity_kernel_scaled::target_column_forward",
-                );
-                if target_column >= board_size {
-                    continue;
-                }
-                target_column
-            } else {
-                let offset = checked::sub_usize(
-                    kernel_column_index,
-                    kernel_center,
-                    "GomokuEvaluator::apply_proximity_kernel_scaled::target_column_backward_offset",
-                );
-                if offset > column_index {
-                    continue;
-                }
-                checked::sub_usize(
-                    column_index,
-                    offset,
-                    "GomokuEvaluator::apply_proximity_kernel_scaled::target_column_backward",
-                )
+            let Some(column) = kernel_offset(
+                column_index,
+                kernel_column_index,
+                kernel_center,
+                board_size,
+                mirrored,
+            ) else {
+                continue;
             };
+            visit(kernel_index, kernel_value, (row, column));
+        }
+    }
+    fn apply_proximity_kernel_scaled(
+        &self,
+        board_size: usize,
+        mov: Coord,
+        scale: f32,
+        target: &mut [f32],
+    ) {
+        ensure_buffer_len(
+            target.len(),
+            board_size,
+            "GomokuEvaluator::apply_proximity_kernel_scaled",
+        );
+        self.for_each_kernel_cell(board_size, mov, false, |_, kernel_value, (row, column)| {
             let target_index = score_index(
                 board_size,
-                target_row,
-                target_column,
+                row,
+                column,
                 "GomokuEvaluator::apply_proximity_kernel_scaled::target_index",
             );
             let score = score_slot_mut(
@@ -270,12 +421,137 @@ impl GomokuEvaluator {
                 "GomokuEvaluator::apply_proximity_kernel_scaled",
             );
             *score = kernel_value.mul_add(scale, *score);
+        });
+    }
+    #[must_use]
+    pub(crate) const fn is_fixed_point(&self) -> bool {
+        self.fixed_point.is_some()
+    }
+    fn fixed_tables(&self) -> &FixedPointTables {
+        let Some(tables) = self.fixed_point.as_ref() else {
+            eprintln!("GomokuEvaluator::fixed_tables 未启用定点评分");
+            panic!("GomokuEvaluator::fixed_tables 未启用定点评分");
+        };
+        tables
+    }
+    #[must_use]
+    pub(crate) const fn uses_bitboard_ordering(&self) -> bool {
+        self.bitboard_ordering.is_some()
+    }
+    fn bitboard_tables(&self) -> &BitboardOrderingTables {
+        let Some(tables) = self.bitboard_ordering.as_ref() else {
+            eprintln!("GomokuEvaluator::bitboard_tables 未启用位棋盘排序");
+            panic!("GomokuEvaluator::bitboard_tables 未启用位棋盘排序");
+        };
+        tables
+    }
+    fn accumulate_proximity_planes(
+        &self,
+        position: &GomokuPosition,
+        player: u8,
+        planes: &mut ProximityPlanes,
+    ) {
+        let tables = self.bitboard_tables();
+        let region = position
+            .region
+            .is_some()
+            .then_some(position.region_bits.as_slice());
+        position.bitboard.accumulate_proximity(
+            player,
+            region,
+            &tables.kernel_shifts,
+            tables.plane_layout,
+            planes,
+        );
+    }
+    pub(crate) fn prime_proximity_planes(
+        &self,
+        position: &GomokuPosition,
+        planes: &mut ProximityPlanes,
+    ) {
+        self.accumulate_proximity_planes(position, 1, planes);
+    }
+    pub(crate) fn rebuild_fixed_proximity_scores(
+        &self,
+        position: &GomokuPosition,
+        player: u8,
+        target: &mut [i32],
+    ) {
+        let board_size = position.board_size;
+        ensure_buffer_len(
+            target.len(),
+            board_size,
+            "GomokuEvaluator::rebuild_fixed_proximity_scores",
+        );
+        target.fill(0_i32);
+        for row_index in 0..board_size {
+            for column_index in 0..board_size {
+                if position.cell(row_index, column_index) == player
+                    && position.in_region((row_index, column_index))
+                {
+                    self.apply_fixed_proximity_kernel(
+                        board_size,
+                        (row_index, column_index),
+                        1_i32,
+                        target,
+                    );
+                }
+            }
         }
     }
-    fn patterns_to_score(
+    pub(crate) fn apply_fixed_proximity_delta(
+        &self,
+        position: &GomokuPosition,
+        mov: Coord,
+        delta: i32,
+        target: &mut [i32],
+    ) {
+        ensure_buffer_len(
+            target.len(),
+            position.board_size,
+            "GomokuEvaluator::apply_fixed_proximity_delta",
+        );
+        self.apply_fixed_proximity_kernel(position.board_size, mov, delta, target);
+    }
+    fn apply_fixed_proximity_kernel(
+        &self,
+        board_size: usize,
+        mov: Coord,
+        delta: i32,
+        target: &mut [i32],
+    ) {
+        let tables = self.fixed_tables();
+        self.for_each_kernel_cell(board_size, mov, false, |kernel_index, _, (row, column)| {
+            let weight = fixed_kernel_value(tables, kernel_index).saturating_mul(delta);
+            let target_index = score_index(
+                board_size,
+                row,
+                column,
+                "GomokuEvaluator::apply_fixed_proximity_kernel::target_index",
+            );
+            let score = score_slot_mut(
+                target,
+                target_index,
+                "GomokuEvaluator::apply_fixed_proximity_kernel",
+            );
+            *score = score.saturating_add(weight);
+        });
+    }
+    fn patterns_to_score<S: Copy>(
         position: &GomokuPosition,
-        evaluation: EvaluationWeights,
-    ) -> [(usize, usize, f32); 9] {
+        weights: [S; 9],
+    ) -> [(usize, usize, S); 9] {
+        let [
+            win,
+            live_four,
+            live_three,
+            live_two,
+            blocked_four,
+            block_win,
+            block_live_four,
+            block_live_three,
+            block_blocked_four,
+        ] = weights;
         let win_minus_one = checked::sub_usize(
             position.win_len,
             1_usize,
@@ -297,15 +573,15 @@ impl GomokuEvaluator {
             "GomokuEvaluator::patterns_to_score::win_minus_four",
         );
         [
-            (win_minus_one, 0, evaluation.score_win),
-            (win_minus_two, 0, evaluation.score_live_four),
-            (win_minus_three, 0, evaluation.score_live_three),
-            (win_minus_four, 0, evaluation.score_live_two),
-            (win_minus_two, 1, evaluation.score_blocked_four),
-            (0, win_minus_one, evaluation.score_block_win),
-            (0, win_minus_two, evaluation.score_block_live_four),
-            (0, win_minus_three, evaluation.score_block_live_three),
-            (1, win_minus_two, evaluation.score_block_blocked_four),
+            (win_minus_one, 0, win),
+            (win_minus_two, 0, live_four),
+            (win_minus_three, 0, live_three),
+            (win_minus_four, 0, live_two),
+            (win_minus_two, 1, blocked_four),
+            (0, win_minus_one, block_win),
+            (0, win_minus_two, block_live_four),
+            (0, win_minus_three, block_live_three),
+            (1, win_minus_two, block_blocked_four),
         ]
     }
     fn positional_score(&self, board_index: usize) -> f32 {
@@ -322,123 +598,72 @@ impl GomokuEvaluator {
         board_index: usize,
         proximity_scores: &[f32],
     ) -> f32 {
-        let required_len = board_area(
+        if let Some(score) = buffered_score(
             position.board_size,
-            "GomokuEvaluator::proximity_score_for_point::required_len",
-        );
-        if proximity_scores.len() == required_len {
-            let Some(&score) = proximity_scores.get(board_index) else {
-                eprintln!(
-                    "GomokuEvaluator::proximity_score_for_point 邻近度评分索引越界: {board_index}"
-                );
-                panic!("GomokuEvaluator::proximity_score_for_point 邻近度评分索引越界");
-            };
+            board_index,
+            proximity_scores,
+            "GomokuEvaluator::proximity_score_for_point",
+        ) {
             return score;
         }
-        if !proximity_scores.is_empty() {
-            eprintln!(
-                "GomokuEvaluator::proximity_score_for_point 邻近度评分长度不匹配: 实际 {}, 期望 {}",
-                proximity_scores.len(),
-                required_len
-            );
-            panic!("GomokuEvaluator::proximity_score_for_point 邻近度评分长度不匹配");
-        }
         let mut score = 0.0_f32;
-        let row_index = checked::div_usize(
-            board_index,
+        let origin = point_coord(position.board_size, board_index);
+        self.for_each_kernel_cell(
             position.board_size,
-            "GomokuEvaluator::proximity_score_for_point::row_index",
+            origin,
+            true,
+            |_, kernel_value, (row, column)| {
+                if position.cell(row, column) == player && position.in_region((row, column)) {
+                    score = kernel_value.mul_add(self.config.proximity_scale, score);
+                }
+            },
         );
-        let column_index = checked::rem_usize(
+        score
+    }
+    fn fixed_proximity_score_for_point(
+        &self,
+        tables: &FixedPointTables,
+        position: &GomokuPosition,
+        player: u8,
+        board_index: usize,
+        proximity_scores: &[i32],
+    ) -> i32 {
+        if let Some(score) = buffered_score(
+            position.board_size,
             board_index,
+            proximity_scores,
+            "GomokuEvaluator::fixed_proximity_score_for_point",
+        ) {
+            return score;
+        }
+        let mut score = 0_i32;
+        let origin = point_coord(position.board_size, board_index);
+        self.for_each_kernel_cell(
             position.board_size,
-            "GomokuEvaluator::proximity_score_for_point::column_index",
-        );
-        let kernel_center = checked::div_usize(
-            self.config.proximity_kernel_size,
-            2_usize,
-            "GomokuEvaluator::proximity_score_for_point::kernel_center",
-        );
-        for &(kernel_row_index, kernel_column_index, kernel_value) in &self.proximity_kernel {
-            let source_row = if kernel_row_index <= kernel_center {
-                let offset = checked::sub_usize(
-                    kernel_center,
-                    kernel_row_index,
-                    "GomokuEvaluator::proximity_score_for_point::source_row_backward_offset",
-                );
-                if offset > row_index {
-                    continue;
-                }
-                checked::sub_usize(
-                    row_index,
-                    offset,
-                    "GomokuEvaluator::proximity_score_for_point::source_row_backward",
-                )
-            } else {
-                let offset = checked::sub_usize(
-                    kernel_row_index,
-                    kernel_center,
-                    "GomokuEvaluator::proximity_score_for_point::source_row_forward_offset",
-                );
-                let source_row = checked::add_usize(
-                    row_index,
-                    offset,
-                    "GomokuEvaluator::proximity_score_for_point::source_row_forward",
-                );
-                if source_row >= position.board_size {
-                    continue;
+            origin,
+            true,
+            |kernel_index, _, (row, column)| {
+                if position.cell(row, column) == player && position.in_region((row, column)) {
+                    score = score.saturating_add(fixed_kernel_value(tables, kernel_index));
                 }
-                source_row
-            };
-            let source_column = if kernel_column_index <= kernel_center {
-                let offset = checked::sub_usize(
-                    kernel_center,
-                    kernel_column_index,
-                    "GomokuEvaluator::proximity_score_for_point::source_column_backward_offset",
-                );
-                if offset > column_index {
-                    continue;
-                }
-                checked::sub_usize(
-                    column_index,
-                    offset,
-                    "GomokuEvaluator::proximity_score_for_point::source_column_backward",
-                )
-            } else {
-                let offset = checked::sub_usize(
-                    kernel_column_index,
-                    kernel_center,
-                    "GomokuEvaluator::proximity_score_for_point::source_column_forward_offset",
-                );
-                let source_column = checked::add_usize(
-                    column_index,
-                    offset,
-                    "GomokuEvaluator::proximity_score_for_point::source_column_forward",
-                );
-                if source_column >= position.board_size {
-                    continue;
-                }
-                source_column
-            };
-            if position.cell(source_row, source_column) == player {
-                score = kernel_value.mul_add(self.config.proximity_scale, score);
-            }
-        }
+            },
+        );
         score
     }
-    fn pattern_score_for_point(
+    fn pattern_score_for_point<S: Copy>(
         position: &GomokuPosition,
+        (row_index, column_index): Coord,
         player: u8,
-        row_index: usize,
-        column_index: usize,
-        patterns: &[(usize, usize, f32); 9],
-    ) -> f32 {
-        let mut score = 0.0_f32;
-        for &window_index_u16 in position
+        patterns: &[(usize, usize, S); 9],
+        zero: S,
+        add: fn(S, S) -> S,
+    ) -> S {
+        let mut score = zero;
+        for window_index in position
             .threat_index
             .window_indices_for_point(row_index, column_index)
         {
-            let window = position.threat_index.window(usize::from(window_index_u16));
+            let window = position.threat_index.window(window_index);
             let (player_count, opponent_count) = match player {
                 1 => (window.p1_count, window.p2_count),
                 2 => (window.p2_count, window.p1_count),
@@ -452,7 +677,7 @@ impl GomokuEvaluator {
             for &(pattern_player_count, pattern_opponent_count, pattern_score) in patterns {
                 if player_count == pattern_player_count && opponent_count == pattern_opponent_count
                 {
-                    score += pattern_score;
+                    score = add(score, pattern_score);
                 }
             }
         }
@@ -465,34 +690,131 @@ impl GomokuEvaluator {
         moves_to_score: &[Coord],
         scored_moves: &mut Vec<(Coord, f32)>,
     ) {
-        self.score_moves_into_with_proximity(position, player, moves_to_score, &[], scored_moves);
+        if self.uses_bitboard_ordering() {
+            let mut planes = ProximityPlanes::default();
+            self.score_moves_into_with_planes(
+                position,
+                player,
+                moves_to_score,
+                &mut planes,
+                scored_moves,
+            );
+            return;
+        }
+        let proximity_scores = if self.is_fixed_point() {
+            ProximityScores::Fixed(&[])
+        } else {
+            ProximityScores::Float(&[])
+        };
+        self.score_moves_into_with_proximity(
+            position,
+            player,
+            moves_to_score,
+            proximity_scores,
+            scored_moves,
+        );
     }
     pub(crate) fn score_moves_into_with_proximity(
         &self,
         position: &GomokuPosition,
         player: u8,
         moves_to_score: &[Coord],
-        proximity_scores: &[f32],
+        proximity_scores: ProximityScores<'_>,
         scored_moves: &mut Vec<(Coord, f32)>,
     ) {
-        let evaluation = self.config;
         scored_moves.clear();
         if moves_to_score.is_empty() {
             return;
         }
-        let patterns = Self::patterns_to_score(position, evaluation);
-        for &(row_index, column_index) in moves_to_score {
-            let board_index = position.board_index(row_index, column_index);
-            let score = self.positional_score(board_index)
-                + self.proximity_score_for_point(position, player, board_index, proximity_scores)
-                + Self::pattern_score_for_point(
+        match proximity_scores {
+            ProximityScores::Float(scores) => {
+                let patterns = Self::patterns_to_score(position, pattern_weights(self.config));
+                for &mov in moves_to_score {
+                    let board_index = position.board_index(mov.0, mov.1);
+                    let score = self.positional_score(board_index)
+                        + self.proximity_score_for_point(position, player, board_index, scores)
+                        + Self::pattern_score_for_point(
+                            position,
+                            mov,
+                            player,
+                            &patterns,
+                            0.0_f32,
+                            |left, right| left + right,
+                        );
+                    scored_moves.push((mov, score));
+                }
+            }
+            ProximityScores::Fixed(scores) => {
+                let tables = self.fixed_tables();
+                let patterns = Self::patterns_to_score(position, tables.pattern_weights);
+                for &mov in moves_to_score {
+                    let board_index = position.board_index(mov.0, mov.1);
+                    let Some(&positional) = tables.positional_bonus.get(board_index) else {
+                        eprintln!(
+                            "GomokuEvaluator::score_moves_into_with_proximity 定点位置评分索引越界: {board_index}"
+                        );
+                        panic!(
+                            "GomokuEvaluator::score_moves_into_with_proximity 定点位置评分索引越界"
+                        );
+                    };
+                    let score = positional
+                        .saturating_add(self.fixed_proximity_score_for_point(
+                            tables,
+                            position,
+                            player,
+                            board_index,
+                            scores,
+                        ))
+                        .saturating_add(Self::pattern_score_for_point(
+                            position,
+                            mov,
+                            player,
+                            &patterns,
+                            0_i32,
+                            i32::saturating_add,
+                        ));
+                    scored_moves.push((mov, fixed_to_score(score)));
+                }
+            }
+        }
+    }
+    pub(crate) fn score_moves_into_with_planes(
+        &self,
+        position: &GomokuPosition,
+        player: u8,
+        moves_to_score: &[Coord],
+        planes: &mut ProximityPlanes,
+        scored_moves: &mut Vec<(Coord, f32)>,
+    ) {
+        scored_moves.clear();
+        if moves_to_score.is_empty() {
+            return;
+        }
+        self.accumulate_proximity_planes(position, player, planes);
+        let tables = self.bitboard_tables();
+        let patterns = Self::patterns_to_score(position, tables.pattern_weights);
+        for &mov in moves_to_score {
+            let board_index = position.board_index(mov.0, mov.1);
+            let Some(&static_weight) = tables.static_weights.get(board_index) else {
+                eprintln!(
+                    "GomokuEvaluator::score_moves_into_with_planes 静态权重索引越界: {board_index}"
+                );
+                panic!("GomokuEvaluator::score_moves_into_with_planes 静态权重索引越界");
+            };
+            let score = static_weight
+                .saturating_add(planes.weighted_sum(
+                    &tables.plane_weights,
+                    position.bitboard.coord_to_bit(mov.0, mov.1),
+                ))
+                .saturating_add(Self::pattern_score_for_point(
                     position,
+                    mov,
                     player,
-                    row_index,
-                    column_index,
                     &patterns,
-                );
-            scored_moves.push(((row_index, column_index), score));
+                    0_i32,
+                    i32::saturating_add,
+                ));
+            scored_moves.push((mov, fixed_to_score(score)));
         }
     }
 }
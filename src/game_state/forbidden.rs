@@ -0,0 +1,223 @@
+use super::{
+    Board, Coord,
+    terminal::{LINE_DIRECTIONS, step},
+};
+use crate::checked;
+use core::{cmp::Ordering, iter};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Rule {
+    #[default]
+    Freestyle,
+    Renju,
+}
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Own,
+    Empty,
+    Blocked,
+}
+type Line = SmallVec<[Cell; 16]>;
+#[derive(Clone, Copy, Default)]
+struct LineShape {
+    five: bool,
+    overline: bool,
+    fours: usize,
+    open_three: bool,
+}
+impl Rule {
+    #[inline]
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Freestyle => "无禁手",
+            Self::Renju => "连珠（黑方禁手）",
+        }
+    }
+    pub(crate) fn forbidden_player(self, board: &Board, side_to_move: u8) -> Option<u8> {
+        if self != Self::Renju {
+            return None;
+        }
+        let (mut first, mut second) = (0_usize, 0_usize);
+        for (_coord, stone) in board.iter_cells() {
+            match stone {
+                1 => first = checked::add_usize(first, 1, "Rule::forbidden_player::first"),
+                2 => second = checked::add_usize(second, 1, "Rule::forbidden_player::second"),
+                _ => {}
+            }
+        }
+        Some(match first.cmp(&second) {
+            Ordering::Greater => 1,
+            Ordering::Less => 2,
+            Ordering::Equal => side_to_move,
+        })
+    }
+}
+pub(crate) fn is_forbidden(board: &Board, win_len: usize, mov: Coord, player: u8) -> bool {
+    if board.get(mov) != Some(0) {
+        return false;
+    }
+    let reach = checked::add_usize(win_len, 1, "forbidden::is_forbidden::reach");
+    let shapes: SmallVec<[LineShape; 4]> = LINE_DIRECTIONS
+        .iter()
+        .map(|&directions| {
+            let (mut line, center) = line_through(board, mov, directions, player, reach);
+            line_shape(&mut line, center, win_len)
+        })
+        .collect();
+    if shapes.iter().any(|shape| shape.five) {
+        return false;
+    }
+    let fours = shapes.iter().fold(0_usize, |total, shape| {
+        checked::add_usize(total, shape.fours, "forbidden::is_forbidden::fours")
+    });
+    let open_threes = shapes.iter().filter(|shape| shape.open_three).count();
+    shapes.iter().any(|shape| shape.overline) || fours >= 2 || open_threes >= 2
+}
+pub(crate) fn is_exact_line(board: &Board, coords: &[Coord], player: u8) -> bool {
+    let (Some(&first), Some(&last)) = (coords.first(), coords.last()) else {
+        return false;
+    };
+    let forward = (axis_delta(last.0, first.0), axis_delta(last.1, first.1));
+    let backward = (axis_delta(first.0, last.0), axis_delta(first.1, last.1));
+    let extends = |coord: Coord, direction: (isize, isize)| {
+        step(board, coord, direction).is_some_and(|next| board.get(next) == Some(player))
+    };
+    !extends(first, backward) && !extends(last, forward)
+}
+fn axis_delta(to: usize, from: usize) -> isize {
+    match to.cmp(&from) {
+        Ordering::Greater => 1,
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+    }
+}
+fn line_through(
+    board: &Board,
+    mov: Coord,
+    (forward, backward): ((isize, isize), (isize, isize)),
+    player: u8,
+    reach: usize,
+) -> (Line, usize) {
+    let mut line: Line = walk(board, mov, backward, player, reach).collect();
+    line.reverse();
+    let center = line.len();
+    line.push(Cell::Own);
+    line.extend(walk(board, mov, forward, player, reach));
+    (line, center)
+}
+fn walk(
+    board: &Board,
+    mov: Coord,
+    direction: (isize, isize),
+    player: u8,
+    reach: usize,
+) -> impl Iterator<Item = Cell> + '_ {
+    iter::successors(step(board, mov, direction), move |&coord| {
+        step(board, coord, direction)
+    })
+    .map(move |coord| match board.get(coord) {
+        Some(0) => Cell::Empty,
+        Some(stone) if stone == player => Cell::Own,
+        _ => Cell::Blocked,
+    })
+    .chain(iter::repeat(Cell::Blocked))
+    .take(reach)
+}
+fn run_bounds(line: &[Cell], index: usize) -> (usize, usize) {
+    let mut start = index;
+    while let Some(previous) = start.checked_sub(1)
+        && line.get(previous) == Some(&Cell::Own)
+    {
+        start = previous;
+    }
+    let mut end = index;
+    while let Some(next) = end.checked_add(1)
+        && line.get(next) == Some(&Cell::Own)
+    {
+        end = next;
+    }
+    (start, end)
+}
+fn run_len(line: &[Cell], index: usize) -> usize {
+    let (start, end) = run_bounds(line, index);
+    checked::add_usize(
+        checked::sub_usize(end, start, "forbidden::run_len"),
+        1,
+        "forbidden::run_len",
+    )
+}
+fn five_points(line: &mut [Cell], center: usize, win_len: usize) -> SmallVec<[usize; 4]> {
+    let mut points = SmallVec::new();
+    for index in 0..line.len() {
+        let Some(cell) = line.get_mut(index) else {
+            continue;
+        };
+        if *cell != Cell::Empty {
+            continue;
+        }
+        *cell = Cell::Own;
+        let (start, end) = run_bounds(line, index);
+        if (start..=end).contains(&center) && run_len(line, index) == win_len {
+            points.push(index);
+        }
+        if let Some(restored) = line.get_mut(index) {
+            *restored = Cell::Empty;
+        }
+    }
+    points
+}
+fn is_straight_four(points: &[usize], win_len: usize) -> bool {
+    matches!(points, &[low, high] if checked::sub_usize(high, low, "forbidden::is_straight_four") == win_len)
+}
+fn four_count(line: &mut [Cell], center: usize, win_len: usize) -> (usize, bool) {
+    let points = five_points(line, center, win_len);
+    if is_straight_four(&points, win_len) {
+        (1, true)
+    } else {
+        (points.len(), false)
+    }
+}
+fn line_shape(line: &mut [Cell], center: usize, win_len: usize) -> LineShape {
+    let run = run_len(line, center);
+    if run >= win_len {
+        return LineShape {
+            five: run == win_len,
+            overline: run > win_len,
+            ..LineShape::default()
+        };
+    }
+    let (fours, _straight) = four_count(line, center, win_len);
+    if fours > 0 {
+        return LineShape {
+            fours,
+            ..LineShape::default()
+        };
+    }
+    let mut open_three = false;
+    for index in 0..line.len() {
+        if open_three {
+            break;
+        }
+        let Some(cell) = line.get_mut(index) else {
+            continue;
+        };
+        if *cell != Cell::Empty {
+            continue;
+        }
+        *cell = Cell::Own;
+        if run_len(line, index) < win_len {
+            open_three = four_count(line, center, win_len).1;
+        }
+        if let Some(restored) = line.get_mut(index) {
+            *restored = Cell::Empty;
+        }
+    }
+    LineShape {
+        open_three,
+        ..LineShape::default()
+    }
+}
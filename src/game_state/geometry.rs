@@ -0,0 +1,175 @@
+use super::{
+    EvaluationWeights, GameState, MAX_BOARD_SIZE, MAX_WIN_LEN, MIN_WIN_LEN, ThreatIndex,
+    ZobristHasher,
+};
+use crate::checked;
+use alloc::sync::Arc;
+use core::fmt;
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShapeError {
+    EmptyBoard,
+    BoardTooLarge {
+        board_size: usize,
+    },
+    WinLenTooShort {
+        win_len: usize,
+    },
+    WinLenTooLong {
+        win_len: usize,
+    },
+    WinLenExceedsBoard {
+        win_len: usize,
+        board_size: usize,
+    },
+    TooManyWindows {
+        board_size: usize,
+        win_len: usize,
+    },
+    BoardLengthMismatch {
+        actual: usize,
+        expected: usize,
+    },
+    HasherMismatch {
+        hasher_size: usize,
+        board_size: usize,
+    },
+    InvalidPlayer {
+        player: u8,
+    },
+    InvalidCell {
+        cell: u8,
+    },
+}
+impl fmt::Display for ShapeError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::EmptyBoard => f.write_str("棋盘大小必须大于 0"),
+            Self::BoardTooLarge { board_size } => {
+                write!(f, "棋盘大小 {board_size} 过大，至多为 {MAX_BOARD_SIZE}")
+            }
+            Self::WinLenTooShort { win_len } => {
+                write!(f, "连珠长度 {win_len} 过短，至少为 {MIN_WIN_LEN}")
+            }
+            Self::WinLenTooLong { win_len } => {
+                write!(f, "连珠长度 {win_len} 过长，至多为 {MAX_WIN_LEN}")
+            }
+            Self::WinLenExceedsBoard {
+                win_len,
+                board_size,
+            } => write!(f, "连珠长度 {win_len} 超过棋盘大小 {board_size}"),
+            Self::TooManyWindows {
+                board_size,
+                win_len,
+            } => write!(
+                f,
+                "棋盘大小 {board_size} 与连珠长度 {win_len} 产生的连线窗口过多"
+            ),
+            Self::BoardLengthMismatch { actual, expected } => {
+                write!(f, "棋盘长度不匹配: 实际 {actual}，期望 {expected}")
+            }
+            Self::HasherMismatch {
+                hasher_size,
+                board_size,
+            } => write!(
+                f,
+                "哈希器棋盘大小不匹配: 哈希器 {hasher_size}，棋盘 {board_size}"
+            ),
+            Self::InvalidPlayer { player } => write!(f, "行棋方无效: {player}"),
+            Self::InvalidCell { cell } => write!(f, "棋盘包含非法棋子: {cell}"),
+        }
+    }
+}
+fn validate_shape(board_size: usize, win_len: usize) -> Result<(), ShapeError> {
+    if board_size == 0 {
+        return Err(ShapeError::EmptyBoard);
+    }
+    if board_size > MAX_BOARD_SIZE {
+        return Err(ShapeError::BoardTooLarge { board_size });
+    }
+    if win_len < MIN_WIN_LEN {
+        return Err(ShapeError::WinLenTooShort { win_len });
+    }
+    if win_len > MAX_WIN_LEN {
+        return Err(ShapeError::WinLenTooLong { win_len });
+    }
+    if win_len > board_size {
+        return Err(ShapeError::WinLenExceedsBoard {
+            win_len,
+            board_size,
+        });
+    }
+    let max_windows = checked::add_usize(
+        usize::from(u16::MAX),
+        1_usize,
+        "geometry::validate_shape::max_windows",
+    );
+    if ThreatIndex::window_count(board_size, win_len) > max_windows {
+        return Err(ShapeError::TooManyWindows {
+            board_size,
+            win_len,
+        });
+    }
+    Ok(())
+}
+pub(super) const fn validate_hasher(
+    hasher: &ZobristHasher,
+    board_size: usize,
+) -> Result<(), ShapeError> {
+    if hasher.board_size == board_size {
+        Ok(())
+    } else {
+        Err(ShapeError::HasherMismatch {
+            hasher_size: hasher.board_size,
+            board_size,
+        })
+    }
+}
+pub(super) fn validate_board(
+    board: &[u8],
+    board_size: usize,
+    win_len: usize,
+    player: u8,
+) -> Result<(), ShapeError> {
+    validate_shape(board_size, win_len)?;
+    let expected = checked::mul_usize(board_size, board_size, "geometry::validate_board");
+    if board.len() != expected {
+        return Err(ShapeError::BoardLengthMismatch {
+            actual: board.len(),
+            expected,
+        });
+    }
+    if player != 1 && player != 2 {
+        return Err(ShapeError::InvalidPlayer { player });
+    }
+    if let Some(&cell) = board.iter().find(|&&cell| cell > 2) {
+        return Err(ShapeError::InvalidCell { cell });
+    }
+    Ok(())
+}
+impl GameState {
+    #[inline]
+    pub fn validate_shape(board_size: usize, win_len: usize) -> Result<(), ShapeError> {
+        validate_shape(board_size, win_len)
+    }
+    #[inline]
+    pub fn try_new(
+        initial_board: Vec<u8>,
+        board_size: usize,
+        hasher: Arc<ZobristHasher>,
+        current_player: u8,
+        win_len: usize,
+        evaluation: EvaluationWeights,
+    ) -> Result<Self, ShapeError> {
+        validate_board(&initial_board, board_size, win_len, current_player)?;
+        validate_hasher(&hasher, board_size)?;
+        Ok(Self::new(
+            initial_board,
+            board_size,
+            hasher,
+            current_player,
+            win_len,
+            evaluation,
+        ))
+    }
+}
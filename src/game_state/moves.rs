@@ -1,11 +1,36 @@
 use super::{
     Bitboard, BitboardWorkspace, Coord, GomokuEvaluator, GomokuMoveCache, GomokuPosition,
-    GomokuRules, MoveApplyTiming, MoveGenBuffers, MoveGenTiming, record_duration_add_ns,
-    record_duration_ns,
+    GomokuRules, MoveApplyTiming, MoveGenBuffers, MoveGenTiming, forbidden,
 };
-use crate::{checked, utils::duration_to_ns};
+use crate::{
+    checked,
+    utils::timing::{
+        ScopedTimer, add_elapsed_ns, elapsed_ns, record_duration_add_ns, record_duration_ns,
+    },
+};
+use core::ops::ControlFlow;
 use smallvec::SmallVec;
 use std::time::Instant;
+const THREAT_RADIUS: usize = 2;
+const THREAT_STONE_MARGIN: usize = 3;
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LegalMoveSet {
+    Empty,
+    Winning,
+    Forced,
+    Scored,
+    Prioritized,
+}
+fn scored_move_at(scored_moves: &[(Coord, f32)], index: u32) -> (Coord, f32) {
+    let Some(&scored_move) = usize::try_from(index)
+        .ok()
+        .and_then(|position| scored_moves.get(position))
+    else {
+        eprintln!("GomokuRules::scored_move_at 评分缓冲区索引越界: {index}");
+        panic!("GomokuRules::scored_move_at 评分缓冲区索引越界");
+    };
+    scored_move
+}
 fn bit_word_mut<'bits>(bits: &'bits mut [u64], word_index: usize, context: &str) -> &'bits mut u64 {
     let Some(word) = bits.get_mut(word_index) else {
         eprintln!("{context} 候选位图索引越界: {word_index}");
@@ -21,41 +46,6 @@ impl GomokuRules {
         moves.clear();
         moves.extend(scored_moves.iter().map(|scored_move| scored_move.0));
     }
-    fn score_and_sort_moves_in_place(
-        evaluator: &GomokuEvaluator,
-        position: &GomokuPosition,
-        player: u8,
-        moves: &mut Vec<Coord>,
-        scored_moves: &mut Vec<(Coord, f32)>,
-    ) {
-        if moves.len() <= 1 {
-            return;
-        }
-        evaluator.score_moves_into(position, player, moves, scored_moves);
-        Self::sort_scored_moves(scored_moves);
-        Self::fill_moves_from_scored(moves, scored_moves);
-    }
-    fn score_and_sort_moves_in_place_with_proximity(
-        evaluator: &GomokuEvaluator,
-        position: &GomokuPosition,
-        player: u8,
-        moves: &mut Vec<Coord>,
-        proximity_scores: &[f32],
-        scored_moves: &mut Vec<(Coord, f32)>,
-    ) {
-        if moves.len() <= 1 {
-            return;
-        }
-        evaluator.score_moves_into_with_proximity(
-            position,
-            player,
-            moves,
-            proximity_scores,
-            scored_moves,
-        );
-        Self::sort_scored_moves(scored_moves);
-        Self::fill_moves_from_scored(moves, scored_moves);
-    }
     pub(crate) fn rebuild_candidate_moves(
         position: &GomokuPosition,
         cache: &mut GomokuMoveCache,
@@ -65,14 +55,20 @@ impl GomokuRules {
         position.bitboard.occupied_into(occupied);
         if Bitboard::is_all_zeros(occupied) {
             cache.candidate_moves.fill(0);
-            let center = checked::div_usize(
-                position.board_size,
-                2_usize,
-                "GomokuRules::rebuild_candidate_moves",
+            let (center_row, center_column) = position.region.map_or_else(
+                || {
+                    let center = checked::div_usize(
+                        position.board_size,
+                        2_usize,
+                        "GomokuRules::rebuild_candidate_moves",
+                    );
+                    (center, center)
+                },
+                |bounds| bounds.center(),
             );
             position
                 .bitboard
-                .set_in(&mut cache.candidate_moves, center, center);
+                .set_in(&mut cache.candidate_moves, center_row, center_column);
             return;
         }
         position.bitboard.neighbors_into(
@@ -86,15 +82,86 @@ impl GomokuRules {
             cache.candidate_moves.resize(neighbors.len(), 0);
         }
         cache.candidate_moves.copy_from_slice(neighbors);
+        let threshold = Self::threat_stone_threshold(position.win_len);
+        let mut centers: SmallVec<[Coord; 8]> = SmallVec::new();
+        for player in [1_u8, 2_u8] {
+            for player_count in threshold..position.win_len {
+                for window_index in
+                    position
+                        .threat_index
+                        .get_pattern_windows(player, player_count, 0)
+                {
+                    centers.extend(
+                        position
+                            .threat_index
+                            .window_coords(window_index)
+                            .iter()
+                            .copied()
+                            .filter(|&(row, column)| position.cell(row, column) == player),
+                    );
+                }
+            }
+        }
+        Self::insert_threat_rings(
+            position,
+            &mut cache.candidate_moves,
+            &mut centers,
+            &mut SmallVec::new(),
+        );
     }
     pub fn check_win(position: &GomokuPosition, player: u8) -> bool {
-        position
+        let mut windows = position
             .threat_index
-            .get_pattern_windows(player, position.win_len, 0)
-            .next()
-            .is_some()
+            .get_pattern_windows(player, position.win_len, 0);
+        if position.forbidden_player != Some(player) {
+            return windows.next().is_some();
+        }
+        windows.any(|window_index| {
+            forbidden::is_exact_line(
+                &position.board,
+                position.threat_index.window_coords(window_index),
+                player,
+            )
+        })
+    }
+    fn drop_forbidden_moves(position: &GomokuPosition, player: u8, moves: &mut Vec<Coord>) {
+        if position.forbidden_player == Some(player) {
+            moves.retain(|&mov| {
+                !forbidden::is_forbidden(&position.board, position.win_len, mov, player)
+            });
+        }
     }
-    fn collect_forcing_moves_bits<I>(
+    pub(super) fn collect_completion_bits(
+        position: &GomokuPosition,
+        player: u8,
+        win_minus_one: usize,
+        bits: &mut Vec<u64>,
+    ) {
+        let windows = position
+            .threat_index
+            .get_pattern_windows(player, win_minus_one, 0);
+        if position.forbidden_player != Some(player) {
+            Self::collect_forcing_moves_bits(position, windows, bits);
+            return;
+        }
+        Self::collect_forcing_moves_bits(
+            position,
+            windows
+                .filter(|&window_index| Self::completes_exact_five(position, window_index, player)),
+            bits,
+        );
+    }
+    fn completes_exact_five(position: &GomokuPosition, window_index: usize, player: u8) -> bool {
+        let threat_index = &position.threat_index;
+        forbidden::is_exact_line(
+            &position.board,
+            threat_index.window_coords(window_index),
+            player,
+        ) && threat_index
+            .window_empty_cells(window_index)
+            .all(|mov| !forbidden::is_forbidden(&position.board, position.win_len, mov, player))
+    }
+    pub(super) fn collect_forcing_moves_bits<I>(
         position: &GomokuPosition,
         window_indices: I,
         bits: &mut Vec<u64>,
@@ -107,14 +174,45 @@ impl GomokuRules {
         }
         bits.fill(0);
         for window_idx in window_indices {
-            let window = position.threat_index.window(window_idx);
-            for &(row_index, column_index) in &window.coords {
-                if position.cell(row_index, column_index) == 0 {
-                    position.bitboard.set_in(bits, row_index, column_index);
-                }
+            for (row_index, column_index) in position.threat_index.window_empty_cells(window_idx) {
+                position.bitboard.set_in(bits, row_index, column_index);
             }
         }
     }
+    pub(crate) fn forcing_move_counts(
+        position: &GomokuPosition,
+        cache: &GomokuMoveCache,
+        player: u8,
+    ) -> (usize, usize) {
+        let opponent = checked::opponent_player(player, "GomokuRules::forcing_move_counts");
+        let win_minus_one = checked::sub_usize(
+            position.win_len,
+            1_usize,
+            "GomokuRules::forcing_move_counts::win_minus_one",
+        );
+        let win_minus_two = win_minus_one.saturating_sub(1).max(1);
+        let threat_index = &position.threat_index;
+        let mut forcing_bits = Vec::new();
+        Self::collect_forcing_moves_bits(
+            position,
+            threat_index
+                .get_pattern_windows(player, win_minus_one, 0)
+                .chain(threat_index.get_pattern_windows(opponent, win_minus_one, 0))
+                .chain(threat_index.get_pattern_windows(player, win_minus_two, 0))
+                .chain(threat_index.get_pattern_windows(opponent, win_minus_two, 0)),
+            &mut forcing_bits,
+        );
+        let forcing_count = position.bitboard.iter_bits(&forcing_bits).count();
+        let mut empty_bits = Vec::new();
+        position.empty_in_region_into(&mut empty_bits);
+        let candidate_bits: Vec<u64> = empty_bits
+            .iter()
+            .zip(cache.candidate_moves.iter())
+            .map(|(empty_word, candidate_word)| empty_word & candidate_word)
+            .collect();
+        let candidate_count = position.bitboard.iter_bits(&candidate_bits).count();
+        (forcing_count, candidate_count)
+    }
     pub fn make_move(
         position: &mut GomokuPosition,
         cache: &mut GomokuMoveCache,
@@ -146,65 +244,19 @@ impl GomokuRules {
                 .bitboard
                 .clear_in(&mut cache.candidate_moves, row_index, column_index);
         });
-        let mut row_start = 0_usize;
-        let mut row_end = 0_usize;
-        let mut column_start = 0_usize;
-        let mut column_end = 0_usize;
+        let mut bounds = (0_usize, 0_usize, 0_usize, 0_usize);
         record_duration_ns(&mut timing.candidate_neighbor_ns, || {
-            let last_board_index = checked::sub_usize(
-                position.board_size,
-                1_usize,
-                "GomokuRules::make_move_with_timing::last_board_index",
-            );
-            row_start = if row_index == 0 {
-                0
-            } else {
-                checked::sub_usize(
-                    row_index,
-                    1_usize,
-                    "GomokuRules::make_move_with_timing::row_start",
-                )
-            };
-            row_end = checked::add_usize(
-                row_index,
-                1_usize,
-                "GomokuRules::make_move_with_timing::row_end_candidate",
-            )
-            .min(last_board_index);
-            column_start = if column_index == 0 {
-                0
-            } else {
-                checked::sub_usize(
-                    column_index,
-                    1_usize,
-                    "GomokuRules::make_move_with_timing::column_start",
-                )
-            };
-            column_end = checked::add_usize(
-                column_index,
-                1_usize,
-                "GomokuRules::make_move_with_timing::column_end_candidate",
-            )
-            .min(last_board_index);
+            bounds = Self::neighborhood_bounds(position.board_size, mov, 1_usize);
         });
+        let (row_start, row_end, column_start, column_end) = bounds;
         let mut candidate_insert_ns = 0_u64;
         let mut candidate_newly_added_ns = 0_u64;
         for neighbor_row_index in row_start..=row_end {
             for neighbor_column_index in column_start..=column_end {
-                if position.cell(neighbor_row_index, neighbor_column_index) != 0 {
-                    continue;
-                }
                 let coord = (neighbor_row_index, neighbor_column_index);
-                let (word_idx, mask) = position.bitboard.coord_to_bit(coord.0, coord.1);
                 let mut inserted = false;
                 record_duration_add_ns(&mut candidate_insert_ns, || {
-                    let candidate_word = bit_word_mut(
-                        &mut cache.candidate_moves,
-                        word_idx,
-                        "GomokuRules::make_move_with_timing::candidate_insert",
-                    );
-                    inserted = *candidate_word & mask == 0;
-                    *candidate_word |= mask;
+                    inserted = Self::insert_candidate(position, &mut cache.candidate_moves, coord);
                 });
                 if inserted {
                     record_duration_add_ns(&mut candidate_newly_added_ns, || {
@@ -213,6 +265,15 @@ impl GomokuRules {
                 }
             }
         }
+        record_duration_add_ns(&mut candidate_insert_ns, || {
+            Self::widen_around_threats(
+                position,
+                &mut cache.candidate_moves,
+                mov,
+                player,
+                &mut newly_added_candidates,
+            );
+        });
         timing.candidate_insert_ns = candidate_insert_ns;
         timing.candidate_newly_added_ns = candidate_newly_added_ns;
         record_duration_ns(&mut timing.candidate_history_ns, || {
@@ -228,6 +289,109 @@ impl GomokuRules {
         });
         timing
     }
+    fn neighborhood_bounds(
+        board_size: usize,
+        (row_index, column_index): Coord,
+        radius: usize,
+    ) -> (usize, usize, usize, usize) {
+        let last_board_index = checked::sub_usize(
+            board_size,
+            1_usize,
+            "GomokuRules::neighborhood_bounds::last_board_index",
+        );
+        (
+            row_index.saturating_sub(radius),
+            checked::add_usize(
+                row_index,
+                radius,
+                "GomokuRules::neighborhood_bounds::row_end",
+            )
+            .min(last_board_index),
+            column_index.saturating_sub(radius),
+            checked::add_usize(
+                column_index,
+                radius,
+                "GomokuRules::neighborhood_bounds::column_end",
+            )
+            .min(last_board_index),
+        )
+    }
+    fn insert_candidate(
+        position: &GomokuPosition,
+        candidate_moves: &mut [u64],
+        (row_index, column_index): Coord,
+    ) -> bool {
+        if position.cell(row_index, column_index) != 0 {
+            return false;
+        }
+        let (word_idx, mask) = position.bitboard.coord_to_bit(row_index, column_index);
+        let candidate_word = bit_word_mut(
+            candidate_moves,
+            word_idx,
+            "GomokuRules::insert_candidate::candidate_word",
+        );
+        let inserted = *candidate_word & mask == 0;
+        *candidate_word |= mask;
+        inserted
+    }
+    fn threat_stone_threshold(win_len: usize) -> usize {
+        win_len.saturating_sub(THREAT_STONE_MARGIN).max(1_usize)
+    }
+    fn widen_around_threats(
+        position: &GomokuPosition,
+        candidate_moves: &mut [u64],
+        mov: Coord,
+        player: u8,
+        newly_added: &mut SmallVec<[Coord; 8]>,
+    ) {
+        let threshold = Self::threat_stone_threshold(position.win_len);
+        let mut centers: SmallVec<[Coord; 8]> = SmallVec::new();
+        for window_index in position.threat_index.window_indices_for_point(mov.0, mov.1) {
+            let window = position.threat_index.window(window_index);
+            let (player_count, opponent_count) = if player == 1 {
+                (window.p1_count, window.p2_count)
+            } else {
+                (window.p2_count, window.p1_count)
+            };
+            if opponent_count > 0 || player_count < threshold {
+                continue;
+            }
+            if player_count == threshold {
+                centers.extend(
+                    position
+                        .threat_index
+                        .window_coords(window_index)
+                        .iter()
+                        .copied()
+                        .filter(|&(row, column)| position.cell(row, column) == player),
+                );
+            } else {
+                centers.push(mov);
+            }
+        }
+        Self::insert_threat_rings(position, candidate_moves, &mut centers, newly_added);
+    }
+    fn insert_threat_rings(
+        position: &GomokuPosition,
+        candidate_moves: &mut [u64],
+        centers: &mut SmallVec<[Coord; 8]>,
+        newly_added: &mut SmallVec<[Coord; 8]>,
+    ) {
+        centers.sort_unstable();
+        centers.dedup();
+        for &center in centers.iter() {
+            let (row_start, row_end, column_start, column_end) =
+                Self::neighborhood_bounds(position.board_size, center, THREAT_RADIUS);
+            for row_index in row_start..=row_end {
+                for column_index in column_start..=column_end {
+                    let coord = (row_index, column_index);
+                    if Self::insert_candidate(position, candidate_moves, coord) {
+                        newly_added.push(coord);
+                    }
+                }
+            }
+        }
+    }
     pub fn undo_move(
         position: &mut GomokuPosition,
         cache: &mut GomokuMoveCache,
@@ -277,97 +441,181 @@ impl GomokuRules {
         workspace: &mut BitboardWorkspace,
         buffers: &mut MoveGenBuffers<'_>,
     ) -> MoveGenTiming {
+        let mut timing = MoveGenTiming::default();
+        let move_set = Self::collect_legal_moves(position, player, workspace, buffers, &mut timing);
+        if matches!(move_set, LegalMoveSet::Empty | LegalMoveSet::Winning) {
+            return timing;
+        }
+        record_duration_ns(&mut timing.scoring_ns, || {
+            if buffers.out_moves.len() <= 1 {
+                return;
+            }
+            Self::score_collected_moves(evaluator, position, player, buffers);
+            Self::sort_scored_moves(buffers.scored_moves);
+            Self::promote_double_threats(position, player, buffers.scored_moves, |scored| scored.0);
+            Self::fill_moves_from_scored(buffers.out_moves, buffers.scored_moves);
+        });
+        if move_set == LegalMoveSet::Prioritized {
+            let _deferred_timer = ScopedTimer::new(
+                &mut timing.candidate_gen_ns,
+                "GomokuRules::get_legal_moves_into::candidate_collect_deferred_empty",
+            );
+            let [_, _, deferred_bits, ..] = workspace.pads_mut();
+            buffers
+                .out_moves
+                .extend(position.bitboard.iter_bits(deferred_bits));
+        }
+        timing
+    }
+    pub fn for_each_legal_move<F>(
+        position: &GomokuPosition,
+        evaluator: &GomokuEvaluator,
+        player: u8,
+        workspace: &mut BitboardWorkspace,
+        buffers: &mut MoveGenBuffers<'_>,
+        order: &mut Vec<u32>,
+        mut visit: F,
+    ) -> MoveGenTiming
+    where
+        F: FnMut(Coord, Option<f32>) -> ControlFlow<()>,
+    {
+        let mut timing = MoveGenTiming::default();
+        let move_set = Self::collect_legal_moves(position, player, workspace, buffers, &mut timing);
+        order.clear();
+        if !matches!(move_set, LegalMoveSet::Empty | LegalMoveSet::Winning)
+            && buffers.out_moves.len() > 1
+        {
+            record_duration_ns(&mut timing.scoring_ns, || {
+                Self::score_collected_moves(evaluator, position, player, buffers);
+                let scored_moves = &*buffers.scored_moves;
+                order.extend((0..scored_moves.len()).map(|index| {
+                    checked::usize_to_u32(index, "GomokuRules::for_each_legal_move::order")
+                }));
+                order.sort_unstable_by(|&left, &right| {
+                    scored_move_at(scored_moves, left)
+                        .1
+                        .total_cmp(&scored_move_at(scored_moves, right).1)
+                        .reverse()
+                });
+                Self::promote_double_threats(position, player, order, |&index| {
+                    scored_move_at(scored_moves, index).0
+                });
+            });
+        }
+        let flow = if order.is_empty() {
+            buffers
+                .out_moves
+                .iter()
+                .try_for_each(|&mov| visit(mov, None))
+        } else {
+            order.iter().try_for_each(|&index| {
+                let (mov, score) = scored_move_at(buffers.scored_moves, index);
+                visit(mov, Some(score))
+            })
+        };
+        if flow.is_continue() && move_set == LegalMoveSet::Prioritized {
+            let [_, _, deferred_bits, ..] = workspace.pads_mut();
+            let _deferred_flow = position
+                .bitboard
+                .iter_bits(deferred_bits)
+                .try_for_each(|mov| visit(mov, None));
+        }
+        timing
+    }
+    fn score_collected_moves(
+        evaluator: &GomokuEvaluator,
+        position: &GomokuPosition,
+        player: u8,
+        buffers: &mut MoveGenBuffers<'_>,
+    ) {
+        if evaluator.uses_bitboard_ordering()
+            && let Some(planes) = buffers.proximity_planes.as_deref_mut()
+        {
+            evaluator.score_moves_into_with_planes(
+                position,
+                player,
+                buffers.out_moves,
+                planes,
+                buffers.scored_moves,
+            );
+        } else if let Some(existing_proximity_scores) = buffers.proximity_scores {
+            evaluator.score_moves_into_with_proximity(
+                position,
+                player,
+                buffers.out_moves,
+                existing_proximity_scores,
+                buffers.scored_moves,
+            );
+        } else {
+            evaluator.score_moves_into(position, player, buffers.out_moves, buffers.scored_moves);
+        }
+    }
+    fn collect_legal_moves(
+        position: &GomokuPosition,
+        player: u8,
+        workspace: &mut BitboardWorkspace,
+        buffers: &mut MoveGenBuffers<'_>,
+        timing: &mut MoveGenTiming,
+    ) -> LegalMoveSet {
         let forcing_bits = &mut *buffers.forcing_bits;
-        let scored_moves = &mut *buffers.scored_moves;
         let out_moves = &mut *buffers.out_moves;
         let candidate_moves = buffers.candidate_moves;
-        let proximity_scores = buffers.proximity_scores;
-        let mut timing = MoveGenTiming::default();
-        let opponent = checked::opponent_player(player, "GomokuRules::get_legal_moves_into");
+        let opponent = checked::opponent_player(player, "GomokuRules::collect_legal_moves");
         let start_candidate = Instant::now();
         let win_minus_one = checked::sub_usize(
             position.win_len,
             1_usize,
-            "GomokuRules::get_legal_moves_into::win_minus_one",
-        );
-        Self::collect_forcing_moves_bits(
-            position,
-            position
-                .threat_index
-                .get_pattern_windows(player, win_minus_one, 0),
-            forcing_bits,
+            "GomokuRules::collect_legal_moves::win_minus_one",
         );
+        Self::collect_completion_bits(position, player, win_minus_one, forcing_bits);
         let found_my_win = !Bitboard::is_all_zeros(forcing_bits);
-        timing.candidate_gen_ns = duration_to_ns(start_candidate.elapsed());
+        timing.candidate_gen_ns = elapsed_ns(start_candidate);
         if found_my_win {
             let start_collect = Instant::now();
             out_moves.clear();
             out_moves.extend(position.bitboard.iter_bits(forcing_bits));
-            timing.candidate_gen_ns = checked::add_u64(
-                timing.candidate_gen_ns,
-                duration_to_ns(start_collect.elapsed()),
-                "GomokuRules::get_legal_moves_into::candidate_collect_my_win",
+            Self::drop_forbidden_moves(position, player, out_moves);
+            add_elapsed_ns(
+                &mut timing.candidate_gen_ns,
+                start_collect,
+                "GomokuRules::collect_legal_moves::candidate_collect_my_win",
             );
-            return timing;
+            if !out_moves.is_empty() {
+                return LegalMoveSet::Winning;
+            }
         }
         let start_threat = Instant::now();
-        Self::collect_forcing_moves_bits(
-            position,
-            position
-                .threat_index
-                .get_pattern_windows(opponent, win_minus_one, 0),
-            forcing_bits,
-        );
+        Self::collect_completion_bits(position, opponent, win_minus_one, forcing_bits);
         let found_opponent_threat = !Bitboard::is_all_zeros(forcing_bits);
-        timing.candidate_gen_ns = checked::add_u64(
-            timing.candidate_gen_ns,
-            duration_to_ns(start_threat.elapsed()),
-            "GomokuRules::get_legal_moves_into::candidate_collect_opponent_threat",
+        add_elapsed_ns(
+            &mut timing.candidate_gen_ns,
+            start_threat,
+            "GomokuRules::collect_legal_moves::candidate_collect_opponent_threat",
         );
         if found_opponent_threat {
             let start_collect = Instant::now();
             out_moves.clear();
             out_moves.extend(position.bitboard.iter_bits(forcing_bits));
-            timing.candidate_gen_ns = checked::add_u64(
-                timing.candidate_gen_ns,
-                duration_to_ns(start_collect.elapsed()),
-                "GomokuRules::get_legal_moves_into::candidate_collect_forced_reply",
+            Self::drop_forbidden_moves(position, player, out_moves);
+            add_elapsed_ns(
+                &mut timing.candidate_gen_ns,
+                start_collect,
+                "GomokuRules::collect_legal_moves::candidate_collect_forced_reply",
             );
-            record_duration_ns(&mut timing.scoring_ns, || {
-                if let Some(existing_proximity_scores) = proximity_scores {
-                    Self::score_and_sort_moves_in_place_with_proximity(
-                        evaluator,
-                        position,
-                        player,
-                        out_moves,
-                        existing_proximity_scores,
-                        scored_moves,
-                    );
-                } else {
-                    Self::score_and_sort_moves_in_place(
-                        evaluator,
-                        position,
-                        player,
-                        out_moves,
-                        scored_moves,
-                    );
-                }
-            });
-            return timing;
+            return LegalMoveSet::Forced;
         }
         let start_empty = Instant::now();
         let [empty_bits, candidate_bits, deferred_bits, ..] = workspace.pads_mut();
-        position.bitboard.empty_into(empty_bits);
+        position.empty_in_region_into(empty_bits);
+        out_moves.clear();
         if Bitboard::is_all_zeros(empty_bits) {
-            out_moves.clear();
-            timing.candidate_gen_ns = checked::add_u64(
-                timing.candidate_gen_ns,
-                duration_to_ns(start_empty.elapsed()),
-                "GomokuRules::get_legal_moves_into::candidate_collect_empty_board",
+            add_elapsed_ns(
+                &mut timing.candidate_gen_ns,
+                start_empty,
+                "GomokuRules::collect_legal_moves::candidate_collect_empty_board",
             );
-            return timing;
+            return LegalMoveSet::Empty;
         }
-        out_moves.clear();
         let use_priority_candidates = if let Some(candidate_words) = candidate_moves
             && candidate_words.len() == empty_bits.len()
         {
@@ -394,40 +642,30 @@ impl GomokuRules {
         if !use_priority_candidates {
             out_moves.extend(position.bitboard.iter_bits(empty_bits));
         }
-        timing.candidate_gen_ns = checked::add_u64(
-            timing.candidate_gen_ns,
-            duration_to_ns(start_empty.elapsed()),
-            "GomokuRules::get_legal_moves_into::candidate_collect_all_empty",
-        );
-        record_duration_ns(&mut timing.scoring_ns, || {
-            if let Some(existing_proximity_scores) = proximity_scores {
-                Self::score_and_sort_moves_in_place_with_proximity(
-                    evaluator,
-                    position,
-                    player,
-                    out_moves,
-                    existing_proximity_scores,
-                    scored_moves,
-                );
-            } else {
-                Self::score_and_sort_moves_in_place(
-                    evaluator,
-                    position,
-                    player,
-                    out_moves,
-                    scored_moves,
-                );
+        Self::drop_forbidden_moves(position, player, out_moves);
+        if use_priority_candidates && position.forbidden_player == Some(player) {
+            let forbidden_deferred: SmallVec<[Coord; 8]> = position
+                .bitboard
+                .iter_bits(deferred_bits)
+                .filter(|&mov| {
+                    forbidden::is_forbidden(&position.board, position.win_len, mov, player)
+                })
+                .collect();
+            for (row_index, column_index) in forbidden_deferred {
+                position
+                    .bitboard
+                    .clear_in(deferred_bits, row_index, column_index);
             }
-        });
+        }
+        add_elapsed_ns(
+            &mut timing.candidate_gen_ns,
+            start_empty,
+            "GomokuRules::collect_legal_moves::candidate_collect_all_empty",
+        );
         if use_priority_candidates {
-            let start_deferred = Instant::now();
-            out_moves.extend(position.bitboard.iter_bits(deferred_bits));
-            timing.candidate_gen_ns = checked::add_u64(
-                timing.candidate_gen_ns,
-                duration_to_ns(start_deferred.elapsed()),
-                "GomokuRules::get_legal_moves_into::candidate_collect_deferred_empty",
-            );
+            LegalMoveSet::Prioritized
+        } else {
+            LegalMoveSet::Scored
         }
-        timing
     }
 }
@@ -0,0 +1,92 @@
+use super::{Coord, MAX_BOARD_SIZE};
+use core::fmt;
+use serde::{Deserialize, Serialize};
+const AXIS_BITS: u16 = 8;
+const AXIS_MASK: u16 = 0xFF;
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Move(u16);
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MoveRangeError {
+    pub coord: Coord,
+}
+impl Move {
+    #[inline]
+    pub fn new((row, column): Coord) -> Result<Self, MoveRangeError> {
+        let out_of_range = || MoveRangeError {
+            coord: (row, column),
+        };
+        let row_bits = u8::try_from(row).map_err(|_err| out_of_range())?;
+        let column_bits = u8::try_from(column).map_err(|_err| out_of_range())?;
+        Ok(Self(
+            (u16::from(row_bits) << AXIS_BITS) | u16::from(column_bits),
+        ))
+    }
+    #[inline]
+    #[must_use]
+    pub fn pack(coord: Coord) -> Self {
+        Self::new(coord).unwrap_or_else(|err| {
+            eprintln!("Move::pack {err}");
+            panic!("Move::pack 坐标超出范围");
+        })
+    }
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+    #[inline]
+    #[must_use]
+    pub fn row(self) -> usize {
+        usize::from(self.0 >> AXIS_BITS)
+    }
+    #[inline]
+    #[must_use]
+    pub fn column(self) -> usize {
+        usize::from(self.0 & AXIS_MASK)
+    }
+    #[inline]
+    #[must_use]
+    pub fn coord(self) -> Coord {
+        (self.row(), self.column())
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_on_board(self, board_size: usize) -> bool {
+        self.row() < board_size && self.column() < board_size
+    }
+}
+impl From<Move> for Coord {
+    #[inline]
+    fn from(mov: Move) -> Self {
+        mov.coord()
+    }
+}
+impl TryFrom<Coord> for Move {
+    type Error = MoveRangeError;
+    #[inline]
+    fn try_from(coord: Coord) -> Result<Self, Self::Error> {
+        Self::new(coord)
+    }
+}
+impl fmt::Display for Move {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row(), self.column())
+    }
+}
+impl fmt::Display for MoveRangeError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (row, column) = self.coord;
+        write!(
+            f,
+            "坐标 ({row}, {column}) 超出可编码范围，行列均须小于 {MAX_BOARD_SIZE}"
+        )
+    }
+}
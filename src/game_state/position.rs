@@ -0,0 +1,423 @@
+use super::{
+    Board, CanonicalHash, Coord, GameState, MAX_BOARD_SIZE, PatternCounts, PositionHash,
+    ThreatIndex, ZobristHasher,
+};
+use crate::{checked, config::EvaluationWeights};
+use alloc::sync::Arc;
+use core::fmt;
+const LINE_DIRECTIONS: [((isize, isize), (isize, isize)); 4] = [
+    ((0, 1), (0, -1)),
+    ((1, 0), (-1, 0)),
+    ((1, 1), (-1, -1)),
+    ((1, -1), (-1, 1)),
+];
+#[derive(Clone)]
+pub struct Position {
+    board: Board,
+    board_size: usize,
+    win_len: usize,
+    side_to_move: u8,
+    hash: u64,
+    winner: Option<u8>,
+    hasher: Arc<ZobristHasher>,
+}
+impl Position {
+    #[inline]
+    #[must_use]
+    pub fn new(board_size: usize, win_len: usize) -> Self {
+        Self {
+            board: Board::new(board_size),
+            board_size,
+            win_len,
+            side_to_move: 1,
+            hash: 0,
+            winner: None,
+            hasher: Arc::new(ZobristHasher::new(board_size)),
+        }
+    }
+    #[inline]
+    pub fn from_board(
+        board: Vec<u8>,
+        board_size: usize,
+        win_len: usize,
+        side_to_move: u8,
+    ) -> Result<Self, String> {
+        let hasher = Arc::new(ZobristHasher::new(board_size));
+        Self::with_hasher(board, board_size, win_len, side_to_move, hasher)
+    }
+    #[inline]
+    pub fn with_hasher(
+        cells: Vec<u8>,
+        board_size: usize,
+        win_len: usize,
+        side_to_move: u8,
+        hasher: Arc<ZobristHasher>,
+    ) -> Result<Self, String> {
+        super::geometry::validate_board(&cells, board_size, win_len, side_to_move)
+            .and_then(|()| super::geometry::validate_hasher(&hasher, board_size))
+            .map_err(|err| err.to_string())?;
+        let board = Board::from_cells(board_size, cells).map_err(|err| err.to_string())?;
+        Ok(Self::assemble(
+            board,
+            board_size,
+            win_len,
+            side_to_move,
+            hasher,
+        ))
+    }
+    #[inline]
+    pub fn parse(text: &str, win_len: usize) -> Result<Self, String> {
+        let mut rows: Vec<&str> = text
+            .split(|character: char| character == '/' || character.is_whitespace())
+            .filter(|row| !row.is_empty())
+            .collect();
+        let Some(first_row) = rows.first() else {
+            return Err(String::from("局面文本为空"));
+        };
+        let board_size = parse_row(first_row)?.len();
+        let side_token = if rows.len() == board_size.saturating_add(1) {
+            rows.pop()
+        } else {
+            None
+        };
+        if rows.len() != board_size {
+            return Err(format!(
+                "局面行数与列数不一致: {} 行，每行 {board_size} 列",
+                rows.len()
+            ));
+        }
+        let mut board = Vec::with_capacity(checked::mul_usize(
+            board_size,
+            board_size,
+            "Position::parse",
+        ));
+        for (row_index, row) in rows.iter().enumerate() {
+            let cells = parse_row(row)?;
+            if cells.len() != board_size {
+                return Err(format!("局面第 {row_index} 行长度不匹配"));
+            }
+            board.extend(cells);
+        }
+        let side_to_move = match side_token {
+            Some(token) => parse_side(token)?,
+            None => infer_side_to_move(&board),
+        };
+        Self::from_board(board, board_size, win_len, side_to_move)
+    }
+    #[inline]
+    #[must_use]
+    pub fn board(&self) -> &[u8] {
+        self.board.as_slice()
+    }
+    #[inline]
+    #[must_use]
+    pub const fn grid(&self) -> &Board {
+        &self.board
+    }
+    #[inline]
+    #[must_use]
+    pub const fn board_size(&self) -> usize {
+        self.board_size
+    }
+    #[inline]
+    #[must_use]
+    pub const fn win_len(&self) -> usize {
+        self.win_len
+    }
+    #[inline]
+    #[must_use]
+    pub const fn side_to_move(&self) -> u8 {
+        self.side_to_move
+    }
+    #[inline]
+    #[must_use]
+    pub const fn hash(&self) -> PositionHash {
+        PositionHash::from_bits(self.hash)
+    }
+    pub(crate) fn canonical_hash(&self) -> CanonicalHash {
+        CanonicalHash::from_bits(self.hasher.canonical_from_symmetric(
+            self.hasher.symmetric_board_hashes(&self.board),
+            self.side_to_move == 2,
+        ))
+    }
+    #[inline]
+    #[must_use]
+    pub const fn winner(&self) -> Option<u8> {
+        self.winner
+    }
+    #[inline]
+    #[must_use]
+    pub fn cell(&self, (row, column): Coord) -> Option<u8> {
+        self.board.get((row, column))
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.board.is_full()
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_legal(&self, mov: Coord) -> bool {
+        self.check_move(mov).is_ok()
+    }
+    #[inline]
+    pub fn check_move(&self, (row, column): Coord) -> Result<(), String> {
+        if let Some(winner) = self.winner {
+            return Err(format!("当前局面已分胜负，胜方为 {winner}"));
+        }
+        match self.cell((row, column)) {
+            None => Err(format!("落子坐标越界: ({row}, {column})")),
+            Some(0) => Ok(()),
+            Some(_) => Err(format!("该位置已有棋子: ({row}, {column})")),
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn legal_moves(&self) -> Vec<Coord> {
+        if self.winner.is_some() {
+            return Vec::new();
+        }
+        self.board
+            .iter_cells()
+            .filter(|&(_, cell)| cell == 0)
+            .map(|(coord, _)| coord)
+            .collect()
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_winning_move(&self, mov: Coord, player: u8) -> bool {
+        LINE_DIRECTIONS.iter().any(|&(forward, backward)| {
+            let run = checked::add_usize(
+                self.run_length(mov, forward, player),
+                self.run_length(mov, backward, player),
+                "Position::is_winning_move",
+            );
+            run.saturating_add(1) >= self.win_len
+        })
+    }
+    #[inline]
+    #[must_use]
+    pub fn has_won(&self, player: u8) -> bool {
+        self.board
+            .iter_cells()
+            .filter(|&(_, cell)| cell == player)
+            .any(|(coord, _)| {
+                LINE_DIRECTIONS.iter().any(|&(forward, backward)| {
+                    self.run_length(coord, backward, player) == 0
+                        && self.run_length(coord, forward, player).saturating_add(1) >= self.win_len
+                })
+            })
+    }
+    #[inline]
+    #[must_use]
+    pub fn pattern_counts(&self, player: u8) -> PatternCounts {
+        let mut threat_index = ThreatIndex::new(self.board_size, self.win_len);
+        threat_index.initialize_from_board(&self.board);
+        threat_index.pattern_counts(&self.board, player)
+    }
+    #[inline]
+    pub fn play(&mut self, mov: Coord) -> Result<(), String> {
+        self.check_move(mov)?;
+        let player = self.side_to_move;
+        self.set_cell(mov, player);
+        if self.is_winning_move(mov, player) {
+            self.winner = Some(player);
+        }
+        self.side_to_move = checked::opponent_player(player, "Position::play");
+        Ok(())
+    }
+    #[inline]
+    pub fn undo(&mut self, (row, column): Coord) -> Result<(), String> {
+        let player = checked::opponent_player(self.side_to_move, "Position::undo");
+        match self.cell((row, column)) {
+            None => return Err(format!("悔棋坐标越界: ({row}, {column})")),
+            Some(cell) if cell != player => {
+                return Err(format!(
+                    "悔棋位置不是上一手行棋方的棋子: ({row}, {column}) 为 {cell}"
+                ));
+            }
+            Some(_) => {}
+        }
+        self.set_cell((row, column), 0);
+        self.side_to_move = player;
+        self.winner = self.find_winner();
+        Ok(())
+    }
+    #[inline]
+    #[must_use]
+    pub fn to_game_state(&self, evaluation: EvaluationWeights) -> GameState {
+        GameState::new(
+            self.board.clone().into_cells(),
+            self.board_size,
+            Arc::clone(&self.hasher),
+            self.side_to_move,
+            self.win_len,
+            evaluation,
+        )
+    }
+    fn assemble(
+        board: Board,
+        board_size: usize,
+        win_len: usize,
+        side_to_move: u8,
+        hasher: Arc<ZobristHasher>,
+    ) -> Self {
+        let mut position = Self {
+            board,
+            board_size,
+            win_len,
+            side_to_move,
+            hash: 0,
+            winner: None,
+            hasher,
+        };
+        position.hash = position.compute_hash();
+        position.winner = position.find_winner();
+        position
+    }
+    fn step(&self, (row, column): Coord, (row_step, column_step): (isize, isize)) -> Option<Coord> {
+        let next_row = row.checked_add_signed(row_step)?;
+        let next_column = column.checked_add_signed(column_step)?;
+        (next_row < self.board_size && next_column < self.board_size)
+            .then_some((next_row, next_column))
+    }
+    fn run_length(&self, start: Coord, direction: (isize, isize), player: u8) -> usize {
+        let mut length = 0_usize;
+        let mut current = start;
+        while let Some(next) = self.step(current, direction)
+            && self.cell(next) == Some(player)
+        {
+            length = checked::add_usize(length, 1_usize, "Position::run_length");
+            current = next;
+        }
+        length
+    }
+    fn find_winner(&self) -> Option<u8> {
+        [1_u8, 2_u8]
+            .into_iter()
+            .find(|&player| self.has_won(player))
+    }
+    fn compute_hash(&self) -> u64 {
+        let stones_hash = self.board.iter_cells().filter(|&(_, cell)| cell != 0).fold(
+            0_u64,
+            |hash, ((row, column), cell)| {
+                hash ^ self.hasher.get_hash(row, column, usize::from(cell))
+            },
+        );
+        if self.side_to_move == 2 {
+            stones_hash ^ self.hasher.side_to_move_hash
+        } else {
+            stones_hash
+        }
+    }
+    fn set_cell(&mut self, (row, column): Coord, value: u8) {
+        let Some(previous) = self.board.set((row, column), value) else {
+            eprintln!("Position::set_cell 棋盘索引越界: ({row}, {column})");
+            panic!("Position::set_cell 棋盘索引越界");
+        };
+        if previous != 0 {
+            self.hash ^= self.hasher.get_hash(row, column, usize::from(previous));
+        }
+        if value != 0 {
+            self.hash ^= self.hasher.get_hash(row, column, usize::from(value));
+        }
+        self.hash ^= self.hasher.side_to_move_hash;
+    }
+}
+impl fmt::Display for Position {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row_index in 0..self.board.rows() {
+            if row_index > 0 {
+                f.write_str("/")?;
+            }
+            let mut empty_run = 0_usize;
+            for cell in self.board.row(row_index) {
+                if cell == 0 {
+                    empty_run = checked::add_usize(empty_run, 1_usize, "Position::fmt");
+                    continue;
+                }
+                if empty_run > 0 {
+                    write!(f, "{empty_run}")?;
+                    empty_run = 0;
+                }
+                f.write_str(cell_symbol(cell))?;
+            }
+            if empty_run > 0 {
+                write!(f, "{empty_run}")?;
+            }
+        }
+        write!(f, " {}", cell_symbol(self.side_to_move))
+    }
+}
+impl GameState {
+    #[inline]
+    #[must_use]
+    pub fn to_position(&self, side_to_move: u8) -> Position {
+        Position::assemble(
+            self.position.board.clone(),
+            self.position.board_size,
+            self.position.win_len,
+            side_to_move,
+            Arc::clone(&self.position.hasher),
+        )
+    }
+}
+const fn cell_symbol(cell: u8) -> &'static str {
+    match cell {
+        1 => "X",
+        2 => "O",
+        _ => ".",
+    }
+}
+fn parse_row(row: &str) -> Result<Vec<u8>, String> {
+    let mut cells = Vec::new();
+    let mut empty_run = 0_usize;
+    for character in row.chars() {
+        if let Some(digit) = character.to_digit(10) {
+            empty_run = empty_run
+                .checked_mul(10)
+                .and_then(|run| run.checked_add(usize::try_from(digit).ok()?))
+                .filter(|&run| run <= MAX_BOARD_SIZE)
+                .ok_or_else(|| format!("局面空格数超过 {MAX_BOARD_SIZE}"))?;
+            continue;
+        }
+        cells.resize(cells.len().saturating_add(empty_run), 0);
+        empty_run = 0;
+        cells.push(parse_cell(character)?);
+        if cells.len() > MAX_BOARD_SIZE {
+            return Err(format!("局面行长度超过 {MAX_BOARD_SIZE}"));
+        }
+    }
+    cells.resize(cells.len().saturating_add(empty_run), 0);
+    if cells.len() > MAX_BOARD_SIZE {
+        return Err(format!("局面行长度超过 {MAX_BOARD_SIZE}"));
+    }
+    Ok(cells)
+}
+fn parse_cell(character: char) -> Result<u8, String> {
+    match character {
+        '.' => Ok(0),
+        'X' | 'x' => Ok(1),
+        'O' | 'o' => Ok(2),
+        _ => Err(format!("局面包含非法字符 '{character}'")),
+    }
+}
+fn parse_side(token: &str) -> Result<u8, String> {
+    match token {
+        "X" | "x" | "B" | "b" => Ok(1),
+        "O" | "o" | "W" | "w" => Ok(2),
+        _ => Err(format!("行棋方标记无效: '{token}'")),
+    }
+}
+fn infer_side_to_move(board: &[u8]) -> u8 {
+    let (first, second) =
+        board
+            .iter()
+            .fold((0_usize, 0_usize), |(first, second), &cell| match cell {
+                1 => (first.saturating_add(1), second),
+                2 => (first, second.saturating_add(1)),
+                _ => (first, second),
+            });
+    if first > second { 2 } else { 1 }
+}
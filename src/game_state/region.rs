@@ -0,0 +1,89 @@
+use super::Coord;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct BoardRegion {
+    pub top: usize,
+    pub left: usize,
+    pub bottom: usize,
+    pub right: usize,
+}
+impl BoardRegion {
+    #[inline]
+    #[must_use]
+    pub const fn new((top, left): Coord, (bottom, right): Coord) -> Self {
+        Self {
+            top: if top < bottom { top } else { bottom },
+            left: if left < right { left } else { right },
+            bottom: if top < bottom { bottom } else { top },
+            right: if left < right { right } else { left },
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn contains(&self, (row, column): Coord) -> bool {
+        row >= self.top && row <= self.bottom && column >= self.left && column <= self.right
+    }
+    #[inline]
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.bottom.saturating_sub(self.top).saturating_add(1)
+    }
+    #[inline]
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.right.saturating_sub(self.left).saturating_add(1)
+    }
+    #[inline]
+    #[must_use]
+    pub const fn center(&self) -> Coord {
+        (
+            self.top.saturating_add(self.height() >> 1_u32),
+            self.left.saturating_add(self.width() >> 1_u32),
+        )
+    }
+    #[inline]
+    pub fn validate(&self, board_size: usize, win_len: usize) -> Result<(), String> {
+        if self.bottom >= board_size || self.right >= board_size {
+            return Err(format!(
+                "搜索区域 {self} 超出 {board_size}x{board_size} 棋盘"
+            ));
+        }
+        if self.height().max(self.width()) < win_len {
+            return Err(format!("搜索区域 {self} 容纳不下 {win_len} 子连珠"));
+        }
+        Ok(())
+    }
+    #[inline]
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (first, second) = text
+            .split_once('-')
+            .ok_or_else(|| format!("无法解析搜索区域 {text}，应为 行,列-行,列"))?;
+        Ok(Self::new(parse_coord(first)?, parse_coord(second)?))
+    }
+}
+fn parse_coord(text: &str) -> Result<Coord, String> {
+    let (row, column) = text
+        .split_once(',')
+        .ok_or_else(|| format!("无法解析坐标 {text}，应为 行,列"))?;
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|err| format!("无法解析坐标 {text}: {err}"))
+    };
+    Ok((parse(row)?, parse(column)?))
+}
+impl fmt::Display for BoardRegion {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({top}, {left})-({bottom}, {right})",
+            top = self.top,
+            left = self.left,
+            bottom = self.bottom,
+            right = self.right
+        )
+    }
+}
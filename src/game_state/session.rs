@@ -0,0 +1,170 @@
+use super::{
+    CanonicalHash, Coord, GameOutcome, GameState, GomokuRules, TerminalCondition, terminal,
+};
+use crate::checked;
+use hashbrown::HashMap;
+pub type PositionKey = (CanonicalHash, u8);
+pub struct GameSession {
+    game_state: GameState,
+    side_to_move: u8,
+    moves: Vec<(Coord, u8)>,
+    hash_history: Vec<PositionKey>,
+    visit_counts: HashMap<PositionKey, u32>,
+    points: Vec<u32>,
+    scores: [u32; 2],
+}
+impl GameSession {
+    #[inline]
+    #[must_use]
+    pub fn new(game_state: GameState, side_to_move: u8) -> Self {
+        let initial_key = (game_state.position.get_canonical_hash(), side_to_move);
+        let mut visit_counts = HashMap::new();
+        visit_counts.insert(initial_key, 1_u32);
+        Self {
+            game_state,
+            side_to_move,
+            moves: Vec::new(),
+            hash_history: vec![initial_key],
+            visit_counts,
+            points: Vec::new(),
+            scores: [0, 0],
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn game_state(&self) -> &GameState {
+        &self.game_state
+    }
+    #[inline]
+    #[must_use]
+    pub fn board(&self) -> &[u8] {
+        self.game_state.position.board.as_slice()
+    }
+    #[inline]
+    #[must_use]
+    pub const fn side_to_move(&self) -> u8 {
+        self.side_to_move
+    }
+    #[inline]
+    #[must_use]
+    pub fn moves(&self) -> &[(Coord, u8)] {
+        &self.moves
+    }
+    #[inline]
+    #[must_use]
+    pub fn hash_history(&self) -> &[PositionKey] {
+        &self.hash_history
+    }
+    #[inline]
+    pub fn visited_positions(&self) -> impl Iterator<Item = PositionKey> + '_ {
+        self.visit_counts.keys().copied()
+    }
+    #[inline]
+    #[must_use]
+    pub fn current_key(&self) -> PositionKey {
+        let Some(&key) = self.hash_history.last() else {
+            eprintln!("GameSession::current_key 哈希历史为空");
+            panic!("GameSession::current_key 哈希历史为空");
+        };
+        key
+    }
+    #[inline]
+    #[must_use]
+    pub fn visit_count(&self, key: PositionKey) -> u32 {
+        self.visit_counts.get(&key).copied().unwrap_or(0)
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_repetition(&self) -> bool {
+        let Some((current, earlier)) = self.hash_history.split_last() else {
+            return false;
+        };
+        earlier.contains(current)
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_revisit(&self) -> bool {
+        self.visit_count(self.current_key()) > 1
+    }
+    #[inline]
+    #[must_use]
+    pub const fn scores(&self) -> [u32; 2] {
+        self.scores
+    }
+    #[inline]
+    #[must_use]
+    pub fn last_move_points(&self) -> u32 {
+        self.points.last().copied().unwrap_or(0)
+    }
+    #[inline]
+    #[must_use]
+    pub fn outcome(&self, rule: &dyn TerminalCondition) -> GameOutcome {
+        rule.outcome(self.scores, self.game_state.position.board.is_full())
+    }
+    #[inline]
+    #[must_use]
+    pub fn fixed_horizon_move(&self) -> Option<Coord> {
+        terminal::fixed_horizon_move(
+            &self.game_state.position.board,
+            self.game_state.position.win_len,
+            self.side_to_move,
+        )
+    }
+    const fn score_slot(&mut self, player: u8) -> &mut u32 {
+        if player == 1 {
+            &mut self.scores[0]
+        } else {
+            &mut self.scores[1]
+        }
+    }
+    #[inline]
+    pub fn play(&mut self, mov: Coord, player: u8) {
+        GomokuRules::make_move(
+            &mut self.game_state.position,
+            &mut self.game_state.move_cache,
+            mov,
+            player,
+        );
+        self.moves.push((mov, player));
+        let gained = terminal::completed_lines(
+            &self.game_state.position.board,
+            self.game_state.position.win_len,
+            mov,
+            player,
+        );
+        self.points.push(gained);
+        let score = self.score_slot(player);
+        *score = checked::add_u32(*score, gained, "GameSession::play::score");
+        self.side_to_move = checked::opponent_player(player, "GameSession::play");
+        let key = (
+            self.game_state.position.get_canonical_hash(),
+            self.side_to_move,
+        );
+        self.hash_history.push(key);
+        let count = self.visit_counts.entry(key).or_insert(0);
+        *count = checked::add_u32(*count, 1_u32, "GameSession::play::visit_count");
+    }
+    #[inline]
+    pub fn undo(&mut self) -> Option<(Coord, u8)> {
+        let (mov, player) = self.moves.pop()?;
+        if let Some(key) = self.hash_history.pop()
+            && let Some(count) = self.visit_counts.get_mut(&key)
+        {
+            *count = checked::sub_u32(*count, 1_u32, "GameSession::undo::visit_count");
+            if *count == 0 {
+                self.visit_counts.remove(&key);
+            }
+        }
+        let gained = self.points.pop().unwrap_or(0);
+        let score = self.score_slot(player);
+        *score = checked::sub_u32(*score, gained, "GameSession::undo::score");
+        GomokuRules::undo_move(
+            &mut self.game_state.position,
+            &mut self.game_state.move_cache,
+            mov,
+            player,
+        );
+        self.side_to_move = player;
+        Some((mov, player))
+    }
+}
@@ -1,8 +1,8 @@
 use super::{
-    Bitboard, BitboardWorkspace, GameState, GomokuEvaluator, GomokuMoveCache, GomokuPosition,
-    GomokuRules, ThreatIndex,
+    Bitboard, BitboardWorkspace, Board, BoardRegion, CanonicalHash, Coord, GameState,
+    GomokuEvaluator, GomokuMoveCache, GomokuPosition, GomokuRules, PositionHash, Rule, ThreatIndex,
 };
-use crate::{checked, config::EvaluationWeights, utils::board_index};
+use crate::{config::EvaluationWeights, symmetry, utils::board_index};
 use alloc::sync::Arc;
 use rand::rngs::StdRng;
 const ZOBRIST_HASH_MASK: u64 = 0x7FFF_FFFF_FFFF_FFFF;
@@ -64,6 +64,32 @@ impl ZobristHasher {
         };
         hash
     }
+    pub(crate) fn symmetric_board_hashes(&self, board: &Board) -> [u64; 8] {
+        let mut hashes = [0_u64; 8];
+        for ((row_index, column_index), piece) in board.iter_cells() {
+            if piece == 0 {
+                continue;
+            }
+            let symmetric_coords = self.get_symmetric_coords(row_index, column_index);
+            for (hash, (symmetric_row, symmetric_column)) in hashes.iter_mut().zip(symmetric_coords)
+            {
+                *hash ^= self.get_hash(symmetric_row, symmetric_column, usize::from(piece));
+            }
+        }
+        hashes
+    }
+    pub(crate) fn canonical_from_symmetric(
+        &self,
+        mut hashes: [u64; 8],
+        side_to_move_is_player2: bool,
+    ) -> u64 {
+        if side_to_move_is_player2 {
+            for hash in &mut hashes {
+                *hash ^= self.side_to_move_hash;
+            }
+        }
+        hashes.iter().copied().min().unwrap_or(0)
+    }
     #[inline]
     #[must_use]
     pub(crate) fn get_symmetric_coords(
@@ -71,31 +97,7 @@ impl ZobristHasher {
         row_index: usize,
         column_index: usize,
     ) -> [(usize, usize); 8] {
-        let last_index = checked::sub_usize(
-            self.board_size,
-            1_usize,
-            "ZobristHasher::get_symmetric_coords::last_index",
-        );
-        let rotated_row = checked::sub_usize(
-            last_index,
-            row_index,
-            "ZobristHasher::get_symmetric_coords::rotated_row",
-        );
-        let rotated_column = checked::sub_usize(
-            last_index,
-            column_index,
-            "ZobristHasher::get_symmetric_coords::rotated_column",
-        );
-        [
-            (row_index, column_index),
-            (column_index, rotated_row),
-            (rotated_row, rotated_column),
-            (rotated_column, row_index),
-            (row_index, rotated_column),
-            (column_index, row_index),
-            (rotated_row, column_index),
-            (rotated_column, rotated_row),
-        ]
+        symmetry::symmetric_coords(self.board_size, (row_index, column_index))
     }
 }
 impl GameState {
@@ -122,6 +124,16 @@ impl GameState {
             move_cache,
         }
     }
+    #[inline]
+    pub fn set_region(&mut self, region: Option<BoardRegion>) {
+        self.position.set_region(region);
+        let mut workspace = BitboardWorkspace::new(self.position.bitboard.num_words());
+        GomokuRules::rebuild_candidate_moves(&self.position, &mut self.move_cache, &mut workspace);
+    }
+    #[inline]
+    pub fn set_rule(&mut self, rule: Rule, side_to_move: u8) {
+        self.position.forbidden_player = rule.forbidden_player(&self.position.board, side_to_move);
+    }
 }
 impl GomokuPosition {
     #[inline]
@@ -133,18 +145,14 @@ impl GomokuPosition {
         current_player: u8,
         win_len: usize,
     ) -> Self {
-        let board = initial_board;
-        let expected_len =
-            checked::mul_usize(board_size, board_size, "GomokuPosition::new::expected_len");
-        if board.len() != expected_len {
-            eprintln!(
-                "GomokuPosition::new 棋盘长度不匹配: 实际 {}, 期望 {}",
-                board.len(),
-                expected_len
-            );
-            panic!("GomokuPosition::new 棋盘长度不匹配");
-        }
-        let bitboard = Bitboard::from_board(&board, board_size);
+        let board = match Board::from_cells(board_size, initial_board) {
+            Ok(board) => board,
+            Err(err) => {
+                eprintln!("GomokuPosition::new {err}");
+                panic!("GomokuPosition::new 棋盘长度不匹配");
+            }
+        };
+        let bitboard = Bitboard::from_board(board.as_slice(), board_size);
         let mut position = Self {
             board,
             bitboard,
@@ -153,29 +161,56 @@ impl GomokuPosition {
             hasher,
             hash: 0_u64,
             threat_index: ThreatIndex::new(board_size, win_len),
+            region: None,
+            region_bits: Vec::new(),
+            forbidden_player: None,
         };
         position.rebuild_hashes(current_player);
         position
     }
+    pub(crate) fn set_region(&mut self, region: Option<BoardRegion>) {
+        self.region = region;
+        self.region_bits.clear();
+        if let Some(bounds) = region {
+            self.region_bits.resize(self.bitboard.num_words(), 0);
+            for row_index in bounds.top..=bounds.bottom {
+                for column_index in bounds.left..=bounds.right {
+                    self.bitboard
+                        .set_in(&mut self.region_bits, row_index, column_index);
+                }
+            }
+        }
+        self.threat_index.set_region(region.as_ref(), &self.board);
+    }
+    #[inline]
+    #[must_use]
+    pub fn in_region(&self, coord: Coord) -> bool {
+        self.region.is_none_or(|bounds| bounds.contains(coord))
+    }
+    pub(crate) fn empty_in_region_into(&self, target: &mut Vec<u64>) {
+        self.bitboard.empty_into(target);
+        if self.region.is_some() {
+            for (word, region_word) in target.iter_mut().zip(&self.region_bits) {
+                *word &= *region_word;
+            }
+        }
+    }
     #[inline]
     pub(crate) fn board_index(&self, row_index: usize, column_index: usize) -> usize {
         board_index(self.board_size, row_index, column_index)
     }
     pub(crate) fn cell(&self, row_index: usize, column_index: usize) -> u8 {
-        let board_index = self.board_index(row_index, column_index);
-        let Some(&cell) = self.board.get(board_index) else {
+        let Some(cell) = self.board.get((row_index, column_index)) else {
             eprintln!("GomokuPosition::cell 棋盘索引越界: ({row_index}, {column_index})");
             panic!("GomokuPosition::cell 棋盘索引越界");
         };
         cell
     }
     pub(crate) fn set_cell(&mut self, row_index: usize, column_index: usize, player: u8) {
-        let board_index = self.board_index(row_index, column_index);
-        let Some(cell) = self.board.get_mut(board_index) else {
+        if self.board.set((row_index, column_index), player).is_none() {
             eprintln!("GomokuPosition::set_cell 棋盘索引越界: ({row_index}, {column_index})");
             panic!("GomokuPosition::set_cell 棋盘索引越界");
-        };
-        *cell = player;
+        }
     }
     pub(crate) fn rebuild_hashes(&mut self, player: u8) {
         self.hash = 0;
@@ -195,31 +230,11 @@ impl GomokuPosition {
     }
     #[inline]
     #[must_use]
-    pub(crate) fn get_canonical_hash(&self) -> u64 {
-        let mut hashes = [0_u64; 8];
-        for row_index in 0..self.board_size {
-            for column_index in 0..self.board_size {
-                let piece = self.cell(row_index, column_index);
-                if piece != 0 {
-                    let symmetric_coords =
-                        self.hasher.get_symmetric_coords(row_index, column_index);
-                    for (hash_index, symmetric_coord) in symmetric_coords.into_iter().enumerate() {
-                        let (symmetric_row, symmetric_column) = symmetric_coord;
-                        let Some(hash) = hashes.get_mut(hash_index) else {
-                            eprintln!(
-                                "GomokuPosition::get_canonical_hash 哈希数组索引越界: {hash_index}"
-                            );
-                            panic!("GomokuPosition::get_canonical_hash 哈希数组索引越界");
-                        };
-                        *hash ^= self.hasher.get_hash(
-                            symmetric_row,
-                            symmetric_column,
-                            usize::from(piece),
-                        );
-                    }
-                }
-            }
+    pub(crate) fn get_canonical_hash(&self) -> CanonicalHash {
+        if self.region.is_some() {
+            return CanonicalHash::from_bits(self.hash);
         }
+        let hashes = self.hasher.symmetric_board_hashes(&self.board);
         let base_hash = hashes[0];
         let side_hash = self.hasher.side_to_move_hash;
         let side_to_move_is_player2 = if self.hash == base_hash {
@@ -227,36 +242,17 @@ impl GomokuPosition {
         } else if self.hash == (base_hash ^ side_hash) {
             true
         } else {
-            let mut count1 = 0_usize;
-            let mut count2 = 0_usize;
-            for &cell in &self.board {
-                if cell == 1 {
-                    count1 = checked::add_usize(
-                        count1,
-                        1_usize,
-                        "GomokuPosition::get_canonical_hash::count1",
-                    );
-                } else if cell == 2 {
-                    count2 = checked::add_usize(
-                        count2,
-                        1_usize,
-                        "GomokuPosition::get_canonical_hash::count2",
-                    );
-                }
-            }
-            count1 > count2
+            self.bitboard.side_to_move() == 2
         };
-        if side_to_move_is_player2 {
-            for hash in &mut hashes {
-                *hash ^= side_hash;
-            }
-        }
-        hashes.iter().copied().min().unwrap_or(0)
+        CanonicalHash::from_bits(
+            self.hasher
+                .canonical_from_symmetric(hashes, side_to_move_is_player2),
+        )
     }
     #[inline]
     #[must_use]
-    pub const fn get_hash(&self) -> u64 {
-        self.hash
+    pub const fn get_hash(&self) -> PositionHash {
+        PositionHash::from_bits(self.hash)
     }
 }
 impl GomokuMoveCache {
@@ -0,0 +1,117 @@
+use super::{Board, BoardChange, Coord, GameState, GomokuRules};
+use crate::checked;
+impl GameState {
+    #[inline]
+    #[must_use]
+    pub fn board(&self) -> &[u8] {
+        self.position.board.as_slice()
+    }
+    #[inline]
+    #[must_use]
+    pub const fn board_size(&self) -> usize {
+        self.position.board_size
+    }
+    #[inline]
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<BoardChange> {
+        self.diff_board(other.position.board.as_slice())
+    }
+    #[inline]
+    #[must_use]
+    pub fn diff_board(&self, other_board: &[u8]) -> Vec<BoardChange> {
+        self.ensure_same_board_len(other_board);
+        self.position
+            .board
+            .iter_cells()
+            .zip(other_board)
+            .filter(|&((_, before), &after)| before != after)
+            .map(|((coord, before), &after)| (coord, before, after))
+            .collect()
+    }
+    #[inline]
+    pub fn apply_diff(
+        &mut self,
+        diff: &[BoardChange],
+        side_to_move: u8,
+    ) -> Result<Vec<(Coord, u8)>, String> {
+        let moves = self.validate_diff(diff, side_to_move)?;
+        for &(mov, player) in &moves {
+            GomokuRules::make_move(&mut self.position, &mut self.move_cache, mov, player);
+        }
+        Ok(moves)
+    }
+    fn ensure_same_board_len(&self, other_board: &[u8]) {
+        if other_board.len() != self.position.board.as_slice().len() {
+            eprintln!(
+                "GameState::diff_board 棋盘长度不匹配: 当前 {}，目标 {}",
+                self.position.board.as_slice().len(),
+                other_board.len()
+            );
+            panic!("GameState::diff_board 棋盘长度不匹配");
+        }
+    }
+    fn validate_diff(
+        &self,
+        diff: &[BoardChange],
+        side_to_move: u8,
+    ) -> Result<Vec<(Coord, u8)>, String> {
+        if side_to_move != 1 && side_to_move != 2 {
+            return Err(format!("行棋方无效: {side_to_move}"));
+        }
+        let opponent = checked::opponent_player(side_to_move, "GameState::validate_diff");
+        let mut seen = Board::new(self.position.board_size);
+        let mut mover_stones = Vec::new();
+        let mut reply_stones = Vec::new();
+        for &((row_index, column_index), before, after) in diff {
+            match seen.set((row_index, column_index), 1) {
+                None => return Err(format!("差异坐标越界: ({row_index}, {column_index})")),
+                Some(0) => {}
+                Some(_) => return Err(format!("差异中坐标重复: ({row_index}, {column_index})")),
+            }
+            let current = self.position.cell(row_index, column_index);
+            if current != before {
+                return Err(format!(
+                    "差异与当前棋盘不一致: ({row_index}, {column_index}) 当前为 {current}，差异记录为 {before}"
+                ));
+            }
+            if before != 0 {
+                return Err(format!(
+                    "棋子不能被移除或替换: ({row_index}, {column_index}) 从 {before} 变为 {after}"
+                ));
+            }
+            if after == side_to_move {
+                mover_stones.push((row_index, column_index));
+            } else if after == opponent {
+                reply_stones.push((row_index, column_index));
+            } else {
+                return Err(format!(
+                    "差异包含非法棋子: ({row_index}, {column_index}) 变为 {after}"
+                ));
+            }
+        }
+        if mover_stones.len() != reply_stones.len()
+            && mover_stones.len() != reply_stones.len().saturating_add(1)
+        {
+            return Err(format!(
+                "双方新增棋子数不符合交替落子: 行棋方 {}，对方 {}",
+                mover_stones.len(),
+                reply_stones.len()
+            ));
+        }
+        if !diff.is_empty()
+            && (GomokuRules::check_win(&self.position, 1)
+                || GomokuRules::check_win(&self.position, 2))
+        {
+            return Err(String::from("当前局面已分胜负，不能继续落子"));
+        }
+        let mut moves = Vec::with_capacity(diff.len());
+        let mut replies = reply_stones.into_iter();
+        for mov in mover_stones {
+            moves.push((mov, side_to_move));
+            if let Some(reply) = replies.next() {
+                moves.push((reply, opponent));
+            }
+        }
+        Ok(moves)
+    }
+}
@@ -0,0 +1,162 @@
+use super::{Board, Coord, TerminalCondition};
+use crate::checked;
+use core::cmp::{Ordering, Reverse};
+pub(super) const LINE_DIRECTIONS: [((isize, isize), (isize, isize)); 4] = [
+    ((0, 1), (0, -1)),
+    ((1, 0), (-1, 0)),
+    ((1, 1), (-1, -1)),
+    ((1, -1), (-1, 1)),
+];
+type CandidateKey = (u32, u32, usize, Reverse<usize>);
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameOutcome {
+    Ongoing,
+    Won(u8),
+    Draw,
+}
+#[derive(Clone, Copy, Debug)]
+pub struct FirstLine;
+impl TerminalCondition for FirstLine {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "freestyle"
+    }
+    #[inline]
+    fn describe(&self, win_len: usize) -> String {
+        format!("{win_len}子连珠")
+    }
+    #[inline]
+    fn continues_after_line(&self) -> bool {
+        false
+    }
+    #[inline]
+    fn outcome(&self, scores: [u32; 2], board_full: bool) -> GameOutcome {
+        match scores {
+            [first, _] if first > 0 => GameOutcome::Won(1),
+            [_, second] if second > 0 => GameOutcome::Won(2),
+            _ if board_full => GameOutcome::Draw,
+            _ => GameOutcome::Ongoing,
+        }
+    }
+    #[inline]
+    fn solver_support(&self, _scores: [u32; 2]) -> Result<(), String> {
+        Ok(())
+    }
+}
+#[derive(Clone, Copy, Debug)]
+pub struct PointTarget {
+    pub target: u32,
+}
+impl TerminalCondition for PointTarget {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "points"
+    }
+    #[inline]
+    fn describe(&self, win_len: usize) -> String {
+        format!(
+            "每成 {win_len} 连得 1 分，先得 {target} 分者获胜",
+            target = self.target
+        )
+    }
+    #[inline]
+    fn continues_after_line(&self) -> bool {
+        true
+    }
+    #[inline]
+    fn outcome(&self, scores: [u32; 2], board_full: bool) -> GameOutcome {
+        let [first, second] = scores;
+        if first >= self.target {
+            return GameOutcome::Won(1);
+        }
+        if second >= self.target {
+            return GameOutcome::Won(2);
+        }
+        if !board_full {
+            return GameOutcome::Ongoing;
+        }
+        match first.cmp(&second) {
+            Ordering::Greater => GameOutcome::Won(1),
+            Ordering::Less => GameOutcome::Won(2),
+            Ordering::Equal => GameOutcome::Draw,
+        }
+    }
+    #[inline]
+    fn solver_support(&self, scores: [u32; 2]) -> Result<(), String> {
+        if scores == [0, 0] {
+            return Ok(());
+        }
+        Err(format!(
+            "积分规则（先得 {target} 分）下盘面已有成线，证明数搜索只能证明首个成线，改用固定视界（单步）贪心选点。",
+            target = self.target
+        ))
+    }
+}
+pub(super) fn step(
+    board: &Board,
+    (row, column): Coord,
+    (row_delta, column_delta): (isize, isize),
+) -> Option<Coord> {
+    let next_row = row.checked_add_signed(row_delta)?;
+    let next_column = column.checked_add_signed(column_delta)?;
+    (next_row < board.rows() && next_column < board.columns()).then_some((next_row, next_column))
+}
+fn run_length(board: &Board, start: Coord, direction: (isize, isize), player: u8) -> usize {
+    let mut length = 0_usize;
+    let mut current = start;
+    while let Some(next) = step(board, current, direction)
+        && board.get(next) == Some(player)
+    {
+        length = checked::add_usize(length, 1_usize, "terminal::run_length");
+        current = next;
+    }
+    length
+}
+pub(super) fn completed_lines(board: &Board, win_len: usize, mov: Coord, player: u8) -> u32 {
+    let completed = LINE_DIRECTIONS
+        .iter()
+        .filter(|&&(forward, backward)| {
+            let ahead = run_length(board, mov, forward, player);
+            let behind = run_length(board, mov, backward, player);
+            ahead < win_len
+                && behind < win_len
+                && checked::add_usize(ahead, behind, "terminal::completed_lines").saturating_add(1)
+                    >= win_len
+        })
+        .count();
+    checked::usize_to_u32(completed, "terminal::completed_lines")
+}
+pub(super) fn fixed_horizon_move(board: &Board, win_len: usize, player: u8) -> Option<Coord> {
+    let opponent = checked::opponent_player(player, "terminal::fixed_horizon_move");
+    let center_row = checked::div_usize(board.rows(), 2_usize, "terminal::fixed_horizon_move");
+    let center_column =
+        checked::div_usize(board.columns(), 2_usize, "terminal::fixed_horizon_move");
+    let mut scratch = board.clone();
+    let mut best: Option<(CandidateKey, Coord)> = None;
+    for (mov, cell) in board.iter_cells() {
+        if cell != 0 {
+            continue;
+        }
+        scratch.set(mov, player);
+        let gained = completed_lines(&scratch, win_len, mov, player);
+        scratch.set(mov, opponent);
+        let blocked = completed_lines(&scratch, win_len, mov, opponent);
+        scratch.set(mov, 0);
+        let neighbours = LINE_DIRECTIONS
+            .iter()
+            .flat_map(|&directions| <[(isize, isize); 2]>::from(directions))
+            .filter_map(|direction| step(board, mov, direction))
+            .filter(|&neighbour| board.get(neighbour).is_some_and(|stone| stone != 0))
+            .count();
+        let distance = checked::add_usize(
+            mov.0.abs_diff(center_row),
+            mov.1.abs_diff(center_column),
+            "terminal::fixed_horizon_move::distance",
+        );
+        let key = (gained, blocked, neighbours, Reverse(distance));
+        if best.is_none_or(|(best_key, _)| key > best_key) {
+            best = Some((key, mov));
+        }
+    }
+    best.map(|(_, mov)| mov)
+}
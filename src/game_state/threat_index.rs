@@ -1,50 +1,88 @@
+use super::{Board, BoardRegion, MAX_WIN_LEN};
 use crate::{checked, utils::board_index};
-use smallvec::SmallVec;
+use alloc::sync::Arc;
 mod buckets;
+mod layout;
 use buckets::PatternBuckets;
+use layout::WindowLayout;
 #[derive(Clone)]
 pub struct Window {
-    pub coords: Vec<(usize, usize)>,
     pub p1_count: usize,
     pub p2_count: usize,
-    pub empty_count: usize,
+    pub empty_mask: u64,
 }
 impl Window {
-    pub const fn new(coords: Vec<(usize, usize)>) -> Self {
-        let empty_count = coords.len();
+    pub fn new(win_len: usize) -> Self {
         Self {
-            coords,
             p1_count: 0,
             p2_count: 0,
-            empty_count,
+            empty_mask: Self::full_mask(win_len),
+        }
+    }
+    fn full_mask(win_len: usize) -> u64 {
+        if win_len >= MAX_WIN_LEN {
+            return u64::MAX;
+        }
+        checked::sub_u64(
+            checked::shl_u64(1_u64, win_len, "Window::full_mask::shift"),
+            1_u64,
+            "Window::full_mask::mask",
+        )
+    }
+    pub(crate) const fn counts(&self, player: u8) -> (usize, usize) {
+        if player == 1 {
+            (self.p1_count, self.p2_count)
+        } else {
+            (self.p2_count, self.p1_count)
         }
     }
 }
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct PatternCounts {
+    pub fours: usize,
+    pub broken_fours: usize,
+    pub open_threes: usize,
+    pub blocked_threes: usize,
+    pub twos: usize,
+}
 #[derive(Clone)]
 pub struct ThreatIndex {
-    board_size: usize,
-    win_len: usize,
-    point_to_windows_map: Vec<SmallVec<[u16; 4]>>,
+    layout: Arc<WindowLayout>,
     all_windows: Vec<Window>,
     pattern_buckets: PatternBuckets,
+    region_windows: Option<Arc<[bool]>>,
 }
 impl ThreatIndex {
     #[inline]
     #[must_use]
     pub fn new(board_size: usize, win_len: usize) -> Self {
-        let total_windows = Self::window_count(board_size, win_len);
-        let point_count =
-            checked::mul_usize(board_size, board_size, "ThreatIndex::new::point_count");
-        let mut threat_index = Self {
-            board_size,
-            win_len,
-            point_to_windows_map: vec![SmallVec::new(); point_count],
-            all_windows: Vec::with_capacity(total_windows),
-            pattern_buckets: PatternBuckets::empty(),
-        };
-        threat_index.enumerate_windows();
-        threat_index.pattern_buckets = PatternBuckets::new(win_len, threat_index.all_windows.len());
-        threat_index
+        let layout = WindowLayout::shared(board_size, win_len);
+        let window_count = layout.window_coords.len();
+        Self {
+            layout,
+            all_windows: vec![Window::new(win_len); window_count],
+            pattern_buckets: PatternBuckets::new(win_len, window_count),
+            region_windows: None,
+        }
+    }
+    #[inline]
+    pub fn set_region(&mut self, region: Option<&BoardRegion>, board: &Board) {
+        self.region_windows = region.map(|bounds| {
+            self.layout
+                .window_coords
+                .iter()
+                .map(|coords| coords.iter().all(|&coord| bounds.contains(coord)))
+                .collect()
+        });
+        self.initialize_from_board(board);
+    }
+    fn in_region(&self, window_index: usize) -> bool {
+        self.region_windows.as_ref().is_none_or(|windows| {
+            windows.get(window_index).copied().unwrap_or_else(|| {
+                eprintln!("ThreatIndex::in_region 窗口索引越界: {window_index}");
+                panic!("ThreatIndex::in_region 窗口索引越界");
+            })
+        })
     }
     pub(crate) fn window(&self, window_index: usize) -> &Window {
         let Some(window) = self.all_windows.get(window_index) else {
@@ -53,6 +91,38 @@ impl ThreatIndex {
         };
         window
     }
+    pub(crate) fn window_coords(&self, window_index: usize) -> &[(usize, usize)] {
+        let Some(coords) = self.layout.window_coords.get(window_index) else {
+            eprintln!("ThreatIndex::window_coords 窗口索引越界: {window_index}");
+            panic!("ThreatIndex::window_coords 窗口索引越界");
+        };
+        coords
+    }
+    pub(crate) fn window_empty_cells(
+        &self,
+        window_index: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let coords = self.window_coords(window_index);
+        let mut mask = self.window(window_index).empty_mask;
+        core::iter::from_fn(move || {
+            if mask == 0 {
+                return None;
+            }
+            let offset = match usize::try_from(mask.trailing_zeros()) {
+                Ok(converted) => converted,
+                Err(err) => {
+                    eprintln!("ThreatIndex::window_empty_cells 位索引转换失败: {err}");
+                    panic!("ThreatIndex::window_empty_cells 位索引转换失败");
+                }
+            };
+            mask &= checked::sub_u64(mask, 1_u64, "ThreatIndex::window_empty_cells::clear_bit");
+            let Some(&coord) = coords.get(offset) else {
+                eprintln!("ThreatIndex::window_empty_cells 空位偏移越界: {offset}");
+                panic!("ThreatIndex::window_empty_cells 空位偏移越界");
+            };
+            Some(coord)
+        })
+    }
     fn window_mut(&mut self, window_index: usize) -> &mut Window {
         let Some(window) = self.all_windows.get_mut(window_index) else {
             eprintln!("ThreatIndex::window_mut 窗口索引越界: {window_index}");
@@ -64,134 +134,28 @@ impl ThreatIndex {
         &self,
         row_index: usize,
         column_index: usize,
-    ) -> &SmallVec<[u16; 4]> {
-        let point_index = board_index(self.board_size, row_index, column_index);
-        let Some(window_indices) = self.point_to_windows_map.get(point_index) else {
+    ) -> impl Iterator<Item = usize> + '_ {
+        let point_index = board_index(self.layout.board_size, row_index, column_index);
+        let Some(window_indices) = self.layout.point_to_windows_map.get(point_index) else {
             eprintln!(
                 "ThreatIndex::window_indices_for_point 点索引越界: ({row_index}, {column_index})"
             );
             panic!("ThreatIndex::window_indices_for_point 点索引越界");
         };
         window_indices
-    }
-    fn enumerate_windows(&mut self) {
-        if self.board_size < self.win_len {
-            return;
-        }
-        let start_limit = checked::sub_usize(
-            self.board_size,
-            self.win_len,
-            "ThreatIndex::enumerate_windows::start_limit",
-        );
-        let descending_column_start = checked::sub_usize(
-            self.win_len,
-            1_usize,
-            "ThreatIndex::enumerate_windows::descending_column_start",
-        );
-        self.add_direction_windows(
-            0..self.board_size,
-            0..=start_limit,
-            &|row_index, column_index, offset| {
-                (
-                    row_index,
-                    checked::add_usize(
-                        column_index,
-                        offset,
-                        "ThreatIndex::enumerate_windows::horizontal_column",
-                    ),
-                )
-            },
-        );
-        self.add_direction_windows(
-            0..=start_limit,
-            0..self.board_size,
-            &|row_index, column_index, offset| {
-                (
-                    checked::add_usize(
-                        row_index,
-                        offset,
-                        "ThreatIndex::enumerate_windows::vertical_row",
-                    ),
-                    column_index,
-                )
-            },
-        );
-        self.add_direction_windows(
-            0..=start_limit,
-            0..=start_limit,
-            &|row_index, column_index, offset| {
-                (
-                    checked::add_usize(
-                        row_index,
-                        offset,
-                        "ThreatIndex::enumerate_windows::diag_down_row",
-                    ),
-                    checked::add_usize(
-                        column_index,
-                        offset,
-                        "ThreatIndex::enumerate_windows::diag_down_column",
-                    ),
-                )
-            },
-        );
-        self.add_direction_windows(
-            0..=start_limit,
-            descending_column_start..self.board_size,
-            &|row_index, column_index, offset| {
-                (
-                    checked::add_usize(
-                        row_index,
-                        offset,
-                        "ThreatIndex::enumerate_windows::diag_up_row",
-                    ),
-                    checked::sub_usize(
-                        column_index,
-                        offset,
-                        "ThreatIndex::enumerate_windows::diag_up_column",
-                    ),
-                )
-            },
-        );
-    }
-    fn add_direction_windows<RI, CI, F>(&mut self, row_iter: RI, column_iter: CI, coord_fn: &F)
-    where
-        RI: Iterator<Item = usize>,
-        CI: Iterator<Item = usize>,
-        F: Fn(usize, usize, usize) -> (usize, usize),
-    {
-        let column_indices: Vec<usize> = column_iter.collect();
-        for row_index in row_iter {
-            for &column_index in &column_indices {
-                let coords: Vec<(usize, usize)> = (0..self.win_len)
-                    .map(|offset| coord_fn(row_index, column_index, offset))
-                    .collect();
-                self.add_window(coords);
-            }
-        }
-    }
-    fn add_window(&mut self, coords: Vec<(usize, usize)>) {
-        let window_index = self.all_windows.len();
-        let window_index_u16 =
-            checked::usize_to_u16(window_index, "ThreatIndex::add_window::window_index");
-        self.all_windows.push(Window::new(coords.clone()));
-        for (row_index, column_index) in coords {
-            let point_index = board_index(self.board_size, row_index, column_index);
-            let Some(window_indices) = self.point_to_windows_map.get_mut(point_index) else {
-                eprintln!("ThreatIndex::add_window 点索引越界: ({row_index}, {column_index})");
-                panic!("ThreatIndex::add_window 点索引越界");
-            };
-            window_indices.push(window_index_u16);
-        }
+            .iter()
+            .map(|&window_index| usize::from(window_index))
+            .filter(|&window_index| self.in_region(window_index))
     }
     #[inline]
-    pub fn initialize_from_board(&mut self, board: &[u8]) {
-        let win_len = self.win_len;
-        for window in &mut self.all_windows {
+    pub fn initialize_from_board(&mut self, board: &Board) {
+        let layout = Arc::clone(&self.layout);
+        for (window, coords) in self.all_windows.iter_mut().zip(&layout.window_coords) {
             window.p1_count = 0;
             window.p2_count = 0;
-            for &(row_index, column_index) in &window.coords {
-                let board_index = board_index(self.board_size, row_index, column_index);
-                let Some(&player) = board.get(board_index) else {
+            window.empty_mask = 0;
+            for (offset, &(row_index, column_index)) in coords.iter().enumerate() {
+                let Some(player) = board.get((row_index, column_index)) else {
                     eprintln!(
                         "ThreatIndex::initialize_from_board 棋盘索引越界: ({row_index}, {column_index})"
                     );
@@ -209,18 +173,14 @@ impl ThreatIndex {
                         1_usize,
                         "ThreatIndex::initialize_from_board::p2_count",
                     );
+                } else {
+                    window.empty_mask |= checked::shl_u64(
+                        1_u64,
+                        offset,
+                        "ThreatIndex::initialize_from_board::empty_mask",
+                    );
                 }
             }
-            let occupied_count = checked::add_usize(
-                window.p1_count,
-                window.p2_count,
-                "ThreatIndex::initialize_from_board::occupied_count",
-            );
-            window.empty_count = checked::sub_usize(
-                win_len,
-                occupied_count,
-                "ThreatIndex::initialize_from_board::empty_count",
-            );
         }
         self.pattern_buckets.reset();
         for window_index in 0..self.all_windows.len() {
@@ -235,7 +195,7 @@ impl ThreatIndex {
     }
     fn update_bucket_add(&mut self, window_index: usize) {
         let window = self.window(window_index);
-        if !Self::is_bucketed(window) {
+        if !Self::is_bucketed(window) || !self.in_region(window_index) {
             return;
         }
         let keys = Self::window_bucket_keys(window);
@@ -246,7 +206,7 @@ impl ThreatIndex {
     }
     fn update_bucket_remove(&mut self, window_index: usize) {
         let window = self.window(window_index);
-        if !Self::is_bucketed(window) {
+        if !Self::is_bucketed(window) || !self.in_region(window_index) {
             return;
         }
         let keys = Self::window_bucket_keys(window);
@@ -257,47 +217,31 @@ impl ThreatIndex {
         !(window.p1_count > 0 && window.p2_count > 0)
     }
     fn apply_window_update(&mut self, mov: (usize, usize), player: u8, is_move: bool) {
-        let point_index = board_index(self.board_size, mov.0, mov.1);
-        let window_count = {
-            let Some(window_indices) = self.point_to_windows_map.get(point_index) else {
-                eprintln!(
-                    "ThreatIndex::apply_window_update 点索引越界: ({}, {})",
-                    mov.0, mov.1
-                );
-                panic!("ThreatIndex::apply_window_update 点索引越界");
-            };
-            window_indices.len()
+        let layout = Arc::clone(&self.layout);
+        let point_index = board_index(layout.board_size, mov.0, mov.1);
+        let (Some(window_indices), Some(window_offsets)) = (
+            layout.point_to_windows_map.get(point_index),
+            layout.point_to_window_offsets.get(point_index),
+        ) else {
+            eprintln!(
+                "ThreatIndex::apply_window_update 点索引越界: ({}, {})",
+                mov.0, mov.1
+            );
+            panic!("ThreatIndex::apply_window_update 点索引越界");
         };
-        for offset in 0..window_count {
-            let window_index_u16 = {
-                let Some(window_indices) = self.point_to_windows_map.get(point_index) else {
-                    eprintln!(
-                        "ThreatIndex::apply_window_update 点索引越界: ({}, {})",
-                        mov.0, mov.1
-                    );
-                    panic!("ThreatIndex::apply_window_update 点索引越界");
-                };
-                let Some(&window_index_u16) = window_indices.get(offset) else {
-                    eprintln!("ThreatIndex::apply_window_update 窗口列表索引越界: {offset}");
-                    panic!("ThreatIndex::apply_window_update 窗口列表索引越界");
-                };
-                window_index_u16
-            };
+        for (&window_index_u16, &offset) in window_indices.iter().zip(window_offsets) {
             let window_index = usize::from(window_index_u16);
+            let cell_bit = checked::shl_u64(
+                1_u64,
+                usize::from(offset),
+                "ThreatIndex::apply_window_update::cell_bit",
+            );
             self.update_bucket_remove(window_index);
             let window = self.window_mut(window_index);
             if is_move {
-                window.empty_count = checked::sub_usize(
-                    window.empty_count,
-                    1_usize,
-                    "ThreatIndex::apply_window_update::empty_count_remove",
-                );
+                window.empty_mask &= !cell_bit;
             } else {
-                window.empty_count = checked::add_usize(
-                    window.empty_count,
-                    1_usize,
-                    "ThreatIndex::apply_window_update::empty_count_restore",
-                );
+                window.empty_mask |= cell_bit;
             }
             match player {
                 1 => {
@@ -356,31 +300,89 @@ impl ThreatIndex {
         self.pattern_buckets
             .iter(player, player_count, opponent_count)
     }
-    fn window_count(board_size: usize, win_len: usize) -> usize {
-        if board_size < win_len {
+    #[inline]
+    #[must_use]
+    pub fn count_patterns(&self, player: u8, player_count: usize, opponent_count: usize) -> usize {
+        let pattern_len = player_count.checked_add(opponent_count);
+        if pattern_len.is_none_or(|len| len > self.layout.win_len) {
             return 0;
         }
-        let span_base =
-            checked::sub_usize(board_size, win_len, "ThreatIndex::window_count::span_base");
-        let span = checked::add_usize(span_base, 1_usize, "ThreatIndex::window_count::span");
-        let line_windows =
-            checked::mul_usize(board_size, span, "ThreatIndex::window_count::line_windows");
-        let diagonal_windows =
-            checked::mul_usize(span, span, "ThreatIndex::window_count::diagonal_windows");
-        let total_line_windows = checked::mul_usize(
-            2_usize,
-            line_windows,
-            "ThreatIndex::window_count::total_line_windows",
-        );
-        let total_diagonal_windows = checked::mul_usize(
-            2_usize,
-            diagonal_windows,
-            "ThreatIndex::window_count::total_diagonal_windows",
+        self.pattern_buckets
+            .len(player, player_count, opponent_count)
+    }
+    #[inline]
+    #[must_use]
+    pub fn pattern_counts(&self, board: &Board, player: u8) -> PatternCounts {
+        let win_len = self.layout.win_len;
+        let stones_short =
+            |missing: usize| win_len.checked_sub(missing).filter(|&stones| stones > 0);
+        let count = |missing: usize| {
+            stones_short(missing).map_or(0, |stones| self.count_patterns(player, stones, 0))
+        };
+        let open_threes = stones_short(2).map_or(0, |stones| {
+            self.get_pattern_windows(player, stones, 0)
+                .filter(|&window_index| self.is_live_three(board, window_index, player))
+                .count()
+        });
+        let broken_fours = stones_short(1).map_or(0, |stones| {
+            self.get_pattern_windows(player, stones, 0)
+                .filter(|&window_index| self.is_broken_four(window_index, player))
+                .count()
+        });
+        PatternCounts {
+            fours: count(1),
+            broken_fours,
+            open_threes,
+            blocked_threes: checked::sub_usize(
+                count(2),
+                open_threes,
+                "ThreatIndex::pattern_counts::blocked_threes",
+            ),
+            twos: count(3),
+        }
+    }
+    pub(crate) fn is_live_three(&self, board: &Board, window_index: usize, player: u8) -> bool {
+        let win_len = self.layout.win_len;
+        self.window(window_index).counts(player)
+            == (
+                checked::sub_usize(win_len, 2, "ThreatIndex::is_live_three"),
+                0,
+            )
+            && self.is_open_window(board, window_index)
+    }
+    pub(crate) fn is_broken_four(&self, window_index: usize, player: u8) -> bool {
+        let win_len = self.layout.win_len;
+        let window = self.window(window_index);
+        let last_bit = checked::shl_u64(
+            1_u64,
+            checked::sub_usize(win_len, 1, "ThreatIndex::is_broken_four::last_offset"),
+            "ThreatIndex::is_broken_four::last_bit",
         );
-        checked::add_usize(
-            total_line_windows,
-            total_diagonal_windows,
-            "ThreatIndex::window_count::total_windows",
-        )
+        window.counts(player)
+            == (
+                checked::sub_usize(win_len, 1, "ThreatIndex::is_broken_four"),
+                0,
+            )
+            && window.empty_mask & (1 | last_bit) == 0
+    }
+    pub(crate) fn window_axis(&self, window_index: usize) -> usize {
+        match *self.window_coords(window_index) {
+            [(first_row, _), (second_row, _), ..] if first_row == second_row => 0,
+            [(_, first_column), (_, second_column), ..] if first_column == second_column => 1,
+            [(_, first_column), (_, second_column), ..] if first_column < second_column => 2,
+            _ => 3,
+        }
+    }
+    pub(crate) fn is_open_window(&self, board: &Board, window_index: usize) -> bool {
+        let Some(flanks) = self.layout.window_flanks.get(window_index) else {
+            eprintln!("ThreatIndex::is_open_window 窗口索引越界: {window_index}");
+            panic!("ThreatIndex::is_open_window 窗口索引越界");
+        };
+        flanks
+            .iter()
+            .all(|flank| flank.is_some_and(|coord| board.is_empty_at(coord)))
+    }
+    pub(super) fn window_count(board_size: usize, win_len: usize) -> usize {
+        WindowLayout::window_count(board_size, win_len)
     }
 }
@@ -3,6 +3,7 @@ const NONE_INDEX: usize = usize::MAX;
 #[derive(Clone, Copy)]
 struct Bucket {
     head: usize,
+    len: usize,
 }
 #[derive(Clone, Copy)]
 struct BucketNode {
@@ -18,14 +19,6 @@ pub(super) struct PatternBuckets {
     nodes: Vec<BucketNode>,
 }
 impl PatternBuckets {
-    pub(super) const fn empty() -> Self {
-        Self {
-            win_len: 0,
-            window_count: 0,
-            buckets: Vec::new(),
-            nodes: Vec::new(),
-        }
-    }
     pub(super) fn new(win_len: usize, window_count: usize) -> Self {
         let win_len_plus_one =
             checked::add_usize(win_len, 1_usize, "PatternBuckets::new::win_len_plus_one");
@@ -41,7 +34,13 @@ impl PatternBuckets {
         );
         let node_count =
             checked::mul_usize(2_usize, window_count, "PatternBuckets::new::node_count");
-        let buckets = vec![Bucket { head: NONE_INDEX }; bucket_count];
+        let buckets = vec![
+            Bucket {
+                head: NONE_INDEX,
+                len: 0,
+            };
+            bucket_count
+        ];
         let nodes = vec![
             BucketNode {
                 prev: NONE_INDEX,
@@ -139,6 +138,7 @@ impl PatternBuckets {
     pub(super) fn reset(&mut self) {
         for bucket in &mut self.buckets {
             bucket.head = NONE_INDEX;
+            bucket.len = 0;
         }
         for node in &mut self.nodes {
             node.prev = NONE_INDEX;
@@ -174,8 +174,9 @@ impl PatternBuckets {
             self.node_mut(next_node_index, "PatternBuckets::insert::next_node")
                 .prev = node_index;
         }
-        self.bucket_mut(bucket_index, "PatternBuckets::insert::bucket_mut")
-            .head = node_index;
+        let bucket = self.bucket_mut(bucket_index, "PatternBuckets::insert::bucket_mut");
+        bucket.head = node_index;
+        bucket.len = checked::add_usize(bucket.len, 1_usize, "PatternBuckets::insert::len");
     }
     pub(super) fn remove(&mut self, player: u8, window_index: usize) {
         let node_index = self.node_index(player, window_index);
@@ -197,11 +198,17 @@ impl PatternBuckets {
             self.node_mut(next_node_index, "PatternBuckets::remove::next_node")
                 .prev = previous_node_index;
         }
+        let bucket = self.bucket_mut(bucket_index, "PatternBuckets::remove::bucket_len");
+        bucket.len = checked::sub_usize(bucket.len, 1_usize, "PatternBuckets::remove::len");
         let node_to_clear = self.node_mut(node_index, "PatternBuckets::remove::node_mut");
         node_to_clear.prev = NONE_INDEX;
         node_to_clear.next = NONE_INDEX;
         node_to_clear.bucket = NONE_INDEX;
     }
+    pub(super) fn len(&self, player: u8, player_count: usize, opponent_count: usize) -> usize {
+        let bucket_index = self.bucket_index(player, player_count, opponent_count);
+        self.bucket(bucket_index, "PatternBuckets::len::bucket").len
+    }
     pub(super) fn iter(
         &self,
         player: u8,
@@ -0,0 +1,202 @@
+use crate::{checked, utils::board_index};
+use alloc::sync::Arc;
+use parking_lot::Mutex;
+use smallvec::SmallVec;
+static LAYOUTS: Mutex<Vec<Arc<WindowLayout>>> = Mutex::new(Vec::new());
+pub(super) struct WindowLayout {
+    pub(super) board_size: usize,
+    pub(super) win_len: usize,
+    pub(super) point_to_windows_map: Vec<SmallVec<[u16; 4]>>,
+    pub(super) point_to_window_offsets: Vec<SmallVec<[u8; 4]>>,
+    pub(super) window_coords: Vec<Vec<(usize, usize)>>,
+    pub(super) window_flanks: Vec<[Option<(usize, usize)>; 2]>,
+}
+impl WindowLayout {
+    pub(super) fn shared(board_size: usize, win_len: usize) -> Arc<Self> {
+        let mut layouts = LAYOUTS.lock();
+        if let Some(layout) = layouts
+            .iter()
+            .find(|layout| layout.board_size == board_size && layout.win_len == win_len)
+        {
+            return Arc::clone(layout);
+        }
+        let layout = Arc::new(Self::new(board_size, win_len));
+        layouts.push(Arc::clone(&layout));
+        layout
+    }
+    fn new(board_size: usize, win_len: usize) -> Self {
+        let point_count =
+            checked::mul_usize(board_size, board_size, "WindowLayout::new::point_count");
+        let mut layout = Self {
+            board_size,
+            win_len,
+            point_to_windows_map: vec![SmallVec::new(); point_count],
+            point_to_window_offsets: vec![SmallVec::new(); point_count],
+            window_coords: Vec::with_capacity(Self::window_count(board_size, win_len)),
+            window_flanks: Vec::with_capacity(Self::window_count(board_size, win_len)),
+        };
+        layout.enumerate_windows();
+        layout
+    }
+    fn enumerate_windows(&mut self) {
+        if self.board_size < self.win_len {
+            return;
+        }
+        let start_limit = checked::sub_usize(
+            self.board_size,
+            self.win_len,
+            "WindowLayout::enumerate_windows::start_limit",
+        );
+        let descending_column_start = checked::sub_usize(
+            self.win_len,
+            1_usize,
+            "WindowLayout::enumerate_windows::descending_column_start",
+        );
+        self.add_direction_windows(
+            0..self.board_size,
+            0..=start_limit,
+            &|row_index, column_index, offset| {
+                (
+                    row_index,
+                    checked::add_usize(
+                        column_index,
+                        offset,
+                        "WindowLayout::enumerate_windows::horizontal_column",
+                    ),
+                )
+            },
+        );
+        self.add_direction_windows(
+            0..=start_limit,
+            0..self.board_size,
+            &|row_index, column_index, offset| {
+                (
+                    checked::add_usize(
+                        row_index,
+                        offset,
+                        "WindowLayout::enumerate_windows::vertical_row",
+                    ),
+                    column_index,
+                )
+            },
+        );
+        self.add_direction_windows(
+            0..=start_limit,
+            0..=start_limit,
+            &|row_index, column_index, offset| {
+                (
+                    checked::add_usize(
+                        row_index,
+                        offset,
+                        "WindowLayout::enumerate_windows::diag_down_row",
+                    ),
+                    checked::add_usize(
+                        column_index,
+                        offset,
+                        "WindowLayout::enumerate_windows::diag_down_column",
+                    ),
+                )
+            },
+        );
+        self.add_direction_windows(
+            0..=start_limit,
+            descending_column_start..self.board_size,
+            &|row_index, column_index, offset| {
+                (
+                    checked::add_usize(
+                        row_index,
+                        offset,
+                        "WindowLayout::enumerate_windows::diag_up_row",
+                    ),
+                    checked::sub_usize(
+                        column_index,
+                        offset,
+                        "WindowLayout::enumerate_windows::diag_up_column",
+                    ),
+                )
+            },
+        );
+    }
+    fn add_direction_windows<RI, CI, F>(&mut self, row_iter: RI, column_iter: CI, coord_fn: &F)
+    where
+        RI: Iterator<Item = usize>,
+        CI: Iterator<Item = usize>,
+        F: Fn(usize, usize, usize) -> (usize, usize),
+    {
+        let column_indices: Vec<usize> = column_iter.collect();
+        for row_index in row_iter {
+            for &column_index in &column_indices {
+                let coords: Vec<(usize, usize)> = (0..self.win_len)
+                    .map(|offset| coord_fn(row_index, column_index, offset))
+                    .collect();
+                self.add_window(coords);
+            }
+        }
+    }
+    fn add_window(&mut self, coords: Vec<(usize, usize)>) {
+        let window_index = self.window_coords.len();
+        let window_index_u16 =
+            checked::usize_to_u16(window_index, "WindowLayout::add_window::window_index");
+        for (offset, &(row_index, column_index)) in coords.iter().enumerate() {
+            let point_index = board_index(self.board_size, row_index, column_index);
+            let (Some(window_indices), Some(window_offsets)) = (
+                self.point_to_windows_map.get_mut(point_index),
+                self.point_to_window_offsets.get_mut(point_index),
+            ) else {
+                eprintln!("WindowLayout::add_window 点索引越界: ({row_index}, {column_index})");
+                panic!("WindowLayout::add_window 点索引越界");
+            };
+            let Ok(offset_u8) = u8::try_from(offset) else {
+                eprintln!("WindowLayout::add_window 窗口内偏移超出 u8 范围: {offset}");
+                panic!("WindowLayout::add_window 窗口内偏移超出 u8 范围");
+            };
+            window_indices.push(window_index_u16);
+            window_offsets.push(offset_u8);
+        }
+        let flanks = match (
+            coords.first(),
+            coords.get(1),
+            coords.iter().rev().nth(1),
+            coords.last(),
+        ) {
+            (Some(&first), Some(&second), Some(&second_last), Some(&last)) => {
+                [self.flank(first, second), self.flank(last, second_last)]
+            }
+            _ => [None, None],
+        };
+        self.window_flanks.push(flanks);
+        self.window_coords.push(coords);
+    }
+    fn flank(&self, end: (usize, usize), inner: (usize, usize)) -> Option<(usize, usize)> {
+        let row = end.0.checked_mul(2)?.checked_sub(inner.0)?;
+        let column = end.1.checked_mul(2)?.checked_sub(inner.1)?;
+        (row < self.board_size && column < self.board_size).then_some((row, column))
+    }
+    pub(super) fn window_count(board_size: usize, win_len: usize) -> usize {
+        if board_size < win_len {
+            return 0;
+        }
+        let span_base =
+            checked::sub_usize(board_size, win_len, "WindowLayout::window_count::span_base");
+        let span = checked::add_usize(span_base, 1_usize, "WindowLayout::window_count::span");
+        let line_windows =
+            checked::mul_usize(board_size, span, "WindowLayout::window_count::line_windows");
+        let diagonal_windows =
+            checked::mul_usize(span, span, "WindowLayout::window_count::diagonal_windows");
+        let total_line_windows = checked::mul_usize(
+            2_usize,
+            line_windows,
+            "WindowLayout::window_count::total_line_windows",
+        );
+        let total_diagonal_windows = checked::mul_usize(
+            2_usize,
+            diagonal_windows,
+            "WindowLayout::window_count::total_diagonal_windows",
+        );
+        checked::add_usize(
+            total_line_windows,
+            total_diagonal_windows,
+            "WindowLayout::window_count::total_windows",
+        )
+    }
+}
@@ -0,0 +1,119 @@
+use super::{Coord, GameState, GomokuPosition, GomokuRules};
+use crate::checked;
+use smallvec::SmallVec;
+const MIN_THREE_WIN_LEN: usize = 4;
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct ForcingMoves {
+    pub wins: Vec<Coord>,
+    pub blocks: Vec<Coord>,
+    pub double_threats: Vec<Coord>,
+}
+impl GomokuRules {
+    fn completion_cells(position: &GomokuPosition, player: u8, needed: usize) -> Vec<Coord> {
+        let mut bits = Vec::new();
+        Self::collect_completion_bits(position, player, needed, &mut bits);
+        position.bitboard.iter_bits(&bits).collect()
+    }
+    pub(crate) fn creates_double_threat(position: &GomokuPosition, mov: Coord, player: u8) -> bool {
+        let (row_index, column_index) = mov;
+        if position.win_len < 2 || position.cell(row_index, column_index) != 0 {
+            return false;
+        }
+        let four_count = checked::sub_usize(
+            position.win_len,
+            2_usize,
+            "GomokuRules::creates_double_threat::four_count",
+        );
+        let three_count = Some(position.win_len)
+            .filter(|&win_len| win_len >= MIN_THREE_WIN_LEN)
+            .and_then(|win_len| win_len.checked_sub(3));
+        let threat_index = &position.threat_index;
+        let mut first_completion = None;
+        let mut threat_axes: SmallVec<[usize; 4]> = SmallVec::new();
+        for window_index in threat_index.window_indices_for_point(row_index, column_index) {
+            let (player_count, opponent_count) = threat_index.window(window_index).counts(player);
+            if opponent_count > 0 {
+                continue;
+            }
+            if player_count == four_count {
+                let completion = threat_index
+                    .window_empty_cells(window_index)
+                    .find(|&cell| cell != mov);
+                match (first_completion, completion) {
+                    (None, Some(cell)) => first_completion = Some(cell),
+                    (Some(first), Some(cell)) if first != cell => return true,
+                    _ => {}
+                }
+            } else if three_count != Some(player_count)
+                || !threat_index.is_open_window(&position.board, window_index)
+            {
+                continue;
+            }
+            let axis = threat_index.window_axis(window_index);
+            if !threat_axes.contains(&axis) {
+                threat_axes.push(axis);
+            }
+        }
+        threat_axes.len() >= 2
+    }
+    pub(crate) fn find_double_threat_moves(position: &GomokuPosition, player: u8) -> Vec<Coord> {
+        let mut empty_bits = Vec::new();
+        position.empty_in_region_into(&mut empty_bits);
+        position
+            .bitboard
+            .iter_bits(&empty_bits)
+            .filter(|&mov| Self::creates_double_threat(position, mov, player))
+            .collect()
+    }
+    pub(crate) fn promote_double_threats<T>(
+        position: &GomokuPosition,
+        player: u8,
+        items: &mut [T],
+        coord_of: impl Fn(&T) -> Coord,
+    ) {
+        let mut front = 0_usize;
+        for index in 0..items.len() {
+            let is_double_threat = items
+                .get(index)
+                .is_some_and(|item| Self::creates_double_threat(position, coord_of(item), player));
+            if !is_double_threat {
+                continue;
+            }
+            if let Some(span) = items.get_mut(front..=index) {
+                span.rotate_right(1);
+            }
+            front = checked::add_usize(front, 1_usize, "GomokuRules::promote_double_threats");
+        }
+    }
+    pub(crate) fn find_forcing_moves(position: &GomokuPosition, player: u8) -> ForcingMoves {
+        let opponent = checked::opponent_player(player, "GomokuRules::find_forcing_moves");
+        let win_minus_one = checked::sub_usize(
+            position.win_len,
+            1_usize,
+            "GomokuRules::find_forcing_moves::win_minus_one",
+        );
+        let wins = Self::completion_cells(position, player, win_minus_one);
+        let blocks = Self::completion_cells(position, opponent, win_minus_one);
+        let double_threats = Self::find_double_threat_moves(position, player)
+            .into_iter()
+            .filter(|mov| !wins.contains(mov))
+            .collect();
+        ForcingMoves {
+            wins,
+            blocks,
+            double_threats,
+        }
+    }
+}
+impl GameState {
+    #[inline]
+    #[must_use]
+    pub fn find_forcing_moves(&self, player: u8) -> ForcingMoves {
+        GomokuRules::find_forcing_moves(&self.position, player)
+    }
+    #[inline]
+    #[must_use]
+    pub fn find_double_threat_moves(&self, player: u8) -> Vec<Coord> {
+        GomokuRules::find_double_threat_moves(&self.position, player)
+    }
+}
@@ -0,0 +1,366 @@
+use crate::{
+    about::engine_info,
+    game_state::{Coord, Position},
+    openings::{OpeningBook, OpeningMatch},
+};
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead as _, BufReader, BufWriter, Write as _},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+mod adjudication;
+mod annotation;
+mod outcome;
+mod psq;
+mod sgf;
+const FIRST_PLAYER: u8 = 1;
+const SECOND_PLAYER: u8 = 2;
+pub type GameResult = outcome::GameResult;
+pub type Adjudication = adjudication::Adjudication;
+pub type AdjudicationRules = adjudication::AdjudicationRules;
+pub type MoveAnnotation = annotation::MoveAnnotation;
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct PlayerInfo {
+    pub name: String,
+    pub engine_version: Option<String>,
+}
+impl PlayerInfo {
+    #[inline]
+    #[must_use]
+    pub fn engine() -> Self {
+        let info = engine_info();
+        Self {
+            name: String::from(info.name),
+            engine_version: Some(String::from(info.version)),
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn human(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            engine_version: None,
+        }
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RecordedMove {
+    pub row: usize,
+    pub column: usize,
+    pub player: u8,
+    pub elapsed_ms: u64,
+    #[serde(default)]
+    pub annotation: Option<MoveAnnotation>,
+}
+impl RecordedMove {
+    #[inline]
+    #[must_use]
+    pub const fn coord(self) -> Coord {
+        (self.row, self.column)
+    }
+}
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub board_size: usize,
+    pub win_len: usize,
+    pub players: [PlayerInfo; 2],
+    pub moves: Vec<RecordedMove>,
+    pub result: GameResult,
+    pub finished_at_secs: u64,
+    #[serde(default)]
+    pub adjudication: Option<Adjudication>,
+}
+impl GameRecord {
+    #[inline]
+    #[must_use]
+    pub fn opening_move(&self) -> Option<Coord> {
+        self.moves.first().map(|recorded| recorded.coord())
+    }
+    #[inline]
+    #[must_use]
+    pub fn opening(&self) -> Option<OpeningMatch> {
+        let moves: Vec<(Coord, u8)> = self
+            .moves
+            .iter()
+            .map(|recorded| (recorded.coord(), recorded.player))
+            .collect();
+        OpeningBook::new(self.board_size)?.detect(&moves)
+    }
+    #[inline]
+    pub fn from_psq(text: &str, win_len: usize) -> Result<Self, String> {
+        psq::parse(text, win_len)
+    }
+    #[inline]
+    #[must_use]
+    pub fn to_psq(&self) -> String {
+        psq::format(self)
+    }
+    #[inline]
+    pub fn load_psq(path: &Path, win_len: usize) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("读取 PSQ 文件 {path} 失败: {err}", path = path.display()))?;
+        Self::from_psq(&text, win_len)
+    }
+    #[inline]
+    pub fn save_psq(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_psq())
+    }
+    #[inline]
+    pub fn position(&self) -> Result<Position, String> {
+        let mut position = Position::new(self.board_size, self.win_len);
+        for recorded in &self.moves {
+            position.play(recorded.coord())?;
+        }
+        Ok(position)
+    }
+    #[inline]
+    pub fn from_sgf(text: &str, win_len: usize) -> Result<Self, String> {
+        sgf::parse(text, win_len)
+    }
+    #[inline]
+    #[must_use]
+    pub fn to_sgf(&self) -> String {
+        sgf::format(self)
+    }
+    #[inline]
+    pub fn load_sgf(path: &Path, win_len: usize) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("读取 SGF 文件 {path} 失败: {err}", path = path.display()))?;
+        Self::from_sgf(&text, win_len)
+    }
+    #[inline]
+    pub fn save_sgf(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_sgf())
+    }
+}
+fn player_label(player: &PlayerInfo) -> String {
+    player.engine_version.as_ref().map_or_else(
+        || player.name.clone(),
+        |version| format!("{name} {version}", name = player.name),
+    )
+}
+pub struct GameRecorder {
+    board_size: usize,
+    win_len: usize,
+    players: [PlayerInfo; 2],
+    moves: Vec<RecordedMove>,
+    adjudication: Option<Adjudication>,
+}
+impl GameRecorder {
+    #[inline]
+    #[must_use]
+    pub const fn new(board_size: usize, win_len: usize, players: [PlayerInfo; 2]) -> Self {
+        Self {
+            board_size,
+            win_len,
+            players,
+            moves: Vec::new(),
+            adjudication: None,
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.moves.len()
+    }
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+    #[inline]
+    pub fn push(&mut self, (row, column): Coord, player: u8, elapsed: Duration) {
+        self.moves.push(RecordedMove {
+            row,
+            column,
+            player,
+            elapsed_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            annotation: None,
+        });
+    }
+    #[inline]
+    pub fn annotate(&mut self, index: usize, annotation: Option<MoveAnnotation>) {
+        if let Some(recorded) = self.moves.get_mut(index) {
+            recorded.annotation = annotation;
+        }
+    }
+    #[inline]
+    pub const fn adjudicate(&mut self, adjudication: Adjudication) {
+        self.adjudication = Some(adjudication);
+    }
+    #[inline]
+    #[must_use]
+    pub const fn adjudication(&self) -> Option<Adjudication> {
+        self.adjudication
+    }
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.moves.truncate(len);
+    }
+    #[inline]
+    #[must_use]
+    pub fn finish(self, result: GameResult) -> GameRecord {
+        self.snapshot(result)
+    }
+    #[inline]
+    #[must_use]
+    pub fn snapshot(&self, result: GameResult) -> GameRecord {
+        let finished_at_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        GameRecord {
+            board_size: self.board_size,
+            win_len: self.win_len,
+            players: self.players.clone(),
+            moves: self.moves.clone(),
+            result,
+            finished_at_secs,
+            adjudication: self.adjudication,
+        }
+    }
+}
+#[derive(Clone, Copy, Default, Debug)]
+pub struct GameQuery {
+    result: Option<GameResult>,
+    board_size: Option<usize>,
+    opening_move: Option<Coord>,
+}
+impl GameQuery {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            result: None,
+            board_size: None,
+            opening_move: None,
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_result(mut self, result: GameResult) -> Self {
+        self.result = Some(result);
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_board_size(mut self, board_size: usize) -> Self {
+        self.board_size = Some(board_size);
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_opening_move(mut self, opening_move: Coord) -> Self {
+        self.opening_move = Some(opening_move);
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn matches(&self, record: &GameRecord) -> bool {
+        self.result.is_none_or(|result| record.result == result)
+            && self
+                .board_size
+                .is_none_or(|board_size| record.board_size == board_size)
+            && self
+                .opening_move
+                .is_none_or(|opening_move| record.opening_move() == Some(opening_move))
+    }
+}
+pub struct GameDatabase {
+    path: PathBuf,
+}
+impl GameDatabase {
+    #[inline]
+    pub fn open<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    #[inline]
+    pub fn append(&self, record: &GameRecord) -> io::Result<()> {
+        let line = serde_json::to_string(record).map_err(io::Error::other)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{line}")?;
+        writer.flush()
+    }
+    #[inline]
+    pub fn load(&self) -> io::Result<Vec<GameRecord>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut records = Vec::new();
+        for (line_index, line) in BufReader::new(file).lines().enumerate() {
+            let text = line?;
+            if text.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&text) {
+                Ok(record) => records.push(record),
+                Err(err) => eprintln!(
+                    "对局库 {path} 第 {line_number} 行无法解析，已跳过: {err}",
+                    path = self.path.display(),
+                    line_number = line_index.saturating_add(1)
+                ),
+            }
+        }
+        Ok(records)
+    }
+    #[inline]
+    pub fn query(&self, query: &GameQuery) -> io::Result<Vec<GameRecord>> {
+        let mut records = self.load()?;
+        records.retain(|record| query.matches(record));
+        Ok(records)
+    }
+    #[inline]
+    pub fn export_sgf(
+        &self,
+        query: &GameQuery,
+        directory: &Path,
+        batch_size: usize,
+    ) -> io::Result<Vec<PathBuf>> {
+        let records = self.query(query)?;
+        write_sgf_batches(&records, directory, batch_size)
+    }
+}
+#[inline]
+pub fn write_sgf_batches(
+    records: &[GameRecord],
+    directory: &Path,
+    batch_size: usize,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(directory)?;
+    let mut paths = Vec::new();
+    for (batch_index, batch) in records.chunks(batch_size.max(1)).enumerate() {
+        let path = directory.join(format!(
+            "games_{number:04}.sgf",
+            number = batch_index.saturating_add(1)
+        ));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        for record in batch {
+            writeln!(writer, "{}", record.to_sgf())?;
+        }
+        writer.flush()?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+#[inline]
+#[must_use]
+pub const fn result_from_winner(winner: Option<u8>) -> Option<GameResult> {
+    match winner {
+        Some(FIRST_PLAYER) => Some(GameResult::FirstPlayerWin),
+        Some(SECOND_PLAYER) => Some(GameResult::SecondPlayerWin),
+        _ => None,
+    }
+}
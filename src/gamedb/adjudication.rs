@@ -0,0 +1,137 @@
+use super::{FIRST_PLAYER, GameResult, SECOND_PLAYER};
+use serde::{Deserialize, Serialize};
+const PERMILLE: f64 = 1000.0;
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AdjudicationRules {
+    pub draw_after_moves: Option<usize>,
+    pub margin_after_moves: Option<usize>,
+    pub margin_probability: f64,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Adjudication {
+    NoProvenAdvantage {
+        moves: usize,
+        quiet_moves: usize,
+    },
+    HeuristicMargin {
+        moves: usize,
+        favoured: u8,
+        win_probability_permille: u16,
+    },
+    TimeForfeit {
+        moves: usize,
+        loser: u8,
+    },
+}
+impl AdjudicationRules {
+    #[inline]
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.draw_after_moves.is_some() || self.margin_after_moves.is_some()
+    }
+    #[inline]
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let draw = self
+            .draw_after_moves
+            .map(|moves| format!("连续 {moves} 手无已证明优势判和"));
+        let margin = self.margin_after_moves.map(|moves| {
+            format!(
+                "第 {moves} 手起启发式胜率不低于 {percent:.0}% 判胜",
+                percent = self.margin_probability * 100.0_f64
+            )
+        });
+        [draw, margin]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("；")
+    }
+    #[inline]
+    #[must_use]
+    pub fn adjudicate(
+        &self,
+        moves: usize,
+        quiet_moves: usize,
+        mover: u8,
+        win_probability: Option<f64>,
+    ) -> Option<Adjudication> {
+        if let Some(probability) = win_probability
+            && self
+                .margin_after_moves
+                .is_some_and(|threshold| moves >= threshold)
+            && probability >= self.margin_probability
+        {
+            return Some(Adjudication::HeuristicMargin {
+                moves,
+                favoured: mover,
+                win_probability_permille: probability_permille(probability),
+            });
+        }
+        self.draw_after_moves
+            .is_some_and(|threshold| quiet_moves >= threshold)
+            .then_some(Adjudication::NoProvenAdvantage { moves, quiet_moves })
+    }
+}
+impl Adjudication {
+    #[inline]
+    #[must_use]
+    pub const fn result(self) -> GameResult {
+        match self {
+            Self::NoProvenAdvantage { .. } => GameResult::Draw,
+            Self::HeuristicMargin {
+                favoured: FIRST_PLAYER,
+                ..
+            }
+            | Self::TimeForfeit {
+                loser: SECOND_PLAYER,
+                ..
+            } => GameResult::FirstPlayerWin,
+            Self::HeuristicMargin {
+                favoured: SECOND_PLAYER,
+                ..
+            }
+            | Self::TimeForfeit {
+                loser: FIRST_PLAYER,
+                ..
+            } => GameResult::SecondPlayerWin,
+            Self::HeuristicMargin { .. } | Self::TimeForfeit { .. } => GameResult::Unfinished,
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn label(self) -> String {
+        match self {
+            Self::NoProvenAdvantage { moves, quiet_moves } => {
+                format!("第 {moves} 手时已连续 {quiet_moves} 手无人取得已证明优势，裁定和棋")
+            }
+            Self::HeuristicMargin {
+                moves,
+                favoured,
+                win_probability_permille,
+            } => {
+                let side = if favoured == FIRST_PLAYER {
+                    "先手"
+                } else {
+                    "后手"
+                };
+                format!(
+                    "第 {moves} 手时{side}启发式胜率估计达 {percent:.1}%，裁定{side}胜",
+                    percent = f64::from(win_probability_permille) / 10.0_f64
+                )
+            }
+            Self::TimeForfeit { moves, loser } => {
+                let side = if loser == FIRST_PLAYER {
+                    "先手"
+                } else {
+                    "后手"
+                };
+                format!("第 {moves} 手时{side}超时，裁定{side}负")
+            }
+        }
+    }
+}
+fn probability_permille(probability: f64) -> u16 {
+    let scaled = probability.clamp(0.0_f64, 1.0_f64) * PERMILLE;
+    format!("{scaled:.0}").parse().unwrap_or_default()
+}
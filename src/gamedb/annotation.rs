@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum MoveAnnotation {
+    Search {
+        depth: usize,
+        pn: u64,
+        dn: u64,
+        mate_in: Option<u64>,
+    },
+    Refuted {
+        mate_in: u64,
+    },
+}
+fn proof_number_text(value: u64) -> String {
+    if value == u64::MAX {
+        String::from("∞")
+    } else {
+        value.to_string()
+    }
+}
+fn elapsed_text(elapsed_ms: u64) -> String {
+    format!(
+        "{secs}.{millis:03}s",
+        secs = elapsed_ms.div_euclid(1000),
+        millis = elapsed_ms.rem_euclid(1000)
+    )
+}
+impl MoveAnnotation {
+    pub(super) fn sgf_comment(self, elapsed_ms: u64) -> String {
+        let elapsed = elapsed_text(elapsed_ms);
+        match self {
+            Self::Search {
+                depth,
+                pn,
+                dn,
+                mate_in,
+            } => {
+                let verdict = mate_in.map_or_else(
+                    || String::from("未证明胜负"),
+                    |plies| format!("已证明必胜，{plies} 步内取胜"),
+                );
+                format!(
+                    "搜索深度 D={depth}，pn={pn}，dn={dn}，{verdict}，用时 {elapsed}",
+                    pn = proof_number_text(pn),
+                    dn = proof_number_text(dn)
+                )
+            }
+            Self::Refuted { mate_in } => {
+                format!("败着：引擎随后证明 {mate_in} 步内取胜，用时 {elapsed}")
+            }
+        }
+    }
+}
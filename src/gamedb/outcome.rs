@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum GameResult {
+    FirstPlayerWin,
+    SecondPlayerWin,
+    Draw,
+    Unfinished,
+}
+impl GameResult {
+    #[inline]
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::FirstPlayerWin => "先手胜",
+            Self::SecondPlayerWin => "后手胜",
+            Self::Draw => "和棋",
+            Self::Unfinished => "未完成",
+        }
+    }
+    pub(super) const fn sgf_value(self) -> &'static str {
+        match self {
+            Self::FirstPlayerWin => "B+",
+            Self::SecondPlayerWin => "W+",
+            Self::Draw => "0",
+            Self::Unfinished => "?",
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use super::{GameRecord, GameResult, PlayerInfo, RecordedMove, player_label, result_from_winner};
+use crate::game_state::{Coord, GameState, Position};
+const PSQ_HEADER: &str = "Piskvorky";
+const UNKNOWN_PLAYER: &str = "unknown";
+fn parse_size(header: &str) -> Result<usize, String> {
+    let dimensions = header
+        .strip_prefix(PSQ_HEADER)
+        .ok_or_else(|| format!("不是 PSQ 文件，首行应以 {PSQ_HEADER} 开头"))?
+        .split(',')
+        .next()
+        .unwrap_or_default()
+        .trim();
+    let (width_text, height_text) = dimensions
+        .split_once('x')
+        .ok_or_else(|| format!("无法解析 PSQ 棋盘尺寸: {dimensions}"))?;
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|err| format!("无法解析 PSQ 棋盘尺寸 {dimensions}: {err}"))
+    };
+    let (width, height) = (parse(width_text)?, parse(height_text)?);
+    if width != height {
+        return Err(format!("仅支持正方形棋盘，PSQ 棋盘为 {width}x{height}"));
+    }
+    Ok(width)
+}
+fn parse_move(line: &str) -> Option<(Coord, u64)> {
+    let fields: Vec<u64> = line
+        .split(',')
+        .map(|field| field.trim().parse::<u64>().ok())
+        .collect::<Option<_>>()?;
+    let (x, y, elapsed_ms) = match *fields.as_slice() {
+        [x, y] => (x, y, 0),
+        [x, y, elapsed_ms] => (x, y, elapsed_ms),
+        _ => return None,
+    };
+    let column = usize::try_from(x.checked_sub(1)?).ok()?;
+    let row = usize::try_from(y.checked_sub(1)?).ok()?;
+    Some(((row, column), elapsed_ms))
+}
+const fn psq_coord((row, column): Coord) -> (usize, usize) {
+    (column.saturating_add(1), row.saturating_add(1))
+}
+pub(super) fn parse(text: &str, win_len: usize) -> Result<GameRecord, String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let board_size = parse_size(lines.next().ok_or("PSQ 文件为空")?)?;
+    GameState::validate_shape(board_size, win_len).map_err(|err| err.to_string())?;
+    let mut position = Position::new(board_size, win_len);
+    let mut moves = Vec::new();
+    let mut trailer = Vec::new();
+    for line in lines.by_ref() {
+        let Some((coord, elapsed_ms)) = parse_move(line) else {
+            trailer.push(line);
+            break;
+        };
+        let player = position.side_to_move();
+        position.play(coord).map_err(|err| {
+            format!(
+                "PSQ 第 {number} 手无效: {err}",
+                number = moves.len().saturating_add(1)
+            )
+        })?;
+        moves.push(RecordedMove {
+            row: coord.0,
+            column: coord.1,
+            player,
+            elapsed_ms,
+            annotation: None,
+        });
+    }
+    trailer.extend(lines);
+    let mut names = trailer
+        .into_iter()
+        .filter(|line| line.parse::<i64>().is_err())
+        .map(PlayerInfo::human);
+    let players = [
+        names
+            .next()
+            .unwrap_or_else(|| PlayerInfo::human(UNKNOWN_PLAYER)),
+        names
+            .next()
+            .unwrap_or_else(|| PlayerInfo::human(UNKNOWN_PLAYER)),
+    ];
+    let result = result_from_winner(position.winner()).unwrap_or_else(|| {
+        if position.is_full() {
+            GameResult::Draw
+        } else {
+            GameResult::Unfinished
+        }
+    });
+    Ok(GameRecord {
+        board_size,
+        win_len,
+        players,
+        moves,
+        result,
+        finished_at_secs: 0,
+        adjudication: None,
+    })
+}
+pub(super) fn format(record: &GameRecord) -> String {
+    let (cursor_x, cursor_y) = record
+        .moves
+        .last()
+        .map_or((1, 1), |recorded| psq_coord(recorded.coord()));
+    let mut lines = vec![format!(
+        "{PSQ_HEADER} {size}x{size}, {cursor_x}:{cursor_y}, 0",
+        size = record.board_size
+    )];
+    lines.extend(record.moves.iter().map(|recorded| {
+        let (x, y) = psq_coord(recorded.coord());
+        format!("{x},{y},{elapsed}", elapsed = recorded.elapsed_ms)
+    }));
+    lines.extend(record.players.iter().map(player_label));
+    lines.push(String::new());
+    lines.join("\n")
+}
@@ -0,0 +1,217 @@
+use super::{
+    FIRST_PLAYER, GameRecord, GameResult, PlayerInfo, RecordedMove, SECOND_PLAYER, player_label,
+    result_from_winner,
+};
+use crate::game_state::{Coord, GameState, Position};
+const SGF_COORDS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const GOMOKU_GAME: &str = "4";
+const DEFAULT_BOARD_SIZE: usize = 15;
+const UNKNOWN_PLAYER: &str = "unknown";
+const SETUP_PROPERTIES: [&str; 3] = ["AB", "AW", "AE"];
+struct Property {
+    ident: String,
+    value: String,
+}
+fn read_value(chars: &mut core::iter::Peekable<core::str::Chars<'_>>) -> Result<String, String> {
+    let mut value = String::new();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ']' => return Ok(value),
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(escaped) => value.push(escaped),
+                None => break,
+            },
+            _ => value.push(ch),
+        }
+    }
+    Err(String::from("SGF 属性值缺少结束的 ]"))
+}
+fn main_line_properties(text: &str) -> Result<Vec<Property>, String> {
+    let start = text.find('(').ok_or("不是 SGF 文件，缺少起始的 (")?;
+    let mut chars = text.get(start..).unwrap_or_default().chars().peekable();
+    let mut properties = Vec::new();
+    let mut ident = String::new();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ')' => return Ok(properties),
+            '[' => {
+                if ident.is_empty() {
+                    return Err(String::from("SGF 属性值前缺少属性名"));
+                }
+                let value = read_value(&mut chars)?;
+                properties.push(Property {
+                    ident: ident.clone(),
+                    value,
+                });
+                while chars.next_if(char::is_ascii_whitespace).is_some() {}
+                if chars.peek() != Some(&'[') {
+                    ident.clear();
+                }
+            }
+            'A'..='Z' => ident.push(ch),
+            'a'..='z' => {}
+            _ => ident.clear(),
+        }
+    }
+    Err(String::from("SGF 文件不完整，缺少结束的 )"))
+}
+fn parse_size(value: &str) -> Result<usize, String> {
+    let parse = |text: &str| {
+        text.trim()
+            .parse::<usize>()
+            .map_err(|err| format!("无法解析 SGF 棋盘尺寸 {value}: {err}"))
+    };
+    match value.split_once(':') {
+        None => parse(value),
+        Some((width_text, height_text)) => {
+            let (width, height) = (parse(width_text)?, parse(height_text)?);
+            if width != height {
+                return Err(format!("仅支持正方形棋盘，SGF 棋盘为 {width}x{height}"));
+            }
+            Ok(width)
+        }
+    }
+}
+fn sgf_index(byte: u8) -> Option<usize> {
+    SGF_COORDS.iter().position(|&coord| coord == byte)
+}
+fn parse_coord(value: &str) -> Option<Coord> {
+    match *value.as_bytes() {
+        [column, row] => Some((sgf_index(row)?, sgf_index(column)?)),
+        _ => None,
+    }
+}
+fn player_of(ident: &str) -> Option<u8> {
+    match ident {
+        "B" => Some(FIRST_PLAYER),
+        "W" => Some(SECOND_PLAYER),
+        _ => None,
+    }
+}
+pub(super) fn parse(text: &str, win_len: usize) -> Result<GameRecord, String> {
+    let properties = main_line_properties(text)?;
+    let value_of = |ident: &str| {
+        properties
+            .iter()
+            .find(|property| property.ident == ident)
+            .map(|property| property.value.as_str())
+    };
+    if let Some(game) = value_of("GM")
+        && game.trim() != GOMOKU_GAME
+    {
+        return Err(format!("不是五子棋 SGF 文件（GM[{game}]）"));
+    }
+    if let Some(setup) = properties
+        .iter()
+        .find(|property| SETUP_PROPERTIES.contains(&property.ident.as_str()))
+    {
+        return Err(format!(
+            "不支持 SGF 摆子属性 {ident}，请改用逐手着法",
+            ident = setup.ident
+        ));
+    }
+    let board_size = value_of("SZ").map_or(Ok(DEFAULT_BOARD_SIZE), parse_size)?;
+    GameState::validate_shape(board_size, win_len).map_err(|err| err.to_string())?;
+    let mut position = Position::new(board_size, win_len);
+    let mut moves = Vec::new();
+    for property in &properties {
+        let Some(player) = player_of(&property.ident) else {
+            continue;
+        };
+        let number = moves.len().saturating_add(1);
+        let coord = parse_coord(&property.value).ok_or_else(|| {
+            format!(
+                "SGF 第 {number} 手坐标无效: {ident}[{value}]",
+                ident = property.ident,
+                value = property.value
+            )
+        })?;
+        if player != position.side_to_move() {
+            return Err(format!("SGF 第 {number} 手行棋方顺序错误"));
+        }
+        position
+            .play(coord)
+            .map_err(|err| format!("SGF 第 {number} 手无效: {err}"))?;
+        moves.push(RecordedMove {
+            row: coord.0,
+            column: coord.1,
+            player,
+            elapsed_ms: 0,
+            annotation: None,
+        });
+    }
+    let name = |ident: &str| PlayerInfo::human(value_of(ident).unwrap_or(UNKNOWN_PLAYER));
+    let result = result_from_winner(position.winner()).unwrap_or_else(|| {
+        if position.is_full() {
+            GameResult::Draw
+        } else {
+            GameResult::Unfinished
+        }
+    });
+    Ok(GameRecord {
+        board_size,
+        win_len,
+        players: [name("PB"), name("PW")],
+        moves,
+        result,
+        finished_at_secs: 0,
+        adjudication: None,
+    })
+}
+pub(super) fn format(record: &GameRecord) -> String {
+    let [black, white] = record.players.each_ref();
+    let moves: String = record
+        .moves
+        .iter()
+        .map_while(|&recorded| format_move(recorded))
+        .collect();
+    let opening = record.opening().map_or_else(String::new, |opening| {
+        format!("ON[{name}]", name = escape(&opening.label()))
+    });
+    let adjudication = record
+        .adjudication
+        .map_or_else(String::new, |adjudication| {
+            format!("C[{reason}]", reason = escape(&adjudication.label()))
+        });
+    format!(
+        "(;GM[{GOMOKU_GAME}]FF[4]CA[UTF-8]SZ[{size}]PB[{black_name}]PW[{white_name}]RE[{result}]GC[{win_len}子连珠]{opening}{adjudication}{moves})",
+        size = record.board_size,
+        black_name = escape(&player_label(black)),
+        white_name = escape(&player_label(white)),
+        result = record.result.sgf_value(),
+        win_len = record.win_len
+    )
+}
+fn format_move(recorded: RecordedMove) -> Option<String> {
+    let color = if recorded.player == FIRST_PLAYER {
+        'B'
+    } else {
+        'W'
+    };
+    let (Some(&column), Some(&row)) = (
+        SGF_COORDS.get(recorded.column),
+        SGF_COORDS.get(recorded.row),
+    ) else {
+        eprintln!(
+            "SGF 坐标超出范围: ({row}, {column})",
+            row = recorded.row,
+            column = recorded.column
+        );
+        return None;
+    };
+    let comment = recorded.annotation.map_or_else(String::new, |annotation| {
+        format!(
+            "C[{text}]",
+            text = escape(&annotation.sgf_comment(recorded.elapsed_ms))
+        )
+    });
+    Some(format!(
+        ";{color}[{column}{row}]{comment}",
+        column = char::from(column),
+        row = char::from(row)
+    ))
+}
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(']', "\\]")
+}
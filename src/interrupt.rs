@@ -0,0 +1,3 @@
+mod token;
+pub type CancelReason = token::CancelReason;
+pub type CancellationToken = token::CancellationToken;
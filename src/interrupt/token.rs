@@ -0,0 +1,163 @@
+use alloc::sync::{Arc, Weak};
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use parking_lot::Mutex;
+use serde::Serialize;
+const REASON_NONE: u8 = 0;
+type CancelCallback = Box<dyn FnOnce(CancelReason) + Send>;
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub enum CancelReason {
+    User,
+    Timeout,
+    Memory,
+}
+impl CancelReason {
+    #[inline]
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::User => "用户中断",
+            Self::Timeout => "超时",
+            Self::Memory => "内存不足",
+        }
+    }
+    const fn code(self) -> u8 {
+        match self {
+            Self::User => 1,
+            Self::Timeout => 2,
+            Self::Memory => 3,
+        }
+    }
+    const fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(Self::User),
+            2 => Some(Self::Timeout),
+            3 => Some(Self::Memory),
+            _ => None,
+        }
+    }
+}
+struct TokenState {
+    flag: Arc<AtomicBool>,
+    reason: AtomicU8,
+    parent: Option<CancellationToken>,
+    children: Mutex<Vec<Weak<Self>>>,
+    callbacks: Mutex<Vec<CancelCallback>>,
+}
+#[derive(Clone)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+impl CancellationToken {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_parent(Arc::new(AtomicBool::new(false)), None)
+    }
+    fn with_parent(flag: Arc<AtomicBool>, parent: Option<Self>) -> Self {
+        Self {
+            state: Arc::new(TokenState {
+                flag,
+                reason: AtomicU8::new(REASON_NONE),
+                parent,
+                children: Mutex::new(Vec::new()),
+                callbacks: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn child(&self) -> Self {
+        let child = Self::with_parent(Arc::new(AtomicBool::new(false)), Some(self.clone()));
+        {
+            let mut children = self.state.children.lock();
+            children.retain(|existing| existing.strong_count() > 0);
+            children.push(Arc::downgrade(&child.state));
+        }
+        if let Some(reason) = self.reason() {
+            child.cancel(reason);
+        }
+        child
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.state.flag.load(Ordering::Acquire)
+            || self.state.parent.as_ref().is_some_and(Self::is_cancelled)
+    }
+    #[inline]
+    #[must_use]
+    pub fn reason(&self) -> Option<CancelReason> {
+        if let Some(reason) = CancelReason::from_code(self.state.reason.load(Ordering::Acquire)) {
+            return Some(reason);
+        }
+        if self.state.flag.load(Ordering::Acquire) {
+            return Some(CancelReason::User);
+        }
+        self.state.parent.as_ref().and_then(Self::reason)
+    }
+    #[inline]
+    pub fn cancel(&self, reason: CancelReason) -> bool {
+        if self
+            .state
+            .reason
+            .compare_exchange(
+                REASON_NONE,
+                reason.code(),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_err()
+        {
+            return false;
+        }
+        self.state.flag.store(true, Ordering::Release);
+        let callbacks = core::mem::take(&mut *self.state.callbacks.lock());
+        for callback in callbacks {
+            callback(reason);
+        }
+        let children = core::mem::take(&mut *self.state.children.lock());
+        for state in children.iter().filter_map(Weak::upgrade) {
+            Self { state }.cancel(reason);
+        }
+        true
+    }
+    #[inline]
+    pub fn cancel_children(&self, reason: CancelReason) -> usize {
+        let children = self.state.children.lock().clone();
+        children
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|state| {
+                Self {
+                    state: Arc::clone(state),
+                }
+                .cancel(reason)
+            })
+            .count()
+    }
+    #[inline]
+    pub fn on_cancel<F>(&self, callback: F)
+    where
+        F: FnOnce(CancelReason) + Send + 'static,
+    {
+        let mut callbacks = self.state.callbacks.lock();
+        if let Some(reason) = self.reason() {
+            drop(callbacks);
+            callback(reason);
+            return;
+        }
+        callbacks.push(Box::new(callback));
+    }
+}
+impl Default for CancellationToken {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl From<Arc<AtomicBool>> for CancellationToken {
+    #[inline]
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        Self::with_parent(flag, None)
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{
+    config::Config,
+    game_state::Board,
+    interrupt::CancellationToken,
+    pns::{Budget, ParallelSolver, SearchOutcome, SearchParams},
+};
+use std::time::Instant;
+mod outcome;
+type Stones = &'static [(usize, usize, u8)];
+const ATTACKER: u8 = 1;
+pub type KnownOutcome = outcome::KnownOutcome;
+pub type ObservedOutcome = outcome::ObservedOutcome;
+const KNOWN_POSITIONS: [KnownPosition; 7] = [
+    KnownPosition {
+        name: "open-four",
+        board_size: 9,
+        win_len: 5,
+        stones: &[
+            (4, 2, 1),
+            (2, 2, 2),
+            (4, 3, 1),
+            (2, 3, 2),
+            (4, 4, 1),
+            (2, 4, 2),
+            (4, 5, 1),
+            (2, 5, 2),
+        ],
+        expected: KnownOutcome::Win {
+            mate_in: 1,
+            best_moves: &[(4, 1), (4, 6)],
+        },
+        node_budget: 10_000,
+    },
+    KnownPosition {
+        name: "open-three",
+        board_size: 9,
+        win_len: 5,
+        stones: &[
+            (4, 3, 1),
+            (0, 0, 2),
+            (4, 4, 1),
+            (0, 8, 2),
+            (4, 5, 1),
+            (8, 0, 2),
+        ],
+        expected: KnownOutcome::Win {
+            mate_in: 3,
+            best_moves: &[(4, 2), (4, 6)],
+        },
+        node_budget: 200_000,
+    },
+    KnownPosition {
+        name: "small-open-two",
+        board_size: 5,
+        win_len: 4,
+        stones: &[(2, 2, 1), (0, 0, 2), (2, 1, 1), (4, 4, 2)],
+        expected: KnownOutcome::Win {
+            mate_in: 3,
+            best_moves: &[(2, 3)],
+        },
+        node_budget: 200_000,
+    },
+    KnownPosition {
+        name: "defender-open-four",
+        board_size: 9,
+        win_len: 5,
+        stones: &[
+            (0, 0, 1),
+            (4, 3, 2),
+            (8, 8, 1),
+            (4, 4, 2),
+            (0, 8, 1),
+            (4, 5, 2),
+            (8, 0, 1),
+            (4, 6, 2),
+        ],
+        expected: KnownOutcome::NoWin,
+        node_budget: 200_000,
+    },
+    KnownPosition {
+        name: "4x4-empty",
+        board_size: 4,
+        win_len: 4,
+        stones: &[],
+        expected: KnownOutcome::NoWin,
+        node_budget: 2_000_000,
+    },
+    KnownPosition {
+        name: "middle-game",
+        board_size: 9,
+        win_len: 5,
+        stones: &[
+            (4, 4, 1),
+            (0, 0, 2),
+            (4, 5, 1),
+            (0, 8, 2),
+            (3, 4, 1),
+            (8, 0, 2),
+            (3, 3, 1),
+            (8, 8, 2),
+        ],
+        expected: KnownOutcome::Win {
+            mate_in: 7,
+            best_moves: &[],
+        },
+        node_budget: 4_000_000,
+    },
+    KnownPosition {
+        name: "9x9-empty",
+        board_size: 9,
+        win_len: 5,
+        stones: &[],
+        expected: KnownOutcome::Unknown,
+        node_budget: 20_000,
+    },
+];
+#[derive(Clone, Copy)]
+pub struct KnownPosition {
+    pub name: &'static str,
+    pub board_size: usize,
+    pub win_len: usize,
+    pub stones: Stones,
+    pub expected: KnownOutcome,
+    pub node_budget: u64,
+}
+#[derive(Clone, Copy)]
+pub struct KnownCheck {
+    pub name: &'static str,
+    pub expected: KnownOutcome,
+    pub observed: ObservedOutcome,
+    pub passed: bool,
+    pub depth: usize,
+    pub nodes: u64,
+    pub elapsed_secs: f64,
+}
+#[inline]
+#[must_use]
+pub const fn known_positions() -> &'static [KnownPosition] {
+    &KNOWN_POSITIONS
+}
+impl KnownPosition {
+    fn board(&self) -> Vec<u8> {
+        let mut board = Board::new(self.board_size);
+        for &(row, column, player) in self.stones {
+            if board.set((row, column), player).is_none() {
+                eprintln!(
+                    "KnownPosition::board 局面 {name} 的棋子越界: ({row}, {column})",
+                    name = self.name
+                );
+                panic!("KnownPosition::board 棋子越界");
+            }
+        }
+        board.into_cells()
+    }
+    #[inline]
+    #[must_use]
+    pub fn verify(&self, config: &Config, cancel: &CancellationToken) -> KnownCheck {
+        let params = SearchParams {
+            board_size: self.board_size,
+            win_len: self.win_len,
+            ..config.search_params()
+        }
+        .with_budget(Budget {
+            nodes: Some(self.node_budget),
+            ..Budget::unlimited()
+        })
+        .with_expansion_recording(None)
+        .with_root_player(ATTACKER)
+        .with_quiet(true);
+        let start = Instant::now();
+        let result = ParallelSolver::search(self.board(), params, false, cancel, None, None);
+        let elapsed_secs = start.elapsed().as_secs_f64();
+        let observed = match result.report.outcome {
+            SearchOutcome::Proven { best_move, win_len } => ObservedOutcome::Win {
+                mate_in: win_len,
+                best_move,
+            },
+            SearchOutcome::Disproven => ObservedOutcome::NoWin,
+            SearchOutcome::Unresolved { .. } | SearchOutcome::Aborted { .. } => {
+                ObservedOutcome::Unknown
+            }
+        };
+        KnownCheck {
+            name: self.name,
+            expected: self.expected,
+            observed,
+            passed: self.expected.matches(observed),
+            depth: result.depth,
+            nodes: result.report.totals.stats.nodes_created,
+            elapsed_secs,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::game_state::Coord;
+use core::fmt;
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KnownOutcome {
+    Win {
+        mate_in: u64,
+        best_moves: &'static [Coord],
+    },
+    NoWin,
+    Unknown,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ObservedOutcome {
+    Win {
+        mate_in: u64,
+        best_move: Option<Coord>,
+    },
+    NoWin,
+    Unknown,
+}
+impl KnownOutcome {
+    pub(super) fn matches(self, observed: ObservedOutcome) -> bool {
+        match (self, observed) {
+            (
+                Self::Win {
+                    mate_in,
+                    best_moves,
+                },
+                ObservedOutcome::Win {
+                    mate_in: observed_mate_in,
+                    best_move,
+                },
+            ) => {
+                mate_in == observed_mate_in
+                    && (best_moves.is_empty()
+                        || best_move.is_some_and(|mov| best_moves.contains(&mov)))
+            }
+            (Self::NoWin, ObservedOutcome::NoWin) | (Self::Unknown, ObservedOutcome::Unknown) => {
+                true
+            }
+            _ => false,
+        }
+    }
+}
+fn format_moves(moves: &[Coord]) -> String {
+    moves
+        .iter()
+        .map(|&(row, column)| format!("({row}, {column})"))
+        .collect::<Vec<_>>()
+        .join(" 或 ")
+}
+impl fmt::Display for KnownOutcome {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Win {
+                mate_in,
+                best_moves: &[],
+            } => write!(f, "{mate_in} 手内必胜"),
+            Self::Win {
+                mate_in,
+                best_moves,
+            } => write!(
+                f,
+                "{mate_in} 手内必胜，着法 {moves}",
+                moves = format_moves(best_moves)
+            ),
+            Self::NoWin => f.write_str("无必胜"),
+            Self::Unknown => f.write_str("预算内未定"),
+        }
+    }
+}
+impl fmt::Display for ObservedOutcome {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Win {
+                mate_in,
+                best_move: Some((row, column)),
+            } => write!(f, "{mate_in} 手内必胜，着法 ({row}, {column})"),
+            Self::Win {
+                mate_in,
+                best_move: None,
+            } => write!(f, "{mate_in} 手内必胜"),
+            Self::NoWin => f.write_str("无必胜"),
+            Self::Unknown => f.write_str("预算内未定"),
+        }
+    }
+}
@@ -1,22 +1,60 @@
 extern crate alloc;
 #[macro_export]
 macro_rules ! for_each_move_apply_timing { ($ macro : ident) => { $ macro ! { board_update_ns => board_update_time_ns , bitboard_update_ns => bitboard_update_time_ns , threat_index_update_ns => threat_index_update_time_ns , candidate_remove_ns => candidate_remove_time_ns , candidate_neighbor_ns => candidate_neighbor_time_ns , candidate_insert_ns => candidate_insert_time_ns , candidate_newly_added_ns => candidate_newly_added_time_ns , candidate_history_ns => candidate_history_time_ns , hash_update_ns => hash_update_time_ns , } } ; }
+pub mod about;
+pub mod benchmark;
+pub mod calibration;
 mod checked;
 pub mod alloc_stats {
-    use crate::utils::duration_to_ns;
+    use crate::utils::timing::add_elapsed_ns_atomic;
     use core::{
         alloc::{GlobalAlloc, Layout},
         cell::Cell,
         sync::atomic::{AtomicU64, Ordering},
-        time::Duration,
     };
-    use mimalloc::MiMalloc;
+    #[cfg(all(
+        feature = "mimalloc",
+        not(any(feature = "jemalloc", feature = "system-allocator"))
+    ))]
+    type InnerAllocator = mimalloc::MiMalloc;
+    #[cfg(all(
+        feature = "mimalloc",
+        not(any(feature = "jemalloc", feature = "system-allocator"))
+    ))]
+    const INNER_ALLOCATOR: InnerAllocator = mimalloc::MiMalloc;
+    #[cfg(all(feature = "jemalloc", not(feature = "system-allocator")))]
+    type InnerAllocator = tikv_jemallocator::Jemalloc;
+    #[cfg(all(feature = "jemalloc", not(feature = "system-allocator")))]
+    const INNER_ALLOCATOR: InnerAllocator = tikv_jemallocator::Jemalloc;
+    #[cfg(any(
+        feature = "system-allocator",
+        not(any(feature = "mimalloc", feature = "jemalloc"))
+    ))]
+    type InnerAllocator = std::alloc::System;
+    #[cfg(any(
+        feature = "system-allocator",
+        not(any(feature = "mimalloc", feature = "jemalloc"))
+    ))]
+    const INNER_ALLOCATOR: InnerAllocator = std::alloc::System;
+    pub const ALLOC_STATS_API_VERSION: u32 = 1;
     use std::time::Instant;
     static ALLOC_TIME_NS: AtomicU64 = AtomicU64::new(0);
     static DEALLOC_TIME_NS: AtomicU64 = AtomicU64::new(0);
     static REALLOC_TIME_NS: AtomicU64 = AtomicU64::new(0);
     static ALLOC_ZEROED_TIME_NS: AtomicU64 = AtomicU64::new(0);
+    static ALLOC_COUNT: AtomicU64 = AtomicU64::new(0);
+    static DEALLOC_COUNT: AtomicU64 = AtomicU64::new(0);
+    static REALLOC_COUNT: AtomicU64 = AtomicU64::new(0);
+    static BYTES_ALLOCATED: AtomicU64 = AtomicU64::new(0);
+    static BYTES_DEALLOCATED: AtomicU64 = AtomicU64::new(0);
+    static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+    static PEAK_LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
     thread_local! { static ALLOC_TRACKING_DEPTH : Cell < u32 > = const { Cell :: new (0) } ; }
+    thread_local! { static THREAD_ALLOC_COUNT : Cell < u64 > = const { Cell :: new (0) } ; }
+    thread_local! { static THREAD_DEALLOC_COUNT : Cell < u64 > = const { Cell :: new (0) } ; }
+    thread_local! { static THREAD_REALLOC_COUNT : Cell < u64 > = const { Cell :: new (0) } ; }
+    thread_local! { static THREAD_BYTES_ALLOCATED : Cell < u64 > = const { Cell :: new (0) } ; }
+    thread_local! { static THREAD_BYTES_DEALLOCATED : Cell < u64 > = const { Cell :: new (0) } ; }
     #[must_use]
     pub struct AllocTrackingGuard;
     impl AllocTrackingGuard {
@@ -42,6 +80,70 @@ pub mod alloc_stats {
             });
         }
     }
+    #[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+    pub struct AllocCounts {
+        pub allocations: u64,
+        pub deallocations: u64,
+        pub reallocations: u64,
+        pub bytes_allocated: u64,
+        pub bytes_deallocated: u64,
+    }
+    impl AllocCounts {
+        #[inline]
+        #[must_use]
+        pub const fn since(self, earlier: Self) -> Self {
+            Self {
+                allocations: self.allocations.wrapping_sub(earlier.allocations),
+                deallocations: self.deallocations.wrapping_sub(earlier.deallocations),
+                reallocations: self.reallocations.wrapping_sub(earlier.reallocations),
+                bytes_allocated: self.bytes_allocated.wrapping_sub(earlier.bytes_allocated),
+                bytes_deallocated: self
+                    .bytes_deallocated
+                    .wrapping_sub(earlier.bytes_deallocated),
+            }
+        }
+        #[inline]
+        #[must_use]
+        pub const fn heap_events(self) -> u64 {
+            self.allocations.saturating_add(self.reallocations)
+        }
+        #[inline]
+        #[must_use]
+        pub fn net_bytes(self) -> i128 {
+            i128::from(self.bytes_allocated).saturating_sub(i128::from(self.bytes_deallocated))
+        }
+    }
+    #[must_use]
+    pub struct AllocScope {
+        start: AllocCounts,
+    }
+    impl AllocScope {
+        #[inline]
+        pub fn begin() -> Self {
+            Self {
+                start: thread_alloc_counts(),
+            }
+        }
+        #[inline]
+        #[must_use]
+        pub fn delta(&self) -> AllocCounts {
+            thread_alloc_counts().since(self.start)
+        }
+        #[inline]
+        pub fn measure<R, F: FnOnce() -> R>(action: F) -> (R, AllocCounts) {
+            let scope = Self::begin();
+            let result = action();
+            (result, scope.delta())
+        }
+    }
+    #[inline]
+    pub fn reset_alloc_counts() {
+        ALLOC_COUNT.store(0, Ordering::Relaxed);
+        DEALLOC_COUNT.store(0, Ordering::Relaxed);
+        REALLOC_COUNT.store(0, Ordering::Relaxed);
+        BYTES_ALLOCATED.store(0, Ordering::Relaxed);
+        BYTES_DEALLOCATED.store(0, Ordering::Relaxed);
+    }
     #[inline]
     pub fn reset_alloc_timing_ns() {
         ALLOC_TIME_NS.store(0, Ordering::Relaxed);
@@ -75,17 +177,104 @@ pub mod alloc_stats {
             alloc_zeroed_ns: ALLOC_ZEROED_TIME_NS.load(Ordering::Relaxed),
         }
     }
+    #[inline]
+    pub fn alloc_counts_snapshot() -> AllocCounts {
+        AllocCounts {
+            allocations: ALLOC_COUNT.load(Ordering::Relaxed),
+            deallocations: DEALLOC_COUNT.load(Ordering::Relaxed),
+            reallocations: REALLOC_COUNT.load(Ordering::Relaxed),
+            bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed),
+            bytes_deallocated: BYTES_DEALLOCATED.load(Ordering::Relaxed),
+        }
+    }
+    #[inline]
+    pub fn live_bytes() -> u64 {
+        LIVE_BYTES.load(Ordering::Relaxed)
+    }
+    #[inline]
+    pub fn peak_live_bytes() -> u64 {
+        PEAK_LIVE_BYTES.load(Ordering::Relaxed)
+    }
+    #[inline]
+    pub fn reset_peak_live_bytes() -> u64 {
+        let current = LIVE_BYTES.load(Ordering::Relaxed);
+        PEAK_LIVE_BYTES.store(current, Ordering::Relaxed);
+        current
+    }
+    #[inline]
+    pub fn thread_alloc_counts() -> AllocCounts {
+        AllocCounts {
+            allocations: THREAD_ALLOC_COUNT.with(Cell::get),
+            deallocations: THREAD_DEALLOC_COUNT.with(Cell::get),
+            reallocations: THREAD_REALLOC_COUNT.with(Cell::get),
+            bytes_allocated: THREAD_BYTES_ALLOCATED.with(Cell::get),
+            bytes_deallocated: THREAD_BYTES_DEALLOCATED.with(Cell::get),
+        }
+    }
+    type ThreadCounter = std::thread::LocalKey<Cell<u64>>;
+    fn add_thread(counter: &'static ThreadCounter, amount: u64) {
+        counter.with(|total| total.set(total.get().wrapping_add(amount)));
+    }
+    fn add_global(counter: &AtomicU64, amount: u64) {
+        counter.fetch_add(amount, Ordering::Relaxed);
+    }
+    fn byte_count(size: usize) -> u64 {
+        u64::try_from(size).unwrap_or(u64::MAX)
+    }
+    fn grow_live_bytes(bytes: u64) {
+        let live = LIVE_BYTES
+            .fetch_add(bytes, Ordering::Relaxed)
+            .wrapping_add(bytes);
+        PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+    fn shrink_live_bytes(bytes: u64) {
+        LIVE_BYTES.fetch_sub(bytes, Ordering::Relaxed);
+    }
+    fn count_alloc(size: usize) {
+        let bytes = byte_count(size);
+        grow_live_bytes(bytes);
+        add_thread(&THREAD_ALLOC_COUNT, 1);
+        add_thread(&THREAD_BYTES_ALLOCATED, bytes);
+        if tracking_enabled() {
+            add_global(&ALLOC_COUNT, 1);
+            add_global(&BYTES_ALLOCATED, bytes);
+        }
+    }
+    fn count_dealloc(size: usize) {
+        let bytes = byte_count(size);
+        shrink_live_bytes(bytes);
+        add_thread(&THREAD_DEALLOC_COUNT, 1);
+        add_thread(&THREAD_BYTES_DEALLOCATED, bytes);
+        if tracking_enabled() {
+            add_global(&DEALLOC_COUNT, 1);
+            add_global(&BYTES_DEALLOCATED, bytes);
+        }
+    }
+    fn count_realloc(old_size: usize, new_size: usize) {
+        let old_bytes = byte_count(old_size);
+        let new_bytes = byte_count(new_size);
+        if new_bytes >= old_bytes {
+            grow_live_bytes(new_bytes.wrapping_sub(old_bytes));
+        } else {
+            shrink_live_bytes(old_bytes.wrapping_sub(new_bytes));
+        }
+        add_thread(&THREAD_REALLOC_COUNT, 1);
+        add_thread(&THREAD_BYTES_ALLOCATED, new_bytes);
+        add_thread(&THREAD_BYTES_DEALLOCATED, old_bytes);
+        if tracking_enabled() {
+            add_global(&REALLOC_COUNT, 1);
+            add_global(&BYTES_ALLOCATED, new_bytes);
+            add_global(&BYTES_DEALLOCATED, old_bytes);
+        }
+    }
     fn tracking_enabled() -> bool {
         ALLOC_TRACKING_DEPTH.with(|depth| depth.get() > 0)
     }
-    fn record_alloc_time(target: &AtomicU64, elapsed: Duration) {
-        target.fetch_add(duration_to_ns(elapsed), Ordering::Relaxed);
-    }
     fn track_alloc_time<R>(target: &AtomicU64, action: impl FnOnce() -> R) -> R {
         if tracking_enabled() {
             let start = Instant::now();
             let result = action();
-            record_alloc_time(target, start.elapsed());
+            add_elapsed_ns_atomic(target, start);
             result
         } else {
             action()
@@ -93,12 +282,14 @@ pub mod alloc_stats {
     }
     #[must_use]
     pub struct TrackingAllocator {
-        inner: MiMalloc,
+        inner: InnerAllocator,
     }
     impl TrackingAllocator {
         #[inline]
         pub const fn new() -> Self {
-            Self { inner: MiMalloc }
+            Self {
+                inner: INNER_ALLOCATOR,
+            }
         }
     }
     impl Default for TrackingAllocator {
@@ -110,22 +301,26 @@ pub mod alloc_stats {
     unsafe impl GlobalAlloc for TrackingAllocator {
         #[inline]
         unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            count_alloc(layout.size());
             track_alloc_time(&ALLOC_TIME_NS, || unsafe { self.inner.alloc(layout) })
         }
         #[inline]
         unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            count_dealloc(layout.size());
             track_alloc_time(&DEALLOC_TIME_NS, || unsafe {
                 self.inner.dealloc(ptr, layout);
             });
         }
         #[inline]
         unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            count_realloc(layout.size(), new_size);
             track_alloc_time(&REALLOC_TIME_NS, || unsafe {
                 self.inner.realloc(ptr, layout, new_size)
             })
         }
         #[inline]
         unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            count_alloc(layout.size());
             track_alloc_time(&ALLOC_ZEROED_TIME_NS, || unsafe {
                 self.inner.alloc_zeroed(layout)
             })
@@ -133,9 +328,26 @@ pub mod alloc_stats {
     }
 }
 pub mod config {
-    use serde::Deserialize;
-    use std::{fs, process, thread};
-    #[derive(Debug, Deserialize, Clone, Copy)]
+    use crate::{
+        calibration::{CALIBRATION_FILE, HostProfile},
+        game_state::{
+            BoardRegion, FirstLine, GameState, PointTarget, RENJU_WIN_LEN, Rule, TerminalCondition,
+        },
+        gamedb::AdjudicationRules,
+        pns::{
+            AllocationPolicy, Budget, EffortOrdering, MoveOrder, MoveSelector, ParallelMode,
+            SearchParams, SpeculationPolicy, TieBreakPolicy,
+        },
+    };
+    use core::time::Duration;
+    use serde::{Deserialize, Serialize};
+    use serde_yaml::Value;
+    use std::{fs, path::Path, process, thread};
+    mod profiles;
+    pub type SearchPreset = profiles::SearchPreset;
+    const BYTES_PER_MB: u64 = 1024 * 1024;
+    const MAX_NUM_THREADS: usize = 1024;
+    #[derive(Debug, Deserialize, Serialize, Clone, Copy)]
     pub struct EvaluationWeights {
         pub proximity_kernel_size: usize,
         pub proximity_scale: f32,
@@ -149,6 +361,19 @@ pub mod config {
         pub score_block_live_four: f32,
         pub score_block_blocked_four: f32,
         pub score_block_live_three: f32,
+        #[serde(skip)]
+        pub fixed_point: bool,
+        #[serde(default)]
+        pub bitboard_ordering: bool,
+    }
+    #[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+    #[serde(rename_all = "snake_case")]
+    #[non_exhaustive]
+    pub enum HumanTimeout {
+        #[default]
+        Unlimited,
+        RandomMove,
+        Loss,
     }
     #[derive(Debug, Deserialize)]
     pub struct Config {
@@ -161,6 +386,84 @@ pub mod config {
         pub min_available_memory_mb: u64,
         #[serde(default = "default_memory_check_interval_ms")]
         pub memory_check_interval_ms: u64,
+        #[serde(default)]
+        pub repetition_is_draw: bool,
+        #[serde(default)]
+        pub game_database: Option<String>,
+        #[serde(default)]
+        pub psq_directory: Option<String>,
+        #[serde(default = "default_solution_directory")]
+        pub solution_directory: String,
+        #[serde(default)]
+        pub resume_file: Option<String>,
+        #[serde(default)]
+        pub preset: Option<String>,
+        #[serde(default)]
+        pub max_children: Option<usize>,
+        #[serde(default)]
+        pub eager_children: Option<usize>,
+        #[serde(default)]
+        pub node_budget: Option<u64>,
+        #[serde(default)]
+        pub time_budget_secs: Option<f64>,
+        #[serde(default)]
+        pub memory_budget_mb: Option<u64>,
+        #[serde(default)]
+        pub soft_memory_percent: Option<u64>,
+        #[serde(default)]
+        pub hot_path_allocations: AllocationPolicy,
+        #[serde(default)]
+        pub record_expansions: Option<usize>,
+        #[serde(default = "default_daemon_workers")]
+        pub daemon_workers: usize,
+        #[serde(default)]
+        pub deterministic: bool,
+        #[serde(default)]
+        pub tie_breaks: TieBreakPolicy,
+        #[serde(default)]
+        pub tie_break_seed: Option<u64>,
+        #[serde(default)]
+        pub overlay_stream: Option<String>,
+        #[serde(default)]
+        pub proof_book: Option<String>,
+        #[serde(default)]
+        pub search_region: Option<BoardRegion>,
+        #[serde(default)]
+        pub speculative_expansion: SpeculationPolicy,
+        #[serde(default)]
+        pub dfpn_epsilon: Option<f64>,
+        #[serde(default)]
+        pub parallel_mode: ParallelMode,
+        #[serde(default)]
+        pub effort_ordering: EffortOrdering,
+        #[serde(default)]
+        pub best_move_order: MoveOrder,
+        #[serde(default = "default_self_play_games")]
+        pub self_play_games: usize,
+        #[serde(default)]
+        pub points_to_win: Option<u32>,
+        #[serde(default)]
+        pub rule: Rule,
+        #[serde(default = "default_root_player")]
+        pub root_player: u8,
+        #[serde(default = "default_hint_node_budget")]
+        pub hint_node_budget: u64,
+        #[serde(default)]
+        pub human_move_seconds: Option<u64>,
+        #[serde(default)]
+        pub human_timeout: HumanTimeout,
+        #[serde(default)]
+        pub ponder_replies: usize,
+        #[serde(default = "default_ponder_node_budget")]
+        pub ponder_node_budget: u64,
+        #[serde(default)]
+        pub adjudicate_draw_after: Option<usize>,
+        #[serde(default)]
+        pub adjudicate_margin_after: Option<usize>,
+        #[serde(default = "default_adjudicate_margin_probability")]
+        pub adjudicate_margin_probability: f64,
+        #[serde(skip)]
+        pub calibration: Option<HostProfile>,
     }
     const fn default_min_available_memory_mb() -> u64 {
         1024
@@ -168,31 +471,298 @@ pub mod config {
     const fn default_memory_check_interval_ms() -> u64 {
         500
     }
+    const fn default_daemon_workers() -> usize {
+        1
+    }
+    const fn default_self_play_games() -> usize {
+        20
+    }
+    const fn default_root_player() -> u8 {
+        1
+    }
+    const fn default_hint_node_budget() -> u64 {
+        200_000
+    }
+    const fn default_ponder_node_budget() -> u64 {
+        200_000
+    }
+    const fn default_adjudicate_margin_probability() -> f64 {
+        0.9
+    }
+    fn default_solution_directory() -> String {
+        String::from("solution")
+    }
+    fn merge_yaml(base: &mut Value, overlay: Value) {
+        let Value::Mapping(overlay_map) = overlay else {
+            *base = overlay;
+            return;
+        };
+        let Some(base_map) = base.as_mapping_mut() else {
+            *base = Value::Mapping(overlay_map);
+            return;
+        };
+        for (key, value) in overlay_map {
+            if let Some(slot) = base_map.get_mut(&key) {
+                merge_yaml(slot, value);
+            } else {
+                base_map.insert(key, value);
+            }
+        }
+    }
+    fn apply_preset(user_values: Value, preset_name: Option<String>) -> Result<Value, String> {
+        let Some(name) = preset_name.or_else(|| {
+            user_values
+                .get("preset")
+                .and_then(Value::as_str)
+                .map(String::from)
+        }) else {
+            return Ok(user_values);
+        };
+        let Some(preset) = SearchPreset::from_name(&name) else {
+            let known: Vec<&str> = SearchPreset::ALL
+                .iter()
+                .map(|preset| preset.name())
+                .collect();
+            return Err(format!(
+                "未知预设: {name}，可用预设: {known}",
+                known = known.join(", ")
+            ));
+        };
+        let mut merged = preset
+            .defaults()
+            .map_err(|err| format!("生成预设 {name} 失败: {err}"))?;
+        merge_yaml(&mut merged, user_values);
+        if let Some(mapping) = merged.as_mapping_mut() {
+            mapping.insert(Value::from("preset"), Value::from(preset.name()));
+        }
+        Ok(merged)
+    }
     impl Config {
         #[inline]
+        #[must_use]
         pub fn load() -> Self {
-            let config_str = fs::read_to_string("config.yaml").unwrap_or_else(|err| {
-                eprintln!("无法读取 config.yaml: {err}");
-                process::exit(1);
-            });
-            let mut config: Self = serde_yaml::from_str(&config_str).unwrap_or_else(|err| {
-                eprintln!("解析 config.yaml 失败: {err}");
+            Self::load_with_preset(None)
+        }
+        #[inline]
+        #[must_use]
+        pub fn load_with_preset(preset_name: Option<String>) -> Self {
+            Self::load_file(Path::new("config.yaml"), preset_name).unwrap_or_else(|err| {
+                eprintln!("{err}");
                 process::exit(1);
-            });
+            })
+        }
+        #[inline]
+        pub fn load_file(path: &Path, preset_name: Option<String>) -> Result<Self, String> {
+            let name = path.display();
+            let config_str =
+                fs::read_to_string(path).map_err(|err| format!("无法读取 {name}: {err}"))?;
+            let user_values: Value = serde_yaml::from_str(&config_str)
+                .map_err(|err| format!("解析 {name} 失败: {err}"))?;
+            let merged = apply_preset(user_values, preset_name)?;
+            let mut config: Self =
+                serde_yaml::from_value(merged).map_err(|err| format!("解析 {name} 失败: {err}"))?;
+            config.calibration = HostProfile::load(Path::new(CALIBRATION_FILE))
+                .unwrap_or_else(|err| {
+                    eprintln!("读取校准文件 {CALIBRATION_FILE} 失败，将忽略: {err}");
+                    None
+                })
+                .filter(HostProfile::is_current);
             if config.num_threads == 0 {
-                config.num_threads =
-                    thread::available_parallelism().map_or(4, core::num::NonZero::get);
+                config.num_threads = config.calibration.map_or_else(
+                    || thread::available_parallelism().map_or(4, core::num::NonZero::get),
+                    |calibration| calibration.recommended_threads,
+                );
             }
+            config.evaluation.fixed_point = config.deterministic;
             config
+                .validate()
+                .map_err(|err| format!("{name} 配置无效: {err}"))?;
+            Ok(config)
+        }
+        #[inline]
+        pub fn validate(&self) -> Result<(), String> {
+            GameState::validate_shape(self.board_size, self.win_len)
+                .map_err(|err| err.to_string())?;
+            if self.num_threads > MAX_NUM_THREADS {
+                return Err(format!(
+                    "线程数 {threads} 过大，最多 {MAX_NUM_THREADS}",
+                    threads = self.num_threads
+                ));
+            }
+            if self.max_children == Some(0) {
+                return Err(String::from("max_children 必须大于 0"));
+            }
+            if self.eager_children == Some(0) {
+                return Err(String::from("eager_children 必须大于 0"));
+            }
+            if self.record_expansions == Some(0) {
+                return Err(String::from("record_expansions 必须大于 0"));
+            }
+            if self.daemon_workers == 0 {
+                return Err(String::from("daemon_workers 必须大于 0"));
+            }
+            if self.self_play_games == 0 {
+                return Err(String::from("self_play_games 必须大于 0"));
+            }
+            if self.points_to_win == Some(0) {
+                return Err(String::from("points_to_win 必须大于 0"));
+            }
+            if !matches!(self.root_player, 1 | 2) {
+                return Err(String::from("root_player 必须为 1（黑方）或 2（白方）"));
+            }
+            if self.rule == Rule::Renju && self.win_len != RENJU_WIN_LEN {
+                return Err(format!("连珠规则要求 win_len 为 {RENJU_WIN_LEN}"));
+            }
+            if self.rule == Rule::Renju && self.points_to_win.is_some() {
+                return Err(String::from("连珠规则不能与 points_to_win 同时使用"));
+            }
+            if self.hint_node_budget == 0 {
+                return Err(String::from("hint_node_budget 必须大于 0"));
+            }
+            if self
+                .soft_memory_percent
+                .is_some_and(|percent| percent > 100)
+            {
+                return Err(String::from("soft_memory_percent 不能超过 100"));
+            }
+            if self
+                .time_budget_secs
+                .is_some_and(|seconds| Duration::try_from_secs_f64(seconds).is_err())
+            {
+                return Err(String::from("time_budget_secs 必须为非负有限数"));
+            }
+            if self
+                .dfpn_epsilon
+                .is_some_and(|epsilon| !epsilon.is_finite() || epsilon < 0.0_f64)
+            {
+                return Err(String::from("dfpn_epsilon 必须为非负有限数"));
+            }
+            if self.evaluation.proximity_kernel_size == 0 {
+                return Err(String::from("proximity_kernel_size 必须大于 0"));
+            }
+            if self.deterministic && self.tie_breaks == TieBreakPolicy::Random {
+                return Err(String::from("deterministic 模式下不能随机打破平局"));
+            }
+            if self.tie_break_seed.is_some() && self.tie_breaks != TieBreakPolicy::Random {
+                return Err(String::from("tie_break_seed 需要 tie_breaks: random"));
+            }
+            if self.adjudicate_draw_after == Some(0) || self.adjudicate_margin_after == Some(0) {
+                return Err(String::from(
+                    "adjudicate_draw_after 与 adjudicate_margin_after 必须大于 0",
+                ));
+            }
+            if !(0.5_f64..=1.0_f64).contains(&self.adjudicate_margin_probability) {
+                return Err(String::from(
+                    "adjudicate_margin_probability 必须介于 0.5 与 1 之间",
+                ));
+            }
+            if self.ponder_replies > 0 && self.ponder_node_budget == 0 {
+                return Err(String::from(
+                    "启用 ponder_replies 时 ponder_node_budget 必须大于 0",
+                ));
+            }
+            if self.human_move_seconds == Some(0) {
+                return Err(String::from("human_move_seconds 必须大于 0"));
+            }
+            if self.human_move_seconds.is_none() && self.human_timeout != HumanTimeout::Unlimited {
+                return Err(String::from(
+                    "human_timeout 需要同时设置 human_move_seconds",
+                ));
+            }
+            if let Some(region) = self.search_region {
+                region.validate(self.board_size, self.win_len)?;
+            }
+            Ok(())
+        }
+        #[inline]
+        #[must_use]
+        pub fn budget(&self) -> Budget {
+            Budget {
+                nodes: self.node_budget.or_else(|| {
+                    self.calibration?.memory_node_budget(
+                        self.min_available_memory_mb.saturating_mul(BYTES_PER_MB),
+                    )
+                }),
+                time: self
+                    .time_budget_secs
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok()),
+                memory_bytes: self
+                    .memory_budget_mb
+                    .map(|megabytes| megabytes.saturating_mul(BYTES_PER_MB)),
+                soft_memory_percent: self.soft_memory_percent,
+                ..Budget::unlimited()
+            }
+        }
+        #[inline]
+        #[must_use]
+        pub fn terminal_condition(&self) -> Box<dyn TerminalCondition> {
+            match self.points_to_win {
+                Some(target) => Box::new(PointTarget { target }),
+                None => Box::new(FirstLine {}),
+            }
+        }
+        #[inline]
+        #[must_use]
+        pub const fn move_selector(&self) -> MoveSelector {
+            MoveSelector::new(self.best_move_order, self.board_size)
+        }
+        #[inline]
+        #[must_use]
+        pub const fn adjudication_rules(&self) -> AdjudicationRules {
+            AdjudicationRules {
+                draw_after_moves: self.adjudicate_draw_after,
+                margin_after_moves: self.adjudicate_margin_after,
+                margin_probability: self.adjudicate_margin_probability,
+            }
+        }
+        #[inline]
+        #[must_use]
+        pub fn search_params(&self) -> SearchParams {
+            SearchParams::new(
+                self.board_size,
+                self.win_len,
+                self.num_threads,
+                self.evaluation,
+            )
+            .with_budget(self.budget())
+            .with_max_children(self.max_children)
+            .with_eager_children(self.eager_children)
+            .with_repetition_draws(self.repetition_is_draw)
+            .with_hot_path_allocations(self.hot_path_allocations)
+            .with_expansion_recording(self.record_expansions)
+            .with_tie_breaks(self.tie_breaks)
+            .with_tie_break_seed(self.tie_break_seed)
+            .with_overlay_stream(self.overlay_stream.clone())
+            .with_proof_book(self.proof_book.clone())
+            .with_solution_directory(
+                (!self.solution_directory.is_empty()).then(|| self.solution_directory.clone()),
+            )
+            .with_region(self.search_region)
+            .with_rule(self.rule)
+            .with_root_player(self.root_player)
+            .with_speculation(self.speculative_expansion)
+            .with_dfpn_epsilon(self.dfpn_epsilon)
+            .with_move_order(self.best_move_order)
+            .with_parallel_mode(self.parallel_mode)
+            .with_effort_ordering(self.effort_ordering)
         }
     }
 }
 pub mod game_state;
+pub mod gamedb;
+pub mod interrupt;
+pub mod known_positions;
+pub mod microbench;
+pub mod openings;
 pub mod pns;
+pub mod puzzles;
+pub mod rating;
+pub mod reference;
+pub mod symmetry;
 pub mod ui;
 pub mod utils {
     use crate::checked;
-    use core::time::Duration;
+    pub(crate) mod timing;
     #[inline]
     #[must_use]
     pub fn board_index(board_size: usize, row_index: usize, column_index: usize) -> usize {
@@ -200,21 +770,6 @@ pub mod utils {
             checked::mul_usize(row_index, board_size, "utils::board_index::row_offset");
         checked::add_usize(row_offset, column_index, "utils::board_index")
     }
-    #[inline]
-    #[must_use]
-    pub(crate) fn duration_to_ns(duration: Duration) -> u64 {
-        match u64::try_from(duration.as_nanos()) {
-            Ok(value) => value,
-            Err(err) => {
-                eprintln!(
-                    "Duration 转换为纳秒失败: secs={}, subsec_nanos={}, 错误: {err}",
-                    duration.as_secs(),
-                    duration.subsec_nanos()
-                );
-                panic!("Duration 转换为纳秒失败");
-            }
-        }
-    }
     #[cfg(target_os = "windows")]
     #[repr(C)]
     struct MemoryStatusEx {
@@ -255,11 +810,12 @@ pub mod utils {
         Some(status.ull_avail_phys)
     }
     #[cfg(target_os = "linux")]
+    #[inline]
     #[must_use]
     pub fn available_memory_bytes() -> Option<u64> {
         let contents = std::fs::read_to_string("/proc/meminfo").ok()?;
-        for line in contents.lines() {
-            let line = line.trim_start();
+        for raw_line in contents.lines() {
+            let line = raw_line.trim_start();
             if let Some(rest) = line.strip_prefix("MemAvailable:") {
                 let mut parts = rest.split_whitespace();
                 let value_kb: u64 = parts.next()?.parse().ok()?;
@@ -269,6 +825,7 @@ pub mod utils {
         None
     }
     #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    #[inline]
     #[must_use]
     pub fn available_memory_bytes() -> Option<u64> {
         None
@@ -1,50 +1,232 @@
-extern crate alloc;
-use inevitable::alloc_stats::TrackingAllocator;
-#[global_allocator]
-static GLOBAL: TrackingAllocator = TrackingAllocator::new();
-use alloc::sync::Arc;
-use core::{
-    sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
+use core::{ops::RangeInclusive, time::Duration};
+use inevitable::{
+    about::engine_info,
+    alloc_stats,
+    config::Config,
+    game_state::BoardRegion,
+    gamedb::GameRecord,
+    interrupt::{CancelReason, CancellationToken},
+    ui,
+    utils::available_memory_bytes,
+};
+use std::{
+    path::{Path, PathBuf},
+    process, thread,
 };
-use inevitable::{config::Config, ui, utils::available_memory_bytes};
-use std::thread;
-fn spawn_memory_watchdog(exit_flag: Arc<AtomicBool>, config: &Config) {
+const DEFAULT_DAEMON_DIR: &str = "daemon";
+const DEFAULT_CHALLENGER_CONFIG: &str = "challenger.yaml";
+const DEFAULT_RATINGS_FILE: &str = "ratings.json";
+const DEFAULT_PUZZLE_FILE: &str = "puzzles.json";
+const DEFAULT_SGF_FILE: &str = "game.sgf";
+const DEFAULT_SUITE_DIR: &str = "benchmarks";
+const DEFAULT_PUZZLE_WIN_LEN: RangeInclusive<u64> = 5..=9;
+#[cfg(feature = "global-allocator")]
+#[global_allocator]
+static GLOBAL: alloc_stats::TrackingAllocator = alloc_stats::TrackingAllocator::new();
+fn spawn_memory_watchdog(exit_token: CancellationToken, config: &Config) {
     let min_available_memory_mb = config.min_available_memory_mb;
     let min_available_memory_bytes = min_available_memory_mb.saturating_mul(1024 * 1024);
     let poll_interval = Duration::from_millis(config.memory_check_interval_ms.max(1));
     thread::spawn(move || {
         loop {
-            if exit_flag.load(Ordering::SeqCst) {
+            if exit_token.is_cancelled() {
                 return;
             }
             if let Some(available) = available_memory_bytes()
                 && available < min_available_memory_bytes
             {
-                eprintln!("剩余内存不足 {min_available_memory_mb}MB，程序将退出。");
-                exit_flag.store(true, Ordering::SeqCst);
-                return;
+                if exit_token.cancel_children(CancelReason::Memory) == 0 {
+                    eprintln!("剩余内存不足 {min_available_memory_mb}MB，程序将退出。");
+                    exit_token.cancel(CancelReason::Memory);
+                    return;
+                }
+                eprintln!("剩余内存不足 {min_available_memory_mb}MB，已中止当前搜索。");
             }
             thread::sleep(poll_interval);
         }
     });
 }
+fn preset_from_args() -> Option<String> {
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        if let Some(name) = arg.strip_prefix("--preset=") {
+            return Some(String::from(name));
+        }
+        if arg == "--preset" {
+            return args.next();
+        }
+    }
+    None
+}
+fn solve_position_from_args() -> Option<String> {
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        if let Some(text) = arg.strip_prefix("--solve=") {
+            return Some(String::from(text));
+        }
+        if arg == "--solve" {
+            return Some(args.next().unwrap_or_default());
+        }
+    }
+    None
+}
+fn path_from_args(flag: &str, default_path: &str) -> Option<PathBuf> {
+    let mut args = std::env::args().peekable();
+    while let Some(arg) = args.next() {
+        if let Some(path) = arg
+            .strip_prefix(flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(PathBuf::from(path));
+        }
+        if arg == flag {
+            let path = args
+                .next_if(|next| !next.starts_with("--"))
+                .unwrap_or_else(|| String::from(default_path));
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+fn win_len_range_from_args() -> Option<RangeInclusive<u64>> {
+    let mut args = std::env::args();
+    let mut value = None;
+    while let Some(arg) = args.next() {
+        if let Some(text) = arg.strip_prefix("--win-len=") {
+            value = Some(String::from(text));
+        } else if arg == "--win-len" {
+            value = args.next();
+        }
+    }
+    let Some(text) = value else {
+        return Some(DEFAULT_PUZZLE_WIN_LEN);
+    };
+    let parsed = text.split_once('-').map_or_else(
+        || text.trim().parse().map(|exact| exact..=exact),
+        |(min, max)| Ok(min.trim().parse()?..=max.trim().parse()?),
+    );
+    parsed
+        .inspect_err(|err: &core::num::ParseIntError| {
+            eprintln!("无法解析胜利步数范围 {text}: {err}，应为 N 或 MIN-MAX");
+        })
+        .ok()
+}
+fn region_from_args(config: &Config) -> Result<Option<BoardRegion>, String> {
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        let text = if let Some(text) = arg.strip_prefix("--region=") {
+            String::from(text)
+        } else if arg == "--region" {
+            args.next()
+                .ok_or_else(|| String::from("--region 需要参数 行,列-行,列"))?
+        } else {
+            continue;
+        };
+        let region = BoardRegion::parse(&text)?;
+        region.validate(config.board_size, config.win_len)?;
+        return Ok(Some(region));
+    }
+    Ok(config.search_region)
+}
+fn plot_data_from_args() -> Option<PathBuf> {
+    path_from_args("--plot-data", "plot_data.csv")
+}
 fn main() {
-    let config = Config::load();
+    if let Some(dir) = path_from_args("--daemon-status", DEFAULT_DAEMON_DIR) {
+        ui::print_daemon_status(&dir);
+        return;
+    }
+    if std::env::args().any(|arg| arg == "--doctor" || arg == "doctor") {
+        println!("{info}", info = engine_info());
+        if !ui::run_doctor(preset_from_args(), Path::new(DEFAULT_DAEMON_DIR)) {
+            process::exit(1);
+        }
+        return;
+    }
+    let mut config = Config::load_with_preset(preset_from_args());
+    let protocol_mode = std::env::args().any(|arg| arg == "--protocol" || arg == "protocol");
+    let solve_position = solve_position_from_args();
+    let scripted_mode = protocol_mode || solve_position.is_some();
+    if !scripted_mode {
+        println!("{info}", info = engine_info());
+    }
+    let verify_known_mode = std::env::args().any(|arg| arg == "--verify-known");
+    let scaling_benchmark_mode = std::env::args().any(|arg| arg == "--scaling-benchmark");
     let benchmark_mode = std::env::args().any(|arg| arg == "--benchmark" || arg == "--bench");
-    let exit_flag = Arc::new(AtomicBool::new(false));
-    let flag = Arc::clone(&exit_flag);
+    let analyze_mode = std::env::args().any(|arg| arg == "--analyze" || arg == "analyze");
+    let arbitrate_mode = std::env::args().any(|arg| arg == "--arbitrate" || arg == "arbitrate");
+    let train_mode = std::env::args().any(|arg| arg == "--train" || arg == "train");
+    let calibrate_mode = std::env::args().any(|arg| arg == "--calibrate" || arg == "calibrate");
+    let exit_token = CancellationToken::new();
+    let handler_token = exit_token.clone();
     if let Err(err) = ctrlc::set_handler(move || {
-        flag.store(true, Ordering::SeqCst);
+        handler_token.cancel(CancelReason::User);
         println!("\n收到 Ctrl+C，正在退出...");
     }) {
         eprintln!("无法设置 Ctrl+C 处理程序: {err}");
         panic!("无法设置 Ctrl+C 处理程序");
     }
-    spawn_memory_watchdog(Arc::clone(&exit_flag), &config);
-    if benchmark_mode {
-        ui::run_benchmark(&exit_flag, &config);
+    spawn_memory_watchdog(exit_token.clone(), &config);
+    if calibrate_mode || (config.calibration.is_none() && !scripted_mode) {
+        let calibrated = ui::run_calibration(&exit_token, &config);
+        if calibrate_mode {
+            return;
+        }
+        if calibrated {
+            config = Config::load_with_preset(preset_from_args());
+        }
+    }
+    match region_from_args(&config) {
+        Ok(region) => config.search_region = region,
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    }
+    if protocol_mode {
+        ui::run_protocol(&exit_token, &config);
+    } else if let Some(position) = solve_position {
+        if !ui::run_solve(&exit_token, &config, &position) {
+            process::exit(1);
+        }
+    } else if let Some(dir) = path_from_args("--daemon", DEFAULT_DAEMON_DIR) {
+        ui::run_daemon(&exit_token, &config, &dir);
+    } else if let Some(output) = path_from_args("--generate-puzzles", DEFAULT_PUZZLE_FILE) {
+        let Some(win_len_range) = win_len_range_from_args() else {
+            process::exit(1);
+        };
+        ui::run_puzzle_generator(&exit_token, &config, &output, &win_len_range);
+    } else if let Some(challenger) = path_from_args("--self-play", DEFAULT_CHALLENGER_CONFIG) {
+        let ratings = path_from_args("--ratings", DEFAULT_RATINGS_FILE)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_RATINGS_FILE));
+        ui::run_self_play(&exit_token, &config, &challenger, &ratings);
+    } else if verify_known_mode {
+        if !ui::run_known_verification(&exit_token, &config) {
+            process::exit(1);
+        }
+    } else if scaling_benchmark_mode {
+        ui::run_scaling_benchmark(&exit_token, &config);
+    } else if benchmark_mode {
+        ui::run_benchmark(
+            &exit_token,
+            &config,
+            plot_data_from_args().as_deref(),
+            path_from_args("--suite", DEFAULT_SUITE_DIR).as_deref(),
+        );
+    } else if analyze_mode {
+        ui::run_analysis(&exit_token, &config);
+    } else if arbitrate_mode {
+        ui::run_arbitration(&exit_token, &config);
+    } else if train_mode {
+        let puzzles = path_from_args("--puzzles", DEFAULT_PUZZLE_FILE);
+        ui::run_trainer(&exit_token, &config, puzzles.as_deref());
     } else {
-        ui::play_game(&exit_flag, &config);
+        let initial = path_from_args("--load", DEFAULT_SGF_FILE).map(|path| {
+            GameRecord::load_sgf(&path, config.win_len).unwrap_or_else(|err| {
+                eprintln!("{err}");
+                process::exit(1);
+            })
+        });
+        ui::play_game(&exit_token, &config, initial.as_ref());
     }
 }
@@ -0,0 +1,316 @@
+use crate::{
+    checked,
+    config::EvaluationWeights,
+    game_state::{BitboardWorkspace, Board, Coord, GameState, GomokuRules, ZobristHasher},
+    interrupt::CancellationToken,
+    pns::{Budget, SharedTree, ThreadLocalContext, to_f64},
+    utils::timing::time_it,
+};
+use alloc::sync::Arc;
+use core::ops::ControlFlow;
+type CannedStones = &'static [(usize, usize, u8)];
+type BenchFn = fn(&CannedPosition, MicroBenchOptions) -> MicroBenchResult;
+const CANNED_SPAN: usize = 5;
+const CANNED_POSITIONS: [(&str, CannedStones); 3] = [
+    ("opening", &[(2, 2, 1), (1, 2, 2)]),
+    (
+        "midgame",
+        &[
+            (2, 2, 1),
+            (1, 2, 2),
+            (2, 3, 1),
+            (3, 1, 2),
+            (2, 1, 1),
+            (2, 4, 2),
+        ],
+    ),
+    (
+        "tactical",
+        &[
+            (2, 2, 1),
+            (1, 1, 2),
+            (2, 3, 1),
+            (3, 3, 2),
+            (2, 4, 1),
+            (0, 0, 2),
+            (3, 2, 1),
+            (4, 4, 2),
+        ],
+    ),
+];
+#[derive(Clone, Copy)]
+pub struct MicroBenchOptions {
+    pub warmup_samples: usize,
+    pub samples: usize,
+    pub iterations: usize,
+}
+impl Default for MicroBenchOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            warmup_samples: 3,
+            samples: 20,
+            iterations: 200,
+        }
+    }
+}
+#[derive(Clone)]
+pub struct MicroBenchResult {
+    pub name: String,
+    pub samples: usize,
+    pub iterations: usize,
+    pub mean_ns: f64,
+    pub std_dev_ns: f64,
+    pub min_ns: f64,
+    pub max_ns: f64,
+}
+impl MicroBenchResult {
+    fn from_samples(name: String, iterations: usize, samples: &[f64]) -> Self {
+        let count = to_f64(checked::usize_to_u64(
+            samples.len(),
+            "MicroBenchResult::from_samples::count",
+        ));
+        let mean_ns = if samples.is_empty() {
+            0.0_f64
+        } else {
+            samples.iter().sum::<f64>() / count
+        };
+        let std_dev_ns = if samples.len() > 1 {
+            let squared_sum = samples
+                .iter()
+                .map(|sample| (sample - mean_ns) * (sample - mean_ns))
+                .sum::<f64>();
+            (squared_sum / (count - 1.0_f64)).sqrt()
+        } else {
+            0.0_f64
+        };
+        let min_ns = samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let max_ns = samples.iter().copied().fold(0.0_f64, f64::max);
+        Self {
+            name,
+            samples: samples.len(),
+            iterations,
+            mean_ns,
+            std_dev_ns,
+            min_ns: if samples.is_empty() { 0.0_f64 } else { min_ns },
+            max_ns,
+        }
+    }
+}
+struct CannedPosition {
+    name: &'static str,
+    game_state: GameState,
+}
+fn canned_positions(
+    board_size: usize,
+    win_len: usize,
+    evaluation: EvaluationWeights,
+) -> Vec<CannedPosition> {
+    if board_size < CANNED_SPAN {
+        return Vec::new();
+    }
+    let origin = checked::div_usize(
+        checked::sub_usize(
+            board_size,
+            CANNED_SPAN,
+            "microbench::canned_positions::slack",
+        ),
+        2_usize,
+        "microbench::canned_positions::origin",
+    );
+    let hasher = Arc::new(ZobristHasher::new(board_size));
+    CANNED_POSITIONS
+        .iter()
+        .map(|&(name, stones)| {
+            let mut board = Board::new(board_size);
+            for &(row_offset, column_offset, player) in stones {
+                let row_index =
+                    checked::add_usize(origin, row_offset, "microbench::canned_positions::row");
+                let column_index = checked::add_usize(
+                    origin,
+                    column_offset,
+                    "microbench::canned_positions::column",
+                );
+                if board.set((row_index, column_index), player).is_none() {
+                    eprintln!("微基准预设局面越界: ({row_index}, {column_index})");
+                    panic!("微基准预设局面越界");
+                }
+            }
+            CannedPosition {
+                name,
+                game_state: GameState::new(
+                    board.into_cells(),
+                    board_size,
+                    Arc::clone(&hasher),
+                    1,
+                    win_len,
+                    evaluation,
+                ),
+            }
+        })
+        .collect()
+}
+fn measure<F>(name: String, options: MicroBenchOptions, mut iteration: F) -> MicroBenchResult
+where
+    F: FnMut() -> u64,
+{
+    let iterations = options.iterations.max(1);
+    let iterations_f64 = to_f64(checked::usize_to_u64(
+        iterations,
+        "microbench::measure::iterations",
+    ));
+    for _ in 0..options.warmup_samples {
+        for _ in 0..iterations {
+            iteration();
+        }
+    }
+    let mut samples = Vec::with_capacity(options.samples);
+    for _ in 0..options.samples {
+        let mut total_ns = 0_u64;
+        for _ in 0..iterations {
+            total_ns = checked::add_u64(total_ns, iteration(), "microbench::measure::total_ns");
+        }
+        samples.push(to_f64(total_ns) / iterations_f64);
+    }
+    MicroBenchResult::from_samples(name, iterations, &samples)
+}
+fn time_ns<F: FnOnce()>(operation: F) -> u64 {
+    let ((), elapsed) = time_it(operation);
+    elapsed
+}
+fn legal_moves(game_state: &GameState) -> Vec<Coord> {
+    let mut ctx = ThreadLocalContext::new(game_state.clone(), 0);
+    ctx.refresh_legal_moves(1);
+    ctx.scratch.legal_moves.clone()
+}
+fn bench_make_undo(position: &CannedPosition, options: MicroBenchOptions) -> MicroBenchResult {
+    let moves = legal_moves(&position.game_state);
+    let mut game_state = position.game_state.clone();
+    measure(format!("make_undo/{}", position.name), options, || {
+        time_ns(|| {
+            for &mov in &moves {
+                GomokuRules::make_move(
+                    &mut game_state.position,
+                    &mut game_state.move_cache,
+                    mov,
+                    1,
+                );
+                GomokuRules::undo_move(
+                    &mut game_state.position,
+                    &mut game_state.move_cache,
+                    mov,
+                    1,
+                );
+            }
+        })
+    })
+}
+fn bench_expand_node(position: &CannedPosition, options: MicroBenchOptions) -> MicroBenchResult {
+    let cancel = CancellationToken::new();
+    let root_hash = position.game_state.position.get_canonical_hash();
+    let root_pos_hash = position.game_state.position.get_hash();
+    measure(format!("expand_node/{}", position.name), options, || {
+        let tree = SharedTree::with_tt_and_stop(
+            1,
+            root_hash,
+            (
+                root_pos_hash,
+                position.game_state.position.board.stone_count(),
+            ),
+            None,
+            cancel.clone(),
+            None,
+            None,
+            Budget::unlimited(),
+        );
+        let mut ctx = ThreadLocalContext::new(position.game_state.clone(), 0);
+        tree.evaluate_node(&tree.root, &ctx);
+        let elapsed_ns = time_ns(|| {
+            tree.expand_node(&tree.root, &mut ctx);
+        });
+        tree.flush_stats(&mut ctx);
+        elapsed_ns
+    })
+}
+fn bench_legal_moves(position: &CannedPosition, options: MicroBenchOptions) -> MicroBenchResult {
+    let mut ctx = ThreadLocalContext::new(position.game_state.clone(), 0);
+    measure(format!("legal_moves/{}", position.name), options, || {
+        time_ns(|| {
+            ctx.refresh_legal_moves(1);
+        })
+    })
+}
+fn bench_for_each_legal_move(
+    position: &CannedPosition,
+    options: MicroBenchOptions,
+) -> MicroBenchResult {
+    let mut ctx = ThreadLocalContext::new(position.game_state.clone(), 0);
+    let mut checksum = 0_usize;
+    let result = measure(
+        format!("for_each_legal_move/{}", position.name),
+        options,
+        || {
+            time_ns(|| {
+                ctx.for_each_legal_move(1, |(row, column), _score| {
+                    checksum ^= row ^ column;
+                    ControlFlow::Continue(())
+                });
+            })
+        },
+    );
+    core::hint::black_box(checksum);
+    result
+}
+fn bench_dilate(position: &CannedPosition, options: MicroBenchOptions) -> MicroBenchResult {
+    let mut game_state = position.game_state.clone();
+    let mut workspace = BitboardWorkspace::new(game_state.position.bitboard.num_words());
+    measure(format!("dilate_into/{}", position.name), options, || {
+        time_ns(|| {
+            GomokuRules::rebuild_candidate_moves(
+                &game_state.position,
+                &mut game_state.move_cache,
+                &mut workspace,
+            );
+        })
+    })
+}
+fn bench_canonical_hash(position: &CannedPosition, options: MicroBenchOptions) -> MicroBenchResult {
+    let mut checksum = 0_u64;
+    let result = measure(format!("canonical_hash/{}", position.name), options, || {
+        time_ns(|| {
+            checksum ^= position.game_state.position.get_canonical_hash().bits();
+        })
+    });
+    core::hint::black_box(checksum);
+    result
+}
+#[inline]
+#[must_use]
+pub fn run(
+    board_size: usize,
+    win_len: usize,
+    evaluation: EvaluationWeights,
+    options: MicroBenchOptions,
+    filter: Option<&str>,
+) -> Vec<MicroBenchResult> {
+    let benches: [(&str, BenchFn); 6] = [
+        ("make_undo", bench_make_undo),
+        ("expand_node", bench_expand_node),
+        ("legal_moves", bench_legal_moves),
+        ("for_each_legal_move", bench_for_each_legal_move),
+        ("dilate_into", bench_dilate),
+        ("canonical_hash", bench_canonical_hash),
+    ];
+    let positions = canned_positions(board_size, win_len, evaluation);
+    let mut results = Vec::new();
+    for &(bench_name, bench) in &benches {
+        for position in &positions {
+            let full_name = format!("{bench_name}/{}", position.name);
+            if filter.is_some_and(|pattern| !full_name.contains(pattern)) {
+                continue;
+            }
+            results.push(bench(position, options));
+        }
+    }
+    results
+}
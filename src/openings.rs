@@ -0,0 +1,171 @@
+use crate::{
+    checked,
+    game_state::{Board, Coord, ZobristHasher},
+    symmetry::symmetric_coords,
+};
+use alloc::collections::BTreeMap;
+const FIRST_PLAYER: u8 = 1;
+const SECOND_PLAYER: u8 = 2;
+const OPENING_PLIES: usize = 3;
+const MIN_BOARD_SIZE: usize = 5;
+type Offset = (isize, isize);
+const DIRECT: Offset = (-1, 0);
+const INDIRECT: Offset = (-1, 1);
+struct OpeningShape {
+    name: &'static str,
+    second: Offset,
+    third: Offset,
+}
+const fn shape(name: &'static str, second: Offset, third: Offset) -> OpeningShape {
+    OpeningShape {
+        name,
+        second,
+        third,
+    }
+}
+const THREE_STONE_OPENINGS: [OpeningShape; 26] = [
+    shape("寒星", DIRECT, (-2, 0)),
+    shape("溪月", DIRECT, (-2, 1)),
+    shape("疏星", DIRECT, (-2, 2)),
+    shape("花月", DIRECT, (-1, 1)),
+    shape("残月", DIRECT, (-1, 2)),
+    shape("雨月", DIRECT, (0, 1)),
+    shape("金星", DIRECT, (0, 2)),
+    shape("松月", DIRECT, (1, 1)),
+    shape("丘月", DIRECT, (1, 0)),
+    shape("新月", DIRECT, (1, 2)),
+    shape("瑞星", DIRECT, (2, 2)),
+    shape("山月", DIRECT, (2, 1)),
+    shape("游星", DIRECT, (2, 0)),
+    shape("长星", INDIRECT, (-2, 2)),
+    shape("峡月", INDIRECT, (-1, 2)),
+    shape("恒星", INDIRECT, (0, 2)),
+    shape("水月", INDIRECT, (0, 1)),
+    shape("流星", INDIRECT, (2, 2)),
+    shape("云月", INDIRECT, (1, 2)),
+    shape("浦月", INDIRECT, (1, 1)),
+    shape("岚月", INDIRECT, (2, 1)),
+    shape("银月", INDIRECT, (1, 0)),
+    shape("明星", INDIRECT, (2, 0)),
+    shape("斜月", INDIRECT, (1, -1)),
+    shape("名月", INDIRECT, (2, -1)),
+    shape("彗星", INDIRECT, (2, -2)),
+];
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OpeningMatch {
+    pub name: &'static str,
+    pub plies: usize,
+    pub transposed: bool,
+}
+impl OpeningMatch {
+    #[inline]
+    #[must_use]
+    pub fn label(&self) -> String {
+        if self.transposed {
+            format!("{name}（换序形成）", name = self.name)
+        } else {
+            String::from(self.name)
+        }
+    }
+}
+pub struct OpeningBook {
+    board_size: usize,
+    hasher: ZobristHasher,
+    names: BTreeMap<u64, &'static str>,
+}
+impl OpeningBook {
+    #[inline]
+    #[must_use]
+    pub fn new(board_size: usize) -> Option<Self> {
+        if board_size < MIN_BOARD_SIZE {
+            return None;
+        }
+        let mut book = Self {
+            board_size,
+            hasher: ZobristHasher::new(board_size),
+            names: BTreeMap::new(),
+        };
+        let center = book.center();
+        book.insert("天元", &[center]);
+        for (name, second) in [("直指", DIRECT), ("斜指", INDIRECT)] {
+            let stones = book.offset_stones(&[second])?;
+            book.insert(name, &stones);
+        }
+        for opening in &THREE_STONE_OPENINGS {
+            let stones = book.offset_stones(&[opening.second, opening.third])?;
+            book.insert(opening.name, &stones);
+        }
+        Some(book)
+    }
+    #[inline]
+    #[must_use]
+    pub const fn board_size(&self) -> usize {
+        self.board_size
+    }
+    #[inline]
+    #[must_use]
+    pub fn detect(&self, moves: &[(Coord, u8)]) -> Option<OpeningMatch> {
+        let mut board = Board::new(self.board_size);
+        let mut expected_player = FIRST_PLAYER;
+        let mut found = None;
+        for (index, &((row, column), player)) in moves.iter().take(OPENING_PLIES).enumerate() {
+            if player != expected_player {
+                break;
+            }
+            if board.set((row, column), player) != Some(0) {
+                break;
+            }
+            expected_player = checked::opponent_player(player, "OpeningBook::detect");
+            if let Some(&name) = self
+                .names
+                .get(&self.canonical_hash(&board, expected_player))
+            {
+                found = Some(OpeningMatch {
+                    name,
+                    plies: checked::add_usize(index, 1_usize, "OpeningBook::detect::plies"),
+                    transposed: false,
+                });
+            }
+        }
+        found.map(|opening| OpeningMatch {
+            transposed: moves
+                .first()
+                .is_some_and(|&(first, _)| !self.is_center(first)),
+            ..opening
+        })
+    }
+    fn center(&self) -> Coord {
+        let middle = checked::div_usize(self.board_size, 2_usize, "OpeningBook::center");
+        (middle, middle)
+    }
+    fn is_center(&self, coord: Coord) -> bool {
+        symmetric_coords(self.board_size, self.center()).contains(&coord)
+    }
+    fn offset_stones(&self, offsets: &[Offset]) -> Option<Vec<Coord>> {
+        let (center_row, center_column) = self.center();
+        let mut stones = vec![self.center()];
+        for &(row_offset, column_offset) in offsets {
+            stones.push((
+                center_row.checked_add_signed(row_offset)?,
+                center_column.checked_add_signed(column_offset)?,
+            ));
+        }
+        Some(stones)
+    }
+    fn insert(&mut self, name: &'static str, stones: &[Coord]) {
+        let mut board = Board::new(self.board_size);
+        let mut player = FIRST_PLAYER;
+        for &coord in stones {
+            board.set(coord, player);
+            player = checked::opponent_player(player, "OpeningBook::insert");
+        }
+        let hash = self.canonical_hash(&board, player);
+        self.names.insert(hash, name);
+    }
+    fn canonical_hash(&self, board: &Board, side_to_move: u8) -> u64 {
+        self.hasher.canonical_from_symmetric(
+            self.hasher.symmetric_board_hashes(board),
+            side_to_move == SECOND_PLAYER,
+        )
+    }
+}
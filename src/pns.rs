@@ -1,8 +1,22 @@
+mod budget;
 mod context;
+mod embedding;
+mod enumeration;
+mod hot_path;
 mod manager;
+mod move_selector;
 mod node;
+mod overlay;
+mod persistence;
+mod pressure;
+mod progress;
+mod proof_book;
+mod recording;
+mod root_filter;
+mod search_handle;
 mod shared_tree;
 mod stats_def;
+mod time_manager;
 mod worker_pool;
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TTEntry {
@@ -10,13 +24,78 @@ pub struct TTEntry {
     pub dn: u64,
     pub win_len: u64,
 }
+pub type AllocationPolicy = hot_path::AllocationPolicy;
+pub type Arbitration = manager::Arbitration;
+pub type BackgroundSearch = manager::BackgroundSearch;
+pub type Budget = budget::Budget;
+pub type BudgetLimit = budget::BudgetLimit;
+pub type ChildDiff = manager::ChildDiff;
+pub type ChildSummary = manager::ChildSummary;
+pub type DebugBundle = manager::DebugBundle;
+pub type DebugParams = manager::DebugParams;
+pub type DegradationRecord = pressure::DegradationRecord;
+pub type DifficultyEstimate = manager::DifficultyEstimate;
+pub type HeuristicWinEstimate = manager::HeuristicWinEstimate;
+pub type MemoryDegradation = pressure::MemoryDegradation;
+pub type MoveClass = manager::MoveClass;
+pub type MoveOrder = move_selector::MoveOrder;
+pub type MoveSelector = move_selector::MoveSelector;
 pub type ParallelSolver = manager::ParallelSolver;
+pub type ReplayDivergence = recording::ReplayDivergence;
+pub type ReplaySummary = recording::ReplaySummary;
+pub type MappedProofBook = proof_book::MappedProofBook;
+pub type ProofSnapshot = persistence::ProofSnapshot;
+pub type ProvenEntry = persistence::ProvenEntry;
+pub type ProgressCallback = progress::ProgressCallback;
+pub type SearchProgress = progress::SearchProgress;
+pub type SubBoardSolution = embedding::SubBoardSolution;
+pub type RootFilterMode = root_filter::RootFilterMode;
+pub type RootMoveFilter = root_filter::RootMoveFilter;
+pub type AllocReport = manager::AllocReport;
+pub type BenchmarkResult = manager::BenchmarkResult;
+pub type DepthReport = manager::DepthReport;
+pub type DepthSample = manager::DepthSample;
+pub type SearchOutcome = manager::SearchOutcome;
+pub type SearchReport = manager::SearchReport;
+pub type SearchResult = manager::SearchResult;
+pub type SolutionSummary = manager::SolutionSummary;
+pub type ProofDag = manager::ProofDag;
+pub type ProofDagNode = manager::ProofDagNode;
 pub type SearchParams = manager::SearchParams;
+pub type SearchHandle = search_handle::SearchHandle;
+pub type ShardOccupancy = shared_tree::ShardOccupancy;
+pub type EmbedError = embedding::EmbedError;
+pub type EmbedSummary = embedding::EmbedSummary;
+pub type EnumerationLevel = enumeration::EnumerationLevel;
+pub type EnumerationSummary = enumeration::EnumerationSummary;
+pub type EngineRun = manager::EngineRun;
+pub type ExpansionLog = recording::ExpansionLog;
+pub type ExpansionRecord = recording::ExpansionRecord;
+pub type FrontierRefresh = shared_tree::FrontierRefresh;
+pub type DfpnThresholds = shared_tree::DfpnThresholds;
+pub type NodeSummary = shared_tree::NodeSummary;
+pub type TreeSnapshot = shared_tree::TreeSnapshot;
+pub type TieBreakPolicy = shared_tree::TieBreakPolicy;
+pub type SpeculationPolicy = shared_tree::SpeculationPolicy;
+pub type EffortOrdering = shared_tree::EffortOrdering;
+pub type NodeDiff = manager::NodeDiff;
 pub type NodeTable = shared_tree::NodeTable;
+pub(crate) type NodeRef = node::NodeRef;
 pub(crate) type SharedTree = shared_tree::SharedTree;
+pub(crate) type ThreadLocalContext = context::ThreadLocalContext;
+pub type TimeControl = time_manager::TimeControl;
+pub type TimeDecision = time_manager::TimeDecision;
+pub type TimeDecisionRecord = time_manager::TimeDecisionRecord;
+pub type TimeManager = time_manager::TimeManager;
 pub type TranspositionTable = shared_tree::TranspositionTable;
+pub type Verdict = manager::Verdict;
+pub type TimingEntry = stats_def::TimingEntry;
 pub(crate) type TimingStats = stats_def::TimingStats;
 pub(crate) type TreeStatsAccumulator = stats_def::TreeStatsAccumulator;
 pub(crate) type TreeStatsAtomic = stats_def::TreeStatsAtomic;
-pub(crate) type TreeStatsSnapshot = stats_def::TreeStatsSnapshot;
+pub type TreeStatsSnapshot = stats_def::TreeStatsSnapshot;
 pub(crate) type WorkerPool = worker_pool::WorkerPool;
+pub type ParallelMode = worker_pool::ParallelMode;
+pub(crate) use manager::LOG_FILE_NAME;
+pub(crate) use shared_tree::probe_line;
+pub(crate) use stats_def::to_f64;
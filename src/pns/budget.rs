@@ -0,0 +1,150 @@
+use core::{
+    sync::atomic::{AtomicU8, Ordering},
+    time::Duration,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+const LIMIT_NONE: u8 = 0;
+pub const BUDGET_CHECK_INTERVAL: u32 = 256;
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Budget {
+    pub depth: Option<usize>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+    pub memory_bytes: Option<u64>,
+    pub soft_memory_percent: Option<u64>,
+}
+impl Budget {
+    #[inline]
+    #[must_use]
+    pub const fn unlimited() -> Self {
+        Self {
+            depth: None,
+            nodes: None,
+            time: None,
+            memory_bytes: None,
+            soft_memory_percent: None,
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn is_unlimited(&self) -> bool {
+        self.depth.is_none()
+            && self.nodes.is_none()
+            && self.time.is_none()
+            && self.memory_bytes.is_none()
+    }
+    #[inline]
+    #[must_use]
+    pub fn soft_memory_bytes(&self) -> Option<u64> {
+        let max_memory_bytes = self.memory_bytes?;
+        let percent = self.soft_memory_percent?.min(100);
+        Some(
+            max_memory_bytes
+                .saturating_mul(percent)
+                .checked_div(100)
+                .unwrap_or(0),
+        )
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub enum BudgetLimit {
+    Depth,
+    Nodes,
+    Time,
+    Memory,
+}
+impl BudgetLimit {
+    #[inline]
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Depth => "深度",
+            Self::Nodes => "节点数",
+            Self::Time => "时间",
+            Self::Memory => "内存",
+        }
+    }
+    const fn code(self) -> u8 {
+        match self {
+            Self::Depth => 1,
+            Self::Nodes => 2,
+            Self::Time => 3,
+            Self::Memory => 4,
+        }
+    }
+    const fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(Self::Depth),
+            2 => Some(Self::Nodes),
+            3 => Some(Self::Time),
+            4 => Some(Self::Memory),
+            _ => None,
+        }
+    }
+}
+pub(crate) struct BudgetTracker {
+    budget: Budget,
+    start: Instant,
+    limit_reached: AtomicU8,
+}
+impl BudgetTracker {
+    pub(crate) fn new(budget: Budget) -> Self {
+        Self {
+            budget,
+            start: Instant::now(),
+            limit_reached: AtomicU8::new(LIMIT_NONE),
+        }
+    }
+    pub(crate) const fn budget(&self) -> Budget {
+        self.budget
+    }
+    pub(crate) fn limit_reached(&self) -> Option<BudgetLimit> {
+        BudgetLimit::from_code(self.limit_reached.load(Ordering::Acquire))
+    }
+    pub(crate) fn is_exhausted(&self) -> bool {
+        self.limit_reached.load(Ordering::Acquire) != LIMIT_NONE
+    }
+    pub(crate) fn record(&self, limit: BudgetLimit) {
+        let _previous = self.limit_reached.compare_exchange(
+            LIMIT_NONE,
+            limit.code(),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+    }
+    pub(crate) fn exceeds_depth(&self, depth: usize) -> bool {
+        self.budget.depth.is_some_and(|max_depth| depth > max_depth)
+    }
+    pub(crate) fn check<F>(&self, nodes: u64, memory_bytes: F) -> Option<BudgetLimit>
+    where
+        F: FnOnce() -> u64,
+    {
+        if let Some(limit) = self.limit_reached() {
+            return Some(limit);
+        }
+        let exceeded = if self
+            .budget
+            .nodes
+            .is_some_and(|max_nodes| nodes >= max_nodes)
+        {
+            Some(BudgetLimit::Nodes)
+        } else if self
+            .budget
+            .time
+            .is_some_and(|max_time| self.start.elapsed() >= max_time)
+        {
+            Some(BudgetLimit::Time)
+        } else if let Some(max_memory_bytes) = self.budget.memory_bytes
+            && memory_bytes() >= max_memory_bytes
+        {
+            Some(BudgetLimit::Memory)
+        } else {
+            None
+        };
+        if let Some(limit) = exceeded {
+            self.record(limit);
+        }
+        self.limit_reached()
+    }
+}
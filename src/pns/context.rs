@@ -1,14 +1,16 @@
-use super::node::NodeRef;
+use super::{TreeStatsAccumulator, node::NodeRef};
 use crate::{
     checked,
     game_state::{
-        BitboardWorkspace, GameState, GomokuRules, MoveApplyTiming, MoveGenBuffers, MoveGenTiming,
+        BitboardWorkspace, CanonicalHash, Coord, GameState, GomokuRules, MoveApplyTiming,
+        MoveGenBuffers, MoveGenTiming, PositionHash, ProximityPlanes, ProximityScores,
     },
 };
 use alloc::collections::VecDeque;
+use core::ops::ControlFlow;
 use hashbrown::HashMap;
 const NODE_CACHE_CAPACITY: usize = 1024;
-type NodeKey = (u64, usize);
+type NodeKey = (PositionHash, usize);
 pub(crate) struct LocalNodeCache {
     capacity: usize,
     entries: HashMap<NodeKey, NodeRef>,
@@ -51,6 +53,26 @@ impl LocalNodeCache {
         self.order.push_back(*key);
     }
 }
+pub(crate) struct ExpansionScratch {
+    pub(crate) bitboard_workspace: BitboardWorkspace,
+    pub(crate) forcing_bits: Vec<u64>,
+    pub(crate) scored_moves: Vec<(Coord, f32)>,
+    pub(crate) legal_moves: Vec<Coord>,
+    pub(crate) move_order: Vec<u32>,
+    pub(crate) proximity_planes: ProximityPlanes,
+}
+impl ExpansionScratch {
+    fn new(num_words: usize, board_cells: usize) -> Self {
+        Self {
+            bitboard_workspace: BitboardWorkspace::new(num_words),
+            forcing_bits: vec![0_u64; num_words],
+            scored_moves: Vec::with_capacity(board_cells),
+            legal_moves: Vec::with_capacity(board_cells),
+            move_order: Vec::with_capacity(board_cells),
+            proximity_planes: ProximityPlanes::default(),
+        }
+    }
+}
 pub struct PathEntry {
     pub node: NodeRef,
     pub mov: (usize, usize),
@@ -61,35 +83,62 @@ pub struct PathEntry {
 pub struct ThreadLocalContext {
     pub(crate) game_state: GameState,
     pub(crate) path_stack: Vec<PathEntry>,
-    pub(crate) bitboard_workspace: BitboardWorkspace,
     pub(crate) current_proximity_scores: Vec<f32>,
-    pub(crate) legal_moves: Vec<(usize, usize)>,
-    pub(crate) scored_moves: Vec<((usize, usize), f32)>,
-    pub(crate) forcing_bits: Vec<u64>,
+    pub(crate) current_fixed_proximity_scores: Vec<i32>,
+    pub(crate) scratch: ExpansionScratch,
     pub(crate) node_cache: LocalNodeCache,
+    pub(crate) pending_stats: TreeStatsAccumulator,
+    pub(crate) thread_id: usize,
 }
 impl ThreadLocalContext {
-    pub fn new(game_state: GameState, _thread_id: usize) -> Self {
+    pub fn new(game_state: GameState, thread_id: usize) -> Self {
         let num_words = game_state.position.bitboard.num_words();
         let board_cells = board_cells(game_state.position.board_size);
-        let mut current_proximity_scores = vec![0.0_f32; double_board_cells(board_cells)];
-        let (player_one_scores, player_two_scores) =
-            current_proximity_scores.split_at_mut(board_cells);
-        game_state
-            .evaluator
-            .rebuild_proximity_scores(&game_state.position, 1, player_one_scores);
-        game_state
-            .evaluator
-            .rebuild_proximity_scores(&game_state.position, 2, player_two_scores);
+        let mut current_proximity_scores = Vec::new();
+        let mut current_fixed_proximity_scores = Vec::new();
+        let mut scratch = ExpansionScratch::new(num_words, board_cells);
+        if game_state.evaluator.uses_bitboard_ordering() {
+            game_state
+                .evaluator
+                .prime_proximity_planes(&game_state.position, &mut scratch.proximity_planes);
+        } else if game_state.evaluator.is_fixed_point() {
+            current_fixed_proximity_scores = vec![0_i32; double_board_cells(board_cells)];
+            let (player_one_scores, player_two_scores) =
+                current_fixed_proximity_scores.split_at_mut(board_cells);
+            game_state.evaluator.rebuild_fixed_proximity_scores(
+                &game_state.position,
+                1,
+                player_one_scores,
+            );
+            game_state.evaluator.rebuild_fixed_proximity_scores(
+                &game_state.position,
+                2,
+                player_two_scores,
+            );
+        } else {
+            current_proximity_scores = vec![0.0_f32; double_board_cells(board_cells)];
+            let (player_one_scores, player_two_scores) =
+                current_proximity_scores.split_at_mut(board_cells);
+            game_state.evaluator.rebuild_proximity_scores(
+                &game_state.position,
+                1,
+                player_one_scores,
+            );
+            game_state.evaluator.rebuild_proximity_scores(
+                &game_state.position,
+                2,
+                player_two_scores,
+            );
+        }
         Self {
             game_state,
             path_stack: Vec::with_capacity(256),
-            bitboard_workspace: BitboardWorkspace::new(num_words),
             current_proximity_scores,
-            legal_moves: Vec::with_capacity(256),
-            scored_moves: Vec::with_capacity(256),
-            forcing_bits: vec![0_u64; num_words],
+            current_fixed_proximity_scores,
+            scratch,
             node_cache: LocalNodeCache::new(NODE_CACHE_CAPACITY),
+            pending_stats: TreeStatsAccumulator::default(),
+            thread_id,
         }
     }
     pub fn make_move(&mut self, mov: (usize, usize), player: u8) {
@@ -99,7 +148,7 @@ impl ThreadLocalContext {
             mov,
             player,
         );
-        self.update_proximity_scores(mov, player, 1.0);
+        self.update_proximity_scores(mov, player, 1_i16);
     }
     pub fn make_move_with_timing(&mut self, mov: (usize, usize), player: u8) -> MoveApplyTiming {
         let timing = GomokuRules::make_move_with_timing(
@@ -108,11 +157,11 @@ impl ThreadLocalContext {
             mov,
             player,
         );
-        self.update_proximity_scores(mov, player, 1.0);
+        self.update_proximity_scores(mov, player, 1_i16);
         timing
     }
     pub fn undo_move(&mut self, mov: (usize, usize), player: u8) {
-        self.update_proximity_scores(mov, player, -1.0);
+        self.update_proximity_scores(mov, player, -1_i16);
         GomokuRules::undo_move(
             &mut self.game_state.position,
             &mut self.game_state.move_cache,
@@ -145,51 +194,128 @@ impl ThreadLocalContext {
     pub fn check_win(&self, player: u8) -> bool {
         GomokuRules::check_win(&self.game_state.position, player)
     }
-    pub fn get_canonical_hash(&self) -> u64 {
+    pub fn get_canonical_hash(&self) -> CanonicalHash {
         self.game_state.position.get_canonical_hash()
     }
-    pub const fn get_hash(&self) -> u64 {
+    pub const fn get_hash(&self) -> PositionHash {
         self.game_state.position.get_hash()
     }
     pub fn refresh_legal_moves(&mut self, player: u8) -> MoveGenTiming {
-        let board_cells = board_cells(self.game_state.position.board_size);
-        let proximity_scores =
-            proximity_scores_for_player(&self.current_proximity_scores, board_cells, player);
+        let proximity_scores = player_proximity_scores(
+            &self.game_state,
+            &self.current_proximity_scores,
+            &self.current_fixed_proximity_scores,
+            player,
+        );
+        let scratch = &mut self.scratch;
         let mut buffers = MoveGenBuffers {
-            forcing_bits: &mut self.forcing_bits,
-            scored_moves: &mut self.scored_moves,
-            out_moves: &mut self.legal_moves,
+            forcing_bits: &mut scratch.forcing_bits,
+            scored_moves: &mut scratch.scored_moves,
+            out_moves: &mut scratch.legal_moves,
             candidate_moves: Some(&self.game_state.move_cache.candidate_moves),
-            proximity_scores: Some(proximity_scores),
+            proximity_scores,
+            proximity_planes: Some(&mut scratch.proximity_planes),
         };
         GomokuRules::get_legal_moves_into(
             &self.game_state.position,
             &self.game_state.evaluator,
             player,
-            &mut self.bitboard_workspace,
+            &mut scratch.bitboard_workspace,
             &mut buffers,
         )
     }
-    pub fn get_cached_node(&mut self, key: &(u64, usize)) -> Option<NodeRef> {
+    pub fn for_each_legal_move<F>(&mut self, player: u8, visit: F) -> MoveGenTiming
+    where
+        F: FnMut((usize, usize), Option<f32>) -> ControlFlow<()>,
+    {
+        let proximity_scores = player_proximity_scores(
+            &self.game_state,
+            &self.current_proximity_scores,
+            &self.current_fixed_proximity_scores,
+            player,
+        );
+        let scratch = &mut self.scratch;
+        let mut buffers = MoveGenBuffers {
+            forcing_bits: &mut scratch.forcing_bits,
+            scored_moves: &mut scratch.scored_moves,
+            out_moves: &mut scratch.legal_moves,
+            candidate_moves: Some(&self.game_state.move_cache.candidate_moves),
+            proximity_scores,
+            proximity_planes: Some(&mut scratch.proximity_planes),
+        };
+        GomokuRules::for_each_legal_move(
+            &self.game_state.position,
+            &self.game_state.evaluator,
+            player,
+            &mut scratch.bitboard_workspace,
+            &mut buffers,
+            &mut scratch.move_order,
+            visit,
+        )
+    }
+    pub fn get_cached_node(&mut self, key: &NodeKey) -> Option<NodeRef> {
         self.node_cache.get(key)
     }
-    pub fn cache_node(&mut self, key: (u64, usize), node: NodeRef) {
+    pub fn cache_node(&mut self, key: NodeKey, node: NodeRef) {
         self.node_cache.insert(key, node);
     }
-    fn update_proximity_scores(&mut self, mov: (usize, usize), player: u8, delta: f32) {
+    fn update_proximity_scores(&mut self, mov: (usize, usize), player: u8, delta: i16) {
         let board_cells = board_cells(self.game_state.position.board_size);
         let game_state = &self.game_state;
-        let scores = proximity_scores_for_player_mut(
-            &mut self.current_proximity_scores,
+        if game_state.evaluator.uses_bitboard_ordering() {
+            return;
+        }
+        if game_state.evaluator.is_fixed_point() {
+            let scores = proximity_scores_for_player_mut(
+                &mut self.current_fixed_proximity_scores,
+                board_cells,
+                player,
+            );
+            game_state.evaluator.apply_fixed_proximity_delta(
+                &game_state.position,
+                mov,
+                i32::from(delta),
+                scores,
+            );
+        } else {
+            let scores = proximity_scores_for_player_mut(
+                &mut self.current_proximity_scores,
+                board_cells,
+                player,
+            );
+            game_state.evaluator.apply_proximity_delta(
+                &game_state.position,
+                mov,
+                f32::from(delta),
+                scores,
+            );
+        }
+    }
+}
+fn player_proximity_scores<'scores>(
+    game_state: &GameState,
+    float_scores: &'scores [f32],
+    fixed_scores: &'scores [i32],
+    player: u8,
+) -> Option<ProximityScores<'scores>> {
+    let board_cells = board_cells(game_state.position.board_size);
+    if game_state.evaluator.uses_bitboard_ordering() {
+        None
+    } else if game_state.evaluator.is_fixed_point() {
+        Some(ProximityScores::Fixed(proximity_scores_for_player(
+            fixed_scores,
             board_cells,
             player,
-        );
-        game_state
-            .evaluator
-            .apply_proximity_delta(&game_state.position, mov, delta, scores);
+        )))
+    } else {
+        Some(ProximityScores::Float(proximity_scores_for_player(
+            float_scores,
+            board_cells,
+            player,
+        )))
     }
 }
-fn proximity_scores_for_player(scores: &[f32], board_cells: usize, player: u8) -> &[f32] {
+fn proximity_scores_for_player<T>(scores: &[T], board_cells: usize, player: u8) -> &[T] {
     let total_cells = double_board_cells(board_cells);
     match player {
         1 => {
@@ -212,11 +338,11 @@ fn proximity_scores_for_player(scores: &[f32], board_cells: usize, player: u8) -
         }
     }
 }
-fn proximity_scores_for_player_mut(
-    scores: &mut [f32],
+fn proximity_scores_for_player_mut<T>(
+    scores: &mut [T],
     board_cells: usize,
     player: u8,
-) -> &mut [f32] {
+) -> &mut [T] {
     let total_cells = double_board_cells(board_cells);
     match player {
         1 => {
@@ -0,0 +1,283 @@
+use super::{NodeRef, NodeTable, TTEntry, shared_tree::ShardedMap};
+use crate::{
+    checked,
+    game_state::{
+        Board, CanonicalHash, Coord, GameState, PositionHash, Rule, ShapeError, ZobristHasher,
+    },
+    utils::board_index,
+};
+use alloc::sync::Arc;
+use core::fmt;
+use std::collections::HashSet;
+const ATTACKER: u8 = 1;
+const DEFENDER: u8 = 2;
+pub struct SubBoardSolution {
+    pub board: Vec<u8>,
+    pub board_size: usize,
+    pub win_len: usize,
+    pub rule: Rule,
+    pub node_table: NodeTable,
+}
+#[derive(Clone, Copy, Default, Debug)]
+pub struct EmbedSummary {
+    pub visited: usize,
+    pub seeded: usize,
+    pub rejected_at_boundary: usize,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EmbedError {
+    Shape(ShapeError),
+    WinLenMismatch {
+        smaller: usize,
+        larger: usize,
+    },
+    RuleMismatch {
+        smaller: Rule,
+        larger: Rule,
+    },
+    BoardLength {
+        expected: usize,
+        actual: usize,
+    },
+    DoesNotFit {
+        smaller: usize,
+        offset: Coord,
+        board_size: usize,
+    },
+    MissingRoot,
+}
+impl fmt::Display for EmbedError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Shape(err) => write!(f, "目标棋盘无效: {err}"),
+            Self::WinLenMismatch { smaller, larger } => write!(
+                f,
+                "连珠长度不一致: 小棋盘 {smaller} 子，目标棋盘 {larger} 子"
+            ),
+            Self::RuleMismatch { smaller, larger } => write!(
+                f,
+                "规则不一致: 小棋盘为{smaller}，目标棋盘为{larger}",
+                smaller = smaller.name(),
+                larger = larger.name()
+            ),
+            Self::BoardLength { expected, actual } => {
+                write!(f, "小棋盘数据长度不匹配: 实际 {actual}，期望 {expected}")
+            }
+            Self::DoesNotFit {
+                smaller,
+                offset,
+                board_size,
+            } => write!(
+                f,
+                "{smaller}x{smaller} 棋盘以偏移 ({row}, {column}) 无法嵌入 {board_size}x{board_size} 棋盘",
+                row = offset.0,
+                column = offset.1
+            ),
+            Self::MissingRoot => write!(f, "小棋盘的节点表中没有根节点"),
+        }
+    }
+}
+struct Embedding {
+    smaller: usize,
+    board_size: usize,
+    win_len: usize,
+    offset: Coord,
+    boundary_windows: Vec<Vec<usize>>,
+}
+impl Embedding {
+    fn new(smaller: usize, board_size: usize, win_len: usize, offset: Coord) -> Self {
+        let mut embedding = Self {
+            smaller,
+            board_size,
+            win_len,
+            offset,
+            boundary_windows: Vec::new(),
+        };
+        embedding.boundary_windows = embedding.collect_boundary_windows();
+        embedding
+    }
+    const fn is_inside(&self, (row, column): Coord) -> bool {
+        let (row_offset, column_offset) = self.offset;
+        row >= row_offset
+            && column >= column_offset
+            && row < row_offset.saturating_add(self.smaller)
+            && column < column_offset.saturating_add(self.smaller)
+    }
+    fn translate(&self, (row, column): Coord) -> Coord {
+        (
+            checked::add_usize(row, self.offset.0, "Embedding::translate::row"),
+            checked::add_usize(column, self.offset.1, "Embedding::translate::column"),
+        )
+    }
+    fn window_cells(&self, (row, column): Coord, direction: usize) -> Option<Vec<Coord>> {
+        let last = checked::sub_usize(self.win_len, 1_usize, "Embedding::window_cells::last");
+        let fits = |index: usize| {
+            index
+                .checked_add(last)
+                .is_some_and(|end| end < self.board_size)
+        };
+        let in_bounds = match direction {
+            0 => fits(column),
+            1 => fits(row),
+            2 => fits(row) && fits(column),
+            _ => fits(row) && column >= last,
+        };
+        in_bounds.then(|| {
+            (0..self.win_len)
+                .map(|step| match direction {
+                    0 => (row, column.saturating_add(step)),
+                    1 => (row.saturating_add(step), column),
+                    2 => (row.saturating_add(step), column.saturating_add(step)),
+                    _ => (row.saturating_add(step), column.saturating_sub(step)),
+                })
+                .collect()
+        })
+    }
+    fn collect_boundary_windows(&self) -> Vec<Vec<usize>> {
+        let mut windows = Vec::new();
+        for row in 0..self.board_size {
+            for column in 0..self.board_size {
+                for direction in 0..4_usize {
+                    let Some(cells) = self.window_cells((row, column), direction) else {
+                        continue;
+                    };
+                    let crosses = cells.iter().any(|&cell| !self.is_inside(cell))
+                        && cells.iter().any(|&cell| self.is_inside(cell));
+                    if crosses {
+                        windows.push(
+                            cells
+                                .into_iter()
+                                .map(|(cell_row, cell_column)| {
+                                    board_index(self.board_size, cell_row, cell_column)
+                                })
+                                .collect(),
+                        );
+                    }
+                }
+            }
+        }
+        windows
+    }
+    fn defender_cannot_cross(&self, board: &[u8], defender_moves: usize) -> bool {
+        self.boundary_windows.iter().all(|window| {
+            let mut defender_stones = 0_usize;
+            for &index in window {
+                match board.get(index).copied() {
+                    Some(ATTACKER) => return true,
+                    Some(DEFENDER) => defender_stones = defender_stones.saturating_add(1),
+                    _ => {}
+                }
+            }
+            defender_stones.saturating_add(defender_moves) < self.win_len
+        })
+    }
+}
+fn defender_moves_within(node: &NodeRef) -> usize {
+    let plies = checked::u64_to_usize(node.get_win_len(), "embedding::defender_moves_within");
+    if node.is_or_node() {
+        plies.saturating_sub(1).div_ceil(2)
+    } else {
+        plies.div_ceil(2)
+    }
+}
+impl ShardedMap<(CanonicalHash, u8), TTEntry> {
+    #[inline]
+    pub fn embed_from(
+        &self,
+        smaller: &SubBoardSolution,
+        offset: Coord,
+        board_size: usize,
+        win_len: usize,
+        rule: Rule,
+    ) -> Result<EmbedSummary, EmbedError> {
+        GameState::validate_shape(board_size, win_len).map_err(EmbedError::Shape)?;
+        if smaller.win_len != win_len {
+            return Err(EmbedError::WinLenMismatch {
+                smaller: smaller.win_len,
+                larger: win_len,
+            });
+        }
+        if smaller.rule != rule {
+            return Err(EmbedError::RuleMismatch {
+                smaller: smaller.rule,
+                larger: rule,
+            });
+        }
+        let expected = checked::mul_usize(
+            smaller.board_size,
+            smaller.board_size,
+            "TranspositionTable::embed_from::smaller_cells",
+        );
+        if smaller.board.len() != expected {
+            return Err(EmbedError::BoardLength {
+                expected,
+                actual: smaller.board.len(),
+            });
+        }
+        let fits = |start: usize| {
+            start
+                .checked_add(smaller.board_size)
+                .is_some_and(|end| end <= board_size)
+        };
+        if !fits(offset.0) || !fits(offset.1) {
+            return Err(EmbedError::DoesNotFit {
+                smaller: smaller.board_size,
+                offset,
+                board_size,
+            });
+        }
+        let smaller_board = Board::from_cells(smaller.board_size, smaller.board.clone())
+            .map_err(EmbedError::Shape)?;
+        let smaller_hasher = ZobristHasher::new(smaller.board_size);
+        let [root_pos_hash, ..] = smaller_hasher.symmetric_board_hashes(&smaller_board);
+        let root = smaller
+            .node_table
+            .get(&(PositionHash::from_bits(root_pos_hash), 0))
+            .ok_or(EmbedError::MissingRoot)?;
+        let embedding = Embedding::new(smaller.board_size, board_size, win_len, offset);
+        let hasher = ZobristHasher::new(board_size);
+        let mut root_board = Board::new(board_size);
+        for ((row, column), piece) in smaller_board.iter_cells() {
+            root_board.set(embedding.translate((row, column)), piece);
+        }
+        let mut summary = EmbedSummary::default();
+        let mut visited = HashSet::new();
+        visited.insert(Arc::as_ptr(&root));
+        let mut stack = vec![(root, root_board)];
+        while let Some((node, board)) = stack.pop() {
+            summary.visited = summary.visited.saturating_add(1);
+            if node.get_pn() == 0 && !node.is_depth_limited() {
+                if embedding.defender_cannot_cross(board.as_slice(), defender_moves_within(&node)) {
+                    let hash = CanonicalHash::from_bits(hasher.canonical_from_symmetric(
+                        hasher.symmetric_board_hashes(&board),
+                        node.player == DEFENDER,
+                    ));
+                    self.insert(
+                        (hash, node.player),
+                        TTEntry {
+                            pn: 0,
+                            dn: node.get_dn(),
+                            win_len: node.get_win_len(),
+                        },
+                    );
+                    summary.seeded = summary.seeded.saturating_add(1);
+                } else {
+                    summary.rejected_at_boundary = summary.rejected_at_boundary.saturating_add(1);
+                }
+            }
+            let Some(children) = node.expanded_children() else {
+                continue;
+            };
+            for child_ref in children {
+                if !visited.insert(Arc::as_ptr(&child_ref.node)) {
+                    continue;
+                }
+                let mut child_board = board.clone();
+                child_board.set(embedding.translate(child_ref.mov.coord()), node.player);
+                stack.push((Arc::clone(&child_ref.node), child_board));
+            }
+        }
+        Ok(summary)
+    }
+}
@@ -0,0 +1,197 @@
+use super::{TTEntry, shared_tree::ShardedMap};
+use crate::{
+    checked,
+    game_state::{CanonicalHash, Position},
+    interrupt::CancellationToken,
+};
+use alloc::collections::BTreeMap;
+const ATTACKER: u8 = 1;
+#[derive(Clone, Copy, Default, Debug)]
+pub struct EnumerationLevel {
+    pub depth: usize,
+    pub paths: u64,
+    pub positions: usize,
+    pub wins: usize,
+    pub draws: usize,
+}
+#[derive(Clone, Default)]
+pub struct EnumerationSummary {
+    pub levels: Vec<EnumerationLevel>,
+    pub exhausted: bool,
+    pub interrupted: bool,
+    pub seeded: usize,
+    pub root: Option<TTEntry>,
+}
+#[derive(Clone, Copy)]
+enum Value {
+    Win(u64),
+    Loss,
+    Unknown,
+}
+struct LevelNode {
+    position: Position,
+    paths: u64,
+}
+type Level = BTreeMap<CanonicalHash, LevelNode>;
+fn terminal_value(position: &Position) -> Option<Value> {
+    match position.winner() {
+        Some(ATTACKER) => Some(Value::Win(0)),
+        Some(_) => Some(Value::Loss),
+        None => position.is_full().then_some(Value::Loss),
+    }
+}
+fn children(position: &Position) -> impl Iterator<Item = Position> + '_ {
+    position.legal_moves().into_iter().filter_map(|mov| {
+        let mut child = position.clone();
+        child.play(mov).ok().map(|()| child)
+    })
+}
+fn level_stats(depth: usize, level: &Level) -> EnumerationLevel {
+    let mut stats = EnumerationLevel {
+        depth,
+        positions: level.len(),
+        ..EnumerationLevel::default()
+    };
+    for node in level.values() {
+        stats.paths = stats.paths.saturating_add(node.paths);
+        if node.position.winner().is_some() {
+            stats.wins = stats.wins.saturating_add(1);
+        } else if node.position.is_full() {
+            stats.draws = stats.draws.saturating_add(1);
+        }
+    }
+    stats
+}
+fn expand_level(level: &Level, cancel: &CancellationToken) -> Option<Level> {
+    let mut next = Level::new();
+    for node in level.values() {
+        if cancel.is_cancelled() {
+            return None;
+        }
+        if terminal_value(&node.position).is_some() {
+            continue;
+        }
+        for child in children(&node.position) {
+            let entry = next
+                .entry(child.canonical_hash())
+                .or_insert_with(|| LevelNode {
+                    position: child,
+                    paths: 0,
+                });
+            entry.paths = entry.paths.saturating_add(node.paths);
+        }
+    }
+    Some(next)
+}
+fn combine(position: &Position, next_values: &BTreeMap<CanonicalHash, Value>) -> Value {
+    let is_or_node = position.side_to_move() == ATTACKER;
+    let mut all_decided = true;
+    let mut best_win: Option<u64> = None;
+    let mut worst_win = 0_u64;
+    for child in children(position) {
+        match next_values
+            .get(&child.canonical_hash())
+            .copied()
+            .unwrap_or(Value::Unknown)
+        {
+            Value::Win(plies) if is_or_node => {
+                best_win = Some(best_win.map_or(plies, |best| best.min(plies)));
+            }
+            Value::Win(plies) => worst_win = worst_win.max(plies),
+            Value::Loss if !is_or_node => return Value::Loss,
+            Value::Loss => {}
+            Value::Unknown => all_decided = false,
+        }
+    }
+    let next_plies = |plies: u64| checked::add_u64(plies, 1_u64, "enumeration::combine::win_len");
+    if is_or_node {
+        match best_win {
+            Some(plies) => Value::Win(next_plies(plies)),
+            None if all_decided => Value::Loss,
+            None => Value::Unknown,
+        }
+    } else if all_decided {
+        Value::Win(next_plies(worst_win))
+    } else {
+        Value::Unknown
+    }
+}
+const fn entry_for(value: Value) -> Option<TTEntry> {
+    match value {
+        Value::Win(win_len) => Some(TTEntry {
+            pn: 0,
+            dn: u64::MAX,
+            win_len,
+        }),
+        Value::Loss => Some(TTEntry {
+            pn: u64::MAX,
+            dn: 0,
+            win_len: u64::MAX,
+        }),
+        Value::Unknown => None,
+    }
+}
+impl ShardedMap<(CanonicalHash, u8), TTEntry> {
+    #[inline]
+    pub fn enumerate_from(
+        &self,
+        root: &Position,
+        max_depth: usize,
+        cancel: &CancellationToken,
+    ) -> EnumerationSummary {
+        let mut summary = EnumerationSummary::default();
+        let mut root_level = Level::new();
+        root_level.insert(
+            root.canonical_hash(),
+            LevelNode {
+                position: root.clone(),
+                paths: 1,
+            },
+        );
+        summary.levels.push(level_stats(0, &root_level));
+        let mut levels = vec![root_level];
+        for depth in 1..=max_depth {
+            let Some(frontier) = levels.last() else {
+                break;
+            };
+            let Some(next) = expand_level(frontier, cancel) else {
+                summary.interrupted = true;
+                return summary;
+            };
+            if next.is_empty() {
+                break;
+            }
+            summary.levels.push(level_stats(depth, &next));
+            levels.push(next);
+        }
+        summary.exhausted = levels.last().is_some_and(|frontier| {
+            frontier
+                .values()
+                .all(|node| terminal_value(&node.position).is_some())
+        });
+        let mut next_values: BTreeMap<CanonicalHash, Value> = BTreeMap::new();
+        let frontier_index = levels.len().saturating_sub(1);
+        for (index, level) in levels.iter().enumerate().rev() {
+            let mut values = BTreeMap::new();
+            for (&key, node) in level {
+                let value = terminal_value(&node.position).unwrap_or_else(|| {
+                    if index == frontier_index {
+                        Value::Unknown
+                    } else {
+                        combine(&node.position, &next_values)
+                    }
+                });
+                if let Some(entry) = entry_for(value) {
+                    self.insert((key, node.position.side_to_move()), entry);
+                    summary.seeded = summary.seeded.saturating_add(1);
+                    if index == 0 {
+                        summary.root = Some(entry);
+                    }
+                }
+                values.insert(key, value);
+            }
+            next_values = values;
+        }
+        summary
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{alloc_stats::AllocScope, game_state::Coord};
+use core::sync::atomic::{AtomicU64, Ordering};
+use serde::{Deserialize, Serialize};
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocationPolicy {
+    #[default]
+    Allowed,
+    Counted,
+    Forbidden,
+}
+impl AllocationPolicy {
+    #[inline]
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Allowed => "不检查",
+            Self::Counted => "计数",
+            Self::Forbidden => "禁止",
+        }
+    }
+}
+pub(crate) struct AllocationAudit {
+    policy: AllocationPolicy,
+    violations: AtomicU64,
+}
+impl AllocationAudit {
+    pub(crate) const fn new(policy: AllocationPolicy) -> Self {
+        Self {
+            policy,
+            violations: AtomicU64::new(0),
+        }
+    }
+    pub(crate) fn begin(&self) -> Option<AllocScope> {
+        (self.policy != AllocationPolicy::Allowed).then(AllocScope::begin)
+    }
+    pub(crate) fn finish(&self, scope: Option<AllocScope>, setup_events: u64, mov: Coord) {
+        let events = self.record(scope, setup_events);
+        if events > 0 && self.panics() {
+            eprintln!(
+                "热路径发生 {events} 次堆分配: 展开着法 ({row}, {column})",
+                row = mov.0,
+                column = mov.1
+            );
+            panic!("热路径发生堆分配");
+        }
+    }
+    pub(crate) fn finish_move_generation(&self, scope: Option<AllocScope>, player: u8) {
+        let events = self.record(scope, 0);
+        if events > 0 && self.panics() {
+            eprintln!("热路径发生 {events} 次堆分配: 为玩家 {player} 生成着法");
+            panic!("热路径发生堆分配");
+        }
+    }
+    fn record(&self, scope: Option<AllocScope>, setup_events: u64) -> u64 {
+        let Some(started) = scope else {
+            return 0;
+        };
+        let events = started.delta().heap_events().saturating_sub(setup_events);
+        if events > 0 {
+            self.violations.fetch_add(events, Ordering::Relaxed);
+        }
+        events
+    }
+    fn panics(&self) -> bool {
+        cfg!(debug_assertions) && self.policy == AllocationPolicy::Forbidden
+    }
+    pub(crate) fn violations(&self) -> u64 {
+        self.violations.load(Ordering::Relaxed)
+    }
+}
@@ -1,12 +1,44 @@
 mod accessors;
+mod background;
 mod benchmark;
 mod best_move;
+mod bundle;
+mod calibration;
+mod classify;
 mod deepening;
+mod difficulty;
+mod dual;
 mod impls;
 mod logging;
+mod plot_data;
+mod reporting;
+mod reproduce;
 mod setup;
+mod solution;
 mod solve;
 mod types;
+pub type AllocReport = types::AllocReport;
+pub type Arbitration = dual::Arbitration;
+pub type BackgroundSearch = background::BackgroundSearch;
 pub type BenchmarkResult = types::BenchmarkResult;
+pub type ChildDiff = dual::ChildDiff;
+pub type DebugBundle = bundle::DebugBundle;
+pub type DebugParams = bundle::DebugParams;
+pub type ChildSummary = dual::ChildSummary;
+pub type DepthReport = types::DepthReport;
+pub type DepthSample = types::DepthSample;
+pub type DifficultyEstimate = types::DifficultyEstimate;
+pub type EngineRun = dual::EngineRun;
+pub type HeuristicWinEstimate = types::HeuristicWinEstimate;
+pub type MoveClass = classify::MoveClass;
+pub type NodeDiff = dual::NodeDiff;
 pub type ParallelSolver = types::ParallelSolver;
+pub type SearchOutcome = types::SearchOutcome;
 pub type SearchParams = types::SearchParams;
+pub type SearchReport = types::SearchReport;
+pub type SearchResult = types::SearchResult;
+pub type SolutionSummary = solution::SolutionSummary;
+pub type ProofDag = solution::ProofDag;
+pub type ProofDagNode = solution::ProofDagNode;
+pub type Verdict = dual::Verdict;
+pub(crate) use logging::LOG_FILE_NAME;
@@ -1,4 +1,6 @@
+use super::super::{BudgetLimit, TTEntry, TreeSnapshot};
 use super::ParallelSolver;
+use crate::{game_state::Coord, interrupt::CancellationToken};
 pub(super) fn root_pn(solver: &ParallelSolver) -> u64 {
     solver.tree.root.get_pn()
 }
@@ -11,6 +13,9 @@ pub(super) fn root_player(solver: &ParallelSolver) -> u8 {
 pub(super) fn root_win_len(solver: &ParallelSolver) -> u64 {
     solver.tree.root.get_win_len()
 }
+pub(super) fn freeze_snapshot(solver: &ParallelSolver) -> TreeSnapshot {
+    solver.tree.freeze_snapshot()
+}
 pub(super) const fn game_state(solver: &ParallelSolver) -> &crate::game_state::GameState {
     &solver.base_game_state
 }
@@ -20,3 +25,15 @@ pub(super) const fn board_size(solver: &ParallelSolver) -> usize {
 pub(super) const fn win_len(solver: &ParallelSolver) -> usize {
     solver.win_len
 }
+pub(super) fn probe_line(solver: &ParallelSolver, line: &[Coord]) -> Vec<Option<TTEntry>> {
+    solver.tree.probe_line(&solver.base_game_state, line)
+}
+pub(super) fn limit_reached(solver: &ParallelSolver) -> Option<BudgetLimit> {
+    solver.tree.limit_reached()
+}
+pub(super) fn cancellation_token(solver: &ParallelSolver) -> &CancellationToken {
+    solver.tree.cancellation_token()
+}
+pub(super) fn iteration_token(solver: &ParallelSolver) -> CancellationToken {
+    solver.tree.iteration_token()
+}
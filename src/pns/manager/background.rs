@@ -0,0 +1,68 @@
+use super::super::{NodeTable, SearchProgress, TranspositionTable};
+use super::{ParallelSolver, SearchParams, SearchResult};
+use crate::interrupt::{CancelReason, CancellationToken};
+use alloc::sync::Arc;
+use parking_lot::Mutex;
+use std::thread::{self, JoinHandle};
+pub struct BackgroundSearch {
+    progress: Arc<Mutex<Option<SearchProgress>>>,
+    stop: CancellationToken,
+    handle: JoinHandle<SearchResult>,
+}
+impl BackgroundSearch {
+    #[inline]
+    #[must_use]
+    pub fn poll_progress(&self) -> Option<SearchProgress> {
+        *self.progress.lock()
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+    #[inline]
+    pub fn stop(&self) {
+        self.stop.cancel(CancelReason::User);
+    }
+    #[inline]
+    pub fn join(self) -> Result<SearchResult, String> {
+        self.handle
+            .join()
+            .map_err(|_panic| String::from("后台搜索线程异常退出"))
+    }
+}
+pub(super) fn spawn_search(
+    initial_board: Vec<u8>,
+    mut params: SearchParams,
+    verbose: bool,
+    cancel: &CancellationToken,
+    existing_tt: Option<TranspositionTable>,
+    existing_node_table: Option<NodeTable>,
+) -> BackgroundSearch {
+    let progress = Arc::new(Mutex::new(None));
+    let latest = Arc::clone(&progress);
+    let forward = params.progress.take();
+    params.progress = Some(Arc::new(move |snapshot: &SearchProgress| {
+        *latest.lock() = Some(*snapshot);
+        if let Some(callback) = forward.as_ref() {
+            callback(snapshot);
+        }
+    }));
+    let stop = cancel.child();
+    let worker_stop = stop.clone();
+    let handle = thread::spawn(move || {
+        ParallelSolver::search(
+            initial_board,
+            params,
+            verbose,
+            &worker_stop,
+            existing_tt,
+            existing_node_table,
+        )
+    });
+    BackgroundSearch {
+        progress,
+        stop,
+        handle,
+    }
+}
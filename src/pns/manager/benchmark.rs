@@ -1,14 +1,27 @@
 use super::super::{TreeStatsSnapshot, stats_def::to_f64};
 use super::{BenchmarkResult, SearchParams};
-use crate::checked;
-use alloc::{collections::BTreeMap, sync::Arc};
-use core::sync::atomic::{AtomicBool, Ordering};
+use crate::{checked, interrupt::CancellationToken};
+use alloc::collections::BTreeMap;
 use std::time::Instant;
 pub(super) fn benchmark_next_move(
     initial_board: &[u8],
-    params: SearchParams,
+    params: &SearchParams,
     runs: usize,
-    stop_flag: &Arc<AtomicBool>,
+    cancel: &CancellationToken,
+) -> Option<BenchmarkResult> {
+    let result = measure_next_move(initial_board, params, runs, cancel)?;
+    if !params.quiet {
+        for report in &result.depth_reports {
+            super::logging::write_csv_log(report);
+        }
+    }
+    Some(result)
+}
+pub(super) fn measure_next_move(
+    initial_board: &[u8],
+    params: &SearchParams,
+    runs: usize,
+    cancel: &CancellationToken,
 ) -> Option<BenchmarkResult> {
     if runs == 0 {
         return None;
@@ -19,16 +32,17 @@ pub(super) fn benchmark_next_move(
     let mut total_elapsed_secs = 0.0_f64;
     let mut total_tt_size: u64 = 0;
     let mut total_node_table_size: u64 = 0;
-    for _ in 0..runs {
-        if stop_flag.load(Ordering::Acquire) {
+    let mut depth_samples = Vec::new();
+    for run in 0..runs {
+        if cancel.is_cancelled() {
             return None;
         }
         let depth = 1_usize;
         let mut solver = super::setup::with_tt_and_stop(
             base_board.clone(),
-            params,
+            params.clone(),
             Some(depth),
-            stop_flag,
+            cancel,
             None,
             None,
         );
@@ -43,33 +57,45 @@ pub(super) fn benchmark_next_move(
             total_elapsed_secs: &mut total_elapsed_secs,
             total_tt_size: &mut total_tt_size,
             total_node_table_size: &mut total_node_table_size,
+            run,
+            samples: &mut depth_samples,
         };
-        super::solve::run_iterative_deepening(&mut solver, stop_flag, depth, &mut hooks)?;
+        super::solve::run_iterative_deepening(&mut solver, cancel, depth, &mut hooks)?;
     }
-    let runs_count = checked::usize_to_u64(runs, "ParallelSolver::benchmark_next_move::runs_count");
+    let runs_count = checked::usize_to_u64(runs, "ParallelSolver::measure_next_move::runs_count");
     let stats = total_stats.div_round(runs_count);
     let elapsed_secs = total_elapsed_secs / to_f64(runs_count);
     let tt_size = checked::u64_to_usize(
         checked::rounded_div_u64(
             total_tt_size,
             runs_count,
-            "ParallelSolver::benchmark_next_move::tt_size",
+            "ParallelSolver::measure_next_move::tt_size",
         ),
-        "ParallelSolver::benchmark_next_move::tt_size",
+        "ParallelSolver::measure_next_move::tt_size",
     );
     let node_table_size = checked::u64_to_usize(
         checked::rounded_div_u64(
             total_node_table_size,
             runs_count,
-            "ParallelSolver::benchmark_next_move::node_table_size",
+            "ParallelSolver::measure_next_move::node_table_size",
         ),
-        "ParallelSolver::benchmark_next_move::node_table_size",
+        "ParallelSolver::measure_next_move::node_table_size",
     );
-    super::deepening::write_benchmark_logs(per_depth);
+    let peak_live_bytes_by_depth = super::deepening::peak_live_bytes_by_depth(&per_depth);
+    let peak_live_bytes = peak_live_bytes_by_depth
+        .iter()
+        .map(|&(_, bytes)| bytes)
+        .max()
+        .unwrap_or(0);
+    let depth_reports = super::deepening::depth_reports(&per_depth);
     Some(BenchmarkResult {
         elapsed_secs,
         stats,
         tt_size,
         node_table_size,
+        peak_live_bytes,
+        peak_live_bytes_by_depth,
+        depth_reports,
+        depth_samples,
     })
 }
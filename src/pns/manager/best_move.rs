@@ -1,8 +1,7 @@
-use super::super::{NodeTable, TranspositionTable};
-use super::{ParallelSolver, SearchParams};
-use crate::{checked, config::EvaluationWeights};
+use super::super::{NodeTable, SharedTree, TranspositionTable};
+use super::{ParallelSolver, SearchOutcome, SearchParams, SearchResult};
+use crate::{config::EvaluationWeights, game_state::GameSession, interrupt::CancellationToken};
 use alloc::sync::Arc;
-use core::sync::atomic::AtomicBool;
 pub(super) fn find_best_move_iterative_deepening(
     initial_board: Vec<u8>,
     board_size: usize,
@@ -12,7 +11,12 @@ pub(super) fn find_best_move_iterative_deepening(
     verbose: bool,
 ) -> Option<(usize, usize)> {
     let params = SearchParams::new(board_size, win_len, num_threads, evaluation);
-    find_best_move_with_tt(initial_board, params, verbose, None, None).0
+    match find_best_move_with_tt(initial_board, params, verbose, None, None).1 {
+        SearchOutcome::Proven { best_move, .. } => best_move,
+        SearchOutcome::Disproven
+        | SearchOutcome::Unresolved { .. }
+        | SearchOutcome::Aborted { .. } => None,
+    }
 }
 pub(super) fn find_best_move_with_tt(
     initial_board: Vec<u8>,
@@ -20,13 +24,17 @@ pub(super) fn find_best_move_with_tt(
     verbose: bool,
     existing_tt: Option<TranspositionTable>,
     existing_node_table: Option<NodeTable>,
-) -> (Option<(usize, usize)>, TranspositionTable, NodeTable) {
-    let stop_flag = Arc::new(AtomicBool::new(false));
+) -> (
+    Option<(usize, usize)>,
+    SearchOutcome,
+    TranspositionTable,
+    NodeTable,
+) {
     find_best_move_with_tt_and_stop(
         initial_board,
         params,
         verbose,
-        &stop_flag,
+        &CancellationToken::new(),
         existing_tt,
         existing_node_table,
     )
@@ -35,21 +43,81 @@ pub(super) fn find_best_move_with_tt_and_stop(
     initial_board: Vec<u8>,
     params: SearchParams,
     verbose: bool,
-    stop_flag: &Arc<AtomicBool>,
+    cancel: &CancellationToken,
     existing_tt: Option<TranspositionTable>,
     existing_node_table: Option<NodeTable>,
-) -> (Option<(usize, usize)>, TranspositionTable, NodeTable) {
+) -> (
+    Option<(usize, usize)>,
+    SearchOutcome,
+    TranspositionTable,
+    NodeTable,
+) {
+    let result = search(
+        initial_board,
+        params,
+        verbose,
+        cancel,
+        existing_tt,
+        existing_node_table,
+    );
+    (
+        result.best_move.or(result.best_known_move),
+        result.report.outcome,
+        result.transposition_table,
+        result.node_table,
+    )
+}
+pub(super) fn search(
+    initial_board: Vec<u8>,
+    params: SearchParams,
+    verbose: bool,
+    cancel: &CancellationToken,
+    existing_tt: Option<TranspositionTable>,
+    existing_node_table: Option<NodeTable>,
+) -> SearchResult {
     let depth = 1_usize;
     let mut solver = super::setup::with_tt_and_stop(
         initial_board,
         params,
         Some(depth),
-        stop_flag,
+        cancel,
         existing_tt,
         existing_node_table,
     );
-    let mut hooks = super::deepening::BestMoveDeepening { verbose };
-    super::solve::run_iterative_deepening(&mut solver, stop_flag, depth, &mut hooks)
+    let mut hooks = super::deepening::BestMoveDeepening {
+        verbose: verbose && !solver.quiet,
+        depth,
+    };
+    super::solve::run_iterative_deepening(&mut solver, cancel, depth, &mut hooks)
+}
+pub(super) fn search_session(
+    session: &GameSession,
+    params: SearchParams,
+    verbose: bool,
+    cancel: &CancellationToken,
+    existing_tt: Option<TranspositionTable>,
+    existing_node_table: Option<NodeTable>,
+) -> SearchResult {
+    let depth = 1_usize;
+    let repetition_is_draw = params.repetition_is_draw;
+    let mut solver = super::setup::with_tt_and_stop(
+        session.board().to_vec(),
+        params,
+        Some(depth),
+        cancel,
+        existing_tt,
+        existing_node_table,
+    );
+    if repetition_is_draw {
+        solver
+            .tree
+            .set_repetition_history(session.hash_history().iter().copied().collect());
+    }
+    let mut hooks = super::deepening::BestMoveDeepening {
+        verbose: verbose && !solver.quiet,
+        depth,
+    };
+    super::solve::run_iterative_deepening(&mut solver, cancel, depth, &mut hooks)
 }
 pub(super) fn get_tt(solver: &ParallelSolver) -> TranspositionTable {
     solver.tree.get_tt()
@@ -57,37 +125,43 @@ pub(super) fn get_tt(solver: &ParallelSolver) -> TranspositionTable {
 pub(super) fn get_node_table(solver: &ParallelSolver) -> NodeTable {
     solver.tree.get_node_table()
 }
+pub(super) fn get_most_proving_move(solver: &ParallelSolver) -> Option<(usize, usize)> {
+    solver.tree.most_proving_move()
+}
+pub(super) fn get_best_known_move(solver: &ParallelSolver) -> Option<(usize, usize)> {
+    get_best_move(solver).or_else(|| {
+        let mut children = solver.tree.root.expanded_children()?;
+        children
+            .clone()
+            .find(|child_ref| child_ref.node.get_dn() != 0)
+            .or_else(|| children.next())
+            .map(|child_ref| child_ref.mov.coord())
+    })
+}
+pub(super) fn get_proof_line(solver: &ParallelSolver) -> Vec<(usize, usize)> {
+    let root = &solver.tree.root;
+    let limit = usize::try_from(root.get_win_len()).unwrap_or(usize::MAX);
+    let selector = solver.tree.move_selector();
+    let mut line = Vec::new();
+    let mut node = Arc::clone(root);
+    while node.get_pn() == 0 && line.len() < limit {
+        let Some(child_ref) = node.principal_child(selector) else {
+            break;
+        };
+        line.push(child_ref.mov.coord());
+        let child = Arc::clone(&child_ref.node);
+        node = child;
+    }
+    line
+}
 pub(super) fn get_best_move(solver: &ParallelSolver) -> Option<(usize, usize)> {
     let root = &solver.tree.root;
     if root.get_pn() != 0 {
         return None;
     }
-    let children = root.children.get()?.clone();
-    if children.is_empty() {
-        return None;
-    }
-    let root_win_len = root.get_win_len();
-    let winning_children: Vec<_> = children
-        .iter()
-        .filter(|child_ref| {
-            child_ref.node.get_pn() == 0
-                && checked::add_u64(
-                    1_u64,
-                    child_ref.node.get_win_len(),
-                    "ParallelSolver::get_best_move::root_win_len",
-                ) == root_win_len
-        })
-        .collect();
-    if winning_children.is_empty() {
-        children
-            .iter()
-            .filter(|child_ref| child_ref.node.get_pn() == 0)
-            .min_by_key(|child_ref| (child_ref.node.get_win_len(), child_ref.mov))
-            .map(|child_ref| child_ref.mov)
-    } else {
-        winning_children
-            .iter()
-            .min_by_key(|child_ref| (child_ref.node.get_win_len(), child_ref.mov))
-            .map(|child_ref| child_ref.mov)
-    }
+    solver
+        .tree
+        .move_selector()
+        .select_min(SharedTree::proven_moves(root), |&candidate| candidate)
+        .map(|(mov, _)| mov)
 }
@@ -0,0 +1,218 @@
+use super::super::{
+    AllocationPolicy, Budget, EffortOrdering, MoveOrder, ParallelMode, RootMoveFilter,
+    SpeculationPolicy, TieBreakPolicy,
+};
+use super::{ParallelSolver, SearchParams, SearchReport, SearchResult, types::DEFAULT_ROOT_PLAYER};
+use crate::{
+    about::engine_info,
+    config::EvaluationWeights,
+    game_state::{BoardRegion, Position},
+    interrupt::CancellationToken,
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use serde::{Deserialize, Serialize};
+const BUNDLE_PREFIX: &str = "inevitable-debug:";
+const BUNDLE_FORMAT: u32 = 1;
+const COMPRESSION_LEVEL: u8 = 9;
+const MAX_BUNDLE_BYTES: usize = 64 * 1024 * 1024;
+const LOG_TAIL_LINES: usize = 20;
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DebugParams {
+    pub board_size: usize,
+    pub win_len: usize,
+    pub num_threads: usize,
+    pub evaluation: EvaluationWeights,
+    pub fixed_point: bool,
+    pub budget: Budget,
+    pub repetition_is_draw: bool,
+    pub stats_flush_interval: u32,
+    pub cross_check_interval: u64,
+    pub root_move_filter: Option<RootMoveFilter>,
+    pub max_children: Option<usize>,
+    pub eager_children: Option<usize>,
+    pub hot_path_allocations: AllocationPolicy,
+    pub tie_breaks: TieBreakPolicy,
+    pub tie_break_seed: Option<u64>,
+    #[serde(default)]
+    pub region: Option<BoardRegion>,
+    #[serde(default)]
+    pub speculation: SpeculationPolicy,
+    #[serde(default)]
+    pub dfpn_epsilon: Option<f64>,
+    #[serde(default)]
+    pub move_order: MoveOrder,
+    #[serde(default)]
+    pub parallel_mode: ParallelMode,
+    #[serde(default)]
+    pub effort_ordering: EffortOrdering,
+    #[serde(default = "default_root_player")]
+    pub root_player: u8,
+}
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DebugBundle {
+    pub format: u32,
+    pub engine: String,
+    pub position: String,
+    pub params: DebugParams,
+    pub report: serde_json::Value,
+    pub log_tail: Vec<String>,
+}
+const fn default_root_player() -> u8 {
+    DEFAULT_ROOT_PLAYER
+}
+impl DebugParams {
+    fn capture(params: &SearchParams, tie_break_seed: Option<u64>) -> Self {
+        Self {
+            board_size: params.board_size,
+            win_len: params.win_len,
+            num_threads: params.num_threads,
+            evaluation: params.evaluation,
+            fixed_point: params.evaluation.fixed_point,
+            budget: params.budget,
+            repetition_is_draw: params.repetition_is_draw,
+            stats_flush_interval: params.stats_flush_interval,
+            cross_check_interval: params.cross_check_interval,
+            root_move_filter: params.root_move_filter.clone(),
+            max_children: params.max_children,
+            eager_children: params.eager_children,
+            hot_path_allocations: params.hot_path_allocations,
+            tie_breaks: params.tie_breaks,
+            tie_break_seed: tie_break_seed.or(params.tie_break_seed),
+            region: params.region,
+            speculation: params.speculation,
+            dfpn_epsilon: params.dfpn_epsilon,
+            move_order: params.move_order,
+            parallel_mode: params.parallel_mode,
+            effort_ordering: params.effort_ordering,
+            root_player: params.root_player,
+        }
+    }
+}
+impl DebugBundle {
+    #[inline]
+    #[must_use]
+    pub fn capture(board: &[u8], params: &SearchParams, report: &SearchReport) -> Self {
+        let position = Position::from_board(
+            board.to_vec(),
+            params.board_size,
+            params.win_len,
+            params.root_player,
+        )
+        .map_or_else(
+            |err| format!("无效局面: {err}"),
+            |position| position.to_string(),
+        );
+        Self {
+            format: BUNDLE_FORMAT,
+            engine: engine_info().to_string(),
+            position,
+            params: DebugParams::capture(params, report.tie_break_seed),
+            report: serde_json::to_value(report).unwrap_or_else(|err| {
+                eprintln!("序列化搜索报告失败: {err}");
+                serde_json::Value::Null
+            }),
+            log_tail: super::logging::log_tail(LOG_TAIL_LINES),
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_else(|err| {
+            eprintln!("序列化调试包失败: {err}");
+            Vec::new()
+        });
+        let compressed = miniz_oxide::deflate::compress_to_vec(&json, COMPRESSION_LEVEL);
+        format!(
+            "{BUNDLE_PREFIX}{encoded}",
+            encoded = STANDARD.encode(compressed)
+        )
+    }
+    #[inline]
+    pub fn decode(text: &str) -> Result<Self, String> {
+        let compact: String = text.split_whitespace().collect();
+        let encoded = compact
+            .strip_prefix(BUNDLE_PREFIX)
+            .ok_or_else(|| format!("调试包须以 {BUNDLE_PREFIX} 开头"))?;
+        let compressed = STANDARD
+            .decode(encoded)
+            .map_err(|err| format!("调试包 base64 解码失败: {err}"))?;
+        let json =
+            miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_BUNDLE_BYTES)
+                .map_err(|err| format!("调试包解压失败: {err}"))?;
+        let bundle: Self =
+            serde_json::from_slice(&json).map_err(|err| format!("调试包解析失败: {err}"))?;
+        if bundle.format != BUNDLE_FORMAT {
+            return Err(format!(
+                "不支持的调试包格式版本 {format}，当前为 {BUNDLE_FORMAT}",
+                format = bundle.format
+            ));
+        }
+        Ok(bundle)
+    }
+    #[inline]
+    pub fn board(&self) -> Result<Vec<u8>, String> {
+        let position = Position::parse(&self.position, self.params.win_len)?;
+        if position.board_size() != self.params.board_size {
+            return Err(format!(
+                "调试包局面为 {size}x{size}，与记录的棋盘大小 {expected} 不符",
+                size = position.board_size(),
+                expected = self.params.board_size
+            ));
+        }
+        Ok(position.board().to_vec())
+    }
+    #[inline]
+    #[must_use]
+    pub fn search_params(&self) -> SearchParams {
+        let params = &self.params;
+        let evaluation = EvaluationWeights {
+            fixed_point: params.fixed_point,
+            ..params.evaluation
+        };
+        let mut search_params = SearchParams::new(
+            params.board_size,
+            params.win_len,
+            params.num_threads,
+            evaluation,
+        )
+        .with_budget(params.budget)
+        .with_repetition_draws(params.repetition_is_draw)
+        .with_stats_flush_interval(params.stats_flush_interval)
+        .with_cross_check(params.cross_check_interval)
+        .with_max_children(params.max_children)
+        .with_eager_children(params.eager_children)
+        .with_hot_path_allocations(params.hot_path_allocations)
+        .with_tie_breaks(params.tie_breaks)
+        .with_tie_break_seed(params.tie_break_seed)
+        .with_region(params.region)
+        .with_speculation(params.speculation)
+        .with_dfpn_epsilon(params.dfpn_epsilon)
+        .with_move_order(params.move_order)
+        .with_parallel_mode(params.parallel_mode)
+        .with_effort_ordering(params.effort_ordering)
+        .with_root_player(params.root_player);
+        if let Some(filter) = params.root_move_filter.clone() {
+            search_params = search_params.with_root_move_filter(filter);
+        }
+        search_params
+    }
+    #[inline]
+    pub fn reproduce(&self, cancel: &CancellationToken) -> Result<SearchResult, String> {
+        let board = self.board()?;
+        Ok(ParallelSolver::search(
+            board,
+            self.search_params(),
+            false,
+            cancel,
+            None,
+            None,
+        ))
+    }
+}
+pub(super) fn debug_bundle(solver: &ParallelSolver) -> DebugBundle {
+    DebugBundle::capture(
+        solver.base_game_state.position.board.as_slice(),
+        &solver.params,
+        &solver.stats_report(),
+    )
+}
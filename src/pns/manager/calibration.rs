@@ -0,0 +1,115 @@
+use super::super::stats_def::to_f64;
+use super::{DepthReport, HeuristicWinEstimate, ParallelSolver, SearchOutcome};
+use crate::checked;
+const HEURISTIC_INTERCEPT: f64 = -0.35;
+const HEURISTIC_RATIO_WEIGHT: f64 = 0.6;
+const HEURISTIC_TREND_WEIGHT: f64 = 0.4;
+const HEURISTIC_EVALUATION_WEIGHT: f64 = 1.5;
+const HEURISTIC_REFUTED_WEIGHT: f64 = -2.0;
+const HEURISTIC_MIN_PROBABILITY: f64 = 0.02;
+const HEURISTIC_MAX_PROBABILITY: f64 = 0.98;
+const ABORTED_PROBABILITY: f64 = 0.5;
+impl SearchOutcome {
+    #[inline]
+    #[must_use]
+    pub const fn win_probability(&self) -> f64 {
+        match *self {
+            Self::Proven { .. } => 1.0,
+            Self::Disproven => 0.0,
+            Self::Unresolved { heuristic, .. } => heuristic.probability,
+            Self::Aborted { .. } => ABORTED_PROBABILITY,
+        }
+    }
+}
+fn finite_log_ratio(pn: u64, dn: u64) -> Option<f64> {
+    let finite = |value: u64| value != 0 && value != u64::MAX;
+    (finite(pn) && finite(dn)).then(|| (to_f64(dn) / to_f64(pn)).ln())
+}
+fn trajectory_trend(ratios: &[f64]) -> f64 {
+    let (Some(&first), Some(&last)) = (ratios.first(), ratios.last()) else {
+        return 0.0;
+    };
+    let steps = checked::usize_to_u64(ratios.len(), "calibration::trajectory_trend::steps");
+    if steps < 2 {
+        return 0.0;
+    }
+    (last - first) / to_f64(steps.saturating_sub(1))
+}
+fn refuted_fraction(solver: &ParallelSolver) -> f64 {
+    let Some(children) = solver.tree.root.children.get() else {
+        return 0.0;
+    };
+    let total = checked::usize_to_u64(children.len(), "calibration::refuted_fraction::total");
+    if total == 0 {
+        return 0.0;
+    }
+    let refuted = children
+        .iter()
+        .filter(|child_ref| child_ref.node.get_dn() == 0)
+        .count();
+    to_f64(checked::usize_to_u64(
+        refuted,
+        "calibration::refuted_fraction::refuted",
+    )) / to_f64(total)
+}
+fn static_evaluation(solver: &ParallelSolver) -> f64 {
+    let game_state = &solver.base_game_state;
+    let position = &game_state.position;
+    let empty_cells: Vec<_> = (0..position.board_size)
+        .flat_map(|row| (0..position.board_size).map(move |column| (row, column)))
+        .filter(|&(row, column)| position.cell(row, column) == 0)
+        .collect();
+    let root_player = super::accessors::root_player(solver);
+    let opponent = checked::opponent_player(root_player, "calibration::static_evaluation");
+    let mut scored_moves = Vec::with_capacity(empty_cells.len());
+    let mut best_score = |player| {
+        game_state
+            .evaluator
+            .score_moves_into(position, player, &empty_cells, &mut scored_moves);
+        scored_moves
+            .iter()
+            .map(|&(_, score)| f64::from(score))
+            .fold(0.0_f64, f64::max)
+    };
+    let own = best_score(root_player);
+    let opposing = best_score(opponent);
+    let scale = f64::from(game_state.evaluator.config.score_live_four).max(1.0_f64);
+    ((own - opposing) / scale).clamp(-1.0, 1.0)
+}
+pub(super) fn heuristic_win_estimate(
+    solver: &ParallelSolver,
+    per_depth: &[DepthReport],
+) -> HeuristicWinEstimate {
+    let ratios: Vec<f64> = per_depth
+        .iter()
+        .filter_map(|report| finite_log_ratio(report.root_pn, report.root_dn))
+        .collect();
+    let pn_dn_log_ratio = finite_log_ratio(
+        super::accessors::root_pn(solver),
+        super::accessors::root_dn(solver),
+    )
+    .or_else(|| ratios.last().copied())
+    .unwrap_or(0.0_f64);
+    let pn_dn_trend = trajectory_trend(&ratios);
+    let refuted_fraction = refuted_fraction(solver);
+    let static_evaluation = static_evaluation(solver);
+    let logit = HEURISTIC_EVALUATION_WEIGHT.mul_add(
+        static_evaluation,
+        HEURISTIC_REFUTED_WEIGHT.mul_add(
+            refuted_fraction,
+            HEURISTIC_TREND_WEIGHT.mul_add(
+                pn_dn_trend,
+                HEURISTIC_RATIO_WEIGHT.mul_add(pn_dn_log_ratio, HEURISTIC_INTERCEPT),
+            ),
+        ),
+    );
+    let probability =
+        (1.0 / (1.0 + (-logit).exp())).clamp(HEURISTIC_MIN_PROBABILITY, HEURISTIC_MAX_PROBABILITY);
+    HeuristicWinEstimate {
+        probability,
+        pn_dn_log_ratio,
+        pn_dn_trend,
+        refuted_fraction,
+        static_evaluation,
+    }
+}
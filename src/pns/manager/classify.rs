@@ -0,0 +1,94 @@
+use super::super::{Budget, RootMoveFilter, TranspositionTable};
+use super::{SearchParams, SearchResult};
+use crate::{
+    checked,
+    game_state::{Coord, Position},
+    interrupt::CancellationToken,
+};
+const EMPTY: u8 = 0;
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MoveClass {
+    PreservesWin,
+    Loss,
+    Unknown,
+}
+impl MoveClass {
+    #[inline]
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::PreservesWin => "保持必胜",
+            Self::Loss => "败着，对手可证明胜利",
+            Self::Unknown => "预算内未能判定",
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn is_blunder(self) -> bool {
+        matches!(self, Self::Loss)
+    }
+}
+pub(super) fn classify_move(
+    position: &Position,
+    mov: Coord,
+    params: SearchParams,
+    budget: Budget,
+) -> MoveClass {
+    let mover = position.side_to_move();
+    if position.check_move(mov).is_err() {
+        return MoveClass::Unknown;
+    }
+    if position.is_winning_move(mov, mover) {
+        return MoveClass::PreservesWin;
+    }
+    let mut after = position.clone();
+    if after.play(mov).is_err() || after.is_full() {
+        return MoveClass::Unknown;
+    }
+    let cancel = CancellationToken::new();
+    let root_player = params.root_player;
+    let bounded = params.with_budget(budget);
+    let win = bounded_search(
+        attacker_board(position, mover, root_player),
+        bounded
+            .clone()
+            .with_root_move_filter(RootMoveFilter::allow([mov])),
+        &cancel,
+        None,
+    );
+    if win.best_move.is_some() {
+        return MoveClass::PreservesWin;
+    }
+    let opponent = checked::opponent_player(mover, "ParallelSolver::classify_move::opponent");
+    let refutation = bounded_search(
+        attacker_board(&after, opponent, root_player),
+        bounded,
+        &cancel,
+        Some(win.transposition_table),
+    );
+    if refutation.best_move.is_some() {
+        MoveClass::Loss
+    } else {
+        MoveClass::Unknown
+    }
+}
+fn bounded_search(
+    board: Vec<u8>,
+    params: SearchParams,
+    cancel: &CancellationToken,
+    existing_tt: Option<TranspositionTable>,
+) -> SearchResult {
+    super::best_move::search(board, params, false, cancel, existing_tt, None)
+}
+fn attacker_board(position: &Position, attacker: u8, root_player: u8) -> Vec<u8> {
+    let defender = checked::opponent_player(root_player, "classify::attacker_board");
+    position
+        .board()
+        .iter()
+        .map(|&cell| match cell {
+            EMPTY => EMPTY,
+            stone if stone == attacker => root_player,
+            _ => defender,
+        })
+        .collect()
+}
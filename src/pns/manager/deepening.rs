@@ -1,26 +1,29 @@
-use super::super::{NodeTable, TranspositionTable, TreeStatsSnapshot};
-use super::ParallelSolver;
-use super::logging::{format_sci_u64, format_sci_usize, write_csv_log_snapshot};
-use crate::checked;
+use super::super::{FrontierRefresh, TreeStatsSnapshot};
+use super::logging::{format_sci_u64, format_sci_usize};
+use super::{DepthReport, DepthSample, ParallelSolver, SearchReport, SearchResult};
+use crate::{alloc_stats, checked};
 use alloc::{collections::BTreeMap, string::String};
-use std::time::Instant;
+use std::{path::Path, time::Instant};
 #[derive(Default)]
 pub(super) struct DepthAccumulator {
     total_stats: TreeStatsSnapshot,
     total_elapsed_secs: f64,
     total_tt_size: u64,
     total_node_table_size: u64,
+    peak_live_bytes: u64,
     count: u64,
 }
 impl DepthAccumulator {
     pub(super) fn add_sample(
         &mut self,
-        stats: TreeStatsSnapshot,
+        stats: &TreeStatsSnapshot,
         elapsed_secs: f64,
         tt_size: u64,
         node_table_size: u64,
+        peak_live_bytes: u64,
     ) {
-        self.total_stats.add_assign(&stats);
+        self.total_stats.add_assign(stats);
+        self.peak_live_bytes = self.peak_live_bytes.max(peak_live_bytes);
         self.total_elapsed_secs += elapsed_secs;
         self.total_tt_size = checked::add_u64(
             self.total_tt_size,
@@ -57,27 +60,39 @@ impl DepthAccumulator {
         (stats, elapsed_secs, tt_size, node_table_size)
     }
 }
-pub(super) fn write_benchmark_logs(per_depth: BTreeMap<usize, DepthAccumulator>) {
-    for (depth, acc) in per_depth {
-        if acc.count == 0 {
-            continue;
-        }
-        let (stats, elapsed_secs, tt_size, node_table_size) = acc.average();
-        write_csv_log_snapshot(
-            1,
-            elapsed_secs,
-            stats,
-            tt_size,
-            node_table_size,
-            Some(depth),
-        );
-    }
+pub(super) fn peak_live_bytes_by_depth(
+    per_depth: &BTreeMap<usize, DepthAccumulator>,
+) -> Vec<(usize, u64)> {
+    per_depth
+        .iter()
+        .filter(|&(_, acc)| acc.count > 0)
+        .map(|(&depth, acc)| (depth, acc.peak_live_bytes))
+        .collect()
+}
+pub(super) fn depth_reports(per_depth: &BTreeMap<usize, DepthAccumulator>) -> Vec<DepthReport> {
+    per_depth
+        .iter()
+        .filter(|&(_, acc)| acc.count > 0)
+        .map(|(&depth, acc)| {
+            let (stats, elapsed_secs, tt_size, node_table_size) = acc.average();
+            DepthReport::from_stats(
+                1,
+                Some(depth),
+                elapsed_secs,
+                &stats,
+                tt_size,
+                node_table_size,
+                acc.peak_live_bytes,
+            )
+        })
+        .collect()
 }
 pub(super) trait IterativeDeepeningHooks<R> {
     fn on_stop(&mut self, solver: &mut ParallelSolver) -> R;
     fn before_solve(&mut self, _depth: usize, _solver: &mut ParallelSolver) {}
     fn solve(&mut self, solver: &mut ParallelSolver) -> bool;
     fn after_solve(&mut self, _depth: usize, _solver: &mut ParallelSolver, _found: bool) {}
+    fn after_deepen(&mut self, _solver: &mut ParallelSolver, _refresh: FrontierRefresh) {}
     fn on_found(&mut self, _depth: usize, solver: &mut ParallelSolver) -> R;
 }
 pub(super) struct BenchmarkDeepening<'benchmark> {
@@ -91,6 +106,8 @@ pub(super) struct BenchmarkDeepening<'benchmark> {
     pub total_elapsed_secs: &'benchmark mut f64,
     pub total_tt_size: &'benchmark mut u64,
     pub total_node_table_size: &'benchmark mut u64,
+    pub run: usize,
+    pub samples: &'benchmark mut Vec<DepthSample>,
 }
 impl IterativeDeepeningHooks<Option<()>> for BenchmarkDeepening<'_> {
     fn on_stop(&mut self, _solver: &mut ParallelSolver) -> Option<()> {
@@ -111,17 +128,29 @@ impl IterativeDeepeningHooks<Option<()>> for BenchmarkDeepening<'_> {
             solver.tree.get_node_table_size(),
             "BenchmarkDeepening::node_table_size",
         );
+        let peak_live_bytes = alloc_stats::peak_live_bytes();
         self.per_depth.entry(depth).or_default().add_sample(
-            delta_stats,
+            &delta_stats,
             delta_elapsed,
             tt_size,
             node_table_size,
+            peak_live_bytes,
         );
+        self.samples.push(DepthSample {
+            run: self.run,
+            depth,
+            elapsed_secs: delta_elapsed,
+            stats: delta_stats,
+            tt_size,
+            node_table_size,
+            peak_live_bytes,
+        });
         self.prev_stats = current_stats;
         self.prev_elapsed = elapsed;
         self.last_tt_size = tt_size;
         self.last_node_table_size = node_table_size;
     }
+    fn after_deepen(&mut self, _solver: &mut ParallelSolver, _refresh: FrontierRefresh) {}
     fn on_found(&mut self, _depth: usize, solver: &mut ParallelSolver) -> Option<()> {
         solver.get_best_move()?;
         *self.total_elapsed_secs += self.prev_elapsed;
@@ -141,17 +170,119 @@ impl IterativeDeepeningHooks<Option<()>> for BenchmarkDeepening<'_> {
 }
 pub(super) struct BestMoveDeepening {
     pub verbose: bool,
+    pub depth: usize,
 }
-impl IterativeDeepeningHooks<(Option<(usize, usize)>, TranspositionTable, NodeTable)>
-    for BestMoveDeepening
-{
-    fn on_stop(
-        &mut self,
-        solver: &mut ParallelSolver,
-    ) -> (Option<(usize, usize)>, TranspositionTable, NodeTable) {
-        (None, solver.get_tt(), solver.get_node_table())
+impl BestMoveDeepening {
+    fn log_time_decisions(&self, solver: &ParallelSolver) {
+        let decisions = solver.tree.drain_time_decisions();
+        if !self.verbose {
+            return;
+        }
+        for record in decisions {
+            println!(
+                "时间管理: {decision}，已用 {elapsed:.3}s，软限制 {soft:.3}s，硬限制 {hard:.3}s，根节点 PN={pn}",
+                decision = record.decision.label(),
+                elapsed = record.elapsed.as_secs_f64(),
+                soft = record.soft_limit.as_secs_f64(),
+                hard = record.hard_limit.as_secs_f64(),
+                pn = format_sci_u64(record.root_pn)
+            );
+        }
+    }
+    fn log_memory_degradations(&self, report: &SearchReport) {
+        if !self.verbose {
+            return;
+        }
+        for record in &report.memory_degradations {
+            println!(
+                "内存软限制 {soft}: {label}，估计内存 {before} -> {after}",
+                soft = format_sci_u64(record.soft_limit_bytes),
+                label = record.degradation.label(),
+                before = format_sci_u64(record.estimated_before),
+                after = format_sci_u64(record.estimated_after)
+            );
+        }
+    }
+    fn export_solution(&self, solver: &ParallelSolver) {
+        let Some(directory) = solver.params.solution_directory.as_deref() else {
+            return;
+        };
+        if solver.quiet || !super::solution::is_full_board_solve(solver) {
+            return;
+        }
+        match super::solution::export_solution(solver, Path::new(directory)) {
+            Ok(summary) if self.verbose => println!(
+                "已将空棋盘的完整解导出到 {directory}: 主要变例 {pv} 手，证明图 {nodes} 个节点",
+                pv = format_sci_usize(summary.principal_variation.len()),
+                nodes = format_sci_usize(summary.proof_nodes)
+            ),
+            Ok(_) => {}
+            Err(err) => eprintln!("导出完整解到 {directory} 失败: {err}"),
+        }
     }
-    fn before_solve(&mut self, depth: usize, _solver: &mut ParallelSolver) {
+    fn result(&self, solver: &ParallelSolver, best_move: Option<(usize, usize)>) -> SearchResult {
+        let report = solver.stats_report();
+        solver.tree.report_overlay(true);
+        self.log_memory_degradations(&report);
+        if self.verbose
+            && let Some(seed) = report.tie_break_seed
+        {
+            println!("随机打破平局种子: {seed}");
+        }
+        if self.verbose && report.hot_path_allocations > 0 {
+            println!(
+                "展开热路径共发生 {count} 次堆分配",
+                count = format_sci_u64(report.hot_path_allocations)
+            );
+        }
+        SearchResult {
+            best_move,
+            most_proving_move: solver.get_most_proving_move(),
+            best_known_move: solver.get_best_known_move(),
+            proof_line: solver.get_proof_line(),
+            transposition_table: solver.get_tt(),
+            node_table: solver.get_node_table(),
+            depth: self.depth,
+            root_pn: solver.root_pn(),
+            root_dn: solver.root_dn(),
+            root_win_len: solver.root_win_len(),
+            limit_reached: solver.limit_reached(),
+            root_move_filter: solver.tree.root_move_filter().cloned(),
+            report,
+            expansion_log: solver
+                .tree
+                .expansion_log(solver.base_game_state.position.board.as_slice()),
+        }
+    }
+}
+impl IterativeDeepeningHooks<SearchResult> for BestMoveDeepening {
+    fn on_stop(&mut self, solver: &mut ParallelSolver) -> SearchResult {
+        self.log_time_decisions(solver);
+        if self.verbose {
+            if let Some(limit) = solver.limit_reached() {
+                println!(
+                    "搜索预算耗尽（{limit}），停止于深度 D={depth}",
+                    limit = limit.label(),
+                    depth = format_sci_usize(self.depth)
+                );
+            } else if let Some(reason) = solver.tree.cancellation_token().reason() {
+                println!(
+                    "搜索已取消（{reason}），停止于深度 D={depth}",
+                    reason = reason.label(),
+                    depth = format_sci_usize(self.depth)
+                );
+            } else if solver.tree.root.get_dn() == 0 {
+                println!(
+                    "根节点在深度 D={depth} 已被完全证伪，不再加深",
+                    depth = format_sci_usize(self.depth)
+                );
+            }
+        }
+        self.result(solver, None)
+    }
+    fn before_solve(&mut self, depth: usize, solver: &mut ParallelSolver) {
+        self.depth = depth;
+        self.log_time_decisions(solver);
         if self.verbose {
             println!("尝试搜索深度 D={depth}", depth = format_sci_usize(depth));
         }
@@ -160,12 +291,21 @@ impl IterativeDeepeningHooks<(Option<(usize, usize)>, TranspositionTable, NodeTa
         solver.solve(self.verbose)
     }
     fn after_solve(&mut self, _depth: usize, _solver: &mut ParallelSolver, _found: bool) {}
-    fn on_found(
-        &mut self,
-        _depth: usize,
-        solver: &mut ParallelSolver,
-    ) -> (Option<(usize, usize)>, TranspositionTable, NodeTable) {
+    fn after_deepen(&mut self, _solver: &mut ParallelSolver, refresh: FrontierRefresh) {
+        if self.verbose {
+            println!(
+                "沿用上一轮结果: 重新打开 {reopened} 个截断叶子，跳过 {skipped} 个已定子树，回收 {evicted} 个无引用节点",
+                reopened = format_sci_usize(refresh.reopened_leaves),
+                skipped = format_sci_usize(refresh.skipped_subtrees),
+                evicted = format_sci_usize(refresh.evicted_nodes)
+            );
+        }
+    }
+    fn on_found(&mut self, _depth: usize, solver: &mut ParallelSolver) -> SearchResult {
         let best_move = solver.get_best_move();
+        self.export_solution(solver);
+        solver.tree.record_proven_time();
+        self.log_time_decisions(solver);
         if self.verbose {
             let path_len = format_sci_u64(solver.root_win_len());
             let best_move_display = best_move.map_or_else(
@@ -174,6 +314,6 @@ impl IterativeDeepeningHooks<(Option<(usize, usize)>, TranspositionTable, NodeTa
             );
             println!("在 {path_len} 步内找到路径，最佳首步: {best_move_display}");
         }
-        (best_move, solver.get_tt(), solver.get_node_table())
+        self.result(solver, best_move)
     }
 }
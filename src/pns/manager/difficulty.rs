@@ -0,0 +1,81 @@
+use super::super::{Budget, stats_def::to_f64};
+use super::{DifficultyEstimate, SearchParams};
+use crate::{checked, game_state::GomokuRules, interrupt::CancellationToken};
+pub(super) fn probe_difficulty(
+    initial_board: &[u8],
+    params: SearchParams,
+    budget: Budget,
+) -> DifficultyEstimate {
+    let cancel = CancellationToken::new();
+    let depth = 1_usize;
+    let mut solver = super::setup::with_tt_and_stop(
+        initial_board.to_vec(),
+        params.with_budget(budget),
+        Some(depth),
+        &cancel,
+        None,
+        None,
+    );
+    let mut hooks = super::deepening::BestMoveDeepening {
+        verbose: false,
+        depth,
+    };
+    let result = super::solve::run_iterative_deepening(&mut solver, &cancel, depth, &mut hooks);
+    let stats = solver.tree.stats_snapshot();
+    let root_pn = solver.root_pn();
+    let root_dn = solver.root_dn();
+    let branching_factor = if stats.expansions > 0_u64 {
+        to_f64(stats.children_generated) / to_f64(stats.expansions)
+    } else {
+        0.0_f64
+    };
+    let nodes_searched = stats.nodes_created;
+    let solved = result.best_move.is_some();
+    let expected_proof_nodes = if solved {
+        to_f64(nodes_searched)
+    } else {
+        let open_leaves = if root_pn == u64::MAX {
+            nodes_searched
+        } else {
+            root_pn
+        };
+        to_f64(open_leaves).mul_add(branching_factor.max(1.0_f64), to_f64(nodes_searched))
+    };
+    let game_state = &solver.base_game_state;
+    let (forcing_moves, candidate_moves) = GomokuRules::forcing_move_counts(
+        &game_state.position,
+        &game_state.move_cache,
+        solver.root_player(),
+    );
+    let position = &game_state.position;
+    let attacker = solver.root_player();
+    let defender = checked::opponent_player(attacker, "ParallelSolver::probe_difficulty::defender");
+    let forcing_density = if candidate_moves == 0 {
+        0.0_f64
+    } else {
+        to_f64(checked::usize_to_u64(
+            forcing_moves,
+            "ParallelSolver::probe_difficulty::forcing_moves",
+        )) / to_f64(checked::usize_to_u64(
+            candidate_moves,
+            "ParallelSolver::probe_difficulty::candidate_moves",
+        ))
+    };
+    DifficultyEstimate {
+        solved,
+        depth_reached: result.depth,
+        nodes_searched,
+        expected_proof_nodes,
+        branching_factor,
+        forcing_density,
+        attacker_patterns: position
+            .threat_index
+            .pattern_counts(&position.board, attacker),
+        defender_patterns: position
+            .threat_index
+            .pattern_counts(&position.board, defender),
+        root_pn,
+        root_dn,
+        limit_reached: result.limit_reached,
+    }
+}
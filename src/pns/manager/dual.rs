@@ -0,0 +1,240 @@
+use super::super::NodeRef;
+use super::{ParallelSolver, SearchOutcome, SearchParams, SearchResult};
+use crate::{game_state::Coord, interrupt::CancellationToken};
+use alloc::sync::Arc;
+use std::time::Instant;
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    Agree,
+    LineDivergence,
+    WinLengthMismatch,
+    OutcomeMismatch,
+    Inconclusive,
+}
+impl Verdict {
+    #[inline]
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Agree => "一致",
+            Self::LineDivergence => "结论一致，胜利路线不同",
+            Self::WinLengthMismatch => "胜利步数不一致",
+            Self::OutcomeMismatch => "结论不一致",
+            Self::Inconclusive => "至少一方未得出结论",
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn is_disagreement(self) -> bool {
+        matches!(self, Self::WinLengthMismatch | Self::OutcomeMismatch)
+    }
+}
+#[derive(Clone)]
+pub struct EngineRun {
+    pub num_threads: usize,
+    pub outcome: SearchOutcome,
+    pub depth: usize,
+    pub nodes: u64,
+    pub elapsed_secs: f64,
+    pub line: Vec<Coord>,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChildSummary {
+    pub pn: u64,
+    pub dn: u64,
+    pub win_len: u64,
+}
+impl ChildSummary {
+    fn of(node: &NodeRef) -> Self {
+        Self {
+            pn: node.get_pn(),
+            dn: node.get_dn(),
+            win_len: node.get_win_len(),
+        }
+    }
+    const fn same_verdict(self, other: Self) -> bool {
+        (self.pn == 0) == (other.pn == 0)
+            && (self.dn == 0) == (other.dn == 0)
+            && (self.pn != 0 || self.win_len == other.win_len)
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChildDiff {
+    pub mov: Coord,
+    pub primary: Option<ChildSummary>,
+    pub secondary: Option<ChildSummary>,
+}
+#[derive(Clone)]
+pub struct NodeDiff {
+    pub path: Vec<Coord>,
+    pub children: Vec<ChildDiff>,
+}
+#[derive(Clone)]
+pub struct Arbitration {
+    pub primary: EngineRun,
+    pub secondary: EngineRun,
+    pub verdict: Verdict,
+    pub divergence: Option<NodeDiff>,
+}
+fn run_engine(
+    initial_board: &[u8],
+    params: SearchParams,
+    cancel: &CancellationToken,
+) -> (ParallelSolver, SearchResult, f64) {
+    let start = Instant::now();
+    let depth = 1_usize;
+    let mut solver = super::setup::with_tt_and_stop(
+        initial_board.to_vec(),
+        params,
+        Some(depth),
+        cancel,
+        None,
+        None,
+    );
+    let mut hooks = super::deepening::BestMoveDeepening {
+        verbose: false,
+        depth,
+    };
+    let result = super::solve::run_iterative_deepening(&mut solver, cancel, depth, &mut hooks);
+    (solver, result, start.elapsed().as_secs_f64())
+}
+fn node_at(root: &NodeRef, path: &[Coord]) -> Option<NodeRef> {
+    let mut node = Arc::clone(root);
+    for &mov in path {
+        let child = node
+            .expanded_children()?
+            .find(|child_ref| child_ref.mov.coord() == mov)
+            .map(|child_ref| Arc::clone(&child_ref.node))?;
+        node = child;
+    }
+    Some(node)
+}
+fn child_summaries(node: Option<&NodeRef>) -> Vec<(Coord, ChildSummary)> {
+    node.and_then(|parent| parent.expanded_children())
+        .map(|children| {
+            children
+                .map(|child_ref| (child_ref.mov.coord(), ChildSummary::of(&child_ref.node)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+fn diff_node(
+    primary_root: &NodeRef,
+    secondary_root: &NodeRef,
+    path: Vec<Coord>,
+    chosen: &[Coord],
+) -> NodeDiff {
+    let primary = child_summaries(node_at(primary_root, &path).as_ref());
+    let secondary = child_summaries(node_at(secondary_root, &path).as_ref());
+    let mut moves: Vec<Coord> = primary
+        .iter()
+        .chain(secondary.iter())
+        .map(|&(mov, _)| mov)
+        .collect();
+    moves.sort_unstable();
+    moves.dedup();
+    let lookup = |summaries: &[(Coord, ChildSummary)], mov: Coord| {
+        summaries
+            .iter()
+            .find(|&&(candidate, _)| candidate == mov)
+            .map(|&(_, summary)| summary)
+    };
+    let children = moves
+        .into_iter()
+        .map(|mov| ChildDiff {
+            mov,
+            primary: lookup(&primary, mov),
+            secondary: lookup(&secondary, mov),
+        })
+        .filter(|diff| {
+            chosen.contains(&diff.mov)
+                || match (diff.primary, diff.secondary) {
+                    (Some(primary_summary), Some(secondary_summary)) => {
+                        !primary_summary.same_verdict(secondary_summary)
+                    }
+                    _ => true,
+                }
+        })
+        .collect();
+    NodeDiff { path, children }
+}
+fn verdict(primary: &EngineRun, secondary: &EngineRun) -> Verdict {
+    match (primary.outcome, secondary.outcome) {
+        (SearchOutcome::Unresolved { .. }, _) | (_, SearchOutcome::Unresolved { .. }) => {
+            Verdict::Inconclusive
+        }
+        (
+            SearchOutcome::Proven {
+                win_len: primary_win_len,
+                ..
+            },
+            SearchOutcome::Proven {
+                win_len: secondary_win_len,
+                ..
+            },
+        ) => {
+            if primary_win_len != secondary_win_len {
+                Verdict::WinLengthMismatch
+            } else if primary.line == secondary.line {
+                Verdict::Agree
+            } else {
+                Verdict::LineDivergence
+            }
+        }
+        (SearchOutcome::Disproven, SearchOutcome::Disproven) => Verdict::Agree,
+        _ => Verdict::OutcomeMismatch,
+    }
+}
+pub(super) fn arbitrate(
+    initial_board: &[u8],
+    primary_params: SearchParams,
+    secondary_params: SearchParams,
+    cancel: &CancellationToken,
+) -> Arbitration {
+    let primary_threads = primary_params.num_threads;
+    let secondary_threads = secondary_params.num_threads;
+    let (primary_solver, primary_result, primary_elapsed) =
+        run_engine(initial_board, primary_params, cancel);
+    let (secondary_solver, secondary_result, secondary_elapsed) =
+        run_engine(initial_board, secondary_params, cancel);
+    let primary_root = &primary_solver.tree.root;
+    let secondary_root = &secondary_solver.tree.root;
+    let primary = EngineRun {
+        num_threads: primary_threads,
+        outcome: primary_result.report.outcome,
+        depth: primary_result.depth,
+        nodes: primary_result.report.totals.stats.nodes_created,
+        elapsed_secs: primary_elapsed,
+        line: primary_solver.get_proof_line(),
+    };
+    let secondary = EngineRun {
+        num_threads: secondary_threads,
+        outcome: secondary_result.report.outcome,
+        depth: secondary_result.depth,
+        nodes: secondary_result.report.totals.stats.nodes_created,
+        elapsed_secs: secondary_elapsed,
+        line: secondary_solver.get_proof_line(),
+    };
+    let verdict = verdict(&primary, &secondary);
+    let divergence = (!matches!(verdict, Verdict::Agree | Verdict::Inconclusive)).then(|| {
+        let common = primary
+            .line
+            .iter()
+            .zip(secondary.line.iter())
+            .take_while(|&(primary_move, secondary_move)| primary_move == secondary_move)
+            .count();
+        let chosen: Vec<Coord> = [primary.line.get(common), secondary.line.get(common)]
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect();
+        let path = primary.line.get(..common).unwrap_or_default().to_vec();
+        diff_node(primary_root, secondary_root, path, &chosen)
+    });
+    Arbitration {
+        primary,
+        secondary,
+        verdict,
+        divergence,
+    }
+}
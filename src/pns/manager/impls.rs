@@ -1,7 +1,15 @@
-use super::super::{NodeTable, TranspositionTable};
-use super::{BenchmarkResult, ParallelSolver, SearchParams};
-use alloc::sync::Arc;
-use core::sync::atomic::AtomicBool;
+use super::super::{
+    Budget, BudgetLimit, ExpansionLog, FrontierRefresh, NodeTable, ReplaySummary, TTEntry,
+    TranspositionTable, TreeSnapshot,
+};
+use super::{
+    Arbitration, BackgroundSearch, BenchmarkResult, DebugBundle, DifficultyEstimate, MoveClass,
+    ParallelSolver, SearchOutcome, SearchParams, SearchReport, SearchResult,
+};
+use crate::{
+    game_state::{Coord, GameSession, Position},
+    interrupt::CancellationToken,
+};
 impl ParallelSolver {
     pub fn new(
         initial_board: Vec<u8>,
@@ -39,7 +47,7 @@ impl ParallelSolver {
         initial_board: Vec<u8>,
         params: SearchParams,
         depth_limit: Option<usize>,
-        stop_flag: &Arc<AtomicBool>,
+        cancel: &CancellationToken,
         existing_tt: Option<TranspositionTable>,
         existing_node_table: Option<NodeTable>,
     ) -> Self {
@@ -47,24 +55,55 @@ impl ParallelSolver {
             initial_board,
             params,
             depth_limit,
-            stop_flag,
+            cancel,
             existing_tt,
             existing_node_table,
         )
     }
-    pub fn increase_depth_limit(&self, new_limit: usize) {
-        super::setup::increase_depth_limit(self, new_limit);
+    pub fn increase_depth_limit(&self, new_limit: usize) -> FrontierRefresh {
+        super::setup::increase_depth_limit(self, new_limit)
     }
     pub fn solve(&self, verbose: bool) -> bool {
         super::solve::solve(self, verbose)
     }
     pub fn benchmark_next_move(
         initial_board: &[u8],
-        params: SearchParams,
+        params: &SearchParams,
+        runs: usize,
+        cancel: &CancellationToken,
+    ) -> Option<BenchmarkResult> {
+        super::benchmark::benchmark_next_move(initial_board, params, runs, cancel)
+    }
+    pub fn measure_next_move(
+        initial_board: &[u8],
+        params: &SearchParams,
         runs: usize,
-        stop_flag: &Arc<AtomicBool>,
+        cancel: &CancellationToken,
     ) -> Option<BenchmarkResult> {
-        super::benchmark::benchmark_next_move(initial_board, params, runs, stop_flag)
+        super::benchmark::measure_next_move(initial_board, params, runs, cancel)
+    }
+    pub fn arbitrate(
+        initial_board: &[u8],
+        primary: SearchParams,
+        secondary: SearchParams,
+        cancel: &CancellationToken,
+    ) -> Arbitration {
+        super::dual::arbitrate(initial_board, primary, secondary, cancel)
+    }
+    pub fn probe_difficulty(
+        initial_board: &[u8],
+        params: SearchParams,
+        budget: Budget,
+    ) -> DifficultyEstimate {
+        super::difficulty::probe_difficulty(initial_board, params, budget)
+    }
+    pub fn classify_move(
+        position: &Position,
+        mov: Coord,
+        params: SearchParams,
+        budget: Budget,
+    ) -> MoveClass {
+        super::classify::classify_move(position, mov, params, budget)
     }
     pub fn find_best_move_iterative_deepening(
         initial_board: Vec<u8>,
@@ -89,7 +128,12 @@ impl ParallelSolver {
         verbose: bool,
         existing_tt: Option<TranspositionTable>,
         existing_node_table: Option<NodeTable>,
-    ) -> (Option<(usize, usize)>, TranspositionTable, NodeTable) {
+    ) -> (
+        Option<(usize, usize)>,
+        SearchOutcome,
+        TranspositionTable,
+        NodeTable,
+    ) {
         super::best_move::find_best_move_with_tt(
             initial_board,
             params,
@@ -102,28 +146,102 @@ impl ParallelSolver {
         initial_board: Vec<u8>,
         params: SearchParams,
         verbose: bool,
-        stop_flag: &Arc<AtomicBool>,
+        cancel: &CancellationToken,
         existing_tt: Option<TranspositionTable>,
         existing_node_table: Option<NodeTable>,
-    ) -> (Option<(usize, usize)>, TranspositionTable, NodeTable) {
+    ) -> (
+        Option<(usize, usize)>,
+        SearchOutcome,
+        TranspositionTable,
+        NodeTable,
+    ) {
         super::best_move::find_best_move_with_tt_and_stop(
             initial_board,
             params,
             verbose,
-            stop_flag,
+            cancel,
             existing_tt,
             existing_node_table,
         )
     }
+    pub fn search(
+        initial_board: Vec<u8>,
+        params: SearchParams,
+        verbose: bool,
+        cancel: &CancellationToken,
+        existing_tt: Option<TranspositionTable>,
+        existing_node_table: Option<NodeTable>,
+    ) -> SearchResult {
+        super::best_move::search(
+            initial_board,
+            params,
+            verbose,
+            cancel,
+            existing_tt,
+            existing_node_table,
+        )
+    }
+    pub fn spawn_search(
+        initial_board: Vec<u8>,
+        params: SearchParams,
+        verbose: bool,
+        cancel: &CancellationToken,
+        existing_tt: Option<TranspositionTable>,
+        existing_node_table: Option<NodeTable>,
+    ) -> BackgroundSearch {
+        super::background::spawn_search(
+            initial_board,
+            params,
+            verbose,
+            cancel,
+            existing_tt,
+            existing_node_table,
+        )
+    }
+    pub fn search_session(
+        session: &GameSession,
+        params: SearchParams,
+        verbose: bool,
+        cancel: &CancellationToken,
+        existing_tt: Option<TranspositionTable>,
+        existing_node_table: Option<NodeTable>,
+    ) -> SearchResult {
+        super::best_move::search_session(
+            session,
+            params,
+            verbose,
+            cancel,
+            existing_tt,
+            existing_node_table,
+        )
+    }
+    pub fn replay_expansions(log: &ExpansionLog, params: SearchParams) -> ReplaySummary {
+        super::reproduce::replay_expansions(log, params)
+    }
+    pub fn debug_bundle(&self) -> DebugBundle {
+        super::bundle::debug_bundle(self)
+    }
     pub fn get_tt(&self) -> TranspositionTable {
         super::best_move::get_tt(self)
     }
     pub fn get_node_table(&self) -> NodeTable {
         super::best_move::get_node_table(self)
     }
+    pub fn stats_report(&self) -> SearchReport {
+        super::reporting::stats_report(self)
+    }
     pub fn get_best_move(&self) -> Option<(usize, usize)> {
         super::best_move::get_best_move(self)
     }
+    pub fn get_most_proving_move(&self) -> Option<(usize, usize)> {
+        super::best_move::get_most_proving_move(self)
+    }
+    pub fn get_best_known_move(&self) -> Option<(usize, usize)> {
+        super::best_move::get_best_known_move(self)
+    }
+    pub fn get_proof_line(&self) -> Vec<(usize, usize)> {
+        super::best_move::get_proof_line(self)
+    }
     pub fn root_pn(&self) -> u64 {
         super::accessors::root_pn(self)
     }
@@ -136,6 +254,21 @@ impl ParallelSolver {
     pub fn root_win_len(&self) -> u64 {
         super::accessors::root_win_len(self)
     }
+    pub fn freeze_snapshot(&self) -> TreeSnapshot {
+        super::accessors::freeze_snapshot(self)
+    }
+    pub fn probe_line(&self, line: &[Coord]) -> Vec<Option<TTEntry>> {
+        super::accessors::probe_line(self, line)
+    }
+    pub fn limit_reached(&self) -> Option<BudgetLimit> {
+        super::accessors::limit_reached(self)
+    }
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        super::accessors::cancellation_token(self)
+    }
+    pub fn iteration_token(&self) -> CancellationToken {
+        super::accessors::iteration_token(self)
+    }
     pub const fn game_state(&self) -> &crate::game_state::GameState {
         super::accessors::game_state(self)
     }
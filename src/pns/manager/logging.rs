@@ -1,14 +1,13 @@
-use super::super::{SharedTree, TimingStats, TreeStatsSnapshot, stats_def::to_f64};
+use super::super::{SpeculationPolicy, TimingStats, TreeStatsSnapshot, to_f64};
+use super::{DepthReport, ParallelSolver};
 use crate::checked;
 use core::sync::atomic::{AtomicBool, Ordering};
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::{self, BufWriter, Write},
-    sync::Mutex,
 };
-const LOG_FILE_NAME: &str = "log.csv";
+pub(crate) const LOG_FILE_NAME: &str = "log.csv";
 static LOG_FILE_TRUNCATED: AtomicBool = AtomicBool::new(false);
-static LAST_LOG_STATE: Mutex<Option<LastLogState>> = Mutex::new(None);
 fn trim_sci(value: String) -> String {
     if let Some(pos) = value.find('e') {
         let (mantissa_text, exp) = value.split_at(pos);
@@ -63,75 +62,6 @@ pub(super) fn format_sci_usize(value: usize) -> String {
     let value_u64 = u64::try_from(value).unwrap_or(u64::MAX);
     format_sci_u64(value_u64)
 }
-fn percentage(part: u64, total: u64) -> f64 {
-    if total > 0 {
-        to_f64(part) / to_f64(total) * 100.0
-    } else {
-        0.0
-    }
-}
-struct HitRates {
-    tt: f64,
-    node_table: f64,
-}
-fn calc_hit_rates(
-    tt_hits: u64,
-    tt_lookups: u64,
-    node_table_hits: u64,
-    node_table_lookups: u64,
-) -> HitRates {
-    HitRates {
-        tt: percentage(tt_hits, tt_lookups),
-        node_table: percentage(node_table_hits, node_table_lookups),
-    }
-}
-struct LogSnapshot {
-    stats: TreeStatsSnapshot,
-    tt_size: usize,
-    node_table_size: usize,
-    depth_limit: Option<usize>,
-}
-fn capture_snapshot(tree: &SharedTree) -> LogSnapshot {
-    LogSnapshot {
-        stats: tree.stats_snapshot(),
-        tt_size: tree.get_tt_size(),
-        node_table_size: tree.get_node_table_size(),
-        depth_limit: tree.depth_limit(),
-    }
-}
-#[derive(Clone, Copy)]
-struct LastLogState {
-    session_id: u64,
-    stats: TreeStatsSnapshot,
-    elapsed_secs: f64,
-}
-fn delta_since_last(
-    session_id: u64,
-    stats: TreeStatsSnapshot,
-    elapsed_secs: f64,
-) -> (TreeStatsSnapshot, f64) {
-    let (delta_stats, delta_elapsed) = {
-        let mut guard = match LAST_LOG_STATE.lock() {
-            Ok(guard) => guard,
-            Err(err) => err.into_inner(),
-        };
-        let prev = *guard;
-        *guard = Some(LastLogState {
-            session_id,
-            stats,
-            elapsed_secs,
-        });
-        drop(guard);
-        match prev {
-            Some(last) if last.session_id == session_id => (
-                stats.delta_since(&last.stats),
-                (elapsed_secs - last.elapsed_secs).max(0.0_f64),
-            ),
-            _ => (stats, elapsed_secs),
-        }
-    };
-    (delta_stats, delta_elapsed)
-}
 fn open_log_writer() -> io::Result<BufWriter<File>> {
     let truncate = !LOG_FILE_TRUNCATED.swap(true, Ordering::AcqRel);
     let mut options = OpenOptions::new();
@@ -150,6 +80,21 @@ fn open_log_writer() -> io::Result<BufWriter<File>> {
     }
     Ok(writer)
 }
+pub(super) fn log_tail(limit: usize) -> Vec<String> {
+    if !LOG_FILE_TRUNCATED.load(Ordering::Acquire) {
+        return Vec::new();
+    }
+    let Ok(contents) = fs::read_to_string(LOG_FILE_NAME) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = contents.lines().collect();
+    lines
+        .get(lines.len().saturating_sub(limit)..)
+        .unwrap_or_default()
+        .iter()
+        .map(|line| String::from(line.trim_start_matches('\u{feff}')))
+        .collect()
+}
 fn write_csv_header(writer: &mut impl Write) -> io::Result<()> {
     let mut headers = Vec::new();
     headers.extend([
@@ -168,97 +113,112 @@ fn write_csv_header(writer: &mut impl Write) -> io::Result<()> {
     ]);
     headers.extend(TimingStats::csv_headers());
     headers.push("其他耗时");
-    headers.extend(["深度截断数", "提前剪枝数"]);
+    headers.extend([
+        "深度截断数",
+        "提前剪枝数",
+        "延迟展开数",
+        "峰值内存字节",
+        "根节点PN",
+        "根节点DN",
+    ]);
     writeln!(writer, "{}", headers.join(","))
 }
-fn write_log(
-    writer: &mut impl Write,
-    turn: usize,
-    elapsed_secs: f64,
-    snapshot: &LogSnapshot,
-    stats: TreeStatsSnapshot,
-) -> io::Result<()> {
-    let hit_rates = calc_hit_rates(
-        stats.tt_hits,
-        stats.tt_lookups,
-        stats.node_table_hits,
-        stats.node_table_lookups,
-    );
-    let timing_stats = TimingStats::from_snapshot(&stats);
-    let depth = snapshot.depth_limit.unwrap_or(0);
+fn write_log(writer: &mut impl Write, report: &DepthReport) -> io::Result<()> {
+    let stats = &report.stats;
     let mut fields = vec![
-        turn.to_string(),
-        format_sci_usize(depth),
-        format_sci_f64(elapsed_secs),
+        report.turn.to_string(),
+        format_sci_usize(report.depth.unwrap_or(0)),
+        format_sci_f64(report.elapsed_secs),
         format_sci_u64(stats.iterations),
         format_sci_u64(stats.expansions),
-        format_sci_usize(snapshot.tt_size),
-        format_sci_f64(hit_rates.tt),
+        format_sci_usize(report.tt_size),
+        format_sci_f64(report.tt_hit_rate),
         format_sci_u64(stats.tt_stores),
-        format_sci_usize(snapshot.node_table_size),
-        format_sci_f64(hit_rates.node_table),
+        format_sci_usize(report.node_table_size),
+        format_sci_f64(report.node_table_hit_rate),
         format_sci_u64(stats.node_table_hits),
         format_sci_u64(stats.nodes_created),
     ];
-    for &value in timing_stats.csv_values() {
-        fields.push(format_sci_f64(value));
+    for entry in &report.timing {
+        fields.push(format_sci_f64(entry.value));
     }
-    let elapsed_us = elapsed_secs * 1_000_000.0_f64;
-    let other_us = (elapsed_us - timing_stats.sum_us()).max(0.0_f64);
-    fields.push(format_sci_f64(other_us));
+    fields.push(format_sci_f64(report.other_us));
     fields.push(format_sci_u64(stats.depth_cutoffs));
     fields.push(format_sci_u64(stats.early_cutoffs));
+    fields.push(format_sci_u64(stats.deferred_expansions));
+    fields.push(format_sci_u64(report.peak_live_bytes));
+    fields.push(format_sci_u64(report.root_pn));
+    fields.push(format_sci_u64(report.root_dn));
     writeln!(writer, "{}", fields.join(","))
 }
-pub(super) fn write_csv_log(tree: &SharedTree, turn: usize, elapsed_secs: f64) {
-    let Ok(mut writer) = open_log_writer() else {
+pub(super) fn print_shard_occupancy(solver: &ParallelSolver, stats: &TreeStatsSnapshot) {
+    let occupancy = solver.tree.node_table_occupancy();
+    if occupancy.shards <= 1 {
+        return;
+    }
+    let local_share = if stats.nodes_created > 0 {
+        to_f64(stats.node_table_local_creates) / to_f64(stats.nodes_created) * 100.0_f64
+    } else {
+        0.0_f64
+    };
+    println!(
+        "NodeTable 分片占用: {shards} 个分片，最少 {min}，最多 {max}，平均 {mean}，失衡系数 {imbalance}；本地分片创建占比 {local_share}%",
+        shards = format_sci_usize(occupancy.shards),
+        min = format_sci_usize(occupancy.min),
+        max = format_sci_usize(occupancy.max),
+        mean = format_sci_f64(occupancy.mean),
+        imbalance = format_sci_f64(occupancy.imbalance()),
+        local_share = format_sci_f64(local_share)
+    );
+}
+pub(super) fn print_speculation(solver: &ParallelSolver, stats: &TreeStatsSnapshot) {
+    if solver.tree.speculation() == SpeculationPolicy::Off {
         return;
+    }
+    let hit_rate = if stats.speculative_expansions > 0 {
+        to_f64(stats.speculation_hits) / to_f64(stats.speculative_expansions) * 100.0_f64
+    } else {
+        0.0_f64
     };
-    let snapshot = capture_snapshot(tree);
-    let (delta_stats, delta_elapsed_secs) =
-        delta_since_last(tree.stats_session_id(), snapshot.stats, elapsed_secs);
-    match write_log(
-        &mut writer,
-        turn,
-        delta_elapsed_secs,
-        &snapshot,
-        delta_stats,
-    ) {
-        Ok(()) => {
-            if let Err(err) = writer.flush() {
-                eprintln!("刷新日志文件失败: {err}");
-            }
-        }
-        Err(err) => {
-            eprintln!("写入日志失败: {err}");
-        }
+    println!(
+        "推测扩展: {count} 次，命中 {hits} 次，命中率 {hit_rate}%",
+        count = format_sci_u64(stats.speculative_expansions),
+        hits = format_sci_u64(stats.speculation_hits),
+        hit_rate = format_sci_f64(hit_rate)
+    );
+}
+pub(super) fn print_lazy_helpers(solver: &ParallelSolver) {
+    let Some(helper_nodes) = solver.worker_pool.helper_nodes() else {
+        return;
+    };
+    println!(
+        "Lazy SMP 辅助树: 共创建 {helper_nodes} 个节点，主树 {main_nodes} 个，置换表共 {tt_size} 项",
+        helper_nodes = format_sci_u64(helper_nodes),
+        main_nodes = format_sci_u64(solver.tree.stats.nodes_created.load(Ordering::Relaxed)),
+        tt_size = format_sci_usize(solver.tree.get_tt_size())
+    );
+}
+pub(super) fn print_overflow_violations() {
+    let violations = checked::overflow_violations();
+    if violations > 0 {
+        println!(
+            "算术溢出: {count} 次（已饱和处理，相关统计可能失真）",
+            count = format_sci_u64(violations)
+        );
     }
 }
-pub(super) fn write_csv_log_snapshot(
-    turn: usize,
-    elapsed_secs: f64,
-    stats: TreeStatsSnapshot,
-    tt_size: usize,
-    node_table_size: usize,
-    depth_limit: Option<usize>,
-) {
+pub(super) fn write_csv_log(report: &DepthReport) {
     let Ok(mut writer) = open_log_writer() else {
         return;
     };
-    let snapshot = LogSnapshot {
-        stats,
-        tt_size,
-        node_table_size,
-        depth_limit,
-    };
-    match write_log(&mut writer, turn, elapsed_secs, &snapshot, stats) {
+    match write_log(&mut writer, report) {
         Ok(()) => {
             if let Err(err) = writer.flush() {
                 eprintln!("刷新日志文件失败: {err}");
             }
         }
         Err(err) => {
-            eprintln!("写入日志快照失败: {err}");
+            eprintln!("写入日志失败: {err}");
         }
     }
 }
@@ -0,0 +1,84 @@
+use super::super::stats_def::to_f64;
+use super::{BenchmarkResult, DepthSample};
+use alloc::collections::BTreeMap;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write as _},
+    path::Path,
+};
+const PLOT_METRICS: [&str; 11] = [
+    "elapsed_secs",
+    "iterations",
+    "expansions",
+    "children_generated",
+    "nodes_created",
+    "tt_hits",
+    "tt_size",
+    "node_table_size",
+    "depth_cutoffs",
+    "early_cutoffs",
+    "peak_live_bytes",
+];
+fn sample_values(sample: &DepthSample) -> [f64; 11] {
+    let stats = &sample.stats;
+    [
+        sample.elapsed_secs,
+        to_f64(stats.iterations),
+        to_f64(stats.expansions),
+        to_f64(stats.children_generated),
+        to_f64(stats.nodes_created),
+        to_f64(stats.tt_hits),
+        to_f64(sample.tt_size),
+        to_f64(sample.node_table_size),
+        to_f64(stats.depth_cutoffs),
+        to_f64(stats.early_cutoffs),
+        to_f64(sample.peak_live_bytes),
+    ]
+}
+fn write_row(
+    writer: &mut impl io::Write,
+    depth: usize,
+    metric: &str,
+    run: &str,
+    value: f64,
+) -> io::Result<()> {
+    writeln!(writer, "{depth},{metric},{run},{value}")
+}
+fn write_aggregates(
+    writer: &mut impl io::Write,
+    grouped: &BTreeMap<(usize, &'static str), Vec<f64>>,
+) -> io::Result<()> {
+    for (&(depth, metric), values) in grouped {
+        if values.is_empty() {
+            continue;
+        }
+        let count = to_f64(u64::try_from(values.len()).unwrap_or(u64::MAX));
+        let mean = values.iter().sum::<f64>() / count;
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        write_row(writer, depth, metric, "mean", mean)?;
+        write_row(writer, depth, metric, "min", min)?;
+        write_row(writer, depth, metric, "max", max)?;
+    }
+    Ok(())
+}
+impl BenchmarkResult {
+    #[inline]
+    pub fn write_plot_data(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "depth,metric,run,value")?;
+        let mut grouped: BTreeMap<(usize, &'static str), Vec<f64>> = BTreeMap::new();
+        for sample in &self.depth_samples {
+            let run = sample.run.saturating_add(1).to_string();
+            for (metric, value) in PLOT_METRICS.into_iter().zip(sample_values(sample)) {
+                write_row(&mut writer, sample.depth, metric, &run, value)?;
+                grouped
+                    .entry((sample.depth, metric))
+                    .or_default()
+                    .push(value);
+            }
+        }
+        write_aggregates(&mut writer, &grouped)?;
+        writer.flush()
+    }
+}
@@ -0,0 +1,136 @@
+use super::super::{TimingStats, TreeStatsSnapshot, stats_def::to_f64};
+use super::{AllocReport, DepthReport, ParallelSolver, SearchOutcome, SearchReport};
+use crate::alloc_stats;
+fn percentage(part: u64, total: u64) -> f64 {
+    if total > 0 {
+        to_f64(part) / to_f64(total) * 100.0
+    } else {
+        0.0
+    }
+}
+impl DepthReport {
+    pub(super) fn from_stats(
+        turn: usize,
+        depth: Option<usize>,
+        elapsed_secs: f64,
+        stats: &TreeStatsSnapshot,
+        tt_size: usize,
+        node_table_size: usize,
+        peak_live_bytes: u64,
+    ) -> Self {
+        let timing_stats = TimingStats::from_snapshot(stats);
+        let elapsed_us = elapsed_secs * 1_000_000.0_f64;
+        Self {
+            turn,
+            depth,
+            elapsed_secs,
+            stats: *stats,
+            timing: timing_stats.entries(),
+            other_us: (elapsed_us - timing_stats.sum_us()).max(0.0_f64),
+            tt_size,
+            tt_hit_rate: percentage(stats.tt_hits, stats.tt_lookups),
+            node_table_size,
+            node_table_hit_rate: percentage(stats.node_table_hits, stats.node_table_lookups),
+            peak_live_bytes,
+            root_pn: 0,
+            root_dn: 0,
+        }
+    }
+    const fn with_root(mut self, root_pn: u64, root_dn: u64) -> Self {
+        self.root_pn = root_pn;
+        self.root_dn = root_dn;
+        self
+    }
+}
+fn alloc_report() -> AllocReport {
+    let timing = alloc_stats::alloc_timing_snapshot();
+    let counts = alloc_stats::alloc_counts_snapshot();
+    AllocReport {
+        alloc_ns: timing.alloc_ns,
+        dealloc_ns: timing.dealloc_ns,
+        realloc_ns: timing.realloc_ns,
+        alloc_zeroed_ns: timing.alloc_zeroed_ns,
+        total_ns: timing.total_ns(),
+        allocations: counts.allocations,
+        deallocations: counts.deallocations,
+        reallocations: counts.reallocations,
+        bytes_allocated: counts.bytes_allocated,
+        bytes_deallocated: counts.bytes_deallocated,
+        live_bytes: alloc_stats::live_bytes(),
+        peak_live_bytes: alloc_stats::peak_live_bytes(),
+    }
+}
+pub(super) fn record_depth(
+    solver: &ParallelSolver,
+    elapsed_secs: f64,
+    stats: &TreeStatsSnapshot,
+    peak_live_bytes: u64,
+) -> DepthReport {
+    let (root_pn, root_dn) = solver
+        .tree
+        .sampled_root()
+        .unwrap_or_else(|| (solver.tree.root.get_pn(), solver.tree.root.get_dn()));
+    let report = DepthReport::from_stats(
+        super::setup::current_turn(solver),
+        solver.tree.depth_limit(),
+        elapsed_secs,
+        stats,
+        solver.tree.get_tt_size(),
+        solver.tree.get_node_table_size(),
+        peak_live_bytes,
+    )
+    .with_root(root_pn, root_dn);
+    solver.depth_reports.lock().push(report.clone());
+    report
+}
+fn outcome(solver: &ParallelSolver, per_depth: &[DepthReport]) -> SearchOutcome {
+    let root = &solver.tree.root;
+    if root.get_pn() == 0 {
+        SearchOutcome::Proven {
+            best_move: super::best_move::get_best_move(solver),
+            win_len: root.get_win_len(),
+        }
+    } else if root.get_dn() == 0 {
+        SearchOutcome::Disproven
+    } else if let Some(reason) = solver.tree.cancellation_token().reason()
+        && solver.tree.limit_reached().is_none()
+    {
+        SearchOutcome::Aborted { reason }
+    } else {
+        SearchOutcome::Unresolved {
+            root_pn: root.get_pn(),
+            root_dn: root.get_dn(),
+            limit_reached: solver.tree.limit_reached(),
+            heuristic: super::calibration::heuristic_win_estimate(solver, per_depth),
+        }
+    }
+}
+pub(super) fn stats_report(solver: &ParallelSolver) -> SearchReport {
+    let per_depth = solver.depth_reports.lock().clone();
+    let elapsed_secs = per_depth.iter().map(|report| report.elapsed_secs).sum();
+    let peak_live_bytes = per_depth
+        .iter()
+        .map(|report| report.peak_live_bytes)
+        .max()
+        .unwrap_or_else(alloc_stats::peak_live_bytes);
+    let totals = DepthReport::from_stats(
+        super::setup::current_turn(solver),
+        solver.tree.depth_limit(),
+        elapsed_secs,
+        &solver.tree.stats_snapshot(),
+        solver.tree.get_tt_size(),
+        solver.tree.get_node_table_size(),
+        peak_live_bytes,
+    )
+    .with_root(solver.tree.root.get_pn(), solver.tree.root.get_dn());
+    SearchReport {
+        outcome: outcome(solver, &per_depth),
+        totals,
+        per_depth,
+        alloc: alloc_report(),
+        memory_degradations: solver.tree.degradation_records(),
+        hot_path_allocations: solver.tree.hot_path_allocations(),
+        tie_break_seed: solver.tree.tie_break_seed(),
+        node_table_shards: solver.tree.node_table_occupancy(),
+    }
+}
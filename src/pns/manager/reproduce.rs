@@ -0,0 +1,77 @@
+use super::super::{
+    Budget, ExpansionLog, NodeRef, ReplayDivergence, ReplaySummary, context::ThreadLocalContext,
+};
+use super::SearchParams;
+use crate::game_state::Coord;
+use alloc::sync::Arc;
+pub(super) fn replay_expansions(log: &ExpansionLog, params: SearchParams) -> ReplaySummary {
+    let single_threaded = SearchParams {
+        num_threads: 1,
+        budget: Budget::unlimited(),
+        time_control: None,
+        progress: None,
+        record_expansions: None,
+        search_handle: None,
+        overlay_stream: None,
+        ..params
+    };
+    let depth_limit = log.records.first().and_then(|record| record.depth_limit);
+    let solver = super::setup::with_tt(log.board.clone(), single_threaded, depth_limit, None, None);
+    let tree = &solver.tree;
+    let mut ctx = ThreadLocalContext::new(super::setup::clone_game_state(&solver), 0);
+    let mut divergences = Vec::new();
+    let mut path_nodes: Vec<(NodeRef, Coord)> = Vec::new();
+    for (index, record) in log.records.iter().enumerate() {
+        if let Some(limit) = record.depth_limit
+            && tree.depth_limit().is_some_and(|current| limit > current)
+        {
+            super::setup::increase_depth_limit(&solver, limit);
+        }
+        let mut node = Arc::clone(&tree.root);
+        let mut missing = None;
+        for &mov in &record.path {
+            let Some(child) = node.expanded_children().and_then(|mut children| {
+                children
+                    .find(|child_ref| child_ref.mov.coord() == mov)
+                    .map(|child_ref| Arc::clone(&child_ref.node))
+            }) else {
+                missing = Some(mov);
+                break;
+            };
+            ctx.make_move(mov, node.player);
+            path_nodes.push((node, mov));
+            node = child;
+        }
+        if let Some(mov) = missing {
+            divergences.push(ReplayDivergence::MissingChild { index, mov });
+        } else {
+            if node.hash != record.hash {
+                divergences.push(ReplayDivergence::HashMismatch {
+                    index,
+                    expected: record.hash,
+                    actual: node.hash,
+                });
+            }
+            let expanded = if record.deferred {
+                tree.expand_deferred(&node, &mut ctx)
+            } else {
+                tree.expand_node(&node, &mut ctx)
+            };
+            if !expanded {
+                divergences.push(ReplayDivergence::AlreadyExpanded { index });
+            }
+            tree.update_node_pdn(&node);
+        }
+        while let Some((parent, mov)) = path_nodes.pop() {
+            ctx.undo_move(mov, parent.player);
+            tree.update_node_pdn(&parent);
+        }
+    }
+    tree.flush_stats(&mut ctx);
+    ReplaySummary {
+        replayed: log.records.len(),
+        divergences,
+        root_pn: tree.root.get_pn(),
+        root_dn: tree.root.get_dn(),
+    }
+}
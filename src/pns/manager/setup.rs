@@ -1,16 +1,19 @@
 use super::super::{
-    NodeTable, SharedTree, TranspositionTable, WorkerPool, context::ThreadLocalContext,
+    FrontierRefresh, MappedProofBook, MoveSelector, NodeTable, SharedTree, TieBreakPolicy,
+    TimeManager, TranspositionTable, WorkerPool, context::ThreadLocalContext,
+    overlay::OverlayStream,
 };
-use super::{ParallelSolver, SearchParams};
+use super::{ParallelSolver, SearchParams, types::DEFAULT_ROOT_PLAYER};
 use crate::{
     alloc_stats,
     alloc_stats::AllocTrackingGuard,
-    checked,
     config::EvaluationWeights,
     game_state::{GameState, ZobristHasher},
+    interrupt::CancellationToken,
 };
 use alloc::sync::Arc;
-use core::sync::atomic::AtomicBool;
+use parking_lot::Mutex;
+use std::path::Path;
 pub(super) fn new(
     initial_board: Vec<u8>,
     board_size: usize,
@@ -29,12 +32,11 @@ pub(super) fn with_tt(
     existing_tt: Option<TranspositionTable>,
     existing_node_table: Option<NodeTable>,
 ) -> ParallelSolver {
-    let stop_flag = Arc::new(AtomicBool::new(false));
     with_tt_and_stop(
         initial_board,
         params,
         depth_limit,
-        &stop_flag,
+        &CancellationToken::new(),
         existing_tt,
         existing_node_table,
     )
@@ -43,59 +45,125 @@ pub(super) fn with_tt_and_stop(
     initial_board: Vec<u8>,
     params: SearchParams,
     depth_limit: Option<usize>,
-    stop_flag: &Arc<AtomicBool>,
+    cancel: &CancellationToken,
     existing_tt: Option<TranspositionTable>,
     existing_node_table: Option<NodeTable>,
 ) -> ParallelSolver {
-    alloc_stats::reset_alloc_timing_ns();
+    if !params.quiet {
+        alloc_stats::reset_alloc_timing_ns();
+        alloc_stats::reset_alloc_counts();
+    }
     let _alloc_guard = AllocTrackingGuard::new();
+    let recorded_params = SearchParams {
+        progress: None,
+        search_handle: None,
+        ..params.clone()
+    };
     let hasher = Arc::new(ZobristHasher::new(params.board_size));
-    let game_state = GameState::new(
+    let mut game_state = GameState::new(
         initial_board,
         params.board_size,
         hasher,
-        1,
+        params.root_player,
         params.win_len,
         params.evaluation,
     );
+    if params.region.is_some() {
+        game_state.set_region(params.region);
+    }
+    game_state.set_rule(params.rule, params.root_player);
     let root_hash = game_state.position.get_canonical_hash();
     let root_pos_hash = game_state.position.get_hash();
-    let tree = Arc::new(SharedTree::with_tt_and_stop(
-        1,
+    let root_stones = game_state.position.board.stone_count();
+    let shared_tree = SharedTree::with_tt_and_stop(
+        params.root_player,
         root_hash,
-        root_pos_hash,
+        (root_pos_hash, root_stones),
         depth_limit,
-        Arc::clone(stop_flag),
+        cancel.child(),
         existing_tt,
         existing_node_table,
-    ));
+        params.budget,
+    )
+    .with_stats_flush_interval(params.stats_flush_interval)
+    .with_cross_check(params.cross_check_interval)
+    .with_max_children(params.max_children)
+    .with_eager_children(params.eager_children)
+    .with_hot_path_allocations(params.hot_path_allocations)
+    .with_expansion_recording(params.record_expansions)
+    .with_root_move_filter(params.root_move_filter)
+    .with_tie_break_seed(
+        (params.tie_breaks == TieBreakPolicy::Random)
+            .then(|| params.tie_break_seed.unwrap_or_else(rand::random)),
+    )
+    .with_speculation(params.speculation)
+    .with_dfpn_epsilon(params.dfpn_epsilon)
+    .with_effort_ordering(params.effort_ordering)
+    .with_move_selector(MoveSelector::new(params.move_order, params.board_size))
+    .with_overlay(
+        params
+            .overlay_stream
+            .as_deref()
+            .and_then(|target| open_overlay(target, params.board_size)),
+    )
+    .with_proof_book(
+        params
+            .proof_book
+            .as_deref()
+            .filter(|_| params.root_player == DEFAULT_ROOT_PLAYER)
+            .and_then(|path| open_proof_book(path, params.board_size)),
+    );
+    let reporting_tree = match params.progress {
+        Some(callback) => shared_tree.with_progress(callback),
+        None => shared_tree,
+    };
+    let tree = Arc::new(match params.time_control {
+        Some(time_control) => reporting_tree.with_time_manager(TimeManager::new(time_control)),
+        None => reporting_tree,
+    });
     tree.evaluate_node(&tree.root, &ThreadLocalContext::new(game_state.clone(), 0));
-    let worker_pool = WorkerPool::new(Arc::clone(&tree), &game_state, params.num_threads);
+    let worker_pool = WorkerPool::new(
+        Arc::clone(&tree),
+        &game_state,
+        (params.num_threads, params.parallel_mode),
+        params.search_handle,
+    );
     ParallelSolver {
         tree,
         worker_pool,
         base_game_state: game_state,
         board_size: params.board_size,
         win_len: params.win_len,
+        depth_reports: Mutex::new(Vec::new()),
+        params: recorded_params,
+        quiet: params.quiet,
+    }
+}
+fn open_overlay(target: &str, board_size: usize) -> Option<OverlayStream> {
+    OverlayStream::open(target, board_size)
+        .map_err(|err| eprintln!("无法打开搜索叠加层输出 {target}: {err}"))
+        .ok()
+}
+fn open_proof_book(path: &str, board_size: usize) -> Option<MappedProofBook> {
+    let proof_book = MappedProofBook::open(Path::new(path))
+        .map_err(|err| eprintln!("无法映射证明库 {path}: {err}"))
+        .ok()?;
+    if proof_book.board_size() != board_size {
+        eprintln!(
+            "证明库 {path} 的棋盘大小为 {actual}，与当前 {board_size} 不符，已忽略。",
+            actual = proof_book.board_size()
+        );
+        return None;
     }
+    Some(proof_book)
 }
 pub(super) fn clone_game_state(solver: &ParallelSolver) -> GameState {
     solver.base_game_state.clone()
 }
 pub(super) fn current_turn(solver: &ParallelSolver) -> usize {
-    solver
-        .base_game_state
-        .position
-        .board
-        .iter()
-        .fold(0_usize, |count, &cell| {
-            checked::add_usize(
-                count,
-                usize::from(cell == 2),
-                "ParallelSolver::current_turn",
-            )
-        })
+    solver.base_game_state.position.bitboard.stone_count(2)
 }
-pub(super) fn increase_depth_limit(solver: &ParallelSolver, new_limit: usize) {
-    solver.tree.increase_depth_limit(new_limit);
+pub(super) fn increase_depth_limit(solver: &ParallelSolver, new_limit: usize) -> FrontierRefresh {
+    solver.tree.begin_iteration();
+    solver.tree.increase_depth_limit(new_limit)
 }
@@ -0,0 +1,173 @@
+use super::super::{NodeRef, SharedTree, node::ChildRef};
+use super::ParallelSolver;
+use crate::{
+    checked,
+    game_state::{CanonicalHash, Coord, Move},
+};
+use alloc::{collections::VecDeque, sync::Arc};
+use core::fmt::Write as _;
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write as _},
+    path::Path,
+};
+const SUMMARY_FILE: &str = "solution.json";
+const README_FILE: &str = "solution.txt";
+const DAG_FILE: &str = "proof_dag.json.deflate";
+const COMPRESSION_LEVEL: u8 = 9;
+const MAX_DAG_BYTES: usize = 1024 * 1024 * 1024;
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ProofDagNode {
+    pub hash: CanonicalHash,
+    pub player: u8,
+    pub win_len: u64,
+    pub children: Vec<(Move, usize)>,
+}
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ProofDag {
+    pub board_size: usize,
+    pub win_len: usize,
+    pub nodes: Vec<ProofDagNode>,
+}
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SolutionSummary {
+    pub board_size: usize,
+    pub win_len: usize,
+    pub moves_to_win: u64,
+    pub principal_variation: Vec<Coord>,
+    pub proven_first_moves: Vec<(Coord, u64)>,
+    pub proof_nodes: usize,
+}
+impl ProofDag {
+    fn collect(tree: &SharedTree, board_size: usize, win_len: usize) -> Self {
+        let mut ids: HashMap<usize, usize> = HashMap::new();
+        ids.insert(Arc::as_ptr(&tree.root).addr(), 0);
+        let mut queue = VecDeque::from([Arc::clone(&tree.root)]);
+        let mut nodes = Vec::new();
+        while let Some(node) = queue.pop_front() {
+            let children = proof_children(tree, &node)
+                .into_iter()
+                .map(|child_ref| {
+                    let next_id = ids.len();
+                    let id = *ids
+                        .entry(Arc::as_ptr(&child_ref.node).addr())
+                        .or_insert_with(|| {
+                            queue.push_back(Arc::clone(&child_ref.node));
+                            next_id
+                        });
+                    (child_ref.mov, id)
+                })
+                .collect();
+            nodes.push(ProofDagNode {
+                hash: node.hash,
+                player: node.player,
+                win_len: node.get_win_len(),
+                children,
+            });
+        }
+        Self {
+            board_size,
+            win_len,
+            nodes,
+        }
+    }
+    #[inline]
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec(self).map_err(io::Error::other)?;
+        let compressed = miniz_oxide::deflate::compress_to_vec(&json, COMPRESSION_LEVEL);
+        fs::write(path, compressed)
+    }
+    #[inline]
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let compressed = fs::read(path)?;
+        let json = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_DAG_BYTES)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{err:?}")))?;
+        serde_json::from_slice(&json).map_err(io::Error::other)
+    }
+}
+impl SolutionSummary {
+    fn describe(&self) -> String {
+        let mut text = format!(
+            "棋盘 {size}x{size}，连 {win_len} 子胜\n先手必胜，最多 {moves} 步内取胜\n主要变例:",
+            size = self.board_size,
+            win_len = self.win_len,
+            moves = self.moves_to_win
+        );
+        for (index, &(row, column)) in self.principal_variation.iter().enumerate() {
+            let side = if checked::rem_usize(index, 2, "SolutionSummary::describe") == 0 {
+                "X"
+            } else {
+                "O"
+            };
+            let _ignored = write!(text, " {side}({row}, {column})");
+        }
+        text.push_str("\n已证明的首步（坐标/胜利步数）:\n");
+        for &((row, column), win_len) in &self.proven_first_moves {
+            let _ignored = writeln!(text, "  ({row}, {column})/{win_len}");
+        }
+        let _ignored = writeln!(
+            text,
+            "证明图节点数: {nodes}（{DAG_FILE}）",
+            nodes = self.proof_nodes
+        );
+        text
+    }
+}
+fn proof_children(tree: &SharedTree, node: &NodeRef) -> Vec<ChildRef> {
+    let Some(children) = node.expanded_children() else {
+        return Vec::new();
+    };
+    let proven = children.filter(|child_ref| child_ref.node.get_pn() == 0);
+    if node.is_or_node() {
+        tree.move_selector()
+            .select_min(proven, |child_ref| {
+                (child_ref.mov.coord(), child_ref.node.get_win_len())
+            })
+            .cloned()
+            .into_iter()
+            .collect()
+    } else {
+        proven.cloned().collect()
+    }
+}
+pub(super) fn is_full_board_solve(solver: &ParallelSolver) -> bool {
+    solver.tree.root.get_pn() == 0
+        && solver.tree.root_move_filter().is_none()
+        && solver
+            .base_game_state
+            .position
+            .board
+            .as_slice()
+            .iter()
+            .all(|&cell| cell == 0)
+}
+pub(super) fn export_solution(
+    solver: &ParallelSolver,
+    directory: &Path,
+) -> io::Result<SolutionSummary> {
+    fs::create_dir_all(directory)?;
+    let tree = &solver.tree;
+    let snapshot = tree.freeze_snapshot();
+    let moves_to_win = tree.root.get_win_len();
+    let dag = ProofDag::collect(tree, solver.board_size, solver.win_len);
+    dag.save(&directory.join(DAG_FILE))?;
+    let summary = SolutionSummary {
+        board_size: solver.board_size,
+        win_len: solver.win_len,
+        moves_to_win,
+        principal_variation: snapshot.principal_variation(
+            &snapshot.root(),
+            tree.move_selector(),
+            usize::try_from(moves_to_win).unwrap_or(usize::MAX),
+        ),
+        proven_first_moves: SharedTree::proven_moves(&tree.root),
+        proof_nodes: dag.nodes.len(),
+    };
+    let mut writer = BufWriter::new(File::create(directory.join(SUMMARY_FILE))?);
+    serde_json::to_writer_pretty(&mut writer, &summary).map_err(io::Error::other)?;
+    writer.flush()?;
+    fs::write(directory.join(README_FILE), summary.describe())?;
+    Ok(summary)
+}
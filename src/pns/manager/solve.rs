@@ -1,19 +1,22 @@
-use super::super::context::ThreadLocalContext;
+use super::super::{BudgetLimit, context::ThreadLocalContext};
 use super::ParallelSolver;
-use crate::alloc_stats::AllocTrackingGuard;
-use crate::checked;
+use crate::alloc_stats::{self, AllocTrackingGuard};
+use crate::{checked, interrupt::CancellationToken};
 use alloc::sync::Arc;
-use core::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 pub(super) fn solve(solver: &ParallelSolver, verbose: bool) -> bool {
     let start_time = Instant::now();
     let _alloc_guard = AllocTrackingGuard::new();
+    let announce = verbose && !solver.quiet;
+    if !solver.quiet {
+        alloc_stats::reset_peak_live_bytes();
+    }
     let tree = Arc::clone(&solver.tree);
     if tree.stop_requested() {
         return false;
     }
     if tree.root.is_terminal() {
-        if verbose {
+        if announce {
             println!(
                 "根节点已是终端状态: PN={}, DN={}",
                 super::logging::format_sci_u64(tree.root.get_pn()),
@@ -23,20 +26,32 @@ pub(super) fn solve(solver: &ParallelSolver, verbose: bool) -> bool {
         if tree.root.get_pn() == 0 && !tree.root.is_expanded() {
             let mut ctx = ThreadLocalContext::new(super::setup::clone_game_state(solver), 0);
             tree.expand_node(&tree.root, &mut ctx);
+            tree.flush_stats(&mut ctx);
             tree.update_node_pdn(&tree.root);
         }
         return tree.root.get_pn() == 0;
     }
+    let stats_before = tree.stats_snapshot();
     solver.worker_pool.run_and_wait();
     let elapsed = start_time.elapsed().as_secs_f64();
-    if verbose {
-        super::logging::write_csv_log(&solver.tree, super::setup::current_turn(solver), elapsed);
+    let report = super::reporting::record_depth(
+        solver,
+        elapsed,
+        &tree.stats_snapshot().delta_since(&stats_before),
+        alloc_stats::peak_live_bytes(),
+    );
+    if announce {
+        super::logging::write_csv_log(&report);
+        super::logging::print_shard_occupancy(solver, &report.stats);
+        super::logging::print_speculation(solver, &report.stats);
+        super::logging::print_lazy_helpers(solver);
+        super::logging::print_overflow_violations();
     }
     solver.tree.root.get_pn() == 0
 }
 pub(super) fn run_iterative_deepening<R, H>(
     solver: &mut ParallelSolver,
-    stop_flag: &Arc<AtomicBool>,
+    cancel: &CancellationToken,
     mut depth: usize,
     hooks: &mut H,
 ) -> R
@@ -44,22 +59,33 @@ where
     H: super::deepening::IterativeDeepeningHooks<R>,
 {
     loop {
-        if stop_flag.load(Ordering::Acquire) {
+        if cancel.is_cancelled() {
+            return hooks.on_stop(solver);
+        }
+        if solver.tree.exceeds_depth_budget(depth) {
+            solver.tree.record_budget_limit(BudgetLimit::Depth);
             return hooks.on_stop(solver);
         }
         hooks.before_solve(depth, solver);
         let found = hooks.solve(solver);
-        if stop_flag.load(Ordering::Acquire) || solver.tree.stop_requested() {
+        if cancel.is_cancelled()
+            || solver.tree.stop_requested()
+            || (!found && solver.tree.check_budget().is_some())
+        {
             return hooks.on_stop(solver);
         }
         hooks.after_solve(depth, solver, found);
         if found {
             return hooks.on_found(depth, solver);
         }
+        if solver.tree.limit_reached().is_some() || solver.tree.root.get_dn() == 0 {
+            return hooks.on_stop(solver);
+        }
         depth = checked::add_usize(depth, 1_usize, "ParallelSolver::run_iterative_deepening");
-        if stop_flag.load(Ordering::Acquire) {
+        if cancel.is_cancelled() {
             return hooks.on_stop(solver);
         }
-        super::setup::increase_depth_limit(solver, depth);
+        let refresh = super::setup::increase_depth_limit(solver, depth);
+        hooks.after_deepen(solver, refresh);
     }
 }
@@ -1,19 +1,60 @@
-use super::super::{SharedTree, TreeStatsSnapshot, WorkerPool};
-use crate::{config::EvaluationWeights, game_state::GameState};
+use super::super::{
+    AllocationPolicy, Budget, BudgetLimit, DegradationRecord, EffortOrdering, ExpansionLog,
+    MoveOrder, NodeTable, ParallelMode, ProgressCallback, RootMoveFilter, SearchHandle,
+    ShardOccupancy, SharedTree, SpeculationPolicy, TieBreakPolicy, TimeControl, TimingEntry,
+    TranspositionTable, TreeStatsSnapshot, WorkerPool, stats_def::DEFAULT_STATS_FLUSH_INTERVAL,
+};
+use crate::{
+    config::EvaluationWeights,
+    game_state::{BoardRegion, Coord, GameState, PatternCounts, Rule},
+    interrupt::CancelReason,
+};
 use alloc::sync::Arc;
+use parking_lot::Mutex;
+use serde::Serialize;
+pub(super) const DEFAULT_ROOT_PLAYER: u8 = 1;
 pub struct ParallelSolver {
     pub(crate) tree: Arc<SharedTree>,
     pub(crate) worker_pool: WorkerPool,
     pub(crate) base_game_state: GameState,
     pub(crate) board_size: usize,
     pub(crate) win_len: usize,
+    pub(crate) depth_reports: Mutex<Vec<DepthReport>>,
+    pub(crate) params: SearchParams,
+    pub(crate) quiet: bool,
 }
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct SearchParams {
     pub board_size: usize,
     pub win_len: usize,
     pub num_threads: usize,
     pub evaluation: EvaluationWeights,
+    pub budget: Budget,
+    pub time_control: Option<TimeControl>,
+    pub repetition_is_draw: bool,
+    pub stats_flush_interval: u32,
+    pub progress: Option<ProgressCallback>,
+    pub cross_check_interval: u64,
+    pub root_move_filter: Option<RootMoveFilter>,
+    pub max_children: Option<usize>,
+    pub eager_children: Option<usize>,
+    pub hot_path_allocations: AllocationPolicy,
+    pub record_expansions: Option<usize>,
+    pub search_handle: Option<SearchHandle>,
+    pub tie_breaks: TieBreakPolicy,
+    pub tie_break_seed: Option<u64>,
+    pub speculation: SpeculationPolicy,
+    pub dfpn_epsilon: Option<f64>,
+    pub parallel_mode: ParallelMode,
+    pub effort_ordering: EffortOrdering,
+    pub move_order: MoveOrder,
+    pub overlay_stream: Option<String>,
+    pub proof_book: Option<String>,
+    pub solution_directory: Option<String>,
+    pub region: Option<BoardRegion>,
+    pub rule: Rule,
+    pub root_player: u8,
+    pub quiet: bool,
 }
 impl SearchParams {
     #[inline]
@@ -29,12 +70,309 @@ impl SearchParams {
             win_len,
             num_threads,
             evaluation,
+            budget: Budget::unlimited(),
+            time_control: None,
+            repetition_is_draw: false,
+            stats_flush_interval: DEFAULT_STATS_FLUSH_INTERVAL,
+            progress: None,
+            cross_check_interval: 0,
+            root_move_filter: None,
+            max_children: None,
+            eager_children: None,
+            hot_path_allocations: AllocationPolicy::Allowed,
+            record_expansions: None,
+            search_handle: None,
+            tie_breaks: TieBreakPolicy::Stable,
+            tie_break_seed: None,
+            speculation: SpeculationPolicy::Off,
+            dfpn_epsilon: None,
+            parallel_mode: ParallelMode::SharedTree,
+            effort_ordering: EffortOrdering::Off,
+            move_order: MoveOrder::Central,
+            overlay_stream: None,
+            proof_book: None,
+            solution_directory: None,
+            region: None,
+            rule: Rule::Freestyle,
+            root_player: DEFAULT_ROOT_PLAYER,
+            quiet: false,
         }
     }
+    #[inline]
+    #[must_use]
+    pub const fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_repetition_draws(mut self, repetition_is_draw: bool) -> Self {
+        self.repetition_is_draw = repetition_is_draw;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_stats_flush_interval(mut self, stats_flush_interval: u32) -> Self {
+        self.stats_flush_interval = stats_flush_interval.max(1);
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_cross_check(mut self, interval: u64) -> Self {
+        self.cross_check_interval = interval;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_root_move_filter(mut self, filter: RootMoveFilter) -> Self {
+        self.root_move_filter = Some(filter);
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_max_children(mut self, max_children: Option<usize>) -> Self {
+        self.max_children = max_children;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_eager_children(mut self, eager_children: Option<usize>) -> Self {
+        self.eager_children = eager_children;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_hot_path_allocations(mut self, policy: AllocationPolicy) -> Self {
+        self.hot_path_allocations = policy;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn forbid_allocations_in_hot_path(self) -> Self {
+        self.with_hot_path_allocations(AllocationPolicy::Forbidden)
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_expansion_recording(mut self, limit: Option<usize>) -> Self {
+        self.record_expansions = limit;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_overlay_stream(mut self, target: Option<String>) -> Self {
+        self.overlay_stream = target;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_proof_book(mut self, path: Option<String>) -> Self {
+        self.proof_book = path;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_solution_directory(mut self, directory: Option<String>) -> Self {
+        self.solution_directory = directory;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_speculation(mut self, speculation: SpeculationPolicy) -> Self {
+        self.speculation = speculation;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_dfpn_epsilon(mut self, epsilon: Option<f64>) -> Self {
+        self.dfpn_epsilon = epsilon;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_parallel_mode(mut self, parallel_mode: ParallelMode) -> Self {
+        self.parallel_mode = parallel_mode;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_effort_ordering(mut self, effort_ordering: EffortOrdering) -> Self {
+        self.effort_ordering = effort_ordering;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_move_order(mut self, move_order: MoveOrder) -> Self {
+        self.move_order = move_order;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_region(mut self, region: Option<BoardRegion>) -> Self {
+        self.region = region;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_root_player(mut self, root_player: u8) -> Self {
+        self.root_player = root_player;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_search_handle(mut self, handle: SearchHandle) -> Self {
+        self.search_handle = Some(handle);
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_tie_breaks(mut self, policy: TieBreakPolicy) -> Self {
+        self.tie_breaks = policy;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_tie_break_seed(mut self, seed: Option<u64>) -> Self {
+        self.tie_break_seed = seed;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_time_control(mut self, time_control: TimeControl) -> Self {
+        self.time_control = Some(time_control);
+        self
+    }
+}
+pub struct SearchResult {
+    pub best_move: Option<Coord>,
+    pub most_proving_move: Option<Coord>,
+    pub best_known_move: Option<Coord>,
+    pub proof_line: Vec<Coord>,
+    pub transposition_table: TranspositionTable,
+    pub node_table: NodeTable,
+    pub depth: usize,
+    pub root_pn: u64,
+    pub root_dn: u64,
+    pub root_win_len: u64,
+    pub limit_reached: Option<BudgetLimit>,
+    pub root_move_filter: Option<RootMoveFilter>,
+    pub report: SearchReport,
+    pub expansion_log: Option<ExpansionLog>,
+}
+#[derive(Clone, Serialize)]
+pub struct DepthReport {
+    pub turn: usize,
+    pub depth: Option<usize>,
+    pub elapsed_secs: f64,
+    pub stats: TreeStatsSnapshot,
+    pub timing: Vec<TimingEntry>,
+    pub other_us: f64,
+    pub tt_size: usize,
+    pub tt_hit_rate: f64,
+    pub node_table_size: usize,
+    pub node_table_hit_rate: f64,
+    pub peak_live_bytes: u64,
+    pub root_pn: u64,
+    pub root_dn: u64,
+}
+#[derive(Clone, Copy, Serialize)]
+pub struct AllocReport {
+    pub alloc_ns: u64,
+    pub dealloc_ns: u64,
+    pub realloc_ns: u64,
+    pub alloc_zeroed_ns: u64,
+    pub total_ns: u64,
+    pub allocations: u64,
+    pub deallocations: u64,
+    pub reallocations: u64,
+    pub bytes_allocated: u64,
+    pub bytes_deallocated: u64,
+    pub live_bytes: u64,
+    pub peak_live_bytes: u64,
+}
+#[derive(Clone, Copy, Serialize)]
+pub enum SearchOutcome {
+    Proven {
+        best_move: Option<Coord>,
+        win_len: u64,
+    },
+    Disproven,
+    Unresolved {
+        root_pn: u64,
+        root_dn: u64,
+        limit_reached: Option<BudgetLimit>,
+        heuristic: HeuristicWinEstimate,
+    },
+    Aborted {
+        reason: CancelReason,
+    },
+}
+#[derive(Clone, Copy, Serialize)]
+pub struct HeuristicWinEstimate {
+    pub probability: f64,
+    pub pn_dn_log_ratio: f64,
+    pub pn_dn_trend: f64,
+    pub refuted_fraction: f64,
+    pub static_evaluation: f64,
+}
+#[derive(Clone, Serialize)]
+pub struct SearchReport {
+    pub outcome: SearchOutcome,
+    pub totals: DepthReport,
+    pub per_depth: Vec<DepthReport>,
+    pub alloc: AllocReport,
+    pub memory_degradations: Vec<DegradationRecord>,
+    pub hot_path_allocations: u64,
+    pub tie_break_seed: Option<u64>,
+    pub node_table_shards: ShardOccupancy,
+}
+pub struct DifficultyEstimate {
+    pub solved: bool,
+    pub depth_reached: usize,
+    pub nodes_searched: u64,
+    pub expected_proof_nodes: f64,
+    pub branching_factor: f64,
+    pub forcing_density: f64,
+    pub attacker_patterns: PatternCounts,
+    pub defender_patterns: PatternCounts,
+    pub root_pn: u64,
+    pub root_dn: u64,
+    pub limit_reached: Option<BudgetLimit>,
+}
+#[derive(Clone, Copy)]
+pub struct DepthSample {
+    pub run: usize,
+    pub depth: usize,
+    pub elapsed_secs: f64,
+    pub stats: TreeStatsSnapshot,
+    pub tt_size: u64,
+    pub node_table_size: u64,
+    pub peak_live_bytes: u64,
 }
 pub struct BenchmarkResult {
     pub elapsed_secs: f64,
     pub stats: TreeStatsSnapshot,
     pub tt_size: usize,
     pub node_table_size: usize,
+    pub peak_live_bytes: u64,
+    pub peak_live_bytes_by_depth: Vec<(usize, u64)>,
+    pub depth_reports: Vec<DepthReport>,
+    pub depth_samples: Vec<DepthSample>,
 }
@@ -0,0 +1,66 @@
+use crate::{checked, game_state::Coord};
+use core::cmp::Reverse;
+use serde::{Deserialize, Serialize};
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveOrder {
+    #[default]
+    Central,
+    Lexicographic,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MoveSelector {
+    pub order: MoveOrder,
+    pub board_size: usize,
+}
+impl MoveSelector {
+    #[inline]
+    #[must_use]
+    pub const fn new(order: MoveOrder, board_size: usize) -> Self {
+        Self { order, board_size }
+    }
+    #[inline]
+    #[must_use]
+    pub fn tie_key(&self, (row, column): Coord) -> (usize, Coord) {
+        let distance = match self.order {
+            MoveOrder::Central => {
+                let span = self.board_size.saturating_sub(1);
+                let row_offset = row.saturating_mul(2).abs_diff(span);
+                let column_offset = column.saturating_mul(2).abs_diff(span);
+                checked::add_usize(
+                    checked::mul_usize(row_offset, row_offset, "MoveSelector::tie_key::row"),
+                    checked::mul_usize(
+                        column_offset,
+                        column_offset,
+                        "MoveSelector::tie_key::column",
+                    ),
+                    "MoveSelector::tie_key",
+                )
+            }
+            MoveOrder::Lexicographic => 0,
+        };
+        (distance, (row, column))
+    }
+    #[inline]
+    pub fn select_min<T, I, F>(&self, candidates: I, key: F) -> Option<T>
+    where
+        I: IntoIterator<Item = T>,
+        F: Fn(&T) -> (Coord, u64),
+    {
+        candidates.into_iter().min_by_key(|candidate| {
+            let (mov, value) = key(candidate);
+            (value, self.tie_key(mov))
+        })
+    }
+    #[inline]
+    pub fn select_max<T, I, F>(&self, candidates: I, key: F) -> Option<T>
+    where
+        I: IntoIterator<Item = T>,
+        F: Fn(&T) -> (Coord, u64),
+    {
+        candidates.into_iter().max_by_key(|candidate| {
+            let (mov, value) = key(candidate);
+            (value, Reverse(self.tie_key(mov)))
+        })
+    }
+}
@@ -1,4 +1,11 @@
-use super::{SharedTree, context::ThreadLocalContext};
+use super::{
+    DfpnThresholds, MoveSelector, SharedTree, SpeculationPolicy, budget::BUDGET_CHECK_INTERVAL,
+    context::ThreadLocalContext,
+};
+use crate::{
+    checked,
+    game_state::{CanonicalHash, Move},
+};
 use alloc::sync::Arc;
 use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::OnceLock;
@@ -6,48 +13,95 @@ pub type NodeRef = Arc<ParallelNode>;
 #[derive(Clone)]
 pub struct ChildRef {
     pub node: NodeRef,
-    pub mov: (usize, usize),
+    pub mov: Move,
 }
 pub struct ParallelNode {
     pub player: u8,
+    pub attacker: u8,
     pub depth: usize,
-    pub hash: u64,
+    pub stones: usize,
+    pub hash: CanonicalHash,
     pub pn: AtomicU64,
     pub dn: AtomicU64,
     pub virtual_pn: AtomicU64,
     pub virtual_dn: AtomicU64,
     pub win_len: AtomicU64,
+    pub effort: AtomicU64,
     pub children: OnceLock<Vec<ChildRef>>,
+    pub deferred_moves: OnceLock<Box<[(usize, usize)]>>,
+    pub deferred_children: OnceLock<Vec<ChildRef>>,
     pub is_depth_limited: AtomicBool,
     pub depth_cutoff: AtomicBool,
+    pub truncated: AtomicBool,
+    pub in_flight: AtomicBool,
 }
 impl ParallelNode {
     #[inline]
     #[must_use]
-    pub const fn new(player: u8, depth: usize, hash: u64, is_depth_limited: bool) -> Self {
+    pub const fn new(
+        player: u8,
+        attacker: u8,
+        (depth, stones): (usize, usize),
+        hash: CanonicalHash,
+        is_depth_limited: bool,
+    ) -> Self {
         Self {
             player,
+            attacker,
             depth,
+            stones,
             hash,
             pn: AtomicU64::new(1),
             dn: AtomicU64::new(1),
             virtual_pn: AtomicU64::new(0),
             virtual_dn: AtomicU64::new(0),
             win_len: AtomicU64::new(u64::MAX),
+            effort: AtomicU64::new(0),
             children: OnceLock::new(),
+            deferred_moves: OnceLock::new(),
+            deferred_children: OnceLock::new(),
             is_depth_limited: AtomicBool::new(is_depth_limited),
             depth_cutoff: AtomicBool::new(false),
+            truncated: AtomicBool::new(false),
+            in_flight: AtomicBool::new(false),
         }
     }
     #[inline]
     pub const fn is_or_node(&self) -> bool {
-        self.player == 1
+        self.player == self.attacker
     }
     #[inline]
     pub fn is_expanded(&self) -> bool {
         self.children.get().is_some() || self.is_depth_cutoff()
     }
     #[inline]
+    pub fn expanded_children(&self) -> Option<impl Iterator<Item = &ChildRef> + Clone> {
+        let eager = self.children.get()?;
+        Some(
+            eager
+                .iter()
+                .chain(self.deferred_children.get().into_iter().flatten()),
+        )
+    }
+    #[inline]
+    pub fn pending_deferred(&self) -> u64 {
+        if self.deferred_children.get().is_some() {
+            return 0;
+        }
+        self.deferred_moves.get().map_or(0, |moves| {
+            checked::usize_to_u64(moves.len(), "ParallelNode::pending_deferred")
+        })
+    }
+    #[inline]
+    pub fn needs_deferred_expansion(&self) -> bool {
+        self.pending_deferred() > 0
+            && self.children.get().is_some_and(|children| {
+                children
+                    .iter()
+                    .all(|child_ref| child_ref.node.get_dn() == 0)
+            })
+    }
+    #[inline]
     pub fn is_terminal(&self) -> bool {
         let pn = self.pn.load(Ordering::Acquire);
         let dn = self.dn.load(Ordering::Acquire);
@@ -71,17 +125,38 @@ impl ParallelNode {
     }
     #[inline]
     pub fn get_effective_pn(&self) -> u64 {
-        self.get_pn().saturating_add(self.get_virtual_pn())
+        let pn = self.get_pn();
+        if pn == u64::MAX {
+            return u64::MAX;
+        }
+        checked::saturating_add_u64(pn, self.get_virtual_pn(), "ParallelNode::get_effective_pn")
     }
     #[inline]
     pub fn get_effective_dn(&self) -> u64 {
-        self.get_dn().saturating_add(self.get_virtual_dn())
+        let dn = self.get_dn();
+        if dn == u64::MAX {
+            return u64::MAX;
+        }
+        checked::saturating_add_u64(dn, self.get_virtual_dn(), "ParallelNode::get_effective_dn")
     }
     #[inline]
     pub fn get_win_len(&self) -> u64 {
         self.win_len.load(Ordering::Acquire)
     }
     #[inline]
+    pub fn get_effort(&self) -> u64 {
+        self.effort.load(Ordering::Relaxed)
+    }
+    #[inline]
+    pub fn charge_effort(&self) {
+        checked::atomic_add_u64(
+            &self.effort,
+            1,
+            Ordering::Relaxed,
+            "ParallelNode::charge_effort",
+        );
+    }
+    #[inline]
     pub fn is_depth_limited(&self) -> bool {
         self.is_depth_limited.load(Ordering::Acquire)
     }
@@ -104,6 +179,28 @@ impl ParallelNode {
             .is_ok()
     }
     #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated.load(Ordering::Acquire)
+    }
+    #[inline]
+    pub fn set_truncated(&self, value: bool) {
+        self.truncated.store(value, Ordering::Release);
+    }
+    #[inline]
+    pub fn is_in_flight(&self) -> bool {
+        self.in_flight.load(Ordering::Acquire)
+    }
+    #[inline]
+    pub fn try_begin_expansion(&self) -> bool {
+        self.in_flight
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+    #[inline]
+    pub fn finish_expansion(&self) {
+        self.in_flight.store(false, Ordering::Release);
+    }
+    #[inline]
     pub fn set_pn(&self, value: u64) {
         self.pn.store(value, Ordering::Release);
     }
@@ -117,13 +214,33 @@ impl ParallelNode {
     }
     #[inline]
     pub fn add_virtual_pressure(&self, vpn: u64, vdn: u64) {
-        self.virtual_pn.fetch_add(vpn, Ordering::AcqRel);
-        self.virtual_dn.fetch_add(vdn, Ordering::AcqRel);
+        checked::atomic_add_u64(
+            &self.virtual_pn,
+            vpn,
+            Ordering::AcqRel,
+            "ParallelNode::add_virtual_pressure::pn",
+        );
+        checked::atomic_add_u64(
+            &self.virtual_dn,
+            vdn,
+            Ordering::AcqRel,
+            "ParallelNode::add_virtual_pressure::dn",
+        );
     }
     #[inline]
     pub fn remove_virtual_pressure(&self, vpn: u64, vdn: u64) {
-        self.virtual_pn.fetch_sub(vpn, Ordering::AcqRel);
-        self.virtual_dn.fetch_sub(vdn, Ordering::AcqRel);
+        checked::atomic_sub_u64(
+            &self.virtual_pn,
+            vpn,
+            Ordering::AcqRel,
+            "ParallelNode::remove_virtual_pressure::pn",
+        );
+        checked::atomic_sub_u64(
+            &self.virtual_dn,
+            vdn,
+            Ordering::AcqRel,
+            "ParallelNode::remove_virtual_pressure::dn",
+        );
     }
     #[inline]
     pub fn set_proven(&self) {
@@ -135,26 +252,93 @@ impl ParallelNode {
         self.set_pn(u64::MAX);
         self.set_dn(0);
     }
+    #[inline]
+    pub fn principal_child(&self, selector: &MoveSelector) -> Option<&ChildRef> {
+        let children = self.expanded_children()?;
+        if self.get_pn() == 0 {
+            let win_len = self.get_win_len();
+            let proven = children.filter(|child_ref| {
+                child_ref.node.get_pn() == 0 && child_ref.node.get_win_len() < win_len
+            });
+            let win_len_of =
+                |child_ref: &&ChildRef| (child_ref.mov.coord(), child_ref.node.get_win_len());
+            return if self.is_or_node() {
+                selector.select_min(proven, win_len_of)
+            } else {
+                selector.select_max(proven, win_len_of)
+            };
+        }
+        if self.is_or_node() {
+            selector.select_min(children, |child_ref| {
+                (child_ref.mov.coord(), child_ref.node.get_pn())
+            })
+        } else {
+            selector.select_min(children, |child_ref| {
+                (child_ref.mov.coord(), child_ref.node.get_dn())
+            })
+        }
+    }
 }
 const VIRTUAL_PRESSURE: u64 = 1;
 pub struct Worker {
     pub tree: Arc<SharedTree>,
     pub ctx: ThreadLocalContext,
+    pub tie_break_salt: Option<u64>,
+    pub speculation: Option<(NodeRef, Move)>,
+    pub thresholds: Vec<DfpnThresholds>,
 }
 impl Worker {
     #[inline]
-    pub const fn new(tree: Arc<SharedTree>, ctx: ThreadLocalContext) -> Self {
-        Self { tree, ctx }
+    pub fn new(tree: Arc<SharedTree>, ctx: ThreadLocalContext) -> Self {
+        let tie_break_salt = tree.tie_break_salt(ctx.thread_id);
+        Self {
+            tree,
+            ctx,
+            tie_break_salt,
+            speculation: None,
+            thresholds: Vec::new(),
+        }
     }
     #[inline]
     pub fn run(&mut self) {
-        while !self.tree.should_stop() {
+        self.run_until_retired(&AtomicBool::new(false));
+    }
+    pub(crate) fn run_until_retired(&mut self, retire: &AtomicBool) {
+        let mut iterations_since_check = 0_u32;
+        let mut iterations_since_flush = 0_u32;
+        let flush_interval = self.tree.stats_flush_interval();
+        let iteration = self.tree.iteration_token();
+        while !self.tree.should_stop()
+            && !iteration.is_cancelled()
+            && !retire.load(Ordering::Acquire)
+        {
             if self.tree.root.get_pn() == u64::MAX {
                 self.tree.mark_solved();
                 break;
             }
-            self.tree.increment_iterations();
+            iterations_since_check =
+                checked::add_u32(iterations_since_check, 1_u32, "Worker::run::budget_check");
+            if iterations_since_check >= BUDGET_CHECK_INTERVAL {
+                iterations_since_check = 0;
+                self.tree.report_progress();
+                self.tree.report_overlay(false);
+                self.tree.sample_root();
+                if self.tree.check_budget().is_some() {
+                    break;
+                }
+            }
+            self.ctx.pending_stats.iterations = checked::add_u64(
+                self.ctx.pending_stats.iterations,
+                1_u64,
+                "Worker::run::iterations",
+            );
             self.one_iteration();
+            iterations_since_flush =
+                checked::add_u32(iterations_since_flush, 1_u32, "Worker::run::stats_flush");
+            if iterations_since_flush >= flush_interval {
+                iterations_since_flush = 0;
+                self.flush_stats();
+            }
             let root = &self.tree.root;
             let pn = root.get_pn();
             let dn = root.get_dn();
@@ -163,23 +347,89 @@ impl Worker {
                 break;
             }
         }
+        self.unwind(false, false);
+        self.flush_stats();
+    }
+    #[inline]
+    pub fn flush_stats(&mut self) {
+        self.tree.flush_stats(&mut self.ctx);
     }
     fn one_iteration(&mut self) {
-        self.ctx.clear_path();
-        let root = Arc::clone(&self.tree.root);
-        let leaf = self.select(root);
+        self.speculation = None;
+        let start = if self.tree.dfpn_epsilon_ppm().is_some() {
+            self.ctx.path_stack.last().map_or_else(
+                || Arc::clone(&self.tree.root),
+                |entry| Arc::clone(&entry.node),
+            )
+        } else {
+            self.ctx.clear_path();
+            Arc::clone(&self.tree.root)
+        };
+        let leaf = self.select(start);
         if self.tree.should_stop() {
-            self.backpropagate();
+            self.unwind(false, false);
             return;
         }
+        let mut expanded = false;
         if let Some(leaf_node) = leaf
             && !leaf_node.is_terminal()
-            && !leaf_node.is_expanded()
         {
-            self.tree.expand_node(&leaf_node, &mut self.ctx);
-            self.tree.update_node_pdn(&leaf_node);
+            let claimed = leaf_node.try_begin_expansion();
+            if !leaf_node.is_expanded() {
+                expanded = self.tree.expand_node(&leaf_node, &mut self.ctx);
+                if expanded {
+                    self.tree.record_expansion(&leaf_node, &self.ctx, false);
+                }
+                self.tree.update_node_pdn(&leaf_node);
+            } else if leaf_node.needs_deferred_expansion() {
+                expanded = self.tree.expand_deferred(&leaf_node, &mut self.ctx);
+                if expanded {
+                    self.tree.record_expansion(&leaf_node, &self.ctx, true);
+                }
+                self.tree.update_node_pdn(&leaf_node);
+            }
+            if claimed {
+                leaf_node.finish_expansion();
+            }
+        }
+        self.backpropagate(expanded && self.tree.effort_ordering());
+        self.score_speculation();
+    }
+    fn speculate(&mut self, parent: &NodeRef, best: &ChildRef) -> Option<ChildRef> {
+        if self.tree.speculation() != SpeculationPolicy::SecondBest
+            || best.node.is_terminal()
+            || !best.node.is_in_flight()
+            || (best.node.is_expanded() && !best.node.needs_deferred_expansion())
+        {
+            return None;
+        }
+        let runner_up =
+            SharedTree::select_runner_up_child(parent, self.child_ordering(), best.mov)?;
+        self.ctx.pending_stats.speculative_expansions = checked::add_u64(
+            self.ctx.pending_stats.speculative_expansions,
+            1_u64,
+            "Worker::speculate",
+        );
+        self.speculation = Some((Arc::clone(parent), runner_up.mov));
+        Some(runner_up)
+    }
+    fn score_speculation(&mut self) {
+        let Some((parent, mov)) = self.speculation.take() else {
+            return;
+        };
+        let paid_off = SharedTree::select_best_child(&parent, self.child_ordering())
+            .is_some_and(|best| best.mov == mov)
+            || parent
+                .expanded_children()
+                .and_then(|mut children| children.find(|child_ref| child_ref.mov == mov))
+                .is_some_and(|child_ref| child_ref.node.is_terminal());
+        if paid_off {
+            self.ctx.pending_stats.speculation_hits = checked::add_u64(
+                self.ctx.pending_stats.speculation_hits,
+                1_u64,
+                "Worker::score_speculation",
+            );
         }
-        self.backpropagate();
     }
     fn select(&mut self, start: NodeRef) -> Option<NodeRef> {
         let mut current = start;
@@ -190,25 +440,39 @@ impl Worker {
             if current.is_terminal() {
                 return Some(current);
             }
-            if !current.is_expanded() {
+            if !current.is_expanded() || current.needs_deferred_expansion() {
                 return Some(current);
             }
-            let Some(ChildRef {
-                node: best_child,
-                mov,
-            }) = SharedTree::select_best_child(&current)
-            else {
+            let Some(best) = SharedTree::select_best_child(&current, self.child_ordering()) else {
                 return Some(current);
             };
-            if best_child.is_terminal() {
-                return Some(best_child);
+            let chosen = self.speculate(&current, &best).unwrap_or(best);
+            if chosen.node.is_terminal() {
+                return Some(chosen.node);
             }
+            if let Some(epsilon_ppm) = self.tree.dfpn_epsilon_ppm() {
+                let parent_thresholds = self
+                    .thresholds
+                    .last()
+                    .copied()
+                    .unwrap_or(DfpnThresholds::UNBOUNDED);
+                self.thresholds.push(SharedTree::dfpn_child_thresholds(
+                    &current,
+                    &chosen,
+                    parent_thresholds,
+                    epsilon_ppm,
+                ));
+            }
+            let ChildRef {
+                node: best_child,
+                mov,
+            } = chosen;
             let player = current.player;
             best_child.add_virtual_pressure(VIRTUAL_PRESSURE, VIRTUAL_PRESSURE);
-            self.ctx.make_move(mov, player);
+            self.ctx.make_move(mov.coord(), player);
             self.ctx.push_path(
                 Arc::clone(&best_child),
-                mov,
+                mov.coord(),
                 player,
                 VIRTUAL_PRESSURE,
                 VIRTUAL_PRESSURE,
@@ -216,13 +480,37 @@ impl Worker {
             current = best_child;
         }
     }
-    fn backpropagate(&mut self) {
-        while let Some(entry) = self.ctx.pop_path() {
+    fn child_ordering(&self) -> (Option<u64>, bool) {
+        (self.tie_break_salt, self.tree.effort_ordering())
+    }
+    fn backpropagate(&mut self, charge_effort: bool) {
+        self.unwind(charge_effort, self.tree.dfpn_epsilon_ppm().is_some());
+    }
+    fn unwind(&mut self, charge_effort: bool, retain_within_thresholds: bool) {
+        if charge_effort {
+            for entry in &self.ctx.path_stack {
+                entry.node.charge_effort();
+            }
+            self.tree.root.charge_effort();
+        }
+        while let Some(top) = self.ctx.path_stack.last() {
+            self.tree.update_node_pdn(&top.node);
+            if retain_within_thresholds
+                && self
+                    .thresholds
+                    .last()
+                    .is_some_and(|thresholds| thresholds.admits(&top.node))
+            {
+                return;
+            }
+            let Some(entry) = self.ctx.pop_path() else {
+                break;
+            };
+            self.thresholds.pop();
             self.ctx.undo_move(entry.mov, entry.player);
             entry
                 .node
                 .remove_virtual_pressure(entry.virtual_pn_added, entry.virtual_dn_added);
-            self.tree.update_node_pdn(&entry.node);
         }
         self.tree.update_node_pdn(&self.tree.root);
     }
@@ -0,0 +1,170 @@
+use super::{node::NodeRef, progress::PROGRESS_INTERVAL_NS};
+use crate::{checked, game_state::Coord, utils::timing::duration_to_ns};
+use core::{
+    iter,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    fs::OpenOptions,
+    io::{self, BufWriter, Write},
+    net::TcpStream,
+    time::Instant,
+};
+const TCP_PREFIX: &str = "tcp://";
+const NS_PER_MS: u64 = 1_000_000;
+#[derive(Serialize)]
+struct RootChildOverlay {
+    row: usize,
+    column: usize,
+    pn: u64,
+    dn: u64,
+}
+#[derive(Serialize)]
+struct OverlayFrame {
+    elapsed_ms: u64,
+    depth: Option<usize>,
+    nodes: u64,
+    root_pn: u64,
+    root_dn: u64,
+    board_size: usize,
+    visits: Vec<u64>,
+    expansions: Vec<u64>,
+    root_children: Vec<RootChildOverlay>,
+}
+pub(crate) struct OverlaySample<'root> {
+    pub(crate) depth: Option<usize>,
+    pub(crate) nodes: u64,
+    pub(crate) root: &'root NodeRef,
+}
+pub(crate) struct OverlayStream {
+    board_size: usize,
+    visits: Box<[AtomicU64]>,
+    expansions: Box<[AtomicU64]>,
+    writer: Mutex<BufWriter<Box<dyn Write + Send>>>,
+    start: Instant,
+    last_emit_ns: AtomicU64,
+    failed: AtomicBool,
+}
+impl OverlayStream {
+    pub(crate) fn open(target: &str, board_size: usize) -> io::Result<Self> {
+        let sink: Box<dyn Write + Send> = match target.strip_prefix(TCP_PREFIX) {
+            Some(address) => Box::new(TcpStream::connect(address)?),
+            None => Box::new(OpenOptions::new().create(true).append(true).open(target)?),
+        };
+        let cells = board_size.saturating_mul(board_size);
+        let counters = || {
+            iter::repeat_with(|| AtomicU64::new(0))
+                .take(cells)
+                .collect()
+        };
+        Ok(Self {
+            board_size,
+            visits: counters(),
+            expansions: counters(),
+            writer: Mutex::new(BufWriter::new(sink)),
+            start: Instant::now(),
+            last_emit_ns: AtomicU64::new(0),
+            failed: AtomicBool::new(false),
+        })
+    }
+    pub(crate) fn record<I>(&self, path: I)
+    where
+        I: IntoIterator<Item = Coord>,
+    {
+        let mut last = None;
+        for mov in path {
+            if let Some(counter) = self.counter(&self.visits, mov) {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+            last = Some(mov);
+        }
+        if let Some(counter) = last.and_then(|mov| self.counter(&self.expansions, mov)) {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    pub(crate) fn maybe_emit(&self, sample: &OverlaySample<'_>, force: bool) {
+        if self.failed.load(Ordering::Relaxed) {
+            return;
+        }
+        let elapsed_ns = duration_to_ns(self.start.elapsed());
+        let last_emit_ns = self.last_emit_ns.load(Ordering::Relaxed);
+        if !force && elapsed_ns.saturating_sub(last_emit_ns) < PROGRESS_INTERVAL_NS {
+            return;
+        }
+        if self
+            .last_emit_ns
+            .compare_exchange(
+                last_emit_ns,
+                elapsed_ns,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_err()
+            && !force
+        {
+            return;
+        }
+        let mut line = match serde_json::to_vec(&self.frame(sample, elapsed_ns)) {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("序列化搜索叠加层数据失败: {err}");
+                return;
+            }
+        };
+        line.push(b'\n');
+        let written = {
+            let mut writer = self.writer.lock();
+            writer.write_all(&line).and_then(|()| writer.flush())
+        };
+        if let Err(err) = written
+            && !self.failed.swap(true, Ordering::Relaxed)
+        {
+            eprintln!("写入搜索叠加层数据失败，已停止输出: {err}");
+        }
+    }
+    fn frame(&self, sample: &OverlaySample<'_>, elapsed_ns: u64) -> OverlayFrame {
+        let snapshot = |counters: &[AtomicU64]| {
+            counters
+                .iter()
+                .map(|counter| counter.load(Ordering::Relaxed))
+                .collect()
+        };
+        let root_children = sample
+            .root
+            .expanded_children()
+            .map(|children| {
+                children
+                    .map(|child_ref| RootChildOverlay {
+                        row: child_ref.mov.row(),
+                        column: child_ref.mov.column(),
+                        pn: child_ref.node.get_pn(),
+                        dn: child_ref.node.get_dn(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        OverlayFrame {
+            elapsed_ms: checked::div_u64(elapsed_ns, NS_PER_MS, "OverlayStream::frame::elapsed_ms"),
+            depth: sample.depth,
+            nodes: sample.nodes,
+            root_pn: sample.root.get_pn(),
+            root_dn: sample.root.get_dn(),
+            board_size: self.board_size,
+            visits: snapshot(&self.visits),
+            expansions: snapshot(&self.expansions),
+            root_children,
+        }
+    }
+    fn counter<'counters>(
+        &self,
+        counters: &'counters [AtomicU64],
+        (row, column): Coord,
+    ) -> Option<&'counters AtomicU64> {
+        if row >= self.board_size || column >= self.board_size {
+            return None;
+        }
+        counters.get(row.saturating_mul(self.board_size).saturating_add(column))
+    }
+}
@@ -0,0 +1,67 @@
+use super::{TTEntry, shared_tree::ShardedMap};
+use crate::game_state::CanonicalHash;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write as _},
+    path::Path,
+};
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ProvenEntry {
+    pub hash: CanonicalHash,
+    pub player: u8,
+    pub dn: u64,
+    pub win_len: u64,
+}
+#[derive(Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ProofSnapshot {
+    pub board_size: usize,
+    pub proofs: Vec<ProvenEntry>,
+}
+impl ProofSnapshot {
+    #[inline]
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self).map_err(io::Error::other)?;
+        writer.flush()
+    }
+    #[inline]
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        serde_json::from_reader(reader).map_err(io::Error::other)
+    }
+}
+impl ShardedMap<(CanonicalHash, u8), TTEntry> {
+    #[inline]
+    #[must_use]
+    pub fn proof_snapshot(&self, board_size: usize) -> ProofSnapshot {
+        let proofs = self
+            .collect_where(|_, entry| entry.pn == 0)
+            .into_iter()
+            .map(|((hash, player), entry)| ProvenEntry {
+                hash,
+                player,
+                dn: entry.dn,
+                win_len: entry.win_len,
+            })
+            .collect();
+        ProofSnapshot { board_size, proofs }
+    }
+    #[inline]
+    pub fn restore_proofs(&self, snapshot: &ProofSnapshot, board_size: usize) -> Option<usize> {
+        if snapshot.board_size != board_size {
+            return None;
+        }
+        for entry in &snapshot.proofs {
+            self.insert(
+                (entry.hash, entry.player),
+                TTEntry {
+                    pn: 0,
+                    dn: entry.dn,
+                    win_len: entry.win_len,
+                },
+            );
+        }
+        Some(snapshot.proofs.len())
+    }
+}
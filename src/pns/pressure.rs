@@ -0,0 +1,89 @@
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use parking_lot::Mutex;
+use serde::Serialize;
+const DEGRADED_MAX_CHILDREN: usize = 6;
+const STEPS: [MemoryDegradation; 3] = [
+    MemoryDegradation::CapChildren,
+    MemoryDegradation::PruneResolved,
+    MemoryDegradation::EvictTransposition,
+];
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub enum MemoryDegradation {
+    CapChildren,
+    PruneResolved,
+    EvictTransposition,
+}
+impl MemoryDegradation {
+    #[inline]
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::CapChildren => "限制进攻方子节点数",
+            Self::PruneResolved => "剪除已定子树的节点表条目",
+            Self::EvictTransposition => "淘汰未定的置换表条目",
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct DegradationRecord {
+    pub degradation: MemoryDegradation,
+    pub soft_limit_bytes: u64,
+    pub estimated_before: u64,
+    pub estimated_after: u64,
+}
+pub(crate) struct DegradationState {
+    soft_limit_bytes: Option<u64>,
+    applied: AtomicU8,
+    max_children: AtomicUsize,
+    prune_resolved: AtomicBool,
+    records: Mutex<Vec<DegradationRecord>>,
+}
+impl DegradationState {
+    pub(crate) const fn new(soft_limit_bytes: Option<u64>) -> Self {
+        Self {
+            soft_limit_bytes,
+            applied: AtomicU8::new(0),
+            max_children: AtomicUsize::new(usize::MAX),
+            prune_resolved: AtomicBool::new(false),
+            records: Mutex::new(Vec::new()),
+        }
+    }
+    pub(crate) const fn soft_limit_bytes(&self) -> Option<u64> {
+        self.soft_limit_bytes
+    }
+    pub(crate) fn claim_next_step(&self) -> Option<MemoryDegradation> {
+        let applied = self.applied.load(Ordering::Acquire);
+        let step = *STEPS.get(usize::from(applied))?;
+        self.applied
+            .compare_exchange(
+                applied,
+                applied.saturating_add(1),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .ok()?;
+        match step {
+            MemoryDegradation::CapChildren => {
+                self.max_children
+                    .store(DEGRADED_MAX_CHILDREN, Ordering::Release);
+            }
+            MemoryDegradation::PruneResolved => {
+                self.prune_resolved.store(true, Ordering::Release);
+            }
+            MemoryDegradation::EvictTransposition => {}
+        }
+        Some(step)
+    }
+    pub(crate) fn max_children(&self) -> usize {
+        self.max_children.load(Ordering::Acquire)
+    }
+    pub(crate) fn prunes_resolved(&self) -> bool {
+        self.prune_resolved.load(Ordering::Acquire)
+    }
+    pub(crate) fn record(&self, record: DegradationRecord) {
+        self.records.lock().push(record);
+    }
+    pub(crate) fn records(&self) -> Vec<DegradationRecord> {
+        self.records.lock().clone()
+    }
+}
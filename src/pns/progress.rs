@@ -0,0 +1,58 @@
+use super::TreeStatsSnapshot;
+use crate::{game_state::Coord, utils::timing::duration_to_ns};
+use alloc::sync::Arc;
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+use std::time::Instant;
+pub(crate) const PROGRESS_INTERVAL_NS: u64 = 100_000_000;
+pub type ProgressCallback = Arc<dyn Fn(&SearchProgress) + Send + Sync>;
+#[derive(Clone, Copy)]
+pub struct SearchProgress {
+    pub depth: Option<usize>,
+    pub root_pn: u64,
+    pub root_dn: u64,
+    pub nodes: u64,
+    pub elapsed: Duration,
+    pub stats: TreeStatsSnapshot,
+    pub best_candidate: Option<Coord>,
+}
+pub(crate) struct ProgressReporter {
+    callback: ProgressCallback,
+    start: Instant,
+    last_report_ns: AtomicU64,
+}
+impl ProgressReporter {
+    pub(crate) fn new(callback: ProgressCallback) -> Self {
+        Self {
+            callback,
+            start: Instant::now(),
+            last_report_ns: AtomicU64::new(0),
+        }
+    }
+    pub(crate) fn maybe_report<F>(&self, progress: F)
+    where
+        F: FnOnce(Duration) -> SearchProgress,
+    {
+        let elapsed = self.start.elapsed();
+        let elapsed_ns = duration_to_ns(elapsed);
+        let last_report_ns = self.last_report_ns.load(Ordering::Relaxed);
+        if elapsed_ns.saturating_sub(last_report_ns) < PROGRESS_INTERVAL_NS {
+            return;
+        }
+        if self
+            .last_report_ns
+            .compare_exchange(
+                last_report_ns,
+                elapsed_ns,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            return;
+        }
+        (self.callback)(&progress(elapsed));
+    }
+}
@@ -0,0 +1,127 @@
+use super::{ProofSnapshot, ProvenEntry, TTEntry};
+use crate::{checked, game_state::CanonicalHash};
+use memmap2::Mmap;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write as _},
+    path::Path,
+};
+const MAGIC: [u8; FIELD_LEN] = *b"INEVPB01";
+const FIELD_LEN: usize = 8;
+const BITS_PER_BYTE: usize = 8;
+const LOW_BYTE_MASK: u64 = 0xFF;
+const HEADER_FIELDS: usize = 3;
+const RECORD_FIELDS: usize = 4;
+const HEADER_LEN: usize = FIELD_LEN * HEADER_FIELDS;
+const RECORD_LEN: usize = FIELD_LEN * RECORD_FIELDS;
+type Record = [u8; RECORD_LEN];
+pub struct MappedProofBook {
+    map: Mmap,
+    board_size: usize,
+}
+impl MappedProofBook {
+    #[inline]
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let map = unsafe { Mmap::map(&file)? };
+        let (header, body) = map
+            .split_first_chunk::<HEADER_LEN>()
+            .ok_or_else(|| invalid_data("文件过短，缺少证明库文件头"))?;
+        let [magic, stored_board_size, count] = decode(header);
+        if magic != decode_field(MAGIC) {
+            return Err(invalid_data("文件标识不符，不是证明库文件"));
+        }
+        let (records, remainder) = body.as_chunks::<RECORD_LEN>();
+        if !remainder.is_empty() || u64::try_from(records.len()).ok() != Some(count) {
+            return Err(invalid_data("记录数量与文件长度不符，文件可能已损坏"));
+        }
+        let board_size =
+            usize::try_from(stored_board_size).map_err(|_err| invalid_data("棋盘大小超出范围"))?;
+        Ok(Self { map, board_size })
+    }
+    #[inline]
+    #[must_use]
+    pub const fn board_size(&self) -> usize {
+        self.board_size
+    }
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.records().len()
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.records().is_empty()
+    }
+    #[inline]
+    #[must_use]
+    pub fn lookup(&self, hash: CanonicalHash, player: u8) -> Option<TTEntry> {
+        let records = self.records();
+        let key = (hash.bits(), u64::from(player));
+        let index = records
+            .binary_search_by(|record| {
+                let [record_hash, record_player, _, _] = decode(record);
+                (record_hash, record_player).cmp(&key)
+            })
+            .ok()?;
+        let [_, _, dn, win_len] = decode(records.get(index)?);
+        Some(TTEntry { pn: 0, dn, win_len })
+    }
+    fn records(&self) -> &[Record] {
+        self.map
+            .get(HEADER_LEN..)
+            .map_or(&[], |body| body.as_chunks::<RECORD_LEN>().0)
+    }
+}
+impl ProofSnapshot {
+    #[inline]
+    pub fn save_mapped(&self, path: &Path) -> io::Result<()> {
+        let mut proofs: Vec<&ProvenEntry> = self.proofs.iter().collect();
+        proofs.sort_unstable_by_key(|entry| (entry.hash, entry.player));
+        proofs.dedup_by_key(|entry| (entry.hash, entry.player));
+        let mut writer = BufWriter::new(File::create(path)?);
+        for field in [
+            decode_field(MAGIC),
+            u64::try_from(self.board_size).map_err(io::Error::other)?,
+            u64::try_from(proofs.len()).map_err(io::Error::other)?,
+        ] {
+            writer.write_all(&encode_field(field))?;
+        }
+        for entry in proofs {
+            for field in [
+                entry.hash.bits(),
+                u64::from(entry.player),
+                entry.dn,
+                entry.win_len,
+            ] {
+                writer.write_all(&encode_field(field))?;
+            }
+        }
+        writer.flush()
+    }
+}
+fn decode<const LEN: usize, const FIELDS: usize>(bytes: &[u8; LEN]) -> [u64; FIELDS] {
+    let mut fields = [0_u64; FIELDS];
+    for (field, chunk) in fields.iter_mut().zip(bytes.as_chunks::<FIELD_LEN>().0) {
+        *field = decode_field(*chunk);
+    }
+    fields
+}
+fn decode_field(bytes: [u8; FIELD_LEN]) -> u64 {
+    bytes.into_iter().fold(0, |value, byte| {
+        checked::shl_u64(value, BITS_PER_BYTE, "MappedProofBook::decode_field") | u64::from(byte)
+    })
+}
+fn encode_field(value: u64) -> [u8; FIELD_LEN] {
+    let mut bytes = [0_u8; FIELD_LEN];
+    let mut remaining = value;
+    for byte in bytes.iter_mut().rev() {
+        *byte = u8::try_from(remaining & LOW_BYTE_MASK).unwrap_or_default();
+        remaining = checked::shr_u64(remaining, BITS_PER_BYTE, "MappedProofBook::encode_field");
+    }
+    bytes
+}
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
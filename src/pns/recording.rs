@@ -0,0 +1,139 @@
+use crate::game_state::{CanonicalHash, Coord};
+use core::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write as _},
+    path::Path,
+};
+const RECORD_PREALLOCATION: usize = 4096;
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ExpansionRecord {
+    pub depth_limit: Option<usize>,
+    pub hash: CanonicalHash,
+    pub path: Vec<Coord>,
+    pub deferred: bool,
+}
+#[derive(Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ExpansionLog {
+    pub board: Vec<u8>,
+    pub records: Vec<ExpansionRecord>,
+    pub dropped: u64,
+}
+impl ExpansionLog {
+    #[inline]
+    #[must_use]
+    pub const fn is_truncated(&self) -> bool {
+        self.dropped > 0
+    }
+    #[inline]
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self).map_err(io::Error::other)?;
+        writer.flush()
+    }
+    #[inline]
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        serde_json::from_reader(reader).map_err(io::Error::other)
+    }
+}
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ReplayDivergence {
+    MissingChild {
+        index: usize,
+        mov: Coord,
+    },
+    HashMismatch {
+        index: usize,
+        expected: CanonicalHash,
+        actual: CanonicalHash,
+    },
+    AlreadyExpanded {
+        index: usize,
+    },
+}
+pub struct ReplaySummary {
+    pub replayed: usize,
+    pub divergences: Vec<ReplayDivergence>,
+    pub root_pn: u64,
+    pub root_dn: u64,
+}
+pub(crate) struct ExpansionRecorder {
+    limit: usize,
+    records: Mutex<Vec<ExpansionRecord>>,
+    dropped: AtomicU64,
+}
+impl ExpansionRecorder {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            records: Mutex::new(Vec::with_capacity(limit.min(RECORD_PREALLOCATION))),
+            dropped: AtomicU64::new(0),
+        }
+    }
+    pub(crate) fn record<I>(
+        &self,
+        depth_limit: Option<usize>,
+        hash: CanonicalHash,
+        path: I,
+        deferred: bool,
+    ) where
+        I: IntoIterator<Item = Coord>,
+    {
+        let mut records = self.records.lock();
+        if records.len() >= self.limit {
+            drop(records);
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        records.push(ExpansionRecord {
+            depth_limit,
+            hash,
+            path: path.into_iter().collect(),
+            deferred,
+        });
+    }
+    pub(crate) fn snapshot(&self, board: Vec<u8>) -> ExpansionLog {
+        ExpansionLog {
+            board,
+            records: self.records.lock().clone(),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+impl ReplaySummary {
+    #[inline]
+    #[must_use]
+    pub const fn is_faithful(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+impl fmt::Display for ReplayDivergence {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::MissingChild { index, mov } => write!(
+                f,
+                "第 {index} 次展开: 路径上缺少着法 ({row}, {column}) 对应的子节点",
+                row = mov.0,
+                column = mov.1
+            ),
+            Self::HashMismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "第 {index} 次展开: 节点哈希不一致，记录 {expected:#018x}，重放 {actual:#018x}"
+            ),
+            Self::AlreadyExpanded { index } => {
+                write!(f, "第 {index} 次展开: 节点在重放中已被展开")
+            }
+        }
+    }
+}
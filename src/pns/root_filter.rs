@@ -0,0 +1,63 @@
+use crate::game_state::Coord;
+use serde::{Deserialize, Serialize};
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum RootFilterMode {
+    Allow,
+    Deny,
+}
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RootMoveFilter {
+    mode: RootFilterMode,
+    moves: Vec<Coord>,
+}
+impl RootMoveFilter {
+    #[inline]
+    #[must_use]
+    pub fn allow<I>(moves: I) -> Self
+    where
+        I: IntoIterator<Item = Coord>,
+    {
+        Self {
+            mode: RootFilterMode::Allow,
+            moves: moves.into_iter().collect(),
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn deny<I>(moves: I) -> Self
+    where
+        I: IntoIterator<Item = Coord>,
+    {
+        Self {
+            mode: RootFilterMode::Deny,
+            moves: moves.into_iter().collect(),
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn permits(&self, mov: Coord) -> bool {
+        let listed = self.moves.contains(&mov);
+        match self.mode {
+            RootFilterMode::Allow => listed,
+            RootFilterMode::Deny => !listed,
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn mode(&self) -> RootFilterMode {
+        self.mode
+    }
+    #[inline]
+    #[must_use]
+    pub fn moves(&self) -> &[Coord] {
+        &self.moves
+    }
+    #[inline]
+    #[must_use]
+    pub const fn label(&self) -> &'static str {
+        match self.mode {
+            RootFilterMode::Allow => "仅允许",
+            RootFilterMode::Deny => "排除",
+        }
+    }
+}
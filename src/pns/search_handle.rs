@@ -0,0 +1,52 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+const NO_REQUEST: usize = 0;
+struct HandleState {
+    requested_threads: AtomicUsize,
+    active_threads: AtomicUsize,
+}
+#[derive(Clone)]
+pub struct SearchHandle {
+    state: Arc<HandleState>,
+}
+impl SearchHandle {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(HandleState {
+                requested_threads: AtomicUsize::new(NO_REQUEST),
+                active_threads: AtomicUsize::new(0),
+            }),
+        }
+    }
+    #[inline]
+    pub fn set_threads(&self, num_threads: usize) {
+        self.state
+            .requested_threads
+            .store(num_threads.max(1), Ordering::Release);
+    }
+    #[inline]
+    #[must_use]
+    pub fn threads(&self) -> usize {
+        self.state.active_threads.load(Ordering::Acquire)
+    }
+    pub(crate) fn take_request(&self) -> Option<usize> {
+        let requested = self
+            .state
+            .requested_threads
+            .swap(NO_REQUEST, Ordering::AcqRel);
+        (requested != NO_REQUEST).then_some(requested)
+    }
+    pub(crate) fn record_threads(&self, num_threads: usize) {
+        self.state
+            .active_threads
+            .store(num_threads, Ordering::Release);
+    }
+}
+impl Default for SearchHandle {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
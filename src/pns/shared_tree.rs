@@ -1,21 +1,57 @@
 use super::node::NodeRef;
 use crate::checked;
-use crate::pns::TTEntry;
+use crate::game_state::{CanonicalHash, PositionHash};
+use crate::pns::{TTEntry, to_f64};
 use ahash::RandomState;
 use alloc::sync::Arc;
-use core::hash::Hash;
+use core::{
+    hash::Hash,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 use hashbrown::HashMap;
 use parking_lot::RwLock;
+use serde::Serialize;
 mod arena;
 mod frontier;
 mod growth;
+mod introspection;
 mod judgement;
 mod resolution;
+mod snapshot;
 pub(crate) use arena::SharedTree;
+pub(crate) use introspection::probe_line;
+pub type FrontierRefresh = frontier::FrontierRefresh;
+pub type DfpnThresholds = frontier::DfpnThresholds;
+pub type TieBreakPolicy = frontier::TieBreakPolicy;
+pub type SpeculationPolicy = frontier::SpeculationPolicy;
+pub type EffortOrdering = frontier::EffortOrdering;
+pub type NodeSummary = snapshot::NodeSummary;
+pub type TreeSnapshot = snapshot::TreeSnapshot;
 const SHARD_COUNT: usize = 64;
+const HASH_BITS: u32 = 64;
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct ShardOccupancy {
+    pub shards: usize,
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+impl ShardOccupancy {
+    #[inline]
+    #[must_use]
+    pub fn imbalance(&self) -> f64 {
+        if self.mean > 0.0_f64 {
+            to_f64(checked::usize_to_u64(self.max, "ShardOccupancy::imbalance")) / self.mean
+        } else {
+            1.0_f64
+        }
+    }
+}
 pub struct ShardedMap<K, V> {
     shards: Vec<RwLock<HashMap<K, V, RandomState>>>,
     hasher: RandomState,
+    shard_shift: u32,
+    eviction_cursor: AtomicUsize,
 }
 impl<K: Hash + Eq, V: Clone> ShardedMap<K, V> {
     pub fn new() -> Self {
@@ -25,7 +61,12 @@ impl<K: Hash + Eq, V: Clone> ShardedMap<K, V> {
         for _ in 0..SHARD_COUNT {
             shards.push(RwLock::new(HashMap::with_hasher(hasher.clone())));
         }
-        Self { shards, hasher }
+        Self {
+            shards,
+            hasher,
+            shard_shift: HASH_BITS.saturating_sub(SHARD_COUNT.trailing_zeros()),
+            eviction_cursor: AtomicUsize::new(0),
+        }
     }
     pub fn clear(&self) {
         for shard in &self.shards {
@@ -42,15 +83,72 @@ impl<K: Hash + Eq, V: Clone> ShardedMap<K, V> {
         let mut guard = self.shard(idx).write();
         guard.insert(key, value);
     }
+    pub fn retain<F>(&self, mut keep: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        for shard in &self.shards {
+            shard.write().retain(|key, value| keep(key, value));
+        }
+    }
+    pub fn collect_where<F>(&self, mut keep: F) -> Vec<(K, V)>
+    where
+        K: Clone,
+        F: FnMut(&K, &V) -> bool,
+    {
+        let mut collected = Vec::new();
+        for shard in &self.shards {
+            collected.extend(
+                shard
+                    .read()
+                    .iter()
+                    .filter(|&(key, value)| keep(key, value))
+                    .map(|(key, value)| (key.clone(), value.clone())),
+            );
+        }
+        collected
+    }
     pub fn len(&self) -> usize {
         self.shards.iter().map(|shard| shard.read().len()).sum()
     }
+    pub fn shard_owner(&self, key: &K, workers: usize) -> usize {
+        self.owner_of_shard(self.shard_index(key), workers)
+    }
+    pub fn reserve_owned(&self, worker: usize, workers: usize, additional: usize) {
+        for (index, shard) in self.shards.iter().enumerate() {
+            if self.owner_of_shard(index, workers) == worker {
+                shard.write().reserve(additional);
+            }
+        }
+    }
+    pub fn occupancy(&self) -> ShardOccupancy {
+        let sizes: Vec<usize> = self.shards.iter().map(|shard| shard.read().len()).collect();
+        let total: usize = sizes.iter().sum();
+        ShardOccupancy {
+            shards: sizes.len(),
+            min: sizes.iter().copied().min().unwrap_or(0),
+            max: sizes.iter().copied().max().unwrap_or(0),
+            mean: to_f64(checked::usize_to_u64(total, "ShardedMap::occupancy::total"))
+                / to_f64(checked::usize_to_u64(
+                    sizes.len().max(1),
+                    "ShardedMap::occupancy::shards",
+                )),
+        }
+    }
+    fn owner_of_shard(&self, index: usize, workers: usize) -> usize {
+        checked::div_usize(
+            checked::mul_usize(index, workers.max(1), "ShardedMap::owner_of_shard"),
+            self.shards.len(),
+            "ShardedMap::owner_of_shard",
+        )
+    }
     fn shard_index(&self, key: &K) -> usize {
-        let hash = self.hasher.hash_one(key);
-        let last_shard_index =
-            checked::sub_usize(self.shards.len(), 1_usize, "ShardedMap::shard_index::mask");
-        let mask = checked::usize_to_u64(last_shard_index, "ShardedMap::shard_index::mask");
-        checked::u64_to_usize(hash & mask, "ShardedMap::shard_index")
+        let prefix = self
+            .hasher
+            .hash_one(key)
+            .checked_shr(self.shard_shift)
+            .unwrap_or(0);
+        checked::u64_to_usize(prefix, "ShardedMap::shard_index")
     }
     fn shard(&self, index: usize) -> &RwLock<HashMap<K, V, RandomState>> {
         let Some(shard) = self.shards.get(index) else {
@@ -65,5 +163,32 @@ impl<K: Hash + Eq, V: Clone> Default for ShardedMap<K, V> {
         Self::new()
     }
 }
-pub type TranspositionTable = Arc<ShardedMap<(u64, u8), TTEntry>>;
-pub type NodeTable = Arc<ShardedMap<(u64, usize), NodeRef>>;
+impl ShardedMap<(PositionHash, usize), NodeRef> {
+    pub fn evict_unreferenced(&self, below_stones: usize) -> usize {
+        self.evict_unreferenced_step(below_stones, self.shards.len())
+    }
+    pub fn evict_unreferenced_step(&self, below_stones: usize, shard_budget: usize) -> usize {
+        let shard_count = self.shards.len();
+        let start = self
+            .eviction_cursor
+            .fetch_add(shard_budget, Ordering::Relaxed);
+        let mut evicted = 0_usize;
+        for step in 0..shard_budget.min(shard_count) {
+            let index = checked::rem_usize(
+                start.wrapping_add(step),
+                shard_count,
+                "ShardedMap::evict_unreferenced_step",
+            );
+            self.shard(index).write().retain(|_, node| {
+                let keep = node.stones >= below_stones || Arc::strong_count(node) > 1;
+                if !keep {
+                    evicted = evicted.saturating_add(1);
+                }
+                keep
+            });
+        }
+        evicted
+    }
+}
+pub type TranspositionTable = Arc<ShardedMap<(CanonicalHash, u8), TTEntry>>;
+pub type NodeTable = Arc<ShardedMap<(PositionHash, usize), NodeRef>>;
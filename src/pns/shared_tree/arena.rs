@@ -1,70 +1,404 @@
 use super::{
     super::{
-        TreeStatsAtomic, TreeStatsSnapshot,
-        node::{NodeRef, ParallelNode},
+        Budget, BudgetLimit, MappedProofBook, MoveOrder, MoveSelector, TimeDecision,
+        TimeDecisionRecord, TimeManager, TreeStatsAtomic, TreeStatsSnapshot,
+        budget::BudgetTracker,
+        context::ThreadLocalContext,
+        hot_path::{AllocationAudit, AllocationPolicy},
+        node::{ChildRef, NodeRef, ParallelNode},
+        overlay::{OverlaySample, OverlayStream},
+        pressure::{DegradationRecord, DegradationState, MemoryDegradation},
+        progress::{ProgressCallback, ProgressReporter, SearchProgress},
+        recording::{ExpansionLog, ExpansionRecorder},
+        root_filter::RootMoveFilter,
+        stats_def::DEFAULT_STATS_FLUSH_INTERVAL,
     },
-    NodeTable, ShardedMap, TranspositionTable,
+    EffortOrdering, NodeTable, ShardOccupancy, ShardedMap, SpeculationPolicy, TranspositionTable,
+    frontier::{DFPN_EPSILON_SCALE, mix_tie_break},
 };
 use crate::checked;
-use crate::pns::TTEntry;
+use crate::game_state::{CanonicalHash, Coord, PositionHash, PositionKey};
+use crate::interrupt::{CancelReason, CancellationToken};
+use crate::pns::{TTEntry, to_f64};
+use crate::reference;
 use alloc::sync::Arc;
 use core::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
-static NEXT_STATS_SESSION_ID: AtomicU64 = AtomicU64::new(1_u64);
+use hashbrown::HashSet;
+use parking_lot::Mutex;
+use std::sync::OnceLock;
 const NO_DEPTH_LIMIT: usize = usize::MAX;
+const ARC_HEADER_BYTES: usize = 16;
+const OWNED_SHARD_PREFAULT: usize = 1024;
 pub(crate) struct SharedTree {
     pub(crate) root: NodeRef,
     pub(crate) transposition_table: TranspositionTable,
     pub(crate) node_table: NodeTable,
     depth_limit: AtomicUsize,
     pub(crate) solved: AtomicBool,
-    pub(crate) stop_flag: Arc<AtomicBool>,
+    cancel: CancellationToken,
+    iteration_cancel: Mutex<CancellationToken>,
     pub(crate) stats: TreeStatsAtomic,
-    stats_session_id: u64,
-}
-fn next_stats_session_id() -> u64 {
-    loop {
-        let current = NEXT_STATS_SESSION_ID.load(Ordering::Relaxed);
-        let next = checked::add_u64(current, 1_u64, "SharedTree::next_stats_session_id");
-        match NEXT_STATS_SESSION_ID.compare_exchange_weak(
-            current,
-            next,
-            Ordering::Relaxed,
-            Ordering::Relaxed,
-        ) {
-            Ok(_) => return current,
-            Err(_) => core::hint::spin_loop(),
-        }
-    }
+    stats_flush_interval: u32,
+    budget: BudgetTracker,
+    pressure: DegradationState,
+    max_children: usize,
+    eager_children: usize,
+    time_manager: Option<Mutex<TimeManager>>,
+    progress: Option<ProgressReporter>,
+    root_sample: Mutex<Option<(u64, u64)>>,
+    cross_check_interval: u64,
+    cross_check_counter: AtomicU64,
+    root_move_filter: Option<RootMoveFilter>,
+    repetition_history: OnceLock<HashSet<PositionKey>>,
+    pub(crate) allocation_audit: AllocationAudit,
+    recorder: Option<ExpansionRecorder>,
+    tie_break_seed: Option<u64>,
+    speculation: SpeculationPolicy,
+    dfpn_epsilon_ppm: Option<u64>,
+    effort_ordering: EffortOrdering,
+    move_selector: MoveSelector,
+    overlay: Option<OverlayStream>,
+    proof_book: Option<Arc<MappedProofBook>>,
+    worker_slots: AtomicUsize,
+    root_pos_hash: PositionHash,
+    leader: Option<Arc<Self>>,
 }
 impl SharedTree {
     #[inline]
     #[must_use]
     pub fn with_tt_and_stop(
         root_player: u8,
-        root_hash: u64,
-        root_pos_hash: u64,
+        root_hash: CanonicalHash,
+        (root_pos_hash, root_stones): (PositionHash, usize),
         depth_limit: Option<usize>,
-        stop_flag: Arc<AtomicBool>,
+        cancel: CancellationToken,
         existing_tt: Option<TranspositionTable>,
         existing_node_table: Option<NodeTable>,
+        budget: Budget,
     ) -> Self {
-        let root = Arc::new(ParallelNode::new(root_player, 0, root_hash, false));
+        let root = Arc::new(ParallelNode::new(
+            root_player,
+            root_player,
+            (0, root_stones),
+            root_hash,
+            false,
+        ));
         let node_table = existing_node_table.unwrap_or_else(|| Arc::new(ShardedMap::new()));
         node_table.insert((root_pos_hash, 0), Arc::clone(&root));
         let transposition_table = existing_tt.unwrap_or_else(|| Arc::new(ShardedMap::new()));
         let stats = TreeStatsAtomic::new();
         stats.nodes_created.store(1, Ordering::Relaxed);
-        let stats_session_id = next_stats_session_id();
         Self {
             root,
             transposition_table,
             node_table,
             depth_limit: AtomicUsize::new(encode_depth_limit(depth_limit)),
             solved: AtomicBool::new(false),
-            stop_flag,
+            iteration_cancel: Mutex::new(cancel.child()),
+            cancel,
             stats,
-            stats_session_id,
+            stats_flush_interval: DEFAULT_STATS_FLUSH_INTERVAL,
+            budget: BudgetTracker::new(budget),
+            pressure: DegradationState::new(budget.soft_memory_bytes()),
+            max_children: usize::MAX,
+            eager_children: usize::MAX,
+            time_manager: None,
+            progress: None,
+            root_sample: Mutex::new(None),
+            cross_check_interval: 0,
+            cross_check_counter: AtomicU64::new(0),
+            root_move_filter: None,
+            repetition_history: OnceLock::new(),
+            allocation_audit: AllocationAudit::new(AllocationPolicy::Allowed),
+            recorder: None,
+            tie_break_seed: None,
+            speculation: SpeculationPolicy::Off,
+            dfpn_epsilon_ppm: None,
+            effort_ordering: EffortOrdering::Off,
+            move_selector: MoveSelector::new(MoveOrder::Lexicographic, 0),
+            overlay: None,
+            proof_book: None,
+            worker_slots: AtomicUsize::new(1),
+            root_pos_hash,
+            leader: None,
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn lazy_helper(self: &Arc<Self>, helper_index: usize) -> Self {
+        let helper_bits = checked::usize_to_u64(helper_index, "SharedTree::lazy_helper");
+        let tie_break_seed = mix_tie_break(
+            self.tie_break_seed.unwrap_or_else(rand::random),
+            helper_bits,
+        );
+        Self {
+            max_children: self.max_children,
+            eager_children: self.eager_children,
+            stats_flush_interval: self.stats_flush_interval,
+            root_move_filter: self.root_move_filter.clone(),
+            tie_break_seed: Some(tie_break_seed),
+            speculation: self.speculation,
+            dfpn_epsilon_ppm: self.dfpn_epsilon_ppm,
+            effort_ordering: self.effort_ordering,
+            move_selector: self.move_selector,
+            proof_book: self.proof_book.clone(),
+            leader: Some(Arc::clone(self)),
+            ..Self::with_tt_and_stop(
+                self.root.player,
+                self.root.hash,
+                (self.root_pos_hash, self.root.stones),
+                self.depth_limit(),
+                self.cancel.child(),
+                Some(self.get_tt()),
+                None,
+                self.budget.budget(),
+            )
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_time_manager(mut self, time_manager: TimeManager) -> Self {
+        self.time_manager = Some(Mutex::new(time_manager));
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(ProgressReporter::new(callback));
+        self
+    }
+    #[inline]
+    pub fn report_progress(&self) {
+        let Some(reporter) = self.progress.as_ref() else {
+            return;
+        };
+        reporter.maybe_report(|elapsed| SearchProgress {
+            depth: self.depth_limit(),
+            root_pn: self.root.get_pn(),
+            root_dn: self.root.get_dn(),
+            nodes: self.stats.nodes_created.load(Ordering::Relaxed),
+            elapsed,
+            stats: self.stats.snapshot(),
+            best_candidate: self.most_proving_move(),
+        });
+    }
+    #[inline]
+    pub fn most_proving_move(&self) -> Option<Coord> {
+        self.root
+            .children
+            .get()?
+            .iter()
+            .min_by_key(|child_ref| {
+                (
+                    child_ref.node.get_dn() == 0,
+                    child_ref.node.get_pn(),
+                    child_ref.mov,
+                )
+            })
+            .map(|child_ref| child_ref.mov.coord())
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_overlay(mut self, overlay: Option<OverlayStream>) -> Self {
+        self.overlay = overlay;
+        self
+    }
+    #[inline]
+    pub fn report_overlay(&self, force: bool) {
+        let Some(overlay) = self.overlay.as_ref() else {
+            return;
+        };
+        overlay.maybe_emit(
+            &OverlaySample {
+                depth: self.depth_limit(),
+                nodes: self.stats.nodes_created.load(Ordering::Relaxed),
+                root: &self.root,
+            },
+            force,
+        );
+    }
+    #[inline]
+    pub fn sample_root(&self) {
+        let (root_pn, root_dn) = (self.root.get_pn(), self.root.get_dn());
+        let finite = |value: u64| value != 0 && value != u64::MAX;
+        if finite(root_pn) && finite(root_dn) {
+            *self.root_sample.lock() = Some((root_pn, root_dn));
+        }
+    }
+    #[inline]
+    pub fn sampled_root(&self) -> Option<(u64, u64)> {
+        *self.root_sample.lock()
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_cross_check(mut self, interval: u64) -> Self {
+        self.cross_check_interval = interval;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_max_children(mut self, max_children: Option<usize>) -> Self {
+        self.max_children = max_children.map_or(usize::MAX, |limit| limit.max(1));
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_eager_children(mut self, eager_children: Option<usize>) -> Self {
+        self.eager_children = eager_children.map_or(usize::MAX, |limit| limit.max(1));
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_hot_path_allocations(mut self, policy: AllocationPolicy) -> Self {
+        self.allocation_audit = AllocationAudit::new(policy);
+        self
+    }
+    #[inline]
+    pub fn hot_path_allocations(&self) -> u64 {
+        self.allocation_audit.violations()
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_expansion_recording(mut self, limit: Option<usize>) -> Self {
+        self.recorder = limit.map(ExpansionRecorder::new);
+        self
+    }
+    #[inline]
+    pub fn record_expansion(&self, node: &NodeRef, ctx: &ThreadLocalContext, deferred: bool) {
+        if let Some(overlay) = self.overlay.as_ref() {
+            overlay.record(ctx.path_stack.iter().map(|entry| entry.mov));
+        }
+        let Some(recorder) = self.recorder.as_ref() else {
+            return;
+        };
+        recorder.record(
+            self.depth_limit(),
+            node.hash,
+            ctx.path_stack.iter().map(|entry| entry.mov),
+            deferred,
+        );
+    }
+    #[inline]
+    pub fn expansion_log(&self, board: &[u8]) -> Option<ExpansionLog> {
+        self.recorder
+            .as_ref()
+            .map(|recorder| recorder.snapshot(board.to_vec()))
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_root_move_filter(mut self, filter: Option<RootMoveFilter>) -> Self {
+        self.root_move_filter = filter;
+        self
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_tie_break_seed(mut self, seed: Option<u64>) -> Self {
+        self.tie_break_seed = seed;
+        self
+    }
+    #[inline]
+    pub const fn tie_break_seed(&self) -> Option<u64> {
+        self.tie_break_seed
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_speculation(mut self, speculation: SpeculationPolicy) -> Self {
+        self.speculation = speculation;
+        self
+    }
+    #[inline]
+    pub const fn speculation(&self) -> SpeculationPolicy {
+        self.speculation
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_dfpn_epsilon(mut self, epsilon: Option<f64>) -> Self {
+        self.dfpn_epsilon_ppm = epsilon.map(|value| {
+            let scaled = (value * to_f64(DFPN_EPSILON_SCALE)).ceil();
+            format!("{scaled:.0}").parse().unwrap_or(u64::MAX)
+        });
+        self
+    }
+    #[inline]
+    pub const fn dfpn_epsilon_ppm(&self) -> Option<u64> {
+        self.dfpn_epsilon_ppm
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_effort_ordering(mut self, effort_ordering: EffortOrdering) -> Self {
+        self.effort_ordering = effort_ordering;
+        self
+    }
+    #[inline]
+    pub const fn effort_ordering(&self) -> bool {
+        matches!(self.effort_ordering, EffortOrdering::FewestExpansions)
+    }
+    #[inline]
+    #[must_use]
+    pub const fn with_move_selector(mut self, move_selector: MoveSelector) -> Self {
+        self.move_selector = move_selector;
+        self
+    }
+    #[inline]
+    pub const fn move_selector(&self) -> &MoveSelector {
+        &self.move_selector
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_proof_book(mut self, proof_book: Option<MappedProofBook>) -> Self {
+        self.proof_book = proof_book.map(Arc::new);
+        self
+    }
+    #[inline]
+    pub const fn root_move_filter(&self) -> Option<&RootMoveFilter> {
+        self.root_move_filter.as_ref()
+    }
+    #[inline]
+    pub const fn is_filtered_root(&self, depth: usize) -> bool {
+        depth == 0 && self.root_move_filter.is_some()
+    }
+    #[inline]
+    pub fn apply_root_move_filter(&self, ctx: &ThreadLocalContext, legal_moves: &mut Vec<Coord>) {
+        let Some(filter) = self.root_move_filter.as_ref() else {
+            return;
+        };
+        legal_moves.retain(|&mov| filter.permits(mov));
+        if !legal_moves.is_empty() {
+            return;
+        }
+        let bitboard = &ctx.game_state.position.bitboard;
+        let empty_mask = bitboard.empty_mask();
+        legal_moves.extend(
+            bitboard
+                .iter_bits(&empty_mask)
+                .filter(|&mov| filter.permits(mov)),
+        );
+    }
+    #[inline]
+    pub fn maybe_cross_check(&self, ctx: &ThreadLocalContext, player: u8, legal_moves: &[Coord]) {
+        if self.cross_check_interval == 0 {
+            return;
         }
+        let sample = self.cross_check_counter.fetch_add(1, Ordering::Relaxed);
+        if checked::rem_u64(
+            sample,
+            self.cross_check_interval,
+            "SharedTree::maybe_cross_check",
+        ) != 0
+        {
+            return;
+        }
+        if let Err(err) = reference::verify_move_generation(&ctx.game_state, player, legal_moves) {
+            eprintln!("交叉校验失败: {err}");
+            panic!("交叉校验失败");
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn with_stats_flush_interval(mut self, stats_flush_interval: u32) -> Self {
+        self.stats_flush_interval = stats_flush_interval.max(1);
+        self
     }
     #[inline]
     pub fn is_solved(&self) -> bool {
@@ -72,23 +406,194 @@ impl SharedTree {
     }
     #[inline]
     pub fn stop_requested(&self) -> bool {
-        self.stop_flag.load(Ordering::Acquire)
+        self.cancel.is_cancelled()
+    }
+    #[inline]
+    pub const fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancel
+    }
+    #[inline]
+    pub fn iteration_token(&self) -> CancellationToken {
+        self.iteration_cancel.lock().clone()
+    }
+    #[inline]
+    pub fn begin_iteration(&self) -> CancellationToken {
+        let token = self.cancel.child();
+        *self.iteration_cancel.lock() = token.clone();
+        token
     }
     #[inline]
     pub fn should_stop(&self) -> bool {
-        self.is_solved() || self.stop_requested()
+        self.is_solved()
+            || self.stop_requested()
+            || self.budget.is_exhausted()
+            || self
+                .leader
+                .as_ref()
+                .is_some_and(|leader| leader.should_stop())
+    }
+    #[inline]
+    pub fn check_budget(&self) -> Option<BudgetLimit> {
+        self.relieve_memory_pressure();
+        let nodes = self.stats.nodes_created.load(Ordering::Relaxed);
+        let mut limit = self.budget.check(nodes, || self.estimated_memory_bytes());
+        if limit.is_none()
+            && let Some(time_manager) = self.time_manager.as_ref()
+            && time_manager
+                .lock()
+                .observe(self.root.get_pn(), self.depth_limit())
+                == TimeDecision::Stop
+        {
+            self.budget.record(BudgetLimit::Time);
+            limit = self.budget.limit_reached();
+        }
+        if limit == Some(BudgetLimit::Time) {
+            self.cancel.cancel(CancelReason::Timeout);
+        }
+        limit
+    }
+    fn relieve_memory_pressure(&self) {
+        let Some(soft_limit_bytes) = self.pressure.soft_limit_bytes() else {
+            return;
+        };
+        let estimated_before = self.estimated_memory_bytes();
+        if estimated_before < soft_limit_bytes {
+            return;
+        }
+        let Some(degradation) = self.pressure.claim_next_step() else {
+            return;
+        };
+        match degradation {
+            MemoryDegradation::CapChildren => {}
+            MemoryDegradation::PruneResolved => {
+                self.node_table
+                    .retain(|&(_, depth), node| depth == 0 || !node.is_terminal());
+            }
+            MemoryDegradation::EvictTransposition => {
+                self.transposition_table
+                    .retain(|_, entry| entry.pn == 0 || entry.dn == 0);
+            }
+        }
+        self.pressure.record(DegradationRecord {
+            degradation,
+            soft_limit_bytes,
+            estimated_before,
+            estimated_after: self.estimated_memory_bytes(),
+        });
+    }
+    #[inline]
+    pub fn cap_children(
+        &self,
+        depth: usize,
+        is_or_node: bool,
+        legal_moves: &mut Vec<Coord>,
+    ) -> bool {
+        let cap = self.pressure.max_children().min(self.max_children);
+        if is_or_node && depth > 0 && legal_moves.len() > cap {
+            legal_moves.truncate(cap);
+            return true;
+        }
+        false
+    }
+    #[inline]
+    pub fn split_deferred_children(
+        &self,
+        depth: usize,
+        is_or_node: bool,
+        legal_moves: &mut Vec<Coord>,
+    ) -> Vec<Coord> {
+        if is_or_node && depth > 0 && legal_moves.len() > self.eager_children {
+            return legal_moves.split_off(self.eager_children);
+        }
+        Vec::new()
+    }
+    #[inline]
+    #[must_use]
+    pub fn prunes_resolved(&self) -> bool {
+        self.pressure.prunes_resolved()
+    }
+    #[inline]
+    #[must_use]
+    pub fn degradation_records(&self) -> Vec<DegradationRecord> {
+        self.pressure.records()
+    }
+    #[inline]
+    pub fn set_repetition_history(&self, history: HashSet<PositionKey>) {
+        if self.repetition_history.set(history).is_err() {
+            eprintln!("SharedTree::set_repetition_history 重复设置局面历史");
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn is_repeated_position(&self, hash: CanonicalHash, player: u8) -> bool {
+        self.leader.as_ref().map_or_else(
+            || {
+                self.repetition_history
+                    .get()
+                    .is_some_and(|history| history.contains(&(hash, player)))
+            },
+            |leader| leader.is_repeated_position(hash, player),
+        )
+    }
+    #[inline]
+    pub fn record_proven_time(&self) {
+        if let Some(time_manager) = self.time_manager.as_ref() {
+            time_manager.lock().record_proven(self.root.get_pn());
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn drain_time_decisions(&self) -> Vec<TimeDecisionRecord> {
+        self.time_manager
+            .as_ref()
+            .map_or_else(Vec::new, |time_manager| {
+                time_manager.lock().drain_decisions()
+            })
+    }
+    #[inline]
+    pub fn record_budget_limit(&self, limit: BudgetLimit) {
+        self.budget.record(limit);
+    }
+    #[inline]
+    #[must_use]
+    pub fn limit_reached(&self) -> Option<BudgetLimit> {
+        self.budget.limit_reached()
+    }
+    #[inline]
+    #[must_use]
+    pub fn exceeds_depth_budget(&self, depth: usize) -> bool {
+        self.budget.exceeds_depth(depth)
+    }
+    #[inline]
+    #[must_use]
+    pub fn estimated_memory_bytes(&self) -> u64 {
+        let node_bytes = core::mem::size_of::<ParallelNode>()
+            .saturating_add(core::mem::size_of::<ChildRef>())
+            .saturating_add(core::mem::size_of::<((u64, usize), NodeRef)>())
+            .saturating_add(ARC_HEADER_BYTES);
+        let tt_entry_bytes = core::mem::size_of::<((u64, u8), TTEntry)>();
+        let node_total = checked::usize_to_u64(
+            self.get_node_table_size().saturating_mul(node_bytes),
+            "SharedTree::estimated_memory_bytes::node_total",
+        );
+        let tt_total = checked::usize_to_u64(
+            self.get_tt_size().saturating_mul(tt_entry_bytes),
+            "SharedTree::estimated_memory_bytes::tt_total",
+        );
+        node_total.saturating_add(tt_total)
     }
     #[inline]
     pub fn mark_solved(&self) {
         self.solved.store(true, Ordering::Release);
     }
     #[inline]
-    pub fn increment_iterations(&self) {
-        self.stats.iterations.fetch_add(1, Ordering::Relaxed);
+    pub const fn stats_flush_interval(&self) -> u32 {
+        self.stats_flush_interval
     }
     #[inline]
-    pub fn increment_expansions(&self) {
-        self.stats.expansions.fetch_add(1, Ordering::Relaxed);
+    pub fn flush_stats(&self, ctx: &mut ThreadLocalContext) {
+        let pending = core::mem::take(&mut ctx.pending_stats);
+        self.stats.merge(&pending);
     }
     #[inline]
     #[must_use]
@@ -97,11 +602,6 @@ impl SharedTree {
     }
     #[inline]
     #[must_use]
-    pub const fn stats_session_id(&self) -> u64 {
-        self.stats_session_id
-    }
-    #[inline]
-    #[must_use]
     pub fn depth_limit(&self) -> Option<usize> {
         decode_depth_limit(self.depth_limit.load(Ordering::Acquire))
     }
@@ -127,18 +627,60 @@ impl SharedTree {
         self.node_table.len()
     }
     #[inline]
-    pub fn lookup_tt(&self, hash: u64, player: u8) -> Option<TTEntry> {
-        self.stats.tt_lookups.fetch_add(1, Ordering::Relaxed);
-        let entry = self.transposition_table.get(&(hash, player));
+    pub fn node_table_occupancy(&self) -> ShardOccupancy {
+        self.node_table.occupancy()
+    }
+    pub(crate) fn set_worker_slots(&self, workers: usize) {
+        self.worker_slots.store(workers.max(1), Ordering::Relaxed);
+    }
+    pub(crate) fn owns_node_shard(
+        &self,
+        thread_id: usize,
+        node_key: &(PositionHash, usize),
+    ) -> bool {
+        let workers = self.worker_slots.load(Ordering::Relaxed);
+        self.node_table.shard_owner(node_key, workers)
+            == checked::rem_usize(thread_id, workers, "SharedTree::owns_node_shard")
+    }
+    pub(crate) fn prefault_owned_shards(&self, thread_id: usize) {
+        let workers = self.worker_slots.load(Ordering::Relaxed);
+        self.node_table.reserve_owned(
+            checked::rem_usize(thread_id, workers, "SharedTree::prefault_owned_shards"),
+            workers,
+            OWNED_SHARD_PREFAULT,
+        );
+    }
+    #[inline]
+    pub fn lookup_tt(&self, hash: CanonicalHash, player: u8) -> Option<TTEntry> {
+        checked::atomic_add_u64(
+            &self.stats.tt_lookups,
+            1,
+            Ordering::Relaxed,
+            "SharedTree::stats::tt_lookups",
+        );
+        let entry = self
+            .transposition_table
+            .get(&(hash, player))
+            .or_else(|| self.proof_book.as_ref()?.lookup(hash, player));
         if entry.is_some() {
-            self.stats.tt_hits.fetch_add(1, Ordering::Relaxed);
+            checked::atomic_add_u64(
+                &self.stats.tt_hits,
+                1,
+                Ordering::Relaxed,
+                "SharedTree::stats::tt_hits",
+            );
         }
         entry
     }
     #[inline]
-    pub fn store_tt(&self, hash: u64, player: u8, entry: TTEntry) {
+    pub fn store_tt(&self, hash: CanonicalHash, player: u8, entry: TTEntry) {
         self.transposition_table.insert((hash, player), entry);
-        self.stats.tt_stores.fetch_add(1, Ordering::Relaxed);
+        checked::atomic_add_u64(
+            &self.stats.tt_stores,
+            1,
+            Ordering::Relaxed,
+            "SharedTree::stats::tt_stores",
+        );
     }
 }
 const fn encode_depth_limit(depth_limit: Option<usize>) -> usize {
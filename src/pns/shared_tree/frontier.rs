@@ -2,9 +2,61 @@ use super::{
     super::node::{ChildRef, NodeRef, ParallelNode},
     arena::SharedTree,
 };
+use crate::{checked, game_state::Move};
 use alloc::{collections::VecDeque, sync::Arc};
 use core::sync::atomic::Ordering;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+const EVICTION_SHARDS_PER_REFRESH: usize = 8;
+pub(crate) const DFPN_EPSILON_SCALE: u64 = 1_000_000;
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreakPolicy {
+    #[default]
+    Stable,
+    Random,
+}
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeculationPolicy {
+    #[default]
+    Off,
+    SecondBest,
+}
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EffortOrdering {
+    #[default]
+    Off,
+    FewestExpansions,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DfpnThresholds {
+    pub pn: u64,
+    pub dn: u64,
+}
+impl DfpnThresholds {
+    pub const UNBOUNDED: Self = Self {
+        pn: u64::MAX,
+        dn: u64::MAX,
+    };
+    #[inline]
+    #[must_use]
+    pub fn admits(self, node: &ParallelNode) -> bool {
+        let pn = node.get_pn();
+        let dn = node.get_dn();
+        pn != 0 && dn != 0 && pn < self.pn && dn < self.dn
+    }
+}
+#[derive(Clone, Copy, Default)]
+pub struct FrontierRefresh {
+    pub reopened_leaves: usize,
+    pub skipped_subtrees: usize,
+    pub evicted_nodes: usize,
+}
+fn is_exactly_resolved(node: &ParallelNode) -> bool {
+    !node.is_depth_limited() && (node.get_pn() == 0 || node.get_dn() == 0)
+}
 impl SharedTree {
     fn push_unvisited_children<F>(
         node: &NodeRef,
@@ -13,7 +65,7 @@ impl SharedTree {
     ) where
         F: FnMut(NodeRef),
     {
-        if let Some(children) = node.children.get() {
+        if let Some(children) = node.expanded_children() {
             for child in children {
                 let ptr = Arc::as_ptr(&child.node);
                 if visited.insert(ptr) {
@@ -23,11 +75,12 @@ impl SharedTree {
         }
     }
     #[inline]
-    pub fn increase_depth_limit(&self, new_depth_limit: usize) {
+    pub fn increase_depth_limit(&self, new_depth_limit: usize) -> FrontierRefresh {
+        let mut refresh = FrontierRefresh::default();
         if let Some(current_limit) = self.depth_limit()
             && new_depth_limit <= current_limit
         {
-            return;
+            return refresh;
         }
         self.set_depth_limit(Some(new_depth_limit));
         self.solved.store(false, Ordering::Release);
@@ -36,12 +89,17 @@ impl SharedTree {
         queue.push_back(Arc::clone(&self.root));
         queue_visited.insert(Arc::as_ptr(&self.root));
         while let Some(node) = queue.pop_front() {
+            if is_exactly_resolved(&node) {
+                refresh.skipped_subtrees = refresh.skipped_subtrees.saturating_add(1);
+                continue;
+            }
             node.set_is_depth_limited(node.depth >= new_depth_limit);
             if node.is_depth_cutoff() && node.depth < new_depth_limit {
                 node.set_depth_cutoff(false);
                 node.set_pn(1);
                 node.set_dn(1);
                 node.set_win_len(u64::MAX);
+                refresh.reopened_leaves = refresh.reopened_leaves.saturating_add(1);
             }
             Self::push_unvisited_children(&node, &mut queue_visited, |child| {
                 queue.push_back(child);
@@ -58,33 +116,139 @@ impl SharedTree {
                 continue;
             }
             stack.push((Arc::clone(&node), true));
+            if is_exactly_resolved(&node) {
+                continue;
+            }
             Self::push_unvisited_children(&node, &mut postorder_visited, |child| {
                 stack.push((child, false));
             });
         }
         for node in postorder {
-            self.update_node_pdn(&node);
+            if !is_exactly_resolved(&node) {
+                self.update_node_pdn(&node);
+            }
         }
+        refresh.evicted_nodes = self
+            .node_table
+            .evict_unreferenced_step(self.root.stones, EVICTION_SHARDS_PER_REFRESH);
+        refresh
+    }
+    pub(crate) fn follow_leader(&self, leader: &Self, depth_offset: usize) {
+        self.begin_iteration();
+        if let Some(leader_limit) = leader.depth_limit() {
+            self.increase_depth_limit(checked::add_usize(
+                leader_limit,
+                depth_offset,
+                "SharedTree::follow_leader",
+            ));
+        }
+    }
+    pub(crate) fn tie_break_salt(&self, thread_id: usize) -> Option<u64> {
+        let thread_bits = checked::usize_to_u64(thread_id, "SharedTree::tie_break_salt");
+        self.tie_break_seed()
+            .map(|seed| mix_tie_break(seed, thread_bits))
     }
     #[inline]
-    pub fn select_best_child(node: &NodeRef) -> Option<ChildRef> {
-        let children = node.children.get()?;
+    pub fn select_best_child(node: &NodeRef, ordering: (Option<u64>, bool)) -> Option<ChildRef> {
         let is_or_node = node.is_or_node();
-        children
-            .iter()
-            .min_by_key(|child_ref| {
-                if is_or_node {
-                    (
-                        child_ref.node.get_effective_pn(),
-                        child_ref.node.get_win_len(),
-                    )
-                } else {
-                    (
-                        child_ref.node.get_effective_dn(),
-                        child_ref.node.get_win_len(),
-                    )
-                }
+        node.expanded_children()?
+            .min_by_key(|child_ref| child_priority(is_or_node, ordering, child_ref))
+            .cloned()
+    }
+    pub(crate) fn dfpn_child_thresholds(
+        node: &NodeRef,
+        child: &ChildRef,
+        thresholds: DfpnThresholds,
+        epsilon_ppm: u64,
+    ) -> DfpnThresholds {
+        let is_or_node = node.is_or_node();
+        let sibling_number = node
+            .expanded_children()
+            .and_then(|children| {
+                children
+                    .filter(|sibling| sibling.mov != child.mov)
+                    .map(|sibling| {
+                        if is_or_node {
+                            sibling.node.get_effective_pn()
+                        } else {
+                            sibling.node.get_effective_dn()
+                        }
+                    })
+                    .min()
+            })
+            .unwrap_or(u64::MAX);
+        let bound = epsilon_bound(sibling_number, epsilon_ppm);
+        if is_or_node {
+            DfpnThresholds {
+                pn: thresholds.pn.min(bound),
+                dn: shift_threshold(thresholds.dn, node.get_dn(), child.node.get_dn()),
+            }
+        } else {
+            DfpnThresholds {
+                pn: shift_threshold(thresholds.pn, node.get_pn(), child.node.get_pn()),
+                dn: thresholds.dn.min(bound),
+            }
+        }
+    }
+    pub(crate) fn select_runner_up_child(
+        node: &NodeRef,
+        ordering: (Option<u64>, bool),
+        best_move: Move,
+    ) -> Option<ChildRef> {
+        let is_or_node = node.is_or_node();
+        node.expanded_children()?
+            .filter(|child_ref| {
+                child_ref.mov != best_move
+                    && !child_ref.node.is_terminal()
+                    && !child_ref.node.is_in_flight()
             })
+            .min_by_key(|child_ref| child_priority(is_or_node, ordering, child_ref))
             .cloned()
     }
 }
+fn child_priority(
+    is_or_node: bool,
+    (tie_break_salt, by_effort): (Option<u64>, bool),
+    child_ref: &ChildRef,
+) -> (u64, u64, u64, u64) {
+    let tie_break = tie_break_salt.map_or(0, |salt| tie_break_key(salt, child_ref.mov.coord()));
+    let number = if is_or_node {
+        child_ref.node.get_effective_pn()
+    } else {
+        child_ref.node.get_effective_dn()
+    };
+    let effort = if by_effort {
+        child_ref.node.get_effort()
+    } else {
+        0
+    };
+    (number, effort, child_ref.node.get_win_len(), tie_break)
+}
+fn epsilon_bound(sibling_number: u64, epsilon_ppm: u64) -> u64 {
+    if sibling_number == u64::MAX {
+        return u64::MAX;
+    }
+    let margin = sibling_number
+        .saturating_mul(epsilon_ppm)
+        .div_ceil(DFPN_EPSILON_SCALE);
+    sibling_number.saturating_add(margin.max(1))
+}
+const fn shift_threshold(threshold: u64, parent_number: u64, child_number: u64) -> u64 {
+    if threshold == u64::MAX {
+        return u64::MAX;
+    }
+    threshold
+        .saturating_sub(parent_number)
+        .saturating_add(child_number)
+}
+pub(super) const fn mix_tie_break(seed: u64, value: u64) -> u64 {
+    let mut mixed = seed ^ value.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    mixed ^ (mixed >> 31)
+}
+fn tie_break_key(salt: u64, (row, column): (usize, usize)) -> u64 {
+    let row_bits = checked::usize_to_u64(row, "tie_break_key::row");
+    let column_bits = checked::usize_to_u64(column, "tie_break_key::column");
+    mix_tie_break(salt, (row_bits << 32) | column_bits)
+}
@@ -6,7 +6,12 @@ use super::{
     },
     arena::SharedTree,
 };
-use crate::{alloc_stats::AllocTrackingGuard, checked, utils::duration_to_ns};
+use crate::{
+    alloc_stats::{AllocScope, AllocTrackingGuard},
+    checked,
+    game_state::{Move, PositionHash},
+    utils::timing::{add_elapsed_ns, add_elapsed_ns_atomic, elapsed_ns},
+};
 use alloc::sync::Arc;
 use core::sync::atomic::Ordering;
 use std::time::Instant;
@@ -24,38 +29,112 @@ impl SharedTree {
             if !node.try_mark_depth_cutoff() {
                 return false;
             }
-            self.stats.depth_cutoffs.fetch_add(1, Ordering::Relaxed);
             node.set_is_depth_limited(true);
             node.set_pn(u64::MAX);
             node.set_dn(u64::MAX);
             node.set_win_len(u64::MAX);
-            self.stats
-                .expand_time_ns
-                .fetch_add(duration_to_ns(expand_start.elapsed()), Ordering::Relaxed);
+            ctx.pending_stats.add_assign(&TreeStatsAccumulator {
+                depth_cutoffs: 1,
+                expand_time_ns: elapsed_ns(expand_start),
+                ..TreeStatsAccumulator::default()
+            });
             return true;
         }
         let player = node.player;
         let depth = node.depth;
         let is_or_node = node.is_or_node();
+        let hot_path = self.allocation_audit.begin();
         let move_gen_timing = ctx.refresh_legal_moves(player);
-        self.stats
-            .move_gen_candidates_time_ns
-            .fetch_add(move_gen_timing.candidate_gen_ns, Ordering::Relaxed);
-        self.stats
-            .move_gen_scoring_time_ns
-            .fetch_add(move_gen_timing.scoring_ns, Ordering::Relaxed);
-        let legal_moves = core::mem::take(&mut ctx.legal_moves);
+        self.allocation_audit
+            .finish_move_generation(hot_path, player);
+        let mut legal_moves = core::mem::take(&mut ctx.scratch.legal_moves);
+        self.maybe_cross_check(ctx, player, &legal_moves);
+        if self.is_filtered_root(depth) {
+            self.apply_root_move_filter(ctx, &mut legal_moves);
+        }
+        if self.cap_children(depth, is_or_node, &mut legal_moves) {
+            node.set_truncated(true);
+        }
+        let deferred_moves = self.split_deferred_children(depth, is_or_node, &mut legal_moves);
         let legal_moves_len = legal_moves.len();
-        let mut children = Vec::with_capacity(legal_moves_len);
+        let mut local_stats = TreeStatsAccumulator {
+            move_gen_candidates_time_ns: move_gen_timing.candidate_gen_ns,
+            move_gen_scoring_time_ns: move_gen_timing.scoring_ns,
+            ..TreeStatsAccumulator::default()
+        };
+        let children = self.build_children(ctx, player, depth, &legal_moves, &mut local_stats);
+        ctx.scratch.legal_moves = legal_moves;
+        let early_cutoff = children.len() < legal_moves_len;
+        let children_len =
+            checked::usize_to_u64(children.len(), "SharedTree::expand_node::children_len");
+        if !early_cutoff
+            && !deferred_moves.is_empty()
+            && node
+                .deferred_moves
+                .set(deferred_moves.into_boxed_slice())
+                .is_err()
+        {
+            return false;
+        }
+        if node.children.set(children).is_err() {
+            return false;
+        }
+        local_stats.expansions = 1;
+        local_stats.early_cutoffs = u64::from(early_cutoff);
+        local_stats.children_generated = children_len;
+        local_stats.expand_time_ns = elapsed_ns(expand_start);
+        ctx.pending_stats.add_assign(&local_stats);
+        true
+    }
+    #[inline]
+    pub fn expand_deferred(&self, node: &NodeRef, ctx: &mut ThreadLocalContext) -> bool {
+        let Some(deferred_moves) = node.deferred_moves.get() else {
+            return false;
+        };
+        if node.deferred_children.get().is_some() {
+            return false;
+        }
+        let expand_start = Instant::now();
+        let _alloc_guard = AllocTrackingGuard::new();
         let mut local_stats = TreeStatsAccumulator::default();
-        for &mov in &legal_moves {
+        let children = self.build_children(
+            ctx,
+            node.player,
+            node.depth,
+            deferred_moves,
+            &mut local_stats,
+        );
+        let children_len =
+            checked::usize_to_u64(children.len(), "SharedTree::expand_deferred::children_len");
+        if node.deferred_children.set(children).is_err() {
+            return false;
+        }
+        local_stats.deferred_expansions = 1;
+        local_stats.children_generated = children_len;
+        local_stats.expand_time_ns = elapsed_ns(expand_start);
+        ctx.pending_stats.add_assign(&local_stats);
+        true
+    }
+    fn build_children(
+        &self,
+        ctx: &mut ThreadLocalContext,
+        player: u8,
+        depth: usize,
+        moves: &[(usize, usize)],
+        local_stats: &mut TreeStatsAccumulator,
+    ) -> Vec<ChildRef> {
+        let is_or_node = player == self.root.player;
+        let mut children = Vec::with_capacity(moves.len());
+        for &mov in moves {
+            let hot_path = self.allocation_audit.begin();
+            let mut setup_events = 0_u64;
             let move_timing = ctx.make_move_with_timing(mov, player);
             local_stats.add_move_apply_timing(&move_timing);
             let pos_hash_start = Instant::now();
             let child_pos_hash = ctx.get_hash();
-            local_stats.hash_time_ns = checked::add_u64(
-                local_stats.hash_time_ns,
-                duration_to_ns(pos_hash_start.elapsed()),
+            add_elapsed_ns(
+                &mut local_stats.hash_time_ns,
+                pos_hash_start,
                 "SharedTree::expand_node::hash_time_ns",
             );
             let child_depth = checked::add_usize(depth, 1_usize, "SharedTree::expand_node::depth");
@@ -67,21 +146,29 @@ impl SharedTree {
                     1_u64,
                     "SharedTree::expand_node::node_table_lookups",
                 );
-                let child =
-                    self.get_or_create_child(ctx, node_key, player, depth, is_depth_limited);
-                ctx.cache_node(node_key, Arc::clone(&child));
+                let (child, setup) = AllocScope::measure(|| {
+                    let child =
+                        self.get_or_create_child(ctx, node_key, player, depth, is_depth_limited);
+                    ctx.cache_node(node_key, Arc::clone(&child));
+                    child
+                });
+                setup_events = setup.heap_events();
                 child
             });
             let undo_start = Instant::now();
             ctx.undo_move(mov, player);
-            local_stats.move_undo_time_ns = checked::add_u64(
-                local_stats.move_undo_time_ns,
-                duration_to_ns(undo_start.elapsed()),
+            add_elapsed_ns(
+                &mut local_stats.move_undo_time_ns,
+                undo_start,
                 "SharedTree::expand_node::move_undo_time_ns",
             );
             let proof_number = child.get_pn();
             let disproof_number = child.get_dn();
-            children.push(ChildRef { node: child, mov });
+            children.push(ChildRef {
+                node: child,
+                mov: Move::pack(mov),
+            });
+            self.allocation_audit.finish(hot_path, setup_events, mov);
             if is_or_node {
                 if proof_number == 0 {
                     break;
@@ -90,64 +177,73 @@ impl SharedTree {
                 break;
             }
         }
-        ctx.legal_moves = legal_moves;
-        let early_cutoff = children.len() < legal_moves_len;
-        let children_len =
-            checked::usize_to_u64(children.len(), "SharedTree::expand_node::children_len");
-        if node.children.set(children).is_err() {
-            return false;
-        }
-        self.stats.merge(&local_stats);
-        self.increment_expansions();
-        if early_cutoff {
-            self.stats.early_cutoffs.fetch_add(1, Ordering::Relaxed);
-        }
-        self.stats
-            .children_generated
-            .fetch_add(children_len, Ordering::Relaxed);
-        self.stats
-            .expand_time_ns
-            .fetch_add(duration_to_ns(expand_start.elapsed()), Ordering::Relaxed);
-        true
+        children
     }
     fn get_or_create_child(
         &self,
         ctx: &ThreadLocalContext,
-        node_key: (u64, usize),
+        node_key: (PositionHash, usize),
         player: u8,
         depth: usize,
         is_depth_limited: bool,
     ) -> Arc<ParallelNode> {
         let lookup_start = Instant::now();
         let existing_child = self.node_table.get(&node_key);
-        self.stats
-            .node_table_lookup_time_ns
-            .fetch_add(duration_to_ns(lookup_start.elapsed()), Ordering::Relaxed);
+        add_elapsed_ns_atomic(&self.stats.node_table_lookup_time_ns, lookup_start);
         existing_child.map_or_else(
             || {
+                let child_depth =
+                    checked::add_usize(depth, 1_usize, "SharedTree::get_or_create_child::depth");
                 let child_hash_start = Instant::now();
                 let child_hash = ctx.get_canonical_hash();
-                self.stats.hash_time_ns.fetch_add(
-                    duration_to_ns(child_hash_start.elapsed()),
-                    Ordering::Relaxed,
-                );
+                add_elapsed_ns_atomic(&self.stats.hash_time_ns, child_hash_start);
                 let child = Arc::new(ParallelNode::new(
                     checked::opponent_player(player, "SharedTree::get_or_create_child"),
-                    checked::add_usize(depth, 1_usize, "SharedTree::get_or_create_child::depth"),
+                    self.root.player,
+                    (
+                        child_depth,
+                        checked::add_usize(
+                            self.root.stones,
+                            child_depth,
+                            "SharedTree::get_or_create_child::stones",
+                        ),
+                    ),
                     child_hash,
                     is_depth_limited,
                 ));
                 self.evaluate_node(&child, ctx);
+                if self.prunes_resolved() && child.is_terminal() {
+                    checked::atomic_add_u64(
+                        &self.stats.nodes_created,
+                        1,
+                        Ordering::Relaxed,
+                        "SharedTree::stats::nodes_created",
+                    );
+                    return child;
+                }
+                if self.owns_node_shard(ctx.thread_id, &node_key) {
+                    self.stats
+                        .node_table_local_creates
+                        .fetch_add(1, Ordering::Relaxed);
+                }
                 let insert_start = Instant::now();
                 self.node_table.insert(node_key, Arc::clone(&child));
-                self.stats
-                    .node_table_write_time_ns
-                    .fetch_add(duration_to_ns(insert_start.elapsed()), Ordering::Relaxed);
-                self.stats.nodes_created.fetch_add(1, Ordering::Relaxed);
+                add_elapsed_ns_atomic(&self.stats.node_table_write_time_ns, insert_start);
+                checked::atomic_add_u64(
+                    &self.stats.nodes_created,
+                    1,
+                    Ordering::Relaxed,
+                    "SharedTree::stats::nodes_created",
+                );
                 child
             },
             |child| {
-                self.stats.node_table_hits.fetch_add(1, Ordering::Relaxed);
+                checked::atomic_add_u64(
+                    &self.stats.node_table_hits,
+                    1,
+                    Ordering::Relaxed,
+                    "SharedTree::stats::node_table_hits",
+                );
                 child
             },
         )
@@ -0,0 +1,88 @@
+use super::{NodeTable, SharedTree, TranspositionTable};
+use crate::{
+    checked,
+    game_state::{Coord, GameState, GomokuRules},
+    pns::{TTEntry, node::ParallelNode},
+};
+fn probe_position(
+    transposition_table: &TranspositionTable,
+    node_table: &NodeTable,
+    state: &GameState,
+    depth: usize,
+    player: u8,
+) -> Option<TTEntry> {
+    node_table
+        .get(&(state.position.get_hash(), depth))
+        .map(|node| TTEntry {
+            pn: node.get_pn(),
+            dn: node.get_dn(),
+            win_len: node.get_win_len(),
+        })
+        .or_else(|| transposition_table.get(&(state.position.get_canonical_hash(), player)))
+}
+pub(crate) fn probe_line(
+    transposition_table: &TranspositionTable,
+    node_table: &NodeTable,
+    root: &GameState,
+    root_player: u8,
+    line: &[Coord],
+) -> Vec<Option<TTEntry>> {
+    let mut state = root.clone();
+    let mut player = root_player;
+    let mut entries = Vec::with_capacity(line.len().saturating_add(1));
+    entries.push(probe_position(
+        transposition_table,
+        node_table,
+        &state,
+        0,
+        player,
+    ));
+    for (ply, &(row, column)) in line.iter().enumerate() {
+        if !state.position.board.is_empty_at((row, column)) {
+            break;
+        }
+        GomokuRules::make_move(
+            &mut state.position,
+            &mut state.move_cache,
+            (row, column),
+            player,
+        );
+        let won = GomokuRules::check_win(&state.position, player);
+        player = checked::opponent_player(player, "probe_line::player");
+        entries.push(probe_position(
+            transposition_table,
+            node_table,
+            &state,
+            checked::add_usize(ply, 1_usize, "probe_line::depth"),
+            player,
+        ));
+        if won {
+            break;
+        }
+    }
+    entries
+}
+impl SharedTree {
+    #[inline]
+    pub fn probe_line(&self, root: &GameState, line: &[Coord]) -> Vec<Option<TTEntry>> {
+        probe_line(
+            &self.transposition_table,
+            &self.node_table,
+            root,
+            self.root.player,
+            line,
+        )
+    }
+    #[inline]
+    pub fn proven_moves(node: &ParallelNode) -> Vec<(Coord, u64)> {
+        let Some(children) = node.expanded_children() else {
+            return Vec::new();
+        };
+        let mut moves: Vec<(Coord, u64)> = children
+            .filter(|child_ref| child_ref.node.get_pn() == 0)
+            .map(|child_ref| (child_ref.mov.coord(), child_ref.node.get_win_len()))
+            .collect();
+        moves.sort_unstable_by_key(|&(mov, win_len)| (win_len, mov));
+        moves
+    }
+}
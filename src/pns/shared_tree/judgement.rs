@@ -2,54 +2,68 @@ use super::{
     super::{context::ThreadLocalContext, node::ParallelNode},
     arena::SharedTree,
 };
-use crate::{checked, utils::duration_to_ns};
+use crate::{checked, utils::timing::AtomicScopedTimer};
 use core::sync::atomic::Ordering;
-use std::time::Instant;
 impl SharedTree {
     #[inline]
     pub fn evaluate_node(&self, node: &ParallelNode, ctx: &ThreadLocalContext) {
-        let start = Instant::now();
-        self.stats.eval_calls.fetch_add(1, Ordering::Relaxed);
-        let tt_entry = self.lookup_tt(node.hash, node.player);
+        let _eval_timer = AtomicScopedTimer::new(&self.stats.eval_time_ns);
+        checked::atomic_add_u64(
+            &self.stats.eval_calls,
+            1,
+            Ordering::Relaxed,
+            "SharedTree::stats::eval_calls",
+        );
+        let tt_entry = if self.is_filtered_root(node.depth) {
+            None
+        } else {
+            self.lookup_tt(node.hash, node.player)
+        };
         if let Some(entry) = tt_entry
             && (entry.pn == 0 || entry.dn == 0)
         {
             node.set_pn(entry.pn);
             node.set_dn(entry.dn);
             node.set_win_len(entry.win_len);
-            self.stats
-                .eval_time_ns
-                .fetch_add(duration_to_ns(start.elapsed()), Ordering::Relaxed);
             return;
         }
-        let mut p1_wins = false;
-        let mut p2_wins = false;
+        let attacker = node.attacker;
+        let defender = checked::opponent_player(attacker, "SharedTree::evaluate_node::defender");
+        let mut attacker_wins = false;
+        let mut defender_wins = false;
         if node.depth > 0 {
             let opponent = checked::opponent_player(node.player, "SharedTree::evaluate_node");
             if ctx.check_win(opponent) {
-                if opponent == 1 {
-                    p1_wins = true;
+                if opponent == attacker {
+                    attacker_wins = true;
                 } else {
-                    p2_wins = true;
+                    defender_wins = true;
                 }
             }
         } else {
-            if ctx.check_win(1) {
-                p1_wins = true;
+            if ctx.check_win(attacker) {
+                attacker_wins = true;
             }
-            if ctx.check_win(2) {
-                p2_wins = true;
+            if ctx.check_win(defender) {
+                defender_wins = true;
             }
         }
-        if p1_wins {
+        if attacker_wins {
             node.set_proven();
             node.set_win_len(0);
-        } else if p2_wins {
+        } else if defender_wins
+            || (node.depth > 0 && self.is_repeated_position(node.hash, node.player))
+        {
             node.set_disproven();
         } else if let Some(limit) = self.depth_limit()
             && node.depth >= limit
         {
-            self.stats.depth_cutoffs.fetch_add(1, Ordering::Relaxed);
+            checked::atomic_add_u64(
+                &self.stats.depth_cutoffs,
+                1,
+                Ordering::Relaxed,
+                "SharedTree::stats::depth_cutoffs",
+            );
             node.set_depth_cutoff(true);
             node.set_is_depth_limited(true);
             node.set_pn(u64::MAX);
@@ -59,8 +73,5 @@ impl SharedTree {
             node.set_dn(entry.dn);
             node.set_win_len(entry.win_len);
         }
-        self.stats
-            .eval_time_ns
-            .fetch_add(duration_to_ns(start.elapsed()), Ordering::Relaxed);
     }
 }
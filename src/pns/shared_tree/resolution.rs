@@ -43,7 +43,11 @@ impl SharedTree {
         let mut min_proven_win_len = u64::MAX;
         let mut max_proven_win_len = 0_u64;
         let mut all_children_proven = true;
-        for child in children {
+        let pending_deferred = node.pending_deferred();
+        for child in children
+            .iter()
+            .chain(node.deferred_children.get().into_iter().flatten())
+        {
             let cpn = child.node.get_pn();
             let cdn = child.node.get_dn();
             let cwl = child.node.get_win_len();
@@ -59,6 +63,23 @@ impl SharedTree {
             }
         }
         if is_or_node {
+            if pending_deferred > 0 {
+                if dn_sum == 0 {
+                    pn_min = 1;
+                }
+                dn_sum = sum_with_infinity(
+                    dn_sum,
+                    pending_deferred,
+                    "SharedTree::update_node_pdn::deferred_dn_sum",
+                );
+            }
+            if dn_sum == 0 && node.is_truncated() {
+                node.set_pn(u64::MAX);
+                node.set_dn(u64::MAX);
+                node.set_win_len(u64::MAX);
+                self.store_tt_if_changed(node, prev_proof, prev_disproof, prev_win_len);
+                return;
+            }
             node.set_pn(pn_min);
             node.set_dn(dn_sum);
             if min_proven_win_len < u64::MAX {
@@ -92,7 +113,7 @@ impl SharedTree {
         prev_disproof: u64,
         prev_win_len: u64,
     ) {
-        if node.is_depth_limited() {
+        if node.is_depth_limited() || self.is_filtered_root(node.depth) {
             return;
         }
         let pn = node.get_pn();
@@ -107,11 +128,12 @@ impl SharedTree {
         self.store_tt(node.hash, node.player, TTEntry { pn, dn, win_len });
     }
 }
+const MAX_FINITE_NUMBER: u64 = u64::MAX.saturating_sub(1);
 fn sum_with_infinity(left: u64, right: u64, context: &str) -> u64 {
     if left == u64::MAX || right == u64::MAX {
         return u64::MAX;
     }
-    checked::add_u64(left, right, context)
+    checked::saturating_add_u64(left, right, context).min(MAX_FINITE_NUMBER)
 }
 fn next_win_len(current: u64, context: &str) -> u64 {
     checked::add_u64(1_u64, current, context)
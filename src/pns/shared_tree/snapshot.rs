@@ -0,0 +1,212 @@
+use super::super::{
+    MoveSelector,
+    node::{ChildRef, NodeRef},
+};
+use super::SharedTree;
+use crate::game_state::Coord;
+use alloc::sync::Arc;
+use core::time::Duration;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+use std::time::Instant;
+pub struct NodeSummary {
+    player: u8,
+    attacker: u8,
+    depth: usize,
+    pn: u64,
+    dn: u64,
+    win_len: u64,
+    pending_deferred: u64,
+    children: Option<Vec<ChildRef>>,
+}
+impl NodeSummary {
+    fn read(node: &NodeRef) -> Self {
+        Self {
+            player: node.player,
+            attacker: node.attacker,
+            depth: node.depth,
+            pn: node.get_pn(),
+            dn: node.get_dn(),
+            win_len: node.get_win_len(),
+            pending_deferred: node.pending_deferred(),
+            children: node.expanded_children().map(|children| {
+                children
+                    .map(|child_ref| ChildRef {
+                        node: Arc::clone(&child_ref.node),
+                        mov: child_ref.mov,
+                    })
+                    .collect()
+            }),
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn player(&self) -> u8 {
+        self.player
+    }
+    #[inline]
+    #[must_use]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+    #[inline]
+    #[must_use]
+    pub const fn pn(&self) -> u64 {
+        self.pn
+    }
+    #[inline]
+    #[must_use]
+    pub const fn dn(&self) -> u64 {
+        self.dn
+    }
+    #[inline]
+    #[must_use]
+    pub const fn win_len(&self) -> u64 {
+        self.win_len
+    }
+    #[inline]
+    #[must_use]
+    pub const fn pending_deferred(&self) -> u64 {
+        self.pending_deferred
+    }
+    #[inline]
+    #[must_use]
+    pub const fn is_or_node(&self) -> bool {
+        self.player == self.attacker
+    }
+    #[inline]
+    #[must_use]
+    pub const fn is_expanded(&self) -> bool {
+        self.children.is_some()
+    }
+}
+pub struct FrozenChild {
+    pub mov: Coord,
+    pub summary: Arc<NodeSummary>,
+}
+pub struct TreeSnapshot {
+    root: NodeRef,
+    frozen_at: Instant,
+    summaries: Mutex<HashMap<usize, Arc<NodeSummary>>>,
+}
+impl TreeSnapshot {
+    #[inline]
+    #[must_use]
+    pub fn freeze(root: &NodeRef) -> Self {
+        Self {
+            root: Arc::clone(root),
+            frozen_at: Instant::now(),
+            summaries: Mutex::new(HashMap::new()),
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn age(&self) -> Duration {
+        self.frozen_at.elapsed()
+    }
+    #[inline]
+    #[must_use]
+    pub fn summaries_read(&self) -> usize {
+        self.summaries.lock().len()
+    }
+    fn summary(&self, node: &NodeRef) -> Arc<NodeSummary> {
+        let mut summaries = self.summaries.lock();
+        Arc::clone(
+            summaries
+                .entry(Arc::as_ptr(node).addr())
+                .or_insert_with(|| Arc::new(NodeSummary::read(node))),
+        )
+    }
+    #[inline]
+    #[must_use]
+    pub fn root(&self) -> Arc<NodeSummary> {
+        self.summary(&self.root)
+    }
+    #[inline]
+    #[must_use]
+    pub fn children(&self, parent: &NodeSummary) -> Vec<FrozenChild> {
+        parent
+            .children
+            .iter()
+            .flatten()
+            .map(|child_ref| FrozenChild {
+                mov: child_ref.mov.coord(),
+                summary: self.summary(&child_ref.node),
+            })
+            .collect()
+    }
+    #[inline]
+    #[must_use]
+    pub fn child(&self, parent: &NodeSummary, mov: Coord) -> Option<Arc<NodeSummary>> {
+        parent
+            .children
+            .as_ref()?
+            .iter()
+            .find(|child_ref| child_ref.mov.coord() == mov)
+            .map(|child_ref| self.summary(&child_ref.node))
+    }
+    #[inline]
+    #[must_use]
+    pub fn resolve(&self, path: &[Coord]) -> Option<Arc<NodeSummary>> {
+        path.iter()
+            .try_fold(self.root(), |node, &mov| self.child(&node, mov))
+    }
+    #[inline]
+    #[must_use]
+    pub fn proven_moves(&self, parent: &NodeSummary) -> Vec<(Coord, u64)> {
+        self.children(parent)
+            .into_iter()
+            .filter(|child| child.summary.pn == 0)
+            .map(|child| (child.mov, child.summary.win_len))
+            .collect()
+    }
+    #[inline]
+    #[must_use]
+    pub fn principal_child(
+        &self,
+        parent: &NodeSummary,
+        selector: &MoveSelector,
+    ) -> Option<FrozenChild> {
+        let children = self.children(parent);
+        if parent.pn == 0 {
+            let proven = children.into_iter().filter(|child| child.summary.pn == 0);
+            let win_len_of = |child: &FrozenChild| (child.mov, child.summary.win_len);
+            return if parent.is_or_node() {
+                selector.select_min(proven, win_len_of)
+            } else {
+                selector.select_max(proven, win_len_of)
+            };
+        }
+        if parent.is_or_node() {
+            selector.select_min(children, |child| (child.mov, child.summary.pn))
+        } else {
+            selector.select_min(children, |child| (child.mov, child.summary.dn))
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn principal_variation(
+        &self,
+        start: &Arc<NodeSummary>,
+        selector: &MoveSelector,
+        limit: usize,
+    ) -> Vec<Coord> {
+        let mut line = Vec::new();
+        let mut node = Arc::clone(start);
+        while line.len() < limit {
+            let Some(child) = self.principal_child(&node, selector) else {
+                break;
+            };
+            line.push(child.mov);
+            node = child.summary;
+        }
+        line
+    }
+}
+impl SharedTree {
+    #[inline]
+    #[must_use]
+    pub fn freeze_snapshot(&self) -> TreeSnapshot {
+        TreeSnapshot::freeze(&self.root)
+    }
+}
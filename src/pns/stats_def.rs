@@ -8,6 +8,7 @@ use crate::{
 use alloc::vec::Vec;
 use core::sync::atomic::{AtomicU64, Ordering};
 use serde::Serialize;
+pub const DEFAULT_STATS_FLUSH_INTERVAL: u32 = 64;
 pub fn to_f64(value: u64) -> f64 {
     match value.to_string().parse::<f64>() {
         Ok(parsed) => parsed,
@@ -34,5 +35,20 @@ fn atomic_checked_add(target: &AtomicU64, amount: u64, context: &str) {
     }
 }
 macro_rules ! add_move_apply_timing { ($ ($ field : ident => $ stat_field : ident) ,* $ (,) ?) => { pub fn add_move_apply_timing (& mut self , timing : & MoveApplyTiming) { $ (self .$ stat_field = checked_add_u64 (self .$ stat_field , timing .$ field , concat ! ("TreeStatsAccumulator::add_move_apply_timing::" , stringify ! ($ stat_field)) ,) ;) * } } ; }
-macro_rules ! define_metrics { (counts : { $ ($ count_name : ident => $ count_desc : expr) ,* $ (,) ? } timings : { $ ($ timing_name : ident => $ timing_desc : expr) ,* $ (,) ? } timing_log : { $ ($ log_name : ident => ($ log_desc : expr , $ calc : expr)) ,* $ (,) ? }) => { pub struct TreeStatsAtomic { $ (pub $ count_name : AtomicU64 ,) * $ (pub $ timing_name : AtomicU64 ,) * } impl TreeStatsAtomic { # [must_use] pub const fn new () -> Self { Self { $ ($ count_name : AtomicU64 :: new (0_u64) ,) * $ ($ timing_name : AtomicU64 :: new (0_u64) ,) * } } # [must_use] pub fn snapshot (& self) -> TreeStatsSnapshot { TreeStatsSnapshot { $ ($ count_name : self .$ count_name . load (Ordering :: Relaxed) ,) * $ ($ timing_name : self .$ timing_name . load (Ordering :: Relaxed) ,) * } } pub fn merge (& self , acc : & TreeStatsAccumulator) { $ (atomic_checked_add (& self .$ count_name , acc .$ count_name , concat ! ("TreeStatsAtomic::merge::" , stringify ! ($ count_name)) ,) ;) * $ (atomic_checked_add (& self .$ timing_name , acc .$ timing_name , concat ! ("TreeStatsAtomic::merge::" , stringify ! ($ timing_name)) ,) ;) * } } # [derive (Clone , Copy , Default , Serialize)] pub struct TreeStatsSnapshot { $ (pub $ count_name : u64 ,) * $ (pub $ timing_name : u64 ,) * } impl TreeStatsSnapshot { # [must_use] pub fn delta_since (& self , previous : & Self) -> Self { Self { $ ($ count_name : checked_sub_u64 (self .$ count_name , previous .$ count_name , concat ! ("TreeStatsSnapshot::delta_since::" , stringify ! ($ count_name)) ,) ,) * $ ($ timing_name : checked_sub_u64 (self .$ timing_name , previous .$ timing_name , concat ! ("TreeStatsSnapshot::delta_since::" , stringify ! ($ timing_name)) ,) ,) * } } pub fn add_assign (& mut self , other : & Self) { $ (self .$ count_name = checked_add_u64 (self .$ count_name , other .$ count_name , concat ! ("TreeStatsSnapshot::add_assign::" , stringify ! ($ count_name)) ,) ;) * $ (self .$ timing_name = checked_add_u64 (self .$ timing_name , other .$ timing_name , concat ! ("TreeStatsSnapshot::add_assign::" , stringify ! ($ timing_name)) ,) ;) * } # [must_use] pub fn div_round (self , divisor : u64) -> Self { Self { $ ($ count_name : div_round_u64 (self .$ count_name , divisor , concat ! ("TreeStatsSnapshot::div_round::" , stringify ! ($ count_name)) ,) ,) * $ ($ timing_name : div_round_u64 (self .$ timing_name , divisor , concat ! ("TreeStatsSnapshot::div_round::" , stringify ! ($ timing_name)) ,) ,) * } } } # [derive (Default)] pub struct TreeStatsAccumulator { $ (pub $ count_name : u64 ,) * $ (pub $ timing_name : u64 ,) * } impl TreeStatsAccumulator { crate :: for_each_move_apply_timing ! (add_move_apply_timing) ; } pub struct TimingStats { values : Vec < f64 >, } impl TimingStats { # [must_use] pub fn from_snapshot (snapshot : & TreeStatsSnapshot) -> Self { let values = vec ! [$ (($ calc) (snapshot) ,) *] ; Self { values } } pub const fn csv_headers () -> &'static [&'static str] { & [$ ($ log_desc ,) *] } # [must_use] pub fn csv_values (& self) -> & [f64] { & self . values } # [must_use] pub fn sum_us (& self) -> f64 { Self :: csv_headers () . iter () . zip (self . values . iter ()) . filter_map (| (header , value) | { if header . contains ("耗时") { Some (* value) } else { None } }) . sum ::< f64 > () } } } ; }
-define_metrics! { counts : { iterations => "迭代次数" , expansions => "扩展节点数" , children_generated => "生成子节点数" , tt_lookups => "TranspositionTable查找次数" , tt_hits => "TranspositionTable命中次数" , tt_stores => "TranspositionTable写入次数" , eval_calls => "评估调用数" , node_table_lookups => "NodeTable查找次数" , node_table_hits => "NodeTable命中次数" , nodes_created => "NodeTable节点数" , depth_cutoffs => "深度截断数" , early_cutoffs => "提前剪枝数" , } timings : { eval_time_ns => "评估耗时" , expand_time_ns => "扩展耗时" , move_gen_candidates_time_ns => "候选耗时" , move_gen_scoring_time_ns => "评分排序耗时" , board_update_time_ns => "基础棋盘更新耗时" , bitboard_update_time_ns => "位棋盘更新耗时" , threat_index_update_time_ns => "威胁索引更新耗时" , candidate_remove_time_ns => "候选着法移除耗时" , candidate_neighbor_time_ns => "邻居空位计算耗时" , candidate_insert_time_ns => "候选着法更新耗时" , candidate_newly_added_time_ns => "新增候选着法耗时" , candidate_history_time_ns => "候选着法保存耗时" , hash_update_time_ns => "Zobrist哈希更新耗时" , move_undo_time_ns => "撤销耗时" , hash_time_ns => "哈希耗时" , children_lock_time_ns => "子节点锁耗时" , node_table_lookup_time_ns => "NodeTable检索耗时" , node_table_write_time_ns => "NodeTable写入耗时" , } timing_log : { branch => ("平均分支数" , | snapshot : & TreeStatsSnapshot | { if snapshot . expansions > 0_u64 { to_f64 (snapshot . children_generated) / to_f64 (snapshot . expansions) } else { 0.0_f64 } }) , move_gen_candidates_us => ("候选耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . move_gen_candidates_time_ns) }) , move_gen_scoring_us => ("评分排序耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . move_gen_scoring_time_ns) }) , board_update_us => ("基础棋盘状态更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . board_update_time_ns) }) , bitboard_update_us => ("位棋盘更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . bitboard_update_time_ns) }) , threat_index_update_us => ("威胁索引更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . threat_index_update_time_ns) }) , candidate_remove_us => ("候选着法移除耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_remove_time_ns) }) , candidate_neighbor_us => ("邻居空位计算耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_neighbor_time_ns) }) , candidate_insert_us => ("候选着法更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_insert_time_ns) }) , candidate_newly_added_us => ("新增候选着法记录耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_newly_added_time_ns) }) , candidate_history_us => ("候选着法历史保存耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_history_time_ns) }) , hash_update_us => ("Zobrist哈希增量更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . hash_update_time_ns) }) , move_undo_us => ("撤销耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . move_undo_time_ns) }) , hash_us => ("哈希耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . hash_time_ns) }) , node_table_write_us => ("NodeTable写入耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . node_table_write_time_ns) }) , node_table_lookup_us => ("NodeTable检索耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . node_table_lookup_time_ns) }) , eval_us => ("评估耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . eval_time_ns) }) , children_lock_us => ("子节点锁耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . children_lock_time_ns) }) , } }
+macro_rules ! define_metrics { (counts : { $ ($ count_name : ident => $ count_desc : expr) ,* $ (,) ? } timings : { $ ($ timing_name : ident => $ timing_desc : expr) ,* $ (,) ? } timing_log : { $ ($ log_name : ident => ($ log_desc : expr , $ calc : expr)) ,* $ (,) ? }) => { pub struct TreeStatsAtomic { $ (pub $ count_name : AtomicU64 ,) * $ (pub $ timing_name : AtomicU64 ,) * } impl TreeStatsAtomic { # [must_use] pub const fn new () -> Self { Self { $ ($ count_name : AtomicU64 :: new (0_u64) ,) * $ ($ timing_name : AtomicU64 :: new (0_u64) ,) * } } # [must_use] pub fn snapshot (& self) -> TreeStatsSnapshot { TreeStatsSnapshot { $ ($ count_name : self .$ count_name . load (Ordering :: Relaxed) ,) * $ ($ timing_name : self .$ timing_name . load (Ordering :: Relaxed) ,) * } } pub fn merge (& self , acc : & TreeStatsAccumulator) { $ (atomic_checked_add (& self .$ count_name , acc .$ count_name , concat ! ("TreeStatsAtomic::merge::" , stringify ! ($ count_name)) ,) ;) * $ (atomic_checked_add (& self .$ timing_name , acc .$ timing_name , concat ! ("TreeStatsAtomic::merge::" , stringify ! ($ timing_name)) ,) ;) * } } # [derive (Clone , Copy , Default , Serialize)] pub struct TreeStatsSnapshot { $ (pub $ count_name : u64 ,) * $ (pub $ timing_name : u64 ,) * } impl TreeStatsSnapshot { # [must_use] pub fn metrics (& self) -> Vec < (&'static str , &'static str , u64) > { vec ! [$ ((stringify ! ($ count_name) , $ count_desc , self .$ count_name) ,) * $ ((stringify ! ($ timing_name) , $ timing_desc , self .$ timing_name) ,) *] } # [must_use] pub fn delta_since (& self , previous : & Self) -> Self { Self { $ ($ count_name : checked_sub_u64 (self .$ count_name , previous .$ count_name , concat ! ("TreeStatsSnapshot::delta_since::" , stringify ! ($ count_name)) ,) ,) * $ ($ timing_name : checked_sub_u64 (self .$ timing_name , previous .$ timing_name , concat ! ("TreeStatsSnapshot::delta_since::" , stringify ! ($ timing_name)) ,) ,) * } } pub fn add_assign (& mut self , other : & Self) { $ (self .$ count_name = checked_add_u64 (self .$ count_name , other .$ count_name , concat ! ("TreeStatsSnapshot::add_assign::" , stringify ! ($ count_name)) ,) ;) * $ (self .$ timing_name = checked_add_u64 (self .$ timing_name , other .$ timing_name , concat ! ("TreeStatsSnapshot::add_assign::" , stringify ! ($ timing_name)) ,) ;) * } # [must_use] pub fn div_round (self , divisor : u64) -> Self { Self { $ ($ count_name : div_round_u64 (self .$ count_name , divisor , concat ! ("TreeStatsSnapshot::div_round::" , stringify ! ($ count_name)) ,) ,) * $ ($ timing_name : div_round_u64 (self .$ timing_name , divisor , concat ! ("TreeStatsSnapshot::div_round::" , stringify ! ($ timing_name)) ,) ,) * } } } # [derive (Default)] pub struct TreeStatsAccumulator { $ (pub $ count_name : u64 ,) * $ (pub $ timing_name : u64 ,) * } impl TreeStatsAccumulator { crate :: for_each_move_apply_timing ! (add_move_apply_timing) ; pub fn add_assign (& mut self , other : & Self) { $ (self .$ count_name = checked_add_u64 (self .$ count_name , other .$ count_name , concat ! ("TreeStatsAccumulator::add_assign::" , stringify ! ($ count_name)) ,) ;) * $ (self .$ timing_name = checked_add_u64 (self .$ timing_name , other .$ timing_name , concat ! ("TreeStatsAccumulator::add_assign::" , stringify ! ($ timing_name)) ,) ;) * } } pub struct TimingStats { values : Vec < f64 >, } impl TimingStats { # [must_use] pub fn from_snapshot (snapshot : & TreeStatsSnapshot) -> Self { let values = vec ! [$ (($ calc) (snapshot) ,) *] ; Self { values } } pub const fn csv_headers () -> &'static [&'static str] { & [$ ($ log_desc ,) *] } # [must_use] pub fn csv_values (& self) -> & [f64] { & self . values } # [must_use] pub fn sum_us (& self) -> f64 { Self :: csv_headers () . iter () . zip (self . values . iter ()) . filter_map (| (header , value) | { if header . contains ("耗时") { Some (* value) } else { None } }) . sum ::< f64 > () } } } ; }
+define_metrics! { counts : { iterations => "迭代次数" , expansions => "扩展节点数" , children_generated => "生成子节点数" , tt_lookups => "TranspositionTable查找次数" , tt_hits => "TranspositionTable命中次数" , tt_stores => "TranspositionTable写入次数" , eval_calls => "评估调用数" , node_table_lookups => "NodeTable查找次数" , node_table_hits => "NodeTable命中次数" , nodes_created => "NodeTable节点数" , node_table_local_creates => "NodeTable本地分片创建数" , depth_cutoffs => "深度截断数" , early_cutoffs => "提前剪枝数" , deferred_expansions => "延迟展开数" , speculative_expansions => "推测扩展次数" , speculation_hits => "推测扩展命中数" , } timings : { eval_time_ns => "评估耗时" , expand_time_ns => "扩展耗时" , move_gen_candidates_time_ns => "候选耗时" , move_gen_scoring_time_ns => "评分排序耗时" , board_update_time_ns => "基础棋盘更新耗时" , bitboard_update_time_ns => "位棋盘更新耗时" , threat_index_update_time_ns => "威胁索引更新耗时" , candidate_remove_time_ns => "候选着法移除耗时" , candidate_neighbor_time_ns => "邻居空位计算耗时" , candidate_insert_time_ns => "候选着法更新耗时" , candidate_newly_added_time_ns => "新增候选着法耗时" , candidate_history_time_ns => "候选着法保存耗时" , hash_update_time_ns => "Zobrist哈希更新耗时" , move_undo_time_ns => "撤销耗时" , hash_time_ns => "哈希耗时" , children_lock_time_ns => "子节点锁耗时" , node_table_lookup_time_ns => "NodeTable检索耗时" , node_table_write_time_ns => "NodeTable写入耗时" , } timing_log : { branch => ("平均分支数" , | snapshot : & TreeStatsSnapshot | { if snapshot . expansions > 0_u64 { to_f64 (snapshot . children_generated) / to_f64 (snapshot . expansions) } else { 0.0_f64 } }) , move_gen_candidates_us => ("候选耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . move_gen_candidates_time_ns) }) , move_gen_scoring_us => ("评分排序耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . move_gen_scoring_time_ns) }) , board_update_us => ("基础棋盘状态更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . board_update_time_ns) }) , bitboard_update_us => ("位棋盘更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . bitboard_update_time_ns) }) , threat_index_update_us => ("威胁索引更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . threat_index_update_time_ns) }) , candidate_remove_us => ("候选着法移除耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_remove_time_ns) }) , candidate_neighbor_us => ("邻居空位计算耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_neighbor_time_ns) }) , candidate_insert_us => ("候选着法更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_insert_time_ns) }) , candidate_newly_added_us => ("新增候选着法记录耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_newly_added_time_ns) }) , candidate_history_us => ("候选着法历史保存耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . candidate_history_time_ns) }) , hash_update_us => ("Zobrist哈希增量更新耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . hash_update_time_ns) }) , move_undo_us => ("撤销耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . move_undo_time_ns) }) , hash_us => ("哈希耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . hash_time_ns) }) , node_table_write_us => ("NodeTable写入耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . node_table_write_time_ns) }) , node_table_lookup_us => ("NodeTable检索耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . node_table_lookup_time_ns) }) , eval_us => ("评估耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . eval_time_ns) }) , children_lock_us => ("子节点锁耗时" , | snapshot : & TreeStatsSnapshot | { total_us (snapshot . children_lock_time_ns) }) , } }
+#[derive(Clone, Copy, Serialize)]
+pub struct TimingEntry {
+    pub label: &'static str,
+    pub value: f64,
+}
+impl TimingStats {
+    #[must_use]
+    pub fn entries(&self) -> Vec<TimingEntry> {
+        Self::csv_headers()
+            .iter()
+            .zip(self.csv_values().iter())
+            .map(|(&label, &value)| TimingEntry { label, value })
+            .collect()
+    }
+}
@@ -0,0 +1,144 @@
+use core::time::Duration;
+use std::time::Instant;
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+const SAFETY_MARGIN_DIVISOR: u32 = 20;
+const HARD_LIMIT_FACTOR: u32 = 4;
+const MAX_EXTENSIONS: u32 = 2;
+const PN_DROP_FACTOR: u64 = 2;
+#[derive(Clone, Copy)]
+pub struct TimeControl {
+    pub remaining: Duration,
+    pub increment: Duration,
+    pub moves_to_go: Option<u32>,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TimeDecision {
+    Allocate,
+    Continue,
+    Extend,
+    Stop,
+    ProvenCutoff,
+}
+impl TimeDecision {
+    #[inline]
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Allocate => "分配",
+            Self::Continue => "继续",
+            Self::Extend => "延长",
+            Self::Stop => "停止",
+            Self::ProvenCutoff => "已证明，提前结束",
+        }
+    }
+}
+#[derive(Clone, Copy)]
+pub struct TimeDecisionRecord {
+    pub decision: TimeDecision,
+    pub elapsed: Duration,
+    pub soft_limit: Duration,
+    pub hard_limit: Duration,
+    pub root_pn: u64,
+}
+pub struct TimeManager {
+    start: Instant,
+    soft_limit: Duration,
+    hard_limit: Duration,
+    checkpoint: Option<(Option<usize>, u64)>,
+    extensions: u32,
+    decisions: Vec<TimeDecisionRecord>,
+}
+impl TimeManager {
+    #[inline]
+    #[must_use]
+    pub fn new(control: TimeControl) -> Self {
+        let moves_to_go = control.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+        let reserve = control
+            .remaining
+            .checked_div(SAFETY_MARGIN_DIVISOR)
+            .unwrap_or_default();
+        let usable = control.remaining.saturating_sub(reserve);
+        let increment_share = control
+            .increment
+            .saturating_mul(3)
+            .checked_div(4)
+            .unwrap_or_default();
+        let soft_limit = usable
+            .checked_div(moves_to_go)
+            .unwrap_or_default()
+            .saturating_add(increment_share)
+            .min(usable);
+        let hard_limit = soft_limit.saturating_mul(HARD_LIMIT_FACTOR).min(usable);
+        let mut manager = Self {
+            start: Instant::now(),
+            soft_limit,
+            hard_limit,
+            checkpoint: None,
+            extensions: 0,
+            decisions: Vec::new(),
+        };
+        manager.record(TimeDecision::Allocate, Duration::ZERO, u64::MAX);
+        manager
+    }
+    #[inline]
+    #[must_use]
+    pub const fn soft_limit(&self) -> Duration {
+        self.soft_limit
+    }
+    #[inline]
+    #[must_use]
+    pub const fn hard_limit(&self) -> Duration {
+        self.hard_limit
+    }
+    #[inline]
+    pub fn observe(&mut self, root_pn: u64, depth: Option<usize>) -> TimeDecision {
+        let elapsed = self.start.elapsed();
+        if elapsed >= self.hard_limit {
+            self.record(TimeDecision::Stop, elapsed, root_pn);
+            return TimeDecision::Stop;
+        }
+        if self
+            .checkpoint
+            .is_some_and(|(checkpoint_depth, _)| checkpoint_depth != depth)
+        {
+            self.checkpoint = None;
+        }
+        if elapsed < self.soft_limit {
+            let midpoint = self.soft_limit.checked_div(2).unwrap_or_default();
+            if self.checkpoint.is_none() && elapsed >= midpoint {
+                self.checkpoint = Some((depth, root_pn));
+            }
+            return TimeDecision::Continue;
+        }
+        let plummeting = self.checkpoint.is_some_and(|(_, checkpoint_pn)| {
+            root_pn != u64::MAX && root_pn.saturating_mul(PN_DROP_FACTOR) <= checkpoint_pn
+        });
+        if plummeting && self.extensions < MAX_EXTENSIONS {
+            self.extensions = self.extensions.saturating_add(1);
+            self.soft_limit = self.soft_limit.saturating_mul(2).min(self.hard_limit);
+            self.checkpoint = Some((depth, root_pn));
+            self.record(TimeDecision::Extend, elapsed, root_pn);
+            return TimeDecision::Extend;
+        }
+        self.record(TimeDecision::Stop, elapsed, root_pn);
+        TimeDecision::Stop
+    }
+    #[inline]
+    pub fn record_proven(&mut self, root_pn: u64) {
+        let elapsed = self.start.elapsed();
+        self.record(TimeDecision::ProvenCutoff, elapsed, root_pn);
+    }
+    #[inline]
+    pub fn drain_decisions(&mut self) -> Vec<TimeDecisionRecord> {
+        core::mem::take(&mut self.decisions)
+    }
+    fn record(&mut self, decision: TimeDecision, elapsed: Duration, root_pn: u64) {
+        self.decisions.push(TimeDecisionRecord {
+            decision,
+            elapsed,
+            soft_limit: self.soft_limit,
+            hard_limit: self.hard_limit,
+            root_pn,
+        });
+    }
+}
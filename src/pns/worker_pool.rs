@@ -1,12 +1,36 @@
-use super::{SharedTree, context::ThreadLocalContext, node::Worker};
+use super::{SearchHandle, SharedTree, context::ThreadLocalContext, node::Worker};
 use crate::{alloc_stats::AllocTrackingGuard, checked, game_state::GameState};
 use alloc::{sync::Arc, vec::Vec};
-use core::panic::AssertUnwindSafe;
+use core::{
+    panic::AssertUnwindSafe,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use parking_lot::Mutex as WorkerMutex;
+use serde::{Deserialize, Serialize};
 use std::{
     panic,
     sync::{Condvar, Mutex, MutexGuard},
     thread::{self, JoinHandle},
 };
+const RESIZE_POLL_INTERVAL: Duration = Duration::from_millis(20);
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParallelMode {
+    #[default]
+    SharedTree,
+    LazySmp,
+}
+impl ParallelMode {
+    #[inline]
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::SharedTree => "共享搜索树",
+            Self::LazySmp => "Lazy SMP（仅共享置换表）",
+        }
+    }
+}
 struct WorkerPoolState {
     generation: u64,
     active_workers: usize,
@@ -50,15 +74,35 @@ impl WorkerPoolSync {
             Err(err) => err.into_inner(),
         }
     }
-    fn mark_ready(&self) {
+    fn wait_timeout<'guard>(
+        condvar: &Condvar,
+        guard: MutexGuard<'guard, WorkerPoolState>,
+        timeout: Duration,
+    ) -> MutexGuard<'guard, WorkerPoolState> {
+        match condvar.wait_timeout(guard, timeout) {
+            Ok((waited_guard, _)) => waited_guard,
+            Err(err) => err.into_inner().0,
+        }
+    }
+    fn mark_ready(&self, observed_generation: &mut u64) -> bool {
         let mut state = self.lock_state();
         state.ready_workers = checked::add_usize(
             state.ready_workers,
             1_usize,
             "WorkerPoolSync::mark_ready::ready_workers",
         );
+        *observed_generation = state.generation;
+        let joins_running_round = state.active_workers > 0 && !state.shutdown;
+        if joins_running_round {
+            state.active_workers = checked::add_usize(
+                state.active_workers,
+                1_usize,
+                "WorkerPoolSync::mark_ready::active_workers",
+            );
+        }
         drop(state);
-        self.ready_condvar.notify_one();
+        self.ready_condvar.notify_all();
+        joins_running_round
     }
     fn wait_until_ready(&self, expected_workers: usize) -> Result<(), ()> {
         let mut state = self.lock_state();
@@ -67,18 +111,26 @@ impl WorkerPoolSync {
         }
         if state.worker_failed { Err(()) } else { Ok(()) }
     }
-    fn wait_for_round(&self, observed_generation: &mut u64) -> bool {
+    fn wait_for_round(&self, observed_generation: &mut u64, retire: &AtomicBool) -> bool {
         let mut state = self.lock_state();
-        while !state.shutdown && state.generation == *observed_generation {
+        while !state.shutdown
+            && state.generation == *observed_generation
+            && !retire.load(Ordering::Acquire)
+        {
             state = Self::wait(&self.round_condvar, state);
         }
-        if state.shutdown {
+        if state.shutdown || retire.load(Ordering::Acquire) {
             return false;
         }
         *observed_generation = state.generation;
         true
     }
-    fn begin_round_and_wait(&self, worker_count: usize) {
+    fn wake_idle_workers(&self) {
+        let state = self.lock_state();
+        drop(state);
+        self.round_condvar.notify_all();
+    }
+    fn begin_round(&self, worker_count: usize) {
         let mut state = self.lock_state();
         if state.worker_failed {
             eprintln!("工作线程池已失效，无法继续搜索。");
@@ -94,14 +146,26 @@ impl WorkerPoolSync {
             "WorkerPoolSync::begin_round_and_wait::generation",
         );
         state.active_workers = worker_count;
+        drop(state);
         self.round_condvar.notify_all();
+    }
+    fn wait_for_idle(&self, poll_interval: Option<Duration>) -> bool {
+        let mut state = self.lock_state();
         while state.active_workers > 0 && !state.worker_failed {
-            state = Self::wait(&self.idle_condvar, state);
+            let Some(timeout) = poll_interval else {
+                state = Self::wait(&self.idle_condvar, state);
+                continue;
+            };
+            state = Self::wait_timeout(&self.idle_condvar, state, timeout);
+            if state.active_workers > 0 {
+                break;
+            }
         }
         if state.worker_failed {
             eprintln!("工作线程在搜索过程中异常退出。");
             panic!("工作线程在搜索过程中异常退出");
         }
+        state.active_workers == 0
     }
     fn finish_round(&self, tree: &SharedTree, round_panicking: bool) {
         let mut state = self.lock_state();
@@ -161,46 +225,180 @@ impl Drop for WorkerRoundGuard {
         self.sync.finish_round(&self.tree, thread::panicking());
     }
 }
+struct PoolWorker {
+    handle: JoinHandle<()>,
+    retire: Arc<AtomicBool>,
+    helper: Option<Arc<SharedTree>>,
+}
+struct WorkerSet {
+    active: Vec<PoolWorker>,
+    retired: Vec<JoinHandle<()>>,
+    spawned: usize,
+}
 pub(crate) struct WorkerPool {
     tree: Arc<SharedTree>,
     sync: Arc<WorkerPoolSync>,
-    handles: Vec<JoinHandle<()>>,
+    game_state: GameState,
+    workers: WorkerMutex<WorkerSet>,
+    inline_worker: Option<WorkerMutex<Worker>>,
+    search_handle: Option<SearchHandle>,
+    parallel_mode: ParallelMode,
 }
 impl WorkerPool {
-    pub(crate) fn new(tree: Arc<SharedTree>, game_state: &GameState, num_threads: usize) -> Self {
-        let sync = Arc::new(WorkerPoolSync::new());
-        let mut handles = Vec::with_capacity(num_threads);
-        for thread_id in 0..num_threads {
-            let cloned_tree = Arc::clone(&tree);
-            let cloned_sync = Arc::clone(&sync);
-            let worker_game_state = (*game_state).clone();
-            handles.push(thread::spawn(move || {
-                run_worker_thread(&cloned_tree, &worker_game_state, thread_id, &cloned_sync);
-            }));
-        }
-        let mut pool = Self {
+    pub(crate) fn new(
+        tree: Arc<SharedTree>,
+        game_state: &GameState,
+        (num_threads, parallel_mode): (usize, ParallelMode),
+        search_handle: Option<SearchHandle>,
+    ) -> Self {
+        let inline_worker = (num_threads <= 1 && search_handle.is_none()).then(|| {
+            let ctx = {
+                let _alloc_guard = AllocTrackingGuard::new();
+                ThreadLocalContext::new((*game_state).clone(), 0)
+            };
+            WorkerMutex::new(Worker::new(Arc::clone(&tree), ctx))
+        });
+        let pool = Self {
             tree,
-            sync,
-            handles,
+            sync: Arc::new(WorkerPoolSync::new()),
+            game_state: (*game_state).clone(),
+            workers: WorkerMutex::new(WorkerSet {
+                active: Vec::with_capacity(num_threads),
+                retired: Vec::new(),
+                spawned: 0,
+            }),
+            inline_worker,
+            search_handle,
+            parallel_mode,
         };
-        if pool.sync.wait_until_ready(num_threads).is_err() {
-            pool.shutdown_and_join();
-            eprintln!("工作线程池初始化失败。");
-            panic!("工作线程池初始化失败");
+        if pool.inline_worker.is_none() {
+            pool.resize(num_threads.max(1));
         }
         pool
     }
     pub(crate) fn run_and_wait(&self) {
-        self.sync.begin_round_and_wait(self.handles.len());
+        if let Some(inline_worker) = self.inline_worker.as_ref() {
+            if self.sync.lock_state().worker_failed {
+                eprintln!("工作线程池已失效，无法继续搜索。");
+                panic!("工作线程池已失效");
+            }
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let _alloc_guard = AllocTrackingGuard::new();
+                inline_worker.lock().run();
+            }));
+            if result.is_err() {
+                self.sync.mark_thread_failure(&self.tree);
+                eprintln!("工作线程在搜索过程中异常退出。");
+                panic!("工作线程在搜索过程中异常退出");
+            }
+            return;
+        }
+        self.apply_requested_threads();
+        let worker_count = {
+            let workers = self.workers.lock();
+            for (helper_index, helper) in workers
+                .active
+                .iter()
+                .filter_map(|worker| worker.helper.as_ref())
+                .enumerate()
+            {
+                helper.follow_leader(&self.tree, lazy_depth_offset(helper_index));
+            }
+            workers.active.len()
+        };
+        self.sync.begin_round(worker_count);
+        let poll_interval = self.search_handle.as_ref().map(|_| RESIZE_POLL_INTERVAL);
+        while !self.sync.wait_for_idle(poll_interval) {
+            self.apply_requested_threads();
+        }
+    }
+    pub(crate) fn helper_nodes(&self) -> Option<u64> {
+        (self.parallel_mode == ParallelMode::LazySmp).then(|| {
+            self.workers
+                .lock()
+                .active
+                .iter()
+                .filter_map(|worker| worker.helper.as_ref())
+                .map(|helper| helper.stats.nodes_created.load(Ordering::Relaxed))
+                .fold(0_u64, u64::saturating_add)
+        })
+    }
+    fn apply_requested_threads(&self) {
+        if let Some(num_threads) = self
+            .search_handle
+            .as_ref()
+            .and_then(SearchHandle::take_request)
+        {
+            self.resize(num_threads);
+        }
+    }
+    fn resize(&self, num_threads: usize) {
+        let lazy_smp = self.parallel_mode == ParallelMode::LazySmp;
+        self.tree
+            .set_worker_slots(if lazy_smp { 1 } else { num_threads });
+        let mut workers = self.workers.lock();
+        while workers.active.len() < num_threads {
+            let thread_id = workers.spawned;
+            let retire = Arc::new(AtomicBool::new(false));
+            let helper = (lazy_smp && !workers.active.is_empty()).then(|| {
+                let helper_tree = Arc::new(self.tree.lazy_helper(thread_id));
+                helper_tree.evaluate_node(
+                    &helper_tree.root,
+                    &ThreadLocalContext::new(self.game_state.clone(), thread_id),
+                );
+                helper_tree
+            });
+            let cloned_tree = Arc::clone(helper.as_ref().unwrap_or(&self.tree));
+            let cloned_sync = Arc::clone(&self.sync);
+            let cloned_retire = Arc::clone(&retire);
+            let worker_game_state = self.game_state.clone();
+            let handle = thread::spawn(move || {
+                run_worker_thread(
+                    &cloned_tree,
+                    &worker_game_state,
+                    thread_id,
+                    &cloned_sync,
+                    &cloned_retire,
+                );
+            });
+            workers.active.push(PoolWorker {
+                handle,
+                retire,
+                helper,
+            });
+            workers.spawned =
+                checked::add_usize(workers.spawned, 1_usize, "WorkerPool::resize::spawned");
+        }
+        while workers.active.len() > num_threads {
+            let Some(worker) = workers.active.pop() else {
+                break;
+            };
+            worker.retire.store(true, Ordering::Release);
+            workers.retired.push(worker.handle);
+        }
+        let (finished, running): (Vec<_>, Vec<_>) =
+            workers.retired.drain(..).partition(JoinHandle::is_finished);
+        workers.retired = running;
+        let spawned = workers.spawned;
+        let active = workers.active.len();
+        drop(workers);
+        self.sync.wake_idle_workers();
+        join_all(finished);
+        if let Some(handle) = self.search_handle.as_ref() {
+            handle.record_threads(active);
+        }
+        if self.sync.wait_until_ready(spawned).is_err() {
+            eprintln!("工作线程池初始化失败。");
+            panic!("工作线程池初始化失败");
+        }
     }
     fn shutdown_and_join(&mut self) {
         self.tree.mark_solved();
         self.sync.shutdown();
-        while let Some(handle) = self.handles.pop() {
-            if handle.join().is_err() {
-                eprintln!("工作线程异常退出。");
-            }
-        }
+        let workers = self.workers.get_mut();
+        let mut handles: Vec<JoinHandle<()>> = workers.retired.drain(..).collect();
+        handles.extend(workers.active.drain(..).map(|worker| worker.handle));
+        join_all(handles);
     }
 }
 impl Drop for WorkerPool {
@@ -208,11 +406,22 @@ impl Drop for WorkerPool {
         self.shutdown_and_join();
     }
 }
+fn join_all(handles: Vec<JoinHandle<()>>) {
+    for handle in handles {
+        if handle.join().is_err() {
+            eprintln!("工作线程异常退出。");
+        }
+    }
+}
+const fn lazy_depth_offset(helper_index: usize) -> usize {
+    helper_index & 1
+}
 fn run_worker_thread(
     tree: &Arc<SharedTree>,
     game_state: &GameState,
     thread_id: usize,
     sync: &Arc<WorkerPoolSync>,
+    retire: &AtomicBool,
 ) {
     let thread_tree = Arc::clone(tree);
     let thread_sync = Arc::clone(sync);
@@ -221,17 +430,25 @@ fn run_worker_thread(
             let _alloc_guard = AllocTrackingGuard::new();
             ThreadLocalContext::new((*game_state).clone(), thread_id)
         };
-        thread_sync.mark_ready();
+        thread_tree.prefault_owned_shards(thread_id);
         let mut worker = Worker::new(Arc::clone(&thread_tree), ctx);
         let mut observed_generation = 0_u64;
+        let mut joins_running_round = thread_sync.mark_ready(&mut observed_generation);
         loop {
-            if !thread_sync.wait_for_round(&mut observed_generation) {
+            if !joins_running_round && !thread_sync.wait_for_round(&mut observed_generation, retire)
+            {
+                return;
+            }
+            joins_running_round = false;
+            {
+                let _round_guard =
+                    WorkerRoundGuard::new(Arc::clone(&thread_sync), Arc::clone(&thread_tree));
+                let _alloc_guard = AllocTrackingGuard::new();
+                worker.run_until_retired(retire);
+            }
+            if retire.load(Ordering::Acquire) {
                 return;
             }
-            let _round_guard =
-                WorkerRoundGuard::new(Arc::clone(&thread_sync), Arc::clone(&thread_tree));
-            let _alloc_guard = AllocTrackingGuard::new();
-            worker.run();
         }
     }));
     if result.is_err() {
@@ -0,0 +1,168 @@
+use crate::{
+    game_state::{Coord, Position},
+    pns::{MoveSelector, NodeRef, SharedTree},
+};
+use alloc::sync::Arc;
+use core::ops::RangeInclusive;
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write as _},
+    path::Path,
+};
+const PUZZLE_FORMAT: u32 = 1;
+const MAX_VISITED_NODES: usize = 1_000_000;
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub position: String,
+    pub solution: Vec<Coord>,
+    pub win_len: u64,
+}
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PuzzleSet {
+    pub format: u32,
+    pub board_size: usize,
+    pub win_len: usize,
+    pub puzzles: Vec<Puzzle>,
+}
+impl PuzzleSet {
+    #[inline]
+    #[must_use]
+    pub const fn new(board_size: usize, win_len: usize) -> Self {
+        Self {
+            format: PUZZLE_FORMAT,
+            board_size,
+            win_len,
+            puzzles: Vec::new(),
+        }
+    }
+    #[inline]
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let set: Self =
+            serde_json::from_reader(BufReader::new(File::open(path)?)).map_err(io::Error::other)?;
+        if set.format != PUZZLE_FORMAT {
+            return Err(io::Error::other(format!(
+                "不支持的题库格式版本 {format}，当前为 {PUZZLE_FORMAT}",
+                format = set.format
+            )));
+        }
+        Ok(set)
+    }
+    #[inline]
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self).map_err(io::Error::other)?;
+        writer.flush()
+    }
+    #[inline]
+    pub fn merge(&mut self, puzzles: Vec<Puzzle>) -> usize {
+        let before = self.puzzles.len();
+        for puzzle in puzzles {
+            if !self
+                .puzzles
+                .iter()
+                .any(|existing| existing.position == puzzle.position)
+            {
+                self.puzzles.push(puzzle);
+            }
+        }
+        self.puzzles.len().saturating_sub(before)
+    }
+    #[inline]
+    pub fn position(&self, puzzle: &Puzzle) -> Result<Position, String> {
+        let position = Position::parse(&puzzle.position, self.win_len)?;
+        if position.board_size() != self.board_size {
+            return Err(format!(
+                "题目局面为 {size}x{size}，与题库棋盘大小 {expected} 不符",
+                size = position.board_size(),
+                expected = self.board_size
+            ));
+        }
+        Ok(position)
+    }
+}
+pub(crate) fn mine<F>(
+    root: &NodeRef,
+    root_position: &Position,
+    win_len_range: &RangeInclusive<u64>,
+    limit: usize,
+    selector: &MoveSelector,
+    mut verify_unique: F,
+) -> Vec<Puzzle>
+where
+    F: FnMut(&Position, Coord, u64) -> bool,
+{
+    let mut puzzles = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![(Arc::clone(root), root_position.clone())];
+    while let Some((node, position)) = stack.pop() {
+        if puzzles.len() >= limit || seen.len() >= MAX_VISITED_NODES {
+            break;
+        }
+        if node.get_pn() != 0 || !seen.insert(position.hash()) {
+            continue;
+        }
+        let Some(children) = node.expanded_children() else {
+            continue;
+        };
+        if node.is_or_node()
+            && let Some(puzzle) = unique_puzzle(
+                &node,
+                &position,
+                win_len_range,
+                selector,
+                &mut verify_unique,
+            )
+        {
+            puzzles.push(puzzle);
+        }
+        for child_ref in children.filter(|child_ref| child_ref.node.get_pn() == 0) {
+            let mut next = position.clone();
+            if next.play(child_ref.mov.coord()).is_ok() && next.winner().is_none() {
+                stack.push((Arc::clone(&child_ref.node), next));
+            }
+        }
+    }
+    puzzles
+}
+fn unique_puzzle<F>(
+    node: &NodeRef,
+    position: &Position,
+    win_len_range: &RangeInclusive<u64>,
+    selector: &MoveSelector,
+    verify_unique: &mut F,
+) -> Option<Puzzle>
+where
+    F: FnMut(&Position, Coord, u64) -> bool,
+{
+    let win_len = node.get_win_len();
+    if !win_len_range.contains(&win_len) {
+        return None;
+    }
+    let &[(solution, _)] = SharedTree::proven_moves(node).as_slice() else {
+        return None;
+    };
+    let settled = node.pending_deferred() == 0
+        && node
+            .expanded_children()?
+            .all(|child_ref| child_ref.node.get_pn() == 0 || child_ref.node.get_dn() == 0);
+    if !settled && !verify_unique(position, solution, win_len) {
+        return None;
+    }
+    Some(Puzzle {
+        position: position.to_string(),
+        solution: solution_line(node, selector),
+        win_len,
+    })
+}
+fn solution_line(start: &NodeRef, selector: &MoveSelector) -> Vec<Coord> {
+    let mut line = Vec::new();
+    let mut node = Arc::clone(start);
+    while let Some(child_ref) = node.principal_child(selector) {
+        line.push(child_ref.mov.coord());
+        let child = Arc::clone(&child_ref.node);
+        node = child;
+    }
+    line
+}
@@ -0,0 +1,141 @@
+use crate::pns::to_f64;
+use alloc::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write as _},
+    path::Path,
+};
+const CONFIDENCE_Z: f64 = 1.96_f64;
+const SPRT_ALPHA: f64 = 0.05_f64;
+const SPRT_BETA: f64 = 0.05_f64;
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+}
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EloEstimate {
+    pub elo: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SprtState {
+    pub llr: f64,
+    pub lower_bound: f64,
+    pub upper_bound: f64,
+}
+impl MatchRecord {
+    #[inline]
+    pub const fn add_win(&mut self) {
+        self.wins = self.wins.saturating_add(1);
+    }
+    #[inline]
+    pub const fn add_draw(&mut self) {
+        self.draws = self.draws.saturating_add(1);
+    }
+    #[inline]
+    pub const fn add_loss(&mut self) {
+        self.losses = self.losses.saturating_add(1);
+    }
+    #[inline]
+    #[must_use]
+    pub const fn games(self) -> u64 {
+        self.wins
+            .saturating_add(self.draws)
+            .saturating_add(self.losses)
+    }
+    #[inline]
+    #[must_use]
+    pub fn score(self) -> Option<f64> {
+        let games = self.games();
+        (games > 0).then(|| 0.5_f64.mul_add(to_f64(self.draws), to_f64(self.wins)) / to_f64(games))
+    }
+    #[inline]
+    #[must_use]
+    pub fn elo(self) -> Option<EloEstimate> {
+        let score = self.score()?;
+        let margin = CONFIDENCE_Z * (self.score_variance(score) / to_f64(self.games())).sqrt();
+        Some(EloEstimate {
+            elo: elo_from_score(score),
+            lower: elo_from_score(score - margin),
+            upper: elo_from_score(score + margin),
+        })
+    }
+    #[inline]
+    #[must_use]
+    pub fn sprt(self, elo0: f64, elo1: f64) -> Option<SprtState> {
+        let score = self.score()?;
+        let variance = self.score_variance(score);
+        if variance <= 0.0_f64 {
+            return None;
+        }
+        let (score0, score1) = (score_from_elo(elo0), score_from_elo(elo1));
+        let llr =
+            to_f64(self.games()) * (score1 - score0) * 2.0_f64.mul_add(score, -score0 - score1)
+                / (2.0_f64 * variance);
+        Some(SprtState {
+            llr,
+            lower_bound: (SPRT_BETA / (1.0_f64 - SPRT_ALPHA)).ln(),
+            upper_bound: ((1.0_f64 - SPRT_BETA) / SPRT_ALPHA).ln(),
+        })
+    }
+    fn score_variance(self, score: f64) -> f64 {
+        let deviation = |value: f64, count: u64| to_f64(count) * (value - score).powi(2);
+        (deviation(1.0_f64, self.wins)
+            + deviation(0.5_f64, self.draws)
+            + deviation(0.0_f64, self.losses))
+            / to_f64(self.games())
+    }
+}
+impl SprtState {
+    #[inline]
+    #[must_use]
+    pub fn accepts_h1(self) -> bool {
+        self.llr >= self.upper_bound
+    }
+    #[inline]
+    #[must_use]
+    pub fn accepts_h0(self) -> bool {
+        self.llr <= self.lower_bound
+    }
+}
+fn elo_from_score(score: f64) -> f64 {
+    if score <= 0.0_f64 {
+        return f64::NEG_INFINITY;
+    }
+    if score >= 1.0_f64 {
+        return f64::INFINITY;
+    }
+    400.0_f64 * (score / (1.0_f64 - score)).log10()
+}
+fn score_from_elo(elo: f64) -> f64 {
+    1.0_f64 / (1.0_f64 + 10.0_f64.powf(-elo / 400.0_f64))
+}
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct RatingsBook {
+    pub matches: BTreeMap<String, MatchRecord>,
+}
+impl RatingsBook {
+    #[inline]
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err),
+        };
+        serde_json::from_reader(BufReader::new(file)).map_err(io::Error::other)
+    }
+    #[inline]
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self).map_err(io::Error::other)?;
+        writer.flush()
+    }
+    #[inline]
+    pub fn record_mut(&mut self, label: &str) -> &mut MatchRecord {
+        self.matches.entry(String::from(label)).or_default()
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{
+    checked,
+    game_state::{Board, BoardRegion, Coord, GameState, GomokuRules, is_forbidden},
+};
+const WINDOW_DIRECTIONS: [((isize, isize), (isize, isize)); 4] = [
+    ((0, 1), (0, -1)),
+    ((1, 0), (-1, 0)),
+    ((1, 1), (-1, -1)),
+    ((1, -1), (-1, 1)),
+];
+#[derive(Clone, Copy, Default)]
+pub struct SlowRules {
+    pub region: Option<BoardRegion>,
+    pub forbidden_player: Option<u8>,
+}
+impl SlowRules {
+    fn in_region(&self, coord: Coord) -> bool {
+        self.region.is_none_or(|bounds| bounds.contains(coord))
+    }
+    fn is_forbidden(&self, board: &Board, win_len: usize, mov: Coord, player: u8) -> bool {
+        self.forbidden_player == Some(player) && is_forbidden(board, win_len, mov, player)
+    }
+}
+struct Window {
+    coords: Vec<Coord>,
+    forward: (isize, isize),
+    backward: (isize, isize),
+}
+fn step(
+    board_size: usize,
+    (row, column): Coord,
+    (row_step, column_step): (isize, isize),
+) -> Option<Coord> {
+    let next_row = row.checked_add_signed(row_step)?;
+    let next_column = column.checked_add_signed(column_step)?;
+    (next_row < board_size && next_column < board_size).then_some((next_row, next_column))
+}
+fn window_from(
+    board_size: usize,
+    win_len: usize,
+    start: Coord,
+    (forward, backward): ((isize, isize), (isize, isize)),
+) -> Option<Window> {
+    let mut coords = Vec::with_capacity(win_len);
+    let mut current = start;
+    coords.push(current);
+    while coords.len() < win_len {
+        current = step(board_size, current, forward)?;
+        coords.push(current);
+    }
+    Some(Window {
+        coords,
+        forward,
+        backward,
+    })
+}
+fn all_windows(board_size: usize, win_len: usize, rules: &SlowRules) -> Vec<Window> {
+    if win_len == 0 {
+        return Vec::new();
+    }
+    let mut windows = Vec::new();
+    for row in 0..board_size {
+        for column in 0..board_size {
+            for &directions in &WINDOW_DIRECTIONS {
+                if let Some(window) = window_from(board_size, win_len, (row, column), directions)
+                    && window.coords.iter().all(|&coord| rules.in_region(coord))
+                {
+                    windows.push(window);
+                }
+            }
+        }
+    }
+    windows
+}
+fn cell_at(board: &Board, (row, column): Coord) -> u8 {
+    let Some(cell) = board.get((row, column)) else {
+        eprintln!("reference::cell_at 棋盘索引越界: ({row}, {column})");
+        panic!("reference::cell_at 棋盘索引越界");
+    };
+    cell
+}
+fn count_in_window(board: &Board, window: &Window, player: u8) -> usize {
+    window
+        .coords
+        .iter()
+        .filter(|&&coord| cell_at(board, coord) == player)
+        .count()
+}
+fn extends_beyond(board: &Board, window: &Window, player: u8) -> bool {
+    let extends = |end: Option<&Coord>, direction: (isize, isize)| {
+        end.and_then(|&coord| step(board.rows(), coord, direction))
+            .is_some_and(|next| cell_at(board, next) == player)
+    };
+    extends(window.coords.first(), window.backward) || extends(window.coords.last(), window.forward)
+}
+fn threat_completions(board: &Board, win_len: usize, player: u8, rules: &SlowRules) -> Vec<Coord> {
+    let opponent = checked::opponent_player(player, "reference::threat_completions");
+    let needed = checked::sub_usize(win_len, 1_usize, "reference::threat_completions");
+    let exact = rules.forbidden_player == Some(player);
+    let mut completions: Vec<Coord> = all_windows(board.rows(), win_len, rules)
+        .into_iter()
+        .filter(|window| {
+            count_in_window(board, window, player) == needed
+                && count_in_window(board, window, opponent) == 0
+                && !(exact && extends_beyond(board, window, player))
+        })
+        .flat_map(|window| {
+            window
+                .coords
+                .into_iter()
+                .filter(|&coord| cell_at(board, coord) == 0)
+        })
+        .filter(|&coord| !rules.is_forbidden(board, win_len, coord, player))
+        .collect();
+    completions.sort_unstable();
+    completions.dedup();
+    completions
+}
+#[inline]
+#[must_use]
+pub fn slow_check_win(board: &Board, win_len: usize, player: u8, rules: &SlowRules) -> bool {
+    let exact = rules.forbidden_player == Some(player);
+    all_windows(board.rows(), win_len, rules)
+        .iter()
+        .any(|window| {
+            count_in_window(board, window, player) == win_len
+                && !(exact && extends_beyond(board, window, player))
+        })
+}
+#[inline]
+#[must_use]
+pub fn slow_legal_moves(
+    board: &Board,
+    win_len: usize,
+    player: u8,
+    rules: &SlowRules,
+) -> Vec<Coord> {
+    let allowed = |moves: Vec<Coord>| -> Vec<Coord> {
+        moves
+            .into_iter()
+            .filter(|&mov| !rules.is_forbidden(board, win_len, mov, player))
+            .collect()
+    };
+    let winning = allowed(threat_completions(board, win_len, player, rules));
+    if !winning.is_empty() {
+        return winning;
+    }
+    let opponent = checked::opponent_player(player, "reference::slow_legal_moves");
+    let forced = threat_completions(board, win_len, opponent, rules);
+    if !forced.is_empty() {
+        return allowed(forced);
+    }
+    let mut empty = Vec::new();
+    for row in 0..board.rows() {
+        for column in 0..board.columns() {
+            if cell_at(board, (row, column)) == 0 && rules.in_region((row, column)) {
+                empty.push((row, column));
+            }
+        }
+    }
+    allowed(empty)
+}
+pub(crate) fn verify_move_generation(
+    game_state: &GameState,
+    player: u8,
+    generated: &[Coord],
+) -> Result<(), String> {
+    let position = &game_state.position;
+    let (board, win_len) = (&position.board, position.win_len);
+    let rules = SlowRules {
+        region: position.region,
+        forbidden_player: position.forbidden_player,
+    };
+    for checked_player in [1_u8, 2_u8] {
+        let fast = GomokuRules::check_win(position, checked_player);
+        let slow = slow_check_win(board, win_len, checked_player, &rules);
+        if fast != slow {
+            return Err(format!(
+                "胜负判定不一致: 玩家 {checked_player}，优化实现 {fast}，参考实现 {slow}"
+            ));
+        }
+    }
+    let mut fast_moves = generated.to_vec();
+    fast_moves.sort_unstable();
+    let generated_len = fast_moves.len();
+    fast_moves.dedup();
+    if fast_moves.len() != generated_len {
+        return Err(format!(
+            "着法生成包含重复着法: 玩家 {player}，共 {generated_len} 个，去重后 {}",
+            fast_moves.len()
+        ));
+    }
+    let slow_moves = slow_legal_moves(board, win_len, player, &rules);
+    if fast_moves != slow_moves {
+        let missing: Vec<Coord> = slow_moves
+            .iter()
+            .filter(|mov| fast_moves.binary_search(mov).is_err())
+            .copied()
+            .collect();
+        let extra: Vec<Coord> = fast_moves
+            .iter()
+            .filter(|mov| slow_moves.binary_search(mov).is_err())
+            .copied()
+            .collect();
+        return Err(format!(
+            "着法生成与参考实现不一致: 玩家 {player}，缺少 {missing:?}，多出 {extra:?}"
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,3 @@
+mod transform;
+pub type Transform = transform::Transform;
+pub(crate) use transform::symmetric_coords;
@@ -0,0 +1,188 @@
+use crate::{checked, game_state::Coord, utils::board_index};
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Transform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    Transpose,
+    FlipVertical,
+    AntiTranspose,
+}
+impl Transform {
+    pub const ALL: [Self; 8] = [
+        Self::Identity,
+        Self::Rotate90,
+        Self::Rotate180,
+        Self::Rotate270,
+        Self::FlipHorizontal,
+        Self::Transpose,
+        Self::FlipVertical,
+        Self::AntiTranspose,
+    ];
+    #[inline]
+    #[must_use]
+    pub const fn index(self) -> usize {
+        match self {
+            Self::Identity => 0,
+            Self::Rotate90 => 1,
+            Self::Rotate180 => 2,
+            Self::Rotate270 => 3,
+            Self::FlipHorizontal => 4,
+            Self::Transpose => 5,
+            Self::FlipVertical => 6,
+            Self::AntiTranspose => 7,
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Self::Identity),
+            1 => Some(Self::Rotate90),
+            2 => Some(Self::Rotate180),
+            3 => Some(Self::Rotate270),
+            4 => Some(Self::FlipHorizontal),
+            5 => Some(Self::Transpose),
+            6 => Some(Self::FlipVertical),
+            7 => Some(Self::AntiTranspose),
+            _ => None,
+        }
+    }
+    const fn parts(self) -> (bool, bool, bool) {
+        match self {
+            Self::Identity => (false, false, false),
+            Self::Rotate90 => (true, false, true),
+            Self::Rotate180 => (false, true, true),
+            Self::Rotate270 => (true, true, false),
+            Self::FlipHorizontal => (false, false, true),
+            Self::Transpose => (true, false, false),
+            Self::FlipVertical => (false, true, false),
+            Self::AntiTranspose => (true, true, true),
+        }
+    }
+    const fn from_parts(swap: bool, flip_row: bool, flip_column: bool) -> Self {
+        match (swap, flip_row, flip_column) {
+            (false, false, false) => Self::Identity,
+            (true, false, true) => Self::Rotate90,
+            (false, true, true) => Self::Rotate180,
+            (true, true, false) => Self::Rotate270,
+            (false, false, true) => Self::FlipHorizontal,
+            (true, false, false) => Self::Transpose,
+            (false, true, false) => Self::FlipVertical,
+            (true, true, true) => Self::AntiTranspose,
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub const fn then(self, next: Self) -> Self {
+        let (first_swap, first_flip_row, first_flip_column) = self.parts();
+        let (next_swap, next_flip_row, next_flip_column) = next.parts();
+        let (carried_flip_row, carried_flip_column) = if next_swap {
+            (first_flip_column, first_flip_row)
+        } else {
+            (first_flip_row, first_flip_column)
+        };
+        Self::from_parts(
+            first_swap ^ next_swap,
+            carried_flip_row ^ next_flip_row,
+            carried_flip_column ^ next_flip_column,
+        )
+    }
+    #[inline]
+    #[must_use]
+    pub const fn inverse(self) -> Self {
+        let (swap, flip_row, flip_column) = self.parts();
+        if swap {
+            Self::from_parts(swap, flip_column, flip_row)
+        } else {
+            self
+        }
+    }
+    #[inline]
+    #[must_use]
+    pub fn apply_to_move(self, board_size: usize, mov: Coord) -> Coord {
+        let (swap, flip_row, flip_column) = self.parts();
+        let (row_index, column_index) = if swap { (mov.1, mov.0) } else { mov };
+        let last_index = last_index(board_size, "Transform::apply_to_move::last_index");
+        (
+            if flip_row {
+                checked::sub_usize(last_index, row_index, "Transform::apply_to_move::row")
+            } else {
+                row_index
+            },
+            if flip_column {
+                checked::sub_usize(last_index, column_index, "Transform::apply_to_move::column")
+            } else {
+                column_index
+            },
+        )
+    }
+    #[inline]
+    #[must_use]
+    pub fn apply_to_moves(self, board_size: usize, moves: &[Coord]) -> Vec<Coord> {
+        moves
+            .iter()
+            .map(|&mov| self.apply_to_move(board_size, mov))
+            .collect()
+    }
+    #[inline]
+    #[must_use]
+    pub fn apply_to_board(self, board_size: usize, board: &[u8]) -> Vec<u8> {
+        let board_cells =
+            checked::mul_usize(board_size, board_size, "Transform::apply_to_board::cells");
+        if board.len() != board_cells {
+            eprintln!(
+                "Transform::apply_to_board 棋盘长度不匹配: 期望 {board_cells}，实际 {}",
+                board.len()
+            );
+            panic!("Transform::apply_to_board 棋盘长度不匹配");
+        }
+        let mut transformed = vec![0_u8; board_cells];
+        for (index, &cell) in board.iter().enumerate() {
+            let mov = (
+                checked::div_usize(index, board_size, "Transform::apply_to_board::row"),
+                checked::rem_usize(index, board_size, "Transform::apply_to_board::column"),
+            );
+            let (row_index, column_index) = self.apply_to_move(board_size, mov);
+            let Some(target) =
+                transformed.get_mut(board_index(board_size, row_index, column_index))
+            else {
+                eprintln!("Transform::apply_to_board 目标索引越界: ({row_index}, {column_index})");
+                panic!("Transform::apply_to_board 目标索引越界");
+            };
+            *target = cell;
+        }
+        transformed
+    }
+}
+fn last_index(board_size: usize, context: &str) -> usize {
+    checked::sub_usize(board_size, 1_usize, context)
+}
+#[inline]
+#[must_use]
+pub(crate) fn symmetric_coords(board_size: usize, mov: Coord) -> [Coord; 8] {
+    let (row_index, column_index) = mov;
+    let last_index = last_index(board_size, "symmetry::symmetric_coords::last_index");
+    let rotated_row = checked::sub_usize(
+        last_index,
+        row_index,
+        "symmetry::symmetric_coords::rotated_row",
+    );
+    let rotated_column = checked::sub_usize(
+        last_index,
+        column_index,
+        "symmetry::symmetric_coords::rotated_column",
+    );
+    [
+        (row_index, column_index),
+        (column_index, rotated_row),
+        (rotated_row, rotated_column),
+        (rotated_column, row_index),
+        (row_index, rotated_column),
+        (column_index, row_index),
+        (rotated_row, column_index),
+        (rotated_column, rotated_row),
+    ]
+}
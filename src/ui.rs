@@ -1,86 +1,116 @@
 use crate::{
+    alloc_stats,
+    benchmark::Suite,
+    calibration::{CALIBRATION_FILE, HostProfile},
     checked,
-    config::Config,
-    game_state::{Coord, GameState, GomokuRules, ZobristHasher},
-    pns::{NodeTable, ParallelSolver, SearchParams, TranspositionTable},
-    utils::board_index,
+    config::{Config, HumanTimeout},
+    game_state::{
+        Board, Coord, GameOutcome, GameSession, GameState, Position, Rule, TerminalCondition,
+        ZobristHasher,
+    },
+    gamedb::{
+        Adjudication, GameDatabase, GameRecord, GameRecorder, GameResult, MoveAnnotation,
+        PlayerInfo, result_from_winner,
+    },
+    interrupt::{CancelReason, CancellationToken},
+    pns::{
+        Budget, NodeTable, ParallelSolver, SearchOutcome, SearchParams, TranspositionTable, to_f64,
+    },
 };
 use alloc::sync::Arc;
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::ops::RangeInclusive;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+mod adjudication;
+mod analyze;
+mod arbitration;
+mod daemon;
+mod doctor;
 mod input;
+mod known;
+mod metrics;
+mod ponder;
+mod progress;
+mod protocol;
+mod puzzles;
+mod resume;
+mod scaling;
+mod selfplay;
+mod solve;
+mod trainer;
+mod turn_budget;
+use adjudication::Adjudicator;
 use input::{PlayerInput, read_player_input};
+use ponder::Ponder;
 const PROGRAM_PLAYER: u8 = 1;
 const HUMAN_PLAYER: u8 = 2;
+const FIRST_PLAYER: u8 = 1;
+const DEFAULT_SGF_FILE: &str = "game.sgf";
 const BENCHMARK_BOARD_7X7: [&str; 7] = [
     ".......", ".......", "..O....", "...X...", ".......", ".......", ".......",
 ];
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 struct PlayedMove {
     coord: Coord,
     player: u8,
+    annotation: Option<MoveAnnotation>,
 }
 enum PlayerTurnResult {
     MoveApplied,
     TakeBack,
+    Hint,
+    Save(PathBuf),
+    TimeForfeit,
     Finished,
 }
 #[inline]
-pub fn print_board(board: &[u8], board_size: usize) {
+pub fn print_board(board: &Board) {
     print!("  ");
-    for column_index in 0..board_size {
+    for column_index in 0..board.columns() {
         print!("{column_index:2} ");
     }
     println!();
-    for row_index in 0..board_size {
+    for row_index in 0..board.rows() {
         print!("{row_index:2} ");
-        for column_index in 0..board_size {
-            let Some(cell) = board.get(board_index(board_size, row_index, column_index)) else {
-                eprintln!("棋盘数据长度不足，无法打印位置 ({row_index}, {column_index})。");
-                return;
-            };
-            let cell_text = match *cell {
-                PROGRAM_PLAYER => "X",
-                HUMAN_PLAYER => "O",
-                _ => ".",
-            };
-            print!("{cell_text}  ");
+        for cell in board.row(row_index) {
+            print!("{cell_text}  ", cell_text = stone_glyph(cell));
         }
         println!();
     }
 }
+const fn stone_glyph(cell: u8) -> &'static str {
+    match cell {
+        PROGRAM_PLAYER => "X",
+        HUMAN_PLAYER => "O",
+        _ => ".",
+    }
+}
+const fn program_player(config: &Config) -> u8 {
+    config.root_player
+}
+fn human_player(config: &Config) -> u8 {
+    checked::opponent_player(config.root_player, "ui::human_player")
+}
+const BENCHMARK_RUNS: usize = 3;
 #[inline]
-pub fn run_benchmark(exit_flag: &Arc<AtomicBool>, config: &Config) {
-    const BENCHMARK_RUNS: usize = 3;
-    if config.board_size != 7 || config.win_len != 5 {
-        eprintln!(
-            "基准测试固定残局仅支持 7x7 棋盘与 5 连珠规则，当前配置为 {}x{}，胜利长度 {}。",
-            config.board_size, config.board_size, config.win_len
-        );
+pub fn run_benchmark(
+    exit_token: &CancellationToken,
+    config: &Config,
+    plot_data: Option<&Path>,
+    suite: Option<&Path>,
+) {
+    if let Some(path) = suite {
+        run_benchmark_suite(exit_token, config, path);
         return;
     }
-    let board = match benchmark_board(config.board_size) {
-        Ok(board) => board,
-        Err(err) => {
-            eprintln!("{err}");
-            return;
-        }
-    };
-    if check_win(
-        &board,
-        config.board_size,
-        config.win_len,
-        config.evaluation,
-        PROGRAM_PLAYER,
-    ) || check_win(
-        &board,
-        config.board_size,
-        config.win_len,
-        config.evaluation,
-        HUMAN_PLAYER,
-    ) {
-        eprintln!("基准残局已出现胜负，无法用于基准测试。");
+    let Some(board) = fixed_benchmark_board(config) else {
         return;
-    }
+    };
     println!("开始基准测试：固定残局，计算下一步棋，循环 {BENCHMARK_RUNS} 次。");
     let params = SearchParams::new(
         config.board_size,
@@ -89,7 +119,7 @@ pub fn run_benchmark(exit_flag: &Arc<AtomicBool>, config: &Config) {
         config.evaluation,
     );
     let Some(result) =
-        ParallelSolver::benchmark_next_move(&board, params, BENCHMARK_RUNS, exit_flag)
+        ParallelSolver::benchmark_next_move(&board, &params, BENCHMARK_RUNS, exit_token)
     else {
         println!("基准测试已被中断。");
         return;
@@ -98,8 +128,110 @@ pub fn run_benchmark(exit_flag: &Arc<AtomicBool>, config: &Config) {
         "基准测试完成，平均耗时 {avg:.6}s，日志已写入 log.csv。",
         avg = result.elapsed_secs
     );
+    for &(depth, bytes) in &result.peak_live_bytes_by_depth {
+        println!(
+            "深度 D={depth} 峰值内存: {mib:.2} MiB",
+            mib = to_f64(bytes) / 1_048_576.0_f64
+        );
+    }
+    if let Some(path) = plot_data {
+        match result.write_plot_data(path) {
+            Ok(()) => println!("绘图数据已写入 {path}。", path = path.display()),
+            Err(err) => eprintln!("写入绘图数据 {path} 失败: {err}", path = path.display()),
+        }
+    }
+}
+fn run_benchmark_suite(exit_token: &CancellationToken, config: &Config, path: &Path) {
+    let suite = match Suite::load(path, config.win_len) {
+        Ok(suite) => suite,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
+    };
+    println!(
+        "开始基准测试集：{count} 个局面，每个局面计算下一步棋，循环 {BENCHMARK_RUNS} 次。",
+        count = suite.entries.len()
+    );
+    let params = SearchParams::new(
+        config.board_size,
+        config.win_len,
+        config.num_threads,
+        config.evaluation,
+    );
+    let report = suite.run(&params, BENCHMARK_RUNS, exit_token, |run| {
+        let entry = &run.report;
+        println!(
+            "{name}: 平均耗时 {elapsed:.6}s | 节点 {nodes} | {rate:.0} 节点/秒 | 峰值内存 {mib:.2} MiB",
+            elapsed = entry.elapsed_secs,
+            nodes = entry.stats.nodes_created,
+            rate = to_f64(entry.stats.nodes_created) / entry.elapsed_secs.max(f64::EPSILON),
+            name = run.name,
+            mib = to_f64(entry.peak_live_bytes) / 1_048_576.0_f64
+        );
+    });
+    match report {
+        Ok(summary) => println!(
+            "基准测试集完成：总耗时 {elapsed:.6}s | 总节点 {nodes} | {rate:.0} 节点/秒 | 峰值内存 {mib:.2} MiB，日志已写入 log.csv。",
+            elapsed = summary.total_elapsed_secs(),
+            nodes = summary.total_nodes(),
+            rate = to_f64(summary.total_nodes()) / summary.total_elapsed_secs().max(f64::EPSILON),
+            mib = to_f64(summary.peak_live_bytes()) / 1_048_576.0_f64
+        ),
+        Err(err) => eprintln!("{err}"),
+    }
 }
-fn benchmark_board(board_size: usize) -> Result<Vec<u8>, String> {
+#[inline]
+#[must_use]
+pub fn run_known_verification(exit_token: &CancellationToken, config: &Config) -> bool {
+    known::run(exit_token, config)
+}
+#[inline]
+#[must_use]
+pub fn run_solve(exit_token: &CancellationToken, config: &Config, position: &str) -> bool {
+    solve::run(exit_token, config, position)
+}
+#[inline]
+#[must_use]
+pub fn run_calibration(exit_token: &CancellationToken, config: &Config) -> bool {
+    println!("正在校准本机性能（扩展吞吐量与每节点内存）...");
+    let Some(calibration) = HostProfile::measure(config, exit_token) else {
+        println!("校准未完成，将沿用默认设置。");
+        return false;
+    };
+    println!(
+        "单线程 {rate:.0} 节点/秒，推荐线程数 {threads}，并行效率 {efficiency:.0}%",
+        rate = calibration.nodes_per_sec,
+        threads = calibration.recommended_threads,
+        efficiency = calibration.parallel_efficiency * 100.0_f64
+    );
+    if let Some(bytes) = calibration.bytes_per_node {
+        println!("每节点约占用 {bytes} 字节");
+    }
+    if let Err(err) = calibration.save(Path::new(CALIBRATION_FILE)) {
+        eprintln!("写入校准文件 {CALIBRATION_FILE} 失败: {err}");
+        return false;
+    }
+    println!("校准结果已写入 {CALIBRATION_FILE}。");
+    true
+}
+#[inline]
+pub fn run_scaling_benchmark(exit_token: &CancellationToken, config: &Config) {
+    scaling::run(exit_token, config);
+}
+fn fixed_benchmark_board(config: &Config) -> Option<Vec<u8>> {
+    if config.board_size != 7 || config.win_len != 5 {
+        eprintln!(
+            "基准测试固定残局仅支持 7x7 棋盘与 5 连珠规则，当前配置为 {}x{}，胜利长度 {}。",
+            config.board_size, config.board_size, config.win_len
+        );
+        return None;
+    }
+    benchmark_board(config.board_size, config.win_len)
+        .inspect_err(|err| eprintln!("{err}"))
+        .ok()
+}
+fn benchmark_board(board_size: usize, win_len: usize) -> Result<Vec<u8>, String> {
     if board_size != BENCHMARK_BOARD_7X7.len() {
         return Err(format!(
             "基准残局仅支持 {}x{} 棋盘。",
@@ -107,207 +239,634 @@ fn benchmark_board(board_size: usize) -> Result<Vec<u8>, String> {
             BENCHMARK_BOARD_7X7.len()
         ));
     }
-    let mut board = Vec::with_capacity(board_size.saturating_mul(board_size));
-    for (row_idx, row) in BENCHMARK_BOARD_7X7.iter().enumerate() {
-        let bytes = row.as_bytes();
-        if bytes.len() != board_size {
-            return Err(format!("基准残局第 {row_idx} 行长度不匹配。"));
-        }
-        for &cell in bytes {
-            let value = match cell {
-                b'.' => 0,
-                b'X' => 1,
-                b'O' => 2,
-                _ => {
-                    return Err(format!("基准残局包含非法字符 '{}'。", char::from(cell)));
-                }
-            };
-            board.push(value);
-        }
+    let position = Position::parse(&BENCHMARK_BOARD_7X7.join("/"), win_len)
+        .map_err(|err| format!("基准残局无效: {err}"))?;
+    if position.winner().is_some() {
+        return Err(String::from("基准残局已出现胜负，无法用于基准测试。"));
     }
-    Ok(board)
+    Ok(position.board().to_vec())
+}
+#[inline]
+pub fn run_analysis(exit_token: &CancellationToken, config: &Config) {
+    analyze::run(exit_token, config);
+}
+#[inline]
+pub fn run_protocol(exit_token: &CancellationToken, config: &Config) {
+    protocol::run(exit_token, config);
+}
+#[inline]
+pub fn run_arbitration(exit_token: &CancellationToken, config: &Config) {
+    arbitration::run(exit_token, config);
 }
 #[inline]
-pub fn play_game(exit_flag: &Arc<AtomicBool>, config: &Config) {
+pub fn run_daemon(exit_token: &CancellationToken, config: &Config, dir: &Path) {
+    daemon::run(exit_token, config, dir);
+}
+#[inline]
+pub fn print_daemon_status(dir: &Path) {
+    daemon::print_status(dir);
+}
+#[inline]
+#[must_use]
+pub fn run_doctor(preset: Option<String>, daemon_dir: &Path) -> bool {
+    doctor::run(preset, daemon_dir)
+}
+#[inline]
+pub fn run_self_play(
+    exit_token: &CancellationToken,
+    config: &Config,
+    challenger: &Path,
+    ratings: &Path,
+) {
+    selfplay::run(exit_token, config, challenger, ratings);
+}
+#[inline]
+pub fn run_trainer(exit_token: &CancellationToken, config: &Config, puzzles: Option<&Path>) {
+    trainer::run(exit_token, config, puzzles);
+}
+#[inline]
+pub fn run_puzzle_generator(
+    exit_token: &CancellationToken,
+    config: &Config,
+    output: &Path,
+    win_len_range: &RangeInclusive<u64>,
+) {
+    puzzles::run(exit_token, config, output, win_len_range);
+}
+#[inline]
+pub fn play_game(exit_token: &CancellationToken, config: &Config, initial: Option<&GameRecord>) {
     print_intro(config);
     let board_size = config.board_size;
-    let mut board = vec![0_u8; board_size.saturating_mul(board_size)];
-    let mut current_player = PROGRAM_PLAYER;
+    let mut board = Board::new(board_size);
+    let program = program_player(config);
+    let human = human_player(config);
+    let mut current_player = FIRST_PLAYER;
     let mut move_history = Vec::new();
     let mut tt: Option<TranspositionTable> = None;
     let mut node_table: NodeTable = NodeTable::default();
+    let mut session = GameSession::new(
+        GameState::new(
+            board.as_slice().to_vec(),
+            board_size,
+            Arc::new(ZobristHasher::new(board_size)),
+            current_player,
+            config.win_len,
+            config.evaluation,
+        ),
+        current_player,
+    );
+    let resume_path = config.resume_file.as_deref().map(Path::new);
+    if let Some(record) = initial {
+        let (loaded_board, loaded_moves) = match replay_record(record, config) {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                eprintln!("载入棋谱失败: {err}");
+                return;
+            }
+        };
+        for played_move in &loaded_moves {
+            session.play(played_move.coord, played_move.player);
+        }
+        if let Some(last) = loaded_moves.last() {
+            current_player = checked::opponent_player(last.player, "play_game::loaded_side");
+        }
+        println!("已载入棋谱中的 {count} 手。", count = loaded_moves.len());
+        board = loaded_board;
+        move_history = loaded_moves;
+    } else if let Some(resumed) =
+        resume_path.and_then(|path| resume::offer(path, config, exit_token))
+    {
+        for played_move in &resumed.moves {
+            session.play(played_move.coord, played_move.player);
+        }
+        board = resumed.board;
+        move_history = resumed.moves;
+        current_player = resumed.side_to_move;
+        tt = resumed.tt;
+    }
+    let players = if program == FIRST_PLAYER {
+        [PlayerInfo::engine(), PlayerInfo::human("human")]
+    } else {
+        [PlayerInfo::human("human"), PlayerInfo::engine()]
+    };
+    let mut recorder = GameRecorder::new(board_size, config.win_len, players);
+    sync_recorder(&mut recorder, &move_history, Instant::now());
+    let rule = config.terminal_condition();
+    let mut adjudicator = Adjudicator::new(config.adjudication_rules());
     loop {
-        if exit_flag.load(Ordering::SeqCst) {
-            return;
+        if exit_token.is_cancelled() {
+            break;
         }
-        let has_stones = board.iter().any(|&cell| cell != 0);
-        if has_stones {
+        if board.has_stones() {
             println!("\n当前棋盘:");
-            print_board(&board, board_size);
+            print_board(&board);
         }
-        if current_player == PROGRAM_PLAYER {
-            if ai_turn(
+        let turn_start = Instant::now();
+        if current_player == program {
+            let (finished, outcome) = ai_turn(
                 &mut board,
                 config,
-                !has_stones,
+                &mut session,
                 &mut tt,
                 &mut node_table,
-                exit_flag,
+                exit_token,
                 &mut move_history,
-            ) {
+            );
+            sync_recorder(&mut recorder, &move_history, turn_start);
+            if finished || exit_token.is_cancelled() {
                 break;
             }
-            if exit_flag.load(Ordering::SeqCst) {
-                return;
+            if adjudicate(&mut adjudicator, &mut recorder, program, outcome.as_ref()) {
+                break;
             }
-            current_player = HUMAN_PLAYER;
+            current_player = human;
         } else {
-            match player_turn(
-                &mut board,
-                board_size,
-                exit_flag.as_ref(),
-                &mut move_history,
-            ) {
+            let ponder = Ponder::start(config, &board, tt.clone(), exit_token);
+            let turn = player_turn(&mut board, config, exit_token, &mut move_history);
+            if let Some(warmed) = ponder.and_then(Ponder::finish) {
+                tt = Some(warmed);
+            }
+            match turn {
                 PlayerTurnResult::MoveApplied => {
-                    current_player = PROGRAM_PLAYER;
+                    sync_recorder(&mut recorder, &move_history, turn_start);
+                    if let Some(&last_move) = move_history.last() {
+                        session.play(last_move.coord, last_move.player);
+                    }
+                    if report_outcome(&*rule, &board, &session, program)
+                        || is_repetition_draw(config, &session)
+                        || adjudicate(&mut adjudicator, &mut recorder, human, None)
+                    {
+                        break;
+                    }
+                    current_player = program;
                 }
                 PlayerTurnResult::TakeBack => {
-                    if take_back_last_player_move(&mut board, board_size, &mut move_history) {
+                    if take_back_last_player_move(&mut board, &mut move_history, program) {
+                        sync_recorder(&mut recorder, &move_history, turn_start);
+                        session.undo();
+                        session.undo();
                         tt = None;
                         node_table.clear();
                     }
-                    current_player = HUMAN_PLAYER;
+                    current_player = human;
                 }
-                PlayerTurnResult::Finished => return,
+                PlayerTurnResult::Hint => {
+                    hint_turn(&session, config, &mut tt, &node_table, exit_token);
+                }
+                PlayerTurnResult::Save(path) => {
+                    match recorder.snapshot(GameResult::Unfinished).save_sgf(&path) {
+                        Ok(()) => println!("棋谱已保存至 {path}。", path = path.display()),
+                        Err(err) => eprintln!("保存棋谱失败: {err}"),
+                    }
+                }
+                PlayerTurnResult::TimeForfeit => {
+                    let forfeit = Adjudication::TimeForfeit {
+                        moves: recorder.len(),
+                        loser: human,
+                    };
+                    println!("\n{reason}。", reason = forfeit.label());
+                    recorder.adjudicate(forfeit);
+                    break;
+                }
+                PlayerTurnResult::Finished => break,
+            }
+        }
+    }
+    if let Some(path) = resume_path
+        && exit_token.is_cancelled()
+        && !move_history.is_empty()
+        && !is_game_over(config, &board, &session)
+        && recorder.adjudication().is_none()
+    {
+        resume::save(path, config, &move_history, current_player, tt.as_ref());
+    }
+    save_game(config, &board, &session, recorder);
+}
+fn replay_record(record: &GameRecord, config: &Config) -> Result<(Board, Vec<PlayedMove>), String> {
+    if record.board_size != config.board_size {
+        return Err(format!(
+            "棋谱为 {size}x{size} 棋盘，与当前配置不符",
+            size = record.board_size
+        ));
+    }
+    if record.result != GameResult::Unfinished {
+        return Err(String::from("棋谱中的对局已结束"));
+    }
+    let mut board = Board::new(config.board_size);
+    let mut moves = Vec::with_capacity(record.moves.len());
+    for recorded in &record.moves {
+        let (row, column) = recorded.coord();
+        if board.set(recorded.coord(), recorded.player) != Some(0) {
+            return Err(format!("着法 ({row}, {column}) 无效"));
+        }
+        moves.push(PlayedMove {
+            coord: recorded.coord(),
+            player: recorded.player,
+            annotation: recorded.annotation,
+        });
+    }
+    Ok((board, moves))
+}
+fn sync_recorder(recorder: &mut GameRecorder, move_history: &[PlayedMove], turn_start: Instant) {
+    if move_history.len() < recorder.len() {
+        recorder.truncate(move_history.len());
+    }
+    for played_move in move_history.get(recorder.len()..).unwrap_or_default() {
+        recorder.push(played_move.coord, played_move.player, turn_start.elapsed());
+    }
+    for (index, played_move) in move_history.iter().enumerate() {
+        recorder.annotate(index, played_move.annotation);
+    }
+}
+fn save_game(config: &Config, board: &Board, session: &GameSession, recorder: GameRecorder) {
+    if recorder.is_empty() || (config.game_database.is_none() && config.psq_directory.is_none()) {
+        return;
+    }
+    let winner = match session.outcome(&*config.terminal_condition()) {
+        GameOutcome::Won(player) => Some(player),
+        GameOutcome::Ongoing | GameOutcome::Draw => None,
+    };
+    let adjudicated = recorder.adjudication().map(Adjudication::result);
+    let result = adjudicated
+        .or_else(|| result_from_winner(winner))
+        .unwrap_or_else(|| {
+            let repeated = config.repetition_is_draw && session.is_repetition();
+            if repeated || board.is_full() {
+                GameResult::Draw
+            } else {
+                GameResult::Unfinished
             }
+        });
+    let record = recorder.finish(result);
+    if let Some(path) = config.game_database.as_deref() {
+        match GameDatabase::open(path).append(&record) {
+            Ok(()) => println!("对局已保存至对局库 {path}。"),
+            Err(err) => eprintln!("保存对局失败: {err}"),
         }
     }
+    if let Some(directory) = config.psq_directory.as_deref() {
+        let path = Path::new(directory).join(format!(
+            "game_{finished_at}.psq",
+            finished_at = record.finished_at_secs
+        ));
+        match fs::create_dir_all(directory).and_then(|()| record.save_psq(&path)) {
+            Ok(()) => println!("对局已导出为 PSQ 文件 {path}。", path = path.display()),
+            Err(err) => eprintln!("导出 PSQ 文件失败: {err}"),
+        }
+    }
+}
+fn adjudicate(
+    adjudicator: &mut Adjudicator,
+    recorder: &mut GameRecorder,
+    mover: u8,
+    outcome: Option<&SearchOutcome>,
+) -> bool {
+    let Some(adjudication) = adjudicator.observe(recorder.len(), mover, outcome) else {
+        return false;
+    };
+    println!("\n{reason}。", reason = adjudication.label());
+    recorder.adjudicate(adjudication);
+    true
+}
+fn is_game_over(config: &Config, board: &Board, session: &GameSession) -> bool {
+    session.outcome(&*config.terminal_condition()) != GameOutcome::Ongoing
+        || (config.repetition_is_draw && session.is_repetition())
+        || board.is_full()
 }
 fn print_intro(config: &Config) {
     println!(
-        "棋盘大小: {size}x{size}, 获胜条件: {win_len}子连珠",
+        "棋盘大小: {size}x{size}, 获胜条件: {condition}",
         size = config.board_size,
-        win_len = config.win_len
+        condition = config.terminal_condition().describe(config.win_len)
     );
+    if config.rule != Rule::Freestyle {
+        println!("对局规则: {rule}", rule = config.rule.name());
+    }
     println!(
         "使用 {threads} 个线程进行搜索",
         threads = config.num_threads
     );
-    println!("程序执黑 [X] 先手，您执白 [O] 后手");
+    let adjudication = config.adjudication_rules();
+    if adjudication.is_enabled() {
+        println!("对局裁定: {rules}", rules = adjudication.describe());
+    }
+    if let Some(seconds) = config.human_move_seconds {
+        let policy = match config.human_timeout {
+            HumanTimeout::Unlimited => "仅提示，不作处理",
+            HumanTimeout::RandomMove => "随机落子",
+            HumanTimeout::Loss => "判负",
+        };
+        println!("您每手限时 {seconds} 秒，超时{policy}");
+    }
+    if program_player(config) == FIRST_PLAYER {
+        println!("程序执黑 [X] 先手，您执白 [O] 后手");
+    } else {
+        println!("您执黑 [X] 先手，程序执白 [O] 后手");
+    }
+}
+fn is_repetition_draw(config: &Config, session: &GameSession) -> bool {
+    if !config.repetition_is_draw || !session.is_repetition() {
+        return false;
+    }
+    println!("\n局面重复出现，按规则判为和棋。");
+    true
 }
 fn ai_turn(
-    board: &mut [u8],
+    board: &mut Board,
     config: &Config,
-    board_empty: bool,
+    session: &mut GameSession,
     tt: &mut Option<TranspositionTable>,
     node_table: &mut NodeTable,
-    exit_flag: &Arc<AtomicBool>,
+    exit_token: &CancellationToken,
     move_history: &mut Vec<PlayedMove>,
-) -> bool {
-    if exit_flag.load(Ordering::SeqCst) {
-        return true;
+) -> (bool, Option<SearchOutcome>) {
+    if exit_token.is_cancelled() {
+        return (true, None);
     }
     node_table.clear();
     let board_size = config.board_size;
-    let win_len = config.win_len;
-    let num_threads = config.num_threads;
     let verbose = config.verbose;
-    println!("\n轮到程序 (X) 落子。");
-    let selected_move = if board_empty {
+    let rule = config.terminal_condition();
+    let program = program_player(config);
+    println!("\n轮到程序 ({stone}) 落子。", stone = stone_glyph(program));
+    let (selected_move, annotation, turn_budget, outcome) = if !board.has_stones() {
         let Some(center) = board_size.checked_div(2) else {
             eprintln!("棋盘大小无法计算中心点。");
-            return true;
+            return (true, None);
+        };
+        ((center, center), None, None, None)
+    } else if let Err(report) = rule.solver_support(session.scores()) {
+        println!("{report}");
+        let Some(fallback_move) = session.fixed_horizon_move() else {
+            println!("棋盘已无可落子位置。");
+            return (true, None);
         };
-        (center, center)
+        (fallback_move, None, None, None)
     } else {
         println!("程序正在思考...");
-        let params = SearchParams::new(board_size, win_len, num_threads, config.evaluation);
-        let (best_move, new_tt, new_node_table) = ParallelSolver::find_best_move_with_tt_and_stop(
-            board.to_vec(),
+        let show_progress = progress::enabled(verbose);
+        let mut params = config.search_params();
+        if show_progress {
+            params = params.with_progress(progress::renderer(config));
+        }
+        let live_bytes_before = alloc_stats::live_bytes();
+        let result = ParallelSolver::search_session(
+            session,
             params,
             verbose,
-            exit_flag,
+            exit_token,
             tt.take(),
             Some(Arc::clone(node_table)),
         );
-        *tt = Some(new_tt);
-        *node_table = new_node_table;
-        if let Some(best_move_coord) = best_move {
-            best_move_coord
+        if show_progress {
+            progress::clear();
+        }
+        let aborted = match result.report.outcome {
+            SearchOutcome::Aborted { reason } if !exit_token.is_cancelled() => Some(reason),
+            SearchOutcome::Proven { .. }
+            | SearchOutcome::Disproven
+            | SearchOutcome::Unresolved { .. }
+            | SearchOutcome::Aborted { .. } => None,
+        };
+        let selected = result.best_move.or_else(|| {
+            let stop_label = result
+                .limit_reached
+                .map(|limit| format!("搜索预算耗尽（{}）", limit.label()))
+                .or_else(|| aborted.map(|reason| format!("搜索已中止（{}）", reason.label())))?;
+            println!("{stop_label}，采用目前已知的最佳着法。");
+            result
+                .best_known_move
+                .or_else(|| session.fixed_horizon_move())
+        });
+        if aborted == Some(CancelReason::Memory) {
+            *tt = None;
+            node_table.clear();
+        } else {
+            *tt = Some(result.transposition_table);
+            *node_table = result.node_table;
+        }
+        let mate_in = (result.root_pn == 0).then_some(result.root_win_len);
+        if let Some(mate_distance) = mate_in
+            && let Some(human_move) = move_history.last_mut()
+            && human_move.player == human_player(config)
+        {
+            human_move.annotation = Some(MoveAnnotation::Refuted {
+                mate_in: mate_distance,
+            });
+        }
+        if let Some(best_move_coord) = selected {
+            (
+                best_move_coord,
+                Some(MoveAnnotation::Search {
+                    depth: result.depth,
+                    pn: result.root_pn,
+                    dn: result.root_dn,
+                    mate_in,
+                }),
+                Some(turn_budget::summary(&result.report, live_bytes_before)),
+                Some(result.report.outcome),
+            )
         } else {
             println!("搜索已中断。");
-            return true;
+            return (true, None);
         }
     };
-    if exit_flag.load(Ordering::SeqCst) {
-        return true;
+    if exit_token.is_cancelled() {
+        return (true, None);
     }
     println!(
         "程序选择落子于: ({row}, {column})",
         row = selected_move.0,
         column = selected_move.1
     );
-    let move_index = board_index(board_size, selected_move.0, selected_move.1);
-    let Some(cell) = board.get_mut(move_index) else {
+    if let Some(line) = turn_budget {
+        println!("{line}");
+    }
+    if board.set(selected_move, program).is_none() {
         eprintln!(
             "程序落子位置超出棋盘数据范围: ({row}, {column})。",
             row = selected_move.0,
             column = selected_move.1
         );
-        return true;
-    };
-    *cell = PROGRAM_PLAYER;
+        return (true, None);
+    }
     move_history.push(PlayedMove {
         coord: selected_move,
-        player: PROGRAM_PLAYER,
+        player: program,
+        annotation,
     });
-    if check_win(
-        board,
-        board_size,
-        win_len,
-        config.evaluation,
-        PROGRAM_PLAYER,
+    session.play(selected_move, program);
+    (
+        report_outcome(&*rule, board, session, program) || is_repetition_draw(config, session),
+        outcome,
+    )
+}
+fn report_outcome(
+    rule: &dyn TerminalCondition,
+    board: &Board,
+    session: &GameSession,
+    program: u8,
+) -> bool {
+    if rule.continues_after_line() && session.last_move_points() > 0 {
+        let [black_score, white_score] = session.scores();
+        println!("成线得分！当前比分 X {black_score} : {white_score} O");
+    }
+    let message = match session.outcome(rule) {
+        GameOutcome::Ongoing => return false,
+        GameOutcome::Won(winner) if winner == program => "程序获胜",
+        GameOutcome::Won(_) => "您获胜",
+        GameOutcome::Draw => "和棋",
+    };
+    println!("\n最终棋盘:");
+    print_board(board);
+    println!("{message}");
+    true
+}
+fn hint_turn(
+    session: &GameSession,
+    config: &Config,
+    tt: &mut Option<TranspositionTable>,
+    node_table: &NodeTable,
+    exit_token: &CancellationToken,
+) {
+    if let Err(report) = config.terminal_condition().solver_support(session.scores()) {
+        println!("{report}");
+        if let Some((row, column)) = session.fixed_horizon_move() {
+            println!("提示：建议落子于 ({row}, {column})。");
+        }
+        return;
+    }
+    let position = match Position::from_board(
+        session.board().to_vec(),
+        config.board_size,
+        config.win_len,
+        human_player(config),
     ) {
-        println!("\n最终棋盘:");
-        print_board(board, board_size);
-        println!("程序获胜");
-        return true;
+        Ok(position) => position,
+        Err(err) => {
+            eprintln!("无法构造提示局面: {err}");
+            return;
+        }
+    };
+    println!("正在为您搜索提示...");
+    let board_for_search = attacker_board(&position, config.root_player);
+    let root_pos_hash = Position::from_board(
+        board_for_search.clone(),
+        config.board_size,
+        config.win_len,
+        config.root_player,
+    )
+    .map(|root| root.hash())
+    .ok();
+    let params = config.search_params().with_budget(Budget {
+        nodes: Some(config.hint_node_budget),
+        ..config.budget()
+    });
+    let result = ParallelSolver::search(
+        board_for_search,
+        params,
+        false,
+        exit_token,
+        tt.take(),
+        Some(Arc::clone(node_table)),
+    );
+    *tt = Some(result.transposition_table);
+    if let Some((row, column)) = result.best_move
+        && result.root_pn == 0
+    {
+        println!(
+            "提示：落子于 ({row}, {column}) 可在 {mate_in} 手内取胜。",
+            mate_in = result.root_win_len
+        );
+    } else if let Some((row, column)) = root_pos_hash
+        .and_then(|hash| result.node_table.get(&(hash, 0)))
+        .and_then(|root| {
+            root.expanded_children()?
+                .min_by_key(|child_ref| child_ref.node.get_pn())
+                .map(|child_ref| child_ref.mov.coord())
+        })
+        .or(result.most_proving_move)
+    {
+        println!("提示：建议落子于 ({row}, {column})（搜索预算内未找到必胜着法）。");
+    } else {
+        println!("提示搜索已中断。");
     }
-    false
 }
 fn player_turn(
-    board: &mut [u8],
-    board_size: usize,
-    exit_flag: &AtomicBool,
+    board: &mut Board,
+    config: &Config,
+    exit_token: &CancellationToken,
     move_history: &mut Vec<PlayedMove>,
 ) -> PlayerTurnResult {
-    println!("\n轮到您 (O) 落子。");
-    let Some(player_input) = read_player_input(board, board_size, exit_flag) else {
+    let human = human_player(config);
+    println!("\n轮到您 ({stone}) 落子。", stone = stone_glyph(human));
+    let deadline = config.human_move_seconds.and_then(|seconds| {
+        println!("本手限时 {seconds} 秒。");
+        Instant::now().checked_add(Duration::from_secs(seconds))
+    });
+    let Some(mut player_input) = read_player_input(board, exit_token, deadline) else {
         return PlayerTurnResult::Finished;
     };
-    let PlayerInput::Move(player_move) = player_input else {
-        return PlayerTurnResult::TakeBack;
+    if matches!(player_input, PlayerInput::Timeout) {
+        match config.human_timeout {
+            HumanTimeout::Unlimited => {
+                println!("已超过本手限时，继续等待您落子。");
+                let Some(late_input) = read_player_input(board, exit_token, None) else {
+                    return PlayerTurnResult::Finished;
+                };
+                player_input = late_input;
+            }
+            HumanTimeout::RandomMove => {
+                let Some(random_move) = random_empty_cell(board) else {
+                    return PlayerTurnResult::Finished;
+                };
+                println!(
+                    "已超时，随机落子于 ({row}, {column})。",
+                    row = random_move.0,
+                    column = random_move.1
+                );
+                player_input = PlayerInput::Move(random_move);
+            }
+            HumanTimeout::Loss => return PlayerTurnResult::TimeForfeit,
+        }
+    }
+    let player_move = match player_input {
+        PlayerInput::Move(player_move) => player_move,
+        PlayerInput::TakeBack => return PlayerTurnResult::TakeBack,
+        PlayerInput::Hint => return PlayerTurnResult::Hint,
+        PlayerInput::Save(path) => {
+            return PlayerTurnResult::Save(PathBuf::from(
+                path.as_deref().unwrap_or(DEFAULT_SGF_FILE),
+            ));
+        }
+        PlayerInput::Timeout => return PlayerTurnResult::Finished,
     };
-    let move_index = board_index(board_size, player_move.0, player_move.1);
-    let Some(cell) = board.get_mut(move_index) else {
+    if board.set(player_move, human).is_none() {
         eprintln!(
             "玩家落子位置超出棋盘数据范围: ({row}, {column})。",
             row = player_move.0,
             column = player_move.1
         );
         return PlayerTurnResult::Finished;
-    };
-    *cell = HUMAN_PLAYER;
+    }
     move_history.push(PlayedMove {
         coord: player_move,
-        player: HUMAN_PLAYER,
+        player: human,
+        annotation: None,
     });
     PlayerTurnResult::MoveApplied
 }
+fn random_empty_cell(board: &Board) -> Option<Coord> {
+    let empty_cells = board
+        .iter_cells()
+        .filter(|&(_coord, cell)| cell == 0)
+        .map(|(coord, _cell)| coord);
+    rand::seq::IteratorRandom::choose(empty_cells, &mut rand::rng())
+}
 fn take_back_last_player_move(
-    board: &mut [u8],
-    board_size: usize,
+    board: &mut Board,
     move_history: &mut Vec<PlayedMove>,
+    program: u8,
 ) -> bool {
     if move_history.is_empty() {
         println!("当前没有可悔棋步。");
@@ -335,29 +894,26 @@ fn take_back_last_player_move(
         eprintln!("悔棋状态异常：找不到玩家上一手落子。");
         return false;
     };
-    if ai_move.player != PROGRAM_PLAYER {
+    if ai_move.player != program {
         eprintln!("悔棋状态异常：上一手不是程序落子。");
         return false;
     }
-    if player_move.player != HUMAN_PLAYER {
+    if player_move.player == program {
         eprintln!("悔棋状态异常：找不到上一手玩家落子。");
         return false;
     }
-    if !recorded_move_matches(board, board_size, ai_move)
-        || !recorded_move_matches(board, board_size, player_move)
-    {
+    if !recorded_move_matches(board, ai_move) || !recorded_move_matches(board, player_move) {
         return false;
     }
-    clear_recorded_move(board, board_size, ai_move);
-    clear_recorded_move(board, board_size, player_move);
+    clear_recorded_move(board, ai_move);
+    clear_recorded_move(board, player_move);
     move_history.truncate(player_move_index);
     println!("已悔棋，回到您上一手落子前。");
     true
 }
-fn recorded_move_matches(board: &[u8], board_size: usize, played_move: PlayedMove) -> bool {
+fn recorded_move_matches(board: &Board, played_move: PlayedMove) -> bool {
     let (row, column) = played_move.coord;
-    let move_index = board_index(board_size, row, column);
-    let Some(&cell) = board.get(move_index) else {
+    let Some(cell) = board.get(played_move.coord) else {
         eprintln!("悔棋位置超出棋盘数据范围: ({row}, {column})。");
         return false;
     };
@@ -367,30 +923,26 @@ fn recorded_move_matches(board: &[u8], board_size: usize, played_move: PlayedMov
     }
     true
 }
-fn clear_recorded_move(board: &mut [u8], board_size: usize, played_move: PlayedMove) {
+fn clear_recorded_move(board: &mut Board, played_move: PlayedMove) {
     let (row, column) = played_move.coord;
-    let move_index = board_index(board_size, row, column);
-    let Some(cell) = board.get_mut(move_index) else {
+    if board.set(played_move.coord, 0).is_none() {
         eprintln!("悔棋位置超出棋盘数据范围: ({row}, {column})。");
         panic!("悔棋位置超出棋盘数据范围");
-    };
-    *cell = 0;
+    }
 }
-fn check_win(
-    board: &[u8],
-    board_size: usize,
-    win_len: usize,
-    evaluation: crate::config::EvaluationWeights,
-    player: u8,
-) -> bool {
-    let hasher = Arc::new(ZobristHasher::new(board_size));
-    let game_state = GameState::new(
-        board.to_vec(),
-        board_size,
-        hasher,
-        PROGRAM_PLAYER,
-        win_len,
-        evaluation,
-    );
-    GomokuRules::check_win(&game_state.position, player)
+fn attacker_board(position: &Position, attacker: u8) -> Vec<u8> {
+    let swapped = position.side_to_move() != attacker;
+    position
+        .board()
+        .iter()
+        .map(|&cell| match cell {
+            PROGRAM_PLAYER if swapped => HUMAN_PLAYER,
+            HUMAN_PLAYER if swapped => PROGRAM_PLAYER,
+            _ => cell,
+        })
+        .collect()
+}
+fn check_win(board: &[u8], board_size: usize, win_len: usize, player: u8) -> bool {
+    Position::from_board(board.to_vec(), board_size, win_len, PROGRAM_PLAYER)
+        .is_ok_and(|position| position.has_won(player))
 }
@@ -0,0 +1,37 @@
+use crate::{
+    gamedb::{Adjudication, AdjudicationRules},
+    pns::SearchOutcome,
+};
+pub(super) struct Adjudicator {
+    rules: AdjudicationRules,
+    last_proven_at: usize,
+}
+impl Adjudicator {
+    pub(super) const fn new(rules: AdjudicationRules) -> Self {
+        Self {
+            rules,
+            last_proven_at: 0,
+        }
+    }
+    pub(super) fn observe(
+        &mut self,
+        moves: usize,
+        mover: u8,
+        outcome: Option<&SearchOutcome>,
+    ) -> Option<Adjudication> {
+        let win_probability = match outcome {
+            Some(&SearchOutcome::Proven { .. }) => {
+                self.last_proven_at = moves;
+                return None;
+            }
+            Some(&SearchOutcome::Unresolved { heuristic, .. }) => Some(heuristic.probability),
+            Some(&(SearchOutcome::Disproven | SearchOutcome::Aborted { .. })) | None => None,
+        };
+        self.rules.adjudicate(
+            moves,
+            moves.saturating_sub(self.last_proven_at),
+            mover,
+            win_probability,
+        )
+    }
+}
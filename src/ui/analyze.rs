@@ -0,0 +1,1117 @@
+use super::{
+    HUMAN_PLAYER, PROGRAM_PLAYER, PlayedMove, check_win,
+    input::{InputError, read_line_with_exit},
+    print_board,
+};
+use crate::{
+    checked,
+    config::Config,
+    game_state::{Board, Coord, GameState, Move, Position, ZobristHasher},
+    gamedb::{GameRecord, GameRecorder, GameResult, PlayerInfo, result_from_winner},
+    interrupt::CancellationToken,
+    openings::{OpeningBook, OpeningMatch},
+    pns::{
+        Budget, DebugBundle, ExpansionLog, NodeSummary, NodeTable, ParallelSolver, RootMoveFilter,
+        SearchOutcome, ThreadLocalContext, TranspositionTable, TreeSnapshot, probe_line,
+    },
+};
+use alloc::sync::Arc;
+use core::{ops::ControlFlow, time::Duration};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+const BYTES_PER_MB: u64 = 1024 * 1024;
+const DEFAULT_TREE_CHILD_LIMIT: usize = 24;
+const PRINCIPAL_VARIATION_LIMIT: usize = 40;
+const REPLAY_DIVERGENCE_LIMIT: usize = 20;
+const ANALYSIS_PLAYER: &str = "analysis";
+const HELP_TEXT: [&str; 21] = [
+    "可用命令:",
+    "  show                 显示当前局面",
+    "  play <行> <列>       当前行棋方落子",
+    "  undo                 撤销上一手",
+    "  clear                清空棋盘",
+    "  search [nodes=N] [time=秒] [depth=D] [mem=MB] [soft=%] [only=行,列;...] [exclude=行,列;...]  按预算搜索当前局面，可限制根节点着法",
+    "  enumerate <K>        广度优先穷举当前局面之后 K 手内的全部局面，统计各层局面数并将已判定结果写入下次搜索的置换表",
+    "  candidates           列出光标局面的候选着法与评分",
+    "  tt [<行> <列>]       查询光标局面（或其后一手）的置换表条目",
+    "  line <行,列;...>     从搜索根局面沿给定着法序列逐手查询引擎已知的证明状态",
+    "  tree [N]             显示证明树光标节点、主变及至多 N 个子节点（* 标记主变着法）",
+    "  down <行> <列>       光标进入指定子节点",
+    "  up                   光标返回父节点",
+    "  top                  光标返回根节点",
+    "  record <N|off>       之后的搜索记录至多 N 次节点展开顺序",
+    "  replay [save|load <路径>]  单线程重放记录的展开顺序，或保存/读取记录文件",
+    "  bundle [save <路径>] 输出或保存上一次搜索的调试包，可直接附在问题报告中",
+    "  bundle load <路径>   读取调试包，按其中的局面与参数重新搜索以复现",
+    "  psq save|load <路径> 将当前着法序列保存为 Piskvork PSQ 文件，或从 PSQ 文件读取",
+    "  book save <路径>     将上一次搜索置换表中的已证明局面导出为可内存映射的证明库（见配置项 proof_book）",
+    "  quit                 退出分析模式",
+];
+enum Command {
+    Help,
+    Show,
+    Play(Coord),
+    Undo,
+    Clear,
+    Search(Budget, Option<RootMoveFilter>),
+    Enumerate(usize),
+    Candidates,
+    Probe(Option<Coord>),
+    Line(Vec<Coord>),
+    Tree(Option<usize>),
+    Down(Coord),
+    Up,
+    Top,
+    Record(Option<usize>),
+    Replay(ReplayAction),
+    Bundle(BundleAction),
+    Psq(PsqAction),
+    Book(PathBuf),
+    Quit,
+}
+enum ReplayAction {
+    Run,
+    Save(PathBuf),
+    Load(PathBuf),
+}
+enum PsqAction {
+    Save(PathBuf),
+    Load(PathBuf),
+}
+enum BundleAction {
+    Show,
+    Save(PathBuf),
+    Load(PathBuf),
+}
+struct SearchSnapshot {
+    board: Vec<u8>,
+    swapped: bool,
+    transposition_table: TranspositionTable,
+    node_table: NodeTable,
+    tree: Option<TreeSnapshot>,
+}
+struct AnalysisSession<'config> {
+    config: &'config Config,
+    hasher: Arc<ZobristHasher>,
+    openings: Option<OpeningBook>,
+    board: Board,
+    history: Vec<PlayedMove>,
+    snapshot: Option<SearchSnapshot>,
+    seeded_tt: Option<TranspositionTable>,
+    cursor: Vec<Coord>,
+    tree_child_limit: usize,
+    record_limit: Option<usize>,
+    expansion_log: Option<ExpansionLog>,
+    debug_bundle: Option<DebugBundle>,
+}
+pub(super) fn run(exit_token: &CancellationToken, config: &Config) {
+    let mut session = AnalysisSession::new(config);
+    println!(
+        "分析模式: {size}x{size} 棋盘, {win_len} 子连珠。输入 'help' 查看命令。",
+        size = config.board_size,
+        win_len = config.win_len
+    );
+    loop {
+        if exit_token.is_cancelled() {
+            return;
+        }
+        print!("analyze> ");
+        if let Err(err) = io::Write::flush(&mut io::stdout()) {
+            eprintln!("刷新标准输出失败: {err}");
+            return;
+        }
+        let raw_line = match read_line_with_exit(exit_token) {
+            Ok(line) => line,
+            Err(InputError::Exit) => return,
+            Err(InputError::Io) => {
+                println!("读取输入失败。");
+                return;
+            }
+        };
+        if raw_line.is_empty() {
+            return;
+        }
+        let command = match parse_command(raw_line.trim()) {
+            Ok(Some(command)) => command,
+            Ok(None) => continue,
+            Err(err) => {
+                println!("{err}");
+                continue;
+            }
+        };
+        match command {
+            Command::Help => {
+                for line in HELP_TEXT {
+                    println!("{line}");
+                }
+            }
+            Command::Show => session.show(),
+            Command::Play(coord) => session.play(coord),
+            Command::Undo => session.undo(),
+            Command::Clear => session.clear(),
+            Command::Search(budget, root_move_filter) => {
+                session.search(budget, root_move_filter, exit_token);
+            }
+            Command::Enumerate(max_depth) => session.enumerate(max_depth, exit_token),
+            Command::Candidates => session.candidates(),
+            Command::Probe(coord) => session.probe(coord),
+            Command::Line(line) => session.probe_line(&line),
+            Command::Tree(limit) => {
+                if let Some(child_limit) = limit {
+                    session.tree_child_limit = child_limit;
+                }
+                session.tree();
+            }
+            Command::Down(coord) => session.down(coord),
+            Command::Up => {
+                if session.cursor.pop().is_none() {
+                    println!("光标已位于根节点。");
+                }
+                session.tree();
+            }
+            Command::Top => {
+                session.cursor.clear();
+                session.tree();
+            }
+            Command::Record(limit) => {
+                session.record_limit = limit;
+                match limit {
+                    Some(count) => println!("之后的搜索将记录至多 {count} 次节点展开。"),
+                    None => println!("已关闭展开记录。"),
+                }
+            }
+            Command::Replay(action) => session.replay(action),
+            Command::Bundle(action) => session.bundle(action, exit_token),
+            Command::Psq(action) => session.psq(action),
+            Command::Book(path) => session.save_proof_book(&path),
+            Command::Quit => return,
+        }
+    }
+}
+fn parse_command(line: &str) -> Result<Option<Command>, String> {
+    let mut parts = line.split_whitespace();
+    let Some(name) = parts.next() else {
+        return Ok(None);
+    };
+    let args: Vec<&str> = parts.collect();
+    let command = match name.to_ascii_lowercase().as_str() {
+        "help" | "?" => Command::Help,
+        "show" => Command::Show,
+        "play" => Command::Play(parse_coord(&args)?),
+        "undo" => Command::Undo,
+        "clear" => Command::Clear,
+        "search" => {
+            let (budget, root_move_filter) = parse_search_args(&args)?;
+            Command::Search(budget, root_move_filter)
+        }
+        "enumerate" => match *args.as_slice() {
+            [depth_text] => Command::Enumerate(
+                depth_text
+                    .parse::<usize>()
+                    .ok()
+                    .ok_or_else(|| format!("穷举深度无效: {depth_text}"))?,
+            ),
+            _ => return Err(String::from("用法: enumerate <K>")),
+        },
+        "candidates" => Command::Candidates,
+        "tt" => {
+            if args.is_empty() {
+                Command::Probe(None)
+            } else {
+                Command::Probe(Some(parse_coord(&args)?))
+            }
+        }
+        "line" => match *args.as_slice() {
+            [line_text] => Command::Line(parse_coord_list(line_text)?),
+            _ => return Err(String::from("用法: line <行,列;...>")),
+        },
+        "tree" => match *args.as_slice() {
+            [] => Command::Tree(None),
+            [limit_text] => Command::Tree(Some(
+                limit_text
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&limit| limit > 0)
+                    .ok_or_else(|| format!("子节点显示数量无效: {limit_text}"))?,
+            )),
+            _ => return Err(String::from("用法: tree [N]")),
+        },
+        "down" => Command::Down(parse_coord(&args)?),
+        "up" => Command::Up,
+        "top" => Command::Top,
+        "record" => match *args.as_slice() {
+            ["off"] => Command::Record(None),
+            [limit_text] => Command::Record(Some(
+                limit_text
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&limit| limit > 0)
+                    .ok_or_else(|| format!("记录数量无效: {limit_text}"))?,
+            )),
+            _ => return Err(String::from("用法: record <N|off>")),
+        },
+        "replay" => match *args.as_slice() {
+            [] => Command::Replay(ReplayAction::Run),
+            ["save", path] => Command::Replay(ReplayAction::Save(PathBuf::from(path))),
+            ["load", path] => Command::Replay(ReplayAction::Load(PathBuf::from(path))),
+            _ => return Err(String::from("用法: replay [save|load <路径>]")),
+        },
+        "bundle" => match *args.as_slice() {
+            [] => Command::Bundle(BundleAction::Show),
+            ["save", path] => Command::Bundle(BundleAction::Save(PathBuf::from(path))),
+            ["load", path] => Command::Bundle(BundleAction::Load(PathBuf::from(path))),
+            _ => return Err(String::from("用法: bundle [save|load <路径>]")),
+        },
+        "psq" => match *args.as_slice() {
+            ["save", path] => Command::Psq(PsqAction::Save(PathBuf::from(path))),
+            ["load", path] => Command::Psq(PsqAction::Load(PathBuf::from(path))),
+            _ => return Err(String::from("用法: psq save|load <路径>")),
+        },
+        "book" => match *args.as_slice() {
+            ["save", path] => Command::Book(PathBuf::from(path)),
+            _ => return Err(String::from("用法: book save <路径>")),
+        },
+        "quit" | "exit" => Command::Quit,
+        _ => return Err(format!("未知命令: {name}，输入 'help' 查看命令。")),
+    };
+    Ok(Some(command))
+}
+fn parse_coord(args: &[&str]) -> Result<Coord, String> {
+    let &[row_text, column_text] = args else {
+        return Err(String::from("坐标格式错误，请输入 '<行> <列>'。"));
+    };
+    let row = row_text
+        .parse::<usize>()
+        .map_err(|err| format!("行坐标无效: {row_text}, 错误: {err}"))?;
+    let column = column_text
+        .parse::<usize>()
+        .map_err(|err| format!("列坐标无效: {column_text}, 错误: {err}"))?;
+    Move::new((row, column))
+        .map(Move::coord)
+        .map_err(|err| err.to_string())
+}
+fn parse_coord_list(value: &str) -> Result<Vec<Coord>, String> {
+    value
+        .split(';')
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let parts: Vec<&str> = item.split(',').collect();
+            parse_coord(&parts)
+        })
+        .collect()
+}
+fn parse_search_args(args: &[&str]) -> Result<(Budget, Option<RootMoveFilter>), String> {
+    let mut budget = Budget::unlimited();
+    let mut root_move_filter = None;
+    for arg in args {
+        let Some((key, value)) = arg.split_once('=') else {
+            return Err(format!("预算参数格式错误: {arg}，应为 key=value。"));
+        };
+        match key {
+            "only" | "exclude" => {
+                if root_move_filter.is_some() {
+                    return Err(String::from("only 与 exclude 只能指定其中一个。"));
+                }
+                let moves = parse_coord_list(value)?;
+                root_move_filter = Some(if key == "only" {
+                    RootMoveFilter::allow(moves)
+                } else {
+                    RootMoveFilter::deny(moves)
+                });
+            }
+            "nodes" => {
+                budget.nodes = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|err| format!("节点数预算无效: {value}, 错误: {err}"))?,
+                );
+            }
+            "time" => {
+                let seconds = value
+                    .parse::<f64>()
+                    .map_err(|err| format!("时间预算无效: {value}, 错误: {err}"))?;
+                budget.time = Some(
+                    Duration::try_from_secs_f64(seconds)
+                        .map_err(|err| format!("时间预算无效: {value}, 错误: {err}"))?,
+                );
+            }
+            "depth" => {
+                budget.depth = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|err| format!("深度预算无效: {value}, 错误: {err}"))?,
+                );
+            }
+            "mem" => {
+                let megabytes = value
+                    .parse::<u64>()
+                    .map_err(|err| format!("内存预算无效: {value}, 错误: {err}"))?;
+                budget.memory_bytes = Some(megabytes.saturating_mul(BYTES_PER_MB));
+            }
+            "soft" => {
+                budget.soft_memory_percent = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|err| format!("内存软限制百分比无效: {value}, 错误: {err}"))?,
+                );
+            }
+            _ => return Err(format!("未知预算参数: {key}")),
+        }
+    }
+    Ok((budget, root_move_filter))
+}
+fn format_line(line: &[Coord]) -> String {
+    if line.is_empty() {
+        return String::from("(无)");
+    }
+    let moves: Vec<String> = line
+        .iter()
+        .map(|&(row, column)| format!("({row},{column})"))
+        .collect();
+    let suffix = if line.len() >= PRINCIPAL_VARIATION_LIMIT {
+        " ..."
+    } else {
+        ""
+    };
+    format!("{moves}{suffix}", moves = moves.join(" -> "))
+}
+fn format_proof_number(value: u64) -> String {
+    if value == u64::MAX {
+        String::from("∞")
+    } else {
+        value.to_string()
+    }
+}
+const fn proof_status(pn: u64, dn: u64) -> &'static str {
+    if pn == 0 {
+        "已证明"
+    } else if dn == 0 {
+        "已否证"
+    } else {
+        "未定"
+    }
+}
+const fn stone_label(player: u8, swapped: bool) -> &'static str {
+    if (player == PROGRAM_PLAYER) == swapped {
+        "O"
+    } else {
+        "X"
+    }
+}
+impl<'config> AnalysisSession<'config> {
+    fn new(config: &'config Config) -> Self {
+        Self {
+            config,
+            hasher: Arc::new(ZobristHasher::new(config.board_size)),
+            openings: OpeningBook::new(config.board_size),
+            board: Board::new(config.board_size),
+            history: Vec::new(),
+            snapshot: None,
+            seeded_tt: None,
+            cursor: Vec::new(),
+            record_limit: config.record_expansions,
+            expansion_log: None,
+            debug_bundle: None,
+            tree_child_limit: DEFAULT_TREE_CHILD_LIMIT,
+        }
+    }
+    fn side_to_move(&self) -> u8 {
+        self.history.last().map_or(PROGRAM_PLAYER, |last_move| {
+            checked::opponent_player(last_move.player, "AnalysisSession::side_to_move")
+        })
+    }
+    fn invalidate_search(&mut self) {
+        self.snapshot = None;
+        self.seeded_tt = None;
+        self.cursor.clear();
+    }
+    fn show(&self) {
+        print_board(&self.board);
+        println!(
+            "已落 {count} 手，轮到 {side} 行棋。",
+            count = self.history.len(),
+            side = stone_label(self.side_to_move(), false)
+        );
+        if let Some(opening) = self.opening() {
+            println!("开局: {label}", label = opening.label());
+        }
+        let Ok(position) = Position::with_hasher(
+            self.board.as_slice().to_vec(),
+            self.config.board_size,
+            self.config.win_len,
+            self.side_to_move(),
+            Arc::clone(&self.hasher),
+        ) else {
+            return;
+        };
+        for player in [PROGRAM_PLAYER, HUMAN_PLAYER] {
+            let counts = position.pattern_counts(player);
+            println!(
+                "{side} 威胁: 四 {fours}（跳四 {broken_fours}），活三 {open_threes}，眠三 {blocked_threes}，二 {twos}",
+                side = stone_label(player, false),
+                fours = counts.fours,
+                broken_fours = counts.broken_fours,
+                open_threes = counts.open_threes,
+                blocked_threes = counts.blocked_threes,
+                twos = counts.twos
+            );
+        }
+    }
+    fn opening(&self) -> Option<OpeningMatch> {
+        let moves: Vec<(Coord, u8)> = self
+            .history
+            .iter()
+            .map(|played_move| (played_move.coord, played_move.player))
+            .collect();
+        self.openings.as_ref()?.detect(&moves)
+    }
+    fn play(&mut self, coord: Coord) {
+        let player = self.side_to_move();
+        match self.board.get(coord) {
+            None => {
+                println!("坐标超出范围。");
+                return;
+            }
+            Some(0) => {}
+            Some(_) => {
+                println!("该位置已有棋子。");
+                return;
+            }
+        }
+        self.board.set(coord, player);
+        self.history.push(PlayedMove {
+            coord,
+            player,
+            annotation: None,
+        });
+        self.invalidate_search();
+        self.show();
+        if check_win(
+            self.board.as_slice(),
+            self.config.board_size,
+            self.config.win_len,
+            player,
+        ) {
+            println!(
+                "{side} 已连成 {win_len} 子。",
+                side = stone_label(player, false),
+                win_len = self.config.win_len
+            );
+        }
+    }
+    fn undo(&mut self) {
+        let Some(last_move) = self.history.pop() else {
+            println!("当前没有可撤销的着法。");
+            return;
+        };
+        let (row, column) = last_move.coord;
+        if self.board.set(last_move.coord, 0).is_none() {
+            eprintln!("撤销位置超出棋盘数据范围: ({row}, {column})。");
+            return;
+        }
+        self.invalidate_search();
+        self.show();
+    }
+    fn clear(&mut self) {
+        self.board.clear();
+        self.history.clear();
+        self.invalidate_search();
+        println!("棋盘已清空。");
+    }
+    fn oriented_board(&self) -> (Vec<u8>, bool) {
+        if self.side_to_move() == PROGRAM_PLAYER {
+            return (self.board.as_slice().to_vec(), false);
+        }
+        let swapped = self
+            .board
+            .as_slice()
+            .iter()
+            .map(|&cell| match cell {
+                PROGRAM_PLAYER => HUMAN_PLAYER,
+                HUMAN_PLAYER => PROGRAM_PLAYER,
+                _ => cell,
+            })
+            .collect();
+        (swapped, true)
+    }
+    fn search(
+        &mut self,
+        budget: Budget,
+        root_move_filter: Option<RootMoveFilter>,
+        exit_token: &CancellationToken,
+    ) {
+        let config = self.config;
+        let cells = self.board.as_slice();
+        if check_win(cells, config.board_size, config.win_len, PROGRAM_PLAYER)
+            || check_win(cells, config.board_size, config.win_len, HUMAN_PLAYER)
+        {
+            println!("当前局面已分胜负，无需搜索。");
+            return;
+        }
+        let (board, swapped) = self.oriented_board();
+        let side = stone_label(PROGRAM_PLAYER, swapped);
+        println!("正在搜索 {side} 的必胜着法...");
+        let mut params = config.search_params();
+        if !budget.is_unlimited() {
+            params = params.with_budget(budget);
+        }
+        if let Some(filter) = root_move_filter {
+            params = params.with_root_move_filter(filter);
+        }
+        params = params
+            .with_expansion_recording(self.record_limit)
+            .with_root_player(PROGRAM_PLAYER);
+        let bundle_params = params.clone();
+        let result = ParallelSolver::search(
+            board.clone(),
+            params,
+            config.verbose,
+            exit_token,
+            self.seeded_tt.take(),
+            None,
+        );
+        match result.best_move {
+            Some((row, column)) => println!(
+                "{side} 必胜，最佳着法: ({row}, {column})，搜索深度 D={depth}",
+                depth = result.depth
+            ),
+            None => println!(
+                "未找到 {side} 的必胜着法，搜索深度 D={depth}",
+                depth = result.depth
+            ),
+        }
+        if !result.proof_line.is_empty() {
+            let moves: Vec<String> = result
+                .proof_line
+                .iter()
+                .map(|&(row, column)| format!("({row}, {column})"))
+                .collect();
+            println!("必胜路线: {moves}", moves = moves.join(" "));
+        }
+        if let Some(limit) = result.limit_reached {
+            println!("搜索因{label}预算耗尽而停止。", label = limit.label());
+        }
+        if let SearchOutcome::Unresolved { heuristic, .. } = result.report.outcome {
+            println!(
+                "启发式胜率估计（未经证明）: {percent:.1}%（PN/DN 对数比 {ratio:.2}，趋势 {trend:.2}，已被反驳着法 {refuted:.0}%，静态评估 {evaluation:.2}）",
+                percent = heuristic.probability * 100.0_f64,
+                ratio = heuristic.pn_dn_log_ratio,
+                trend = heuristic.pn_dn_trend,
+                refuted = heuristic.refuted_fraction * 100.0_f64,
+                evaluation = heuristic.static_evaluation
+            );
+        }
+        if let Some(filter) = result.root_move_filter.as_ref() {
+            let moves: Vec<String> = filter
+                .moves()
+                .iter()
+                .map(|&(row, column)| format!("({row}, {column})"))
+                .collect();
+            println!(
+                "根节点着法过滤: {label} {moves}",
+                label = filter.label(),
+                moves = moves.join(" ")
+            );
+        }
+        if let Some(log) = result.expansion_log {
+            println!(
+                "已记录 {recorded} 次节点展开，超出记录上限 {dropped} 次。",
+                recorded = log.records.len(),
+                dropped = log.dropped
+            );
+            self.expansion_log = Some(log);
+        }
+        self.debug_bundle = Some(DebugBundle::capture(&board, &bundle_params, &result.report));
+        let root_pos_hash = self.context_for(&board).get_hash();
+        let tree = result
+            .node_table
+            .get(&(root_pos_hash, 0))
+            .map(|root| TreeSnapshot::freeze(&root));
+        self.snapshot = Some(SearchSnapshot {
+            board,
+            swapped,
+            transposition_table: result.transposition_table,
+            node_table: result.node_table,
+            tree,
+        });
+        self.cursor.clear();
+        self.tree();
+    }
+    fn enumerate(&mut self, max_depth: usize, exit_token: &CancellationToken) {
+        let config = self.config;
+        let (board, swapped) = self.oriented_board();
+        let position = match Position::with_hasher(
+            board,
+            config.board_size,
+            config.win_len,
+            PROGRAM_PLAYER,
+            Arc::clone(&self.hasher),
+        ) {
+            Ok(position) => position,
+            Err(err) => {
+                println!("局面无效: {err}");
+                return;
+            }
+        };
+        if position.winner().is_some() {
+            println!("当前局面已分胜负，无需穷举。");
+            return;
+        }
+        let transposition_table = TranspositionTable::default();
+        let summary = transposition_table.enumerate_from(&position, max_depth, exit_token);
+        println!("深度  路径数  局面数  终局胜  和棋");
+        for level in &summary.levels {
+            println!(
+                "{depth:>4}  {paths:>6}  {positions:>6}  {wins:>6}  {draws:>4}",
+                depth = level.depth,
+                paths = level.paths,
+                positions = level.positions,
+                wins = level.wins,
+                draws = level.draws
+            );
+        }
+        if summary.interrupted {
+            println!("穷举已中断。");
+            return;
+        }
+        if summary.exhausted {
+            println!("博弈树已在 {max_depth} 手内穷尽。");
+        }
+        let side = stone_label(PROGRAM_PLAYER, swapped);
+        match summary.root {
+            Some(entry) if entry.pn == 0 => {
+                println!("{side} 必胜，{win_len} 手内取胜。", win_len = entry.win_len);
+            }
+            Some(_) => println!("{side} 无法取胜。"),
+            None => println!("{side} 在 {max_depth} 手内的胜负未定。"),
+        }
+        println!(
+            "已将 {seeded} 个已判定局面写入置换表，将用于下一次 search。",
+            seeded = summary.seeded
+        );
+        self.seeded_tt = Some(transposition_table);
+    }
+    fn replay(&mut self, action: ReplayAction) {
+        match action {
+            ReplayAction::Save(path) => {
+                let Some(log) = self.expansion_log.as_ref() else {
+                    println!("没有可保存的展开记录，请先用 'record <N>' 开启记录后搜索。");
+                    return;
+                };
+                match log.save(&path) {
+                    Ok(()) => println!("展开记录已保存到 {path}", path = path.display()),
+                    Err(err) => println!("保存展开记录失败: {err}"),
+                }
+            }
+            ReplayAction::Load(path) => match ExpansionLog::load(&path) {
+                Ok(log) => {
+                    println!("已读取 {count} 次节点展开记录。", count = log.records.len());
+                    self.expansion_log = Some(log);
+                }
+                Err(err) => println!("读取展开记录失败: {err}"),
+            },
+            ReplayAction::Run => {
+                let Some(log) = self.expansion_log.as_ref() else {
+                    println!("没有可重放的展开记录，请先用 'record <N>' 开启记录后搜索。");
+                    return;
+                };
+                if log.is_truncated() {
+                    println!(
+                        "记录已被截断，仅重放前 {count} 次展开。",
+                        count = log.records.len()
+                    );
+                }
+                let summary = ParallelSolver::replay_expansions(
+                    log,
+                    self.config.search_params().with_root_player(PROGRAM_PLAYER),
+                );
+                println!(
+                    "已单线程重放 {count} 次展开，根节点 PN={pn}，DN={dn}",
+                    count = summary.replayed,
+                    pn = format_proof_number(summary.root_pn),
+                    dn = format_proof_number(summary.root_dn)
+                );
+                if summary.is_faithful() {
+                    println!("重放与记录完全一致。");
+                }
+                for divergence in summary.divergences.iter().take(REPLAY_DIVERGENCE_LIMIT) {
+                    println!("  {divergence}");
+                }
+                let hidden = summary
+                    .divergences
+                    .len()
+                    .saturating_sub(REPLAY_DIVERGENCE_LIMIT);
+                if hidden > 0 {
+                    println!("  ... 另有 {hidden} 处偏差未显示。");
+                }
+            }
+        }
+    }
+    fn bundle(&self, action: BundleAction, exit_token: &CancellationToken) {
+        match action {
+            BundleAction::Show => match self.debug_bundle.as_ref() {
+                Some(bundle) => println!("{blob}", blob = bundle.encode()),
+                None => println!("没有可导出的调试包，请先执行 search。"),
+            },
+            BundleAction::Save(path) => {
+                let Some(bundle) = self.debug_bundle.as_ref() else {
+                    println!("没有可导出的调试包，请先执行 search。");
+                    return;
+                };
+                match fs::write(&path, bundle.encode()) {
+                    Ok(()) => println!("调试包已保存到 {path}", path = path.display()),
+                    Err(err) => println!("保存调试包失败: {err}"),
+                }
+            }
+            BundleAction::Load(path) => {
+                let bundle = match fs::read_to_string(&path)
+                    .map_err(|err| err.to_string())
+                    .and_then(|text| DebugBundle::decode(&text))
+                {
+                    Ok(bundle) => bundle,
+                    Err(err) => {
+                        println!("读取调试包失败: {err}");
+                        return;
+                    }
+                };
+                println!("调试包来自: {engine}", engine = bundle.engine);
+                println!("局面: {position}", position = bundle.position);
+                if let Some(nodes) = bundle
+                    .report
+                    .pointer("/totals/stats/nodes_created")
+                    .and_then(serde_json::Value::as_u64)
+                {
+                    println!("记录的搜索节点数: {nodes}");
+                }
+                println!("正在按调试包中的参数复现搜索...");
+                match bundle.reproduce(exit_token) {
+                    Ok(result) => {
+                        match result.best_move {
+                            Some((row, column)) => println!(
+                                "复现结果: 必胜，最佳着法 ({row}, {column})，搜索深度 D={depth}",
+                                depth = result.depth
+                            ),
+                            None => println!(
+                                "复现结果: 未找到必胜着法，搜索深度 D={depth}",
+                                depth = result.depth
+                            ),
+                        }
+                        println!(
+                            "复现的搜索节点数: {nodes}",
+                            nodes = result.report.totals.stats.nodes_created
+                        );
+                    }
+                    Err(err) => println!("复现失败: {err}"),
+                }
+            }
+        }
+    }
+    fn save_proof_book(&self, path: &Path) {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            println!("没有可导出的证明，请先执行 search。");
+            return;
+        };
+        let proofs = snapshot
+            .transposition_table
+            .proof_snapshot(self.config.board_size);
+        match proofs.save_mapped(path) {
+            Ok(()) => println!(
+                "已将 {count} 个已证明局面写入证明库 {path}",
+                count = proofs.proofs.len(),
+                path = path.display()
+            ),
+            Err(err) => println!("保存证明库失败: {err}"),
+        }
+    }
+    fn psq(&mut self, action: PsqAction) {
+        match action {
+            PsqAction::Save(path) => {
+                let mut recorder = GameRecorder::new(
+                    self.config.board_size,
+                    self.config.win_len,
+                    [
+                        PlayerInfo::human(ANALYSIS_PLAYER),
+                        PlayerInfo::human(ANALYSIS_PLAYER),
+                    ],
+                );
+                for played_move in &self.history {
+                    recorder.push(played_move.coord, played_move.player, Duration::ZERO);
+                }
+                let winner = [PROGRAM_PLAYER, HUMAN_PLAYER].into_iter().find(|&player| {
+                    check_win(
+                        self.board.as_slice(),
+                        self.config.board_size,
+                        self.config.win_len,
+                        player,
+                    )
+                });
+                let result = result_from_winner(winner).unwrap_or_else(|| {
+                    if self.board.is_full() {
+                        GameResult::Draw
+                    } else {
+                        GameResult::Unfinished
+                    }
+                });
+                match recorder.finish(result).save_psq(&path) {
+                    Ok(()) => println!("PSQ 文件已保存到 {path}", path = path.display()),
+                    Err(err) => println!("保存 PSQ 文件失败: {err}"),
+                }
+            }
+            PsqAction::Load(path) => {
+                let record = match GameRecord::load_psq(&path, self.config.win_len) {
+                    Ok(record) => record,
+                    Err(err) => {
+                        println!("{err}");
+                        return;
+                    }
+                };
+                if record.board_size != self.config.board_size {
+                    println!(
+                        "PSQ 棋盘为 {size}x{size}，与当前 {expected}x{expected} 棋盘不符。",
+                        size = record.board_size,
+                        expected = self.config.board_size
+                    );
+                    return;
+                }
+                self.board.clear();
+                self.history.clear();
+                for recorded in &record.moves {
+                    self.board.set(recorded.coord(), recorded.player);
+                    self.history.push(PlayedMove {
+                        coord: recorded.coord(),
+                        player: recorded.player,
+                        annotation: None,
+                    });
+                }
+                self.invalidate_search();
+                println!(
+                    "已从 {path} 读取 {count} 手，结果: {result}",
+                    path = path.display(),
+                    count = record.moves.len(),
+                    result = record.result.label()
+                );
+                self.show();
+            }
+        }
+    }
+    fn context_for(&self, board: &[u8]) -> ThreadLocalContext {
+        let game_state = GameState::new(
+            board.to_vec(),
+            self.config.board_size,
+            Arc::clone(&self.hasher),
+            PROGRAM_PLAYER,
+            self.config.win_len,
+            self.config.evaluation,
+        );
+        ThreadLocalContext::new(game_state, 0)
+    }
+    fn cursor_context(&self) -> (ThreadLocalContext, u8, bool) {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            let (board, swapped) = self.oriented_board();
+            return (self.context_for(&board), PROGRAM_PLAYER, swapped);
+        };
+        let mut ctx = self.context_for(&snapshot.board);
+        let mut player = PROGRAM_PLAYER;
+        for &mov in &self.cursor {
+            ctx.make_move(mov, player);
+            player = checked::opponent_player(player, "AnalysisSession::cursor_context");
+        }
+        (ctx, player, snapshot.swapped)
+    }
+    fn candidates(&self) {
+        let (mut ctx, player, swapped) = self.cursor_context();
+        println!("{side} 的候选着法:", side = stone_label(player, swapped));
+        let mut rank = 0_usize;
+        ctx.for_each_legal_move(player, |(row, column), score| {
+            rank = checked::add_usize(rank, 1_usize, "AnalysisSession::candidates::rank");
+            let score_text = score.map_or_else(|| String::from("-"), |value| format!("{value:.3}"));
+            println!("  #{rank:<3} ({row:2}, {column:2})  评分 {score_text}");
+            ControlFlow::Continue(())
+        });
+        println!("共 {rank} 个候选着法。");
+    }
+    fn probe(&self, coord: Option<Coord>) {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            println!("尚未搜索，置换表为空。请先执行 'search'。");
+            return;
+        };
+        let (mut ctx, mut player, swapped) = self.cursor_context();
+        if let Some(mov) = coord {
+            let (row, column) = mov;
+            let board_size = self.config.board_size;
+            if row >= board_size || column >= board_size {
+                println!("坐标超出范围。");
+                return;
+            }
+            if !ctx.game_state.position.board.is_empty_at((row, column)) {
+                println!("该位置已有棋子。");
+                return;
+            }
+            ctx.make_move(mov, player);
+            player = checked::opponent_player(player, "AnalysisSession::probe");
+        }
+        let hash = ctx.get_canonical_hash();
+        let side = stone_label(player, swapped);
+        match snapshot.transposition_table.get(&(hash, player)) {
+            Some(entry) => println!(
+                "置换表条目 [{side} 行棋]: pn={pn}, dn={dn}, 胜利步数={win_len}, 状态: {status}",
+                pn = format_proof_number(entry.pn),
+                dn = format_proof_number(entry.dn),
+                win_len = format_proof_number(entry.win_len),
+                status = proof_status(entry.pn, entry.dn)
+            ),
+            None => println!("置换表中没有该局面 [{side} 行棋] 的条目。"),
+        }
+    }
+    fn probe_line(&self, line: &[Coord]) {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            println!("尚未搜索，置换表为空。请先执行 'search'。");
+            return;
+        };
+        let ctx = self.context_for(&snapshot.board);
+        let entries = probe_line(
+            &snapshot.transposition_table,
+            &snapshot.node_table,
+            &ctx.game_state,
+            PROGRAM_PLAYER,
+            line,
+        );
+        let mut player = PROGRAM_PLAYER;
+        for (ply, entry) in entries.iter().enumerate() {
+            let path_text = if ply == 0 {
+                String::from("根")
+            } else {
+                format_line(line.get(..ply).unwrap_or_default())
+            };
+            let side = stone_label(player, snapshot.swapped);
+            match *entry {
+                Some(known) => println!(
+                    "  {ply:>2}. {path_text} [{side} 行棋]: pn={pn}, dn={dn}, 胜利步数={win_len}, 状态: {status}",
+                    pn = format_proof_number(known.pn),
+                    dn = format_proof_number(known.dn),
+                    win_len = format_proof_number(known.win_len),
+                    status = proof_status(known.pn, known.dn)
+                ),
+                None => println!("  {ply:>2}. {path_text} [{side} 行棋]: 未知"),
+            }
+            player = checked::opponent_player(player, "AnalysisSession::probe_line");
+        }
+        if let Some(&(row, column)) = line.get(entries.len().saturating_sub(1)) {
+            println!("着法 ({row}, {column}) 非法或对局已结束，查询在此停止。");
+        }
+    }
+    fn cursor_node(snapshot: &SearchSnapshot, path: &[Coord]) -> Option<Arc<NodeSummary>> {
+        snapshot.tree.as_ref()?.resolve(path)
+    }
+    fn tree(&self) {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            println!("尚未搜索，没有可浏览的证明树。请先执行 'search'。");
+            return;
+        };
+        let (Some(tree), Some(node)) = (
+            snapshot.tree.as_ref(),
+            Self::cursor_node(snapshot, &self.cursor),
+        ) else {
+            println!("节点表中找不到光标节点。");
+            return;
+        };
+        let path_text = if self.cursor.is_empty() {
+            String::from("根")
+        } else {
+            format_line(&self.cursor)
+        };
+        let pn = node.pn();
+        let dn = node.dn();
+        println!(
+            "节点 {path_text} [{side} 行棋, 深度 {depth}]: pn={pn_text}, dn={dn_text}, 胜利步数={win_len}, 状态: {status}",
+            side = stone_label(node.player(), snapshot.swapped),
+            depth = node.depth(),
+            pn_text = format_proof_number(pn),
+            dn_text = format_proof_number(dn),
+            win_len = format_proof_number(node.win_len()),
+            status = proof_status(pn, dn)
+        );
+        println!(
+            "  （快照建立于 {age:.1}s 前；各节点数值取自首次查看时，之后保持不变）",
+            age = tree.age().as_secs_f64()
+        );
+        if !node.is_expanded() {
+            println!("  该节点尚未展开。");
+            return;
+        }
+        let children = tree.children(&node);
+        let selector = self.config.move_selector();
+        let principal = tree
+            .principal_child(&node, &selector)
+            .map(|child| child.mov);
+        println!(
+            "  主变: {line}",
+            line =
+                format_line(&tree.principal_variation(&node, &selector, PRINCIPAL_VARIATION_LIMIT))
+        );
+        let proven = tree.proven_moves(&node);
+        if node.is_or_node() && !proven.is_empty() {
+            let moves: Vec<String> = proven
+                .iter()
+                .map(|&((row, column), win_len)| format!("({row}, {column})/{win_len}"))
+                .collect();
+            println!(
+                "  必胜着法（坐标/胜利步数）: {moves}",
+                moves = moves.join(" ")
+            );
+        }
+        let limit = self.tree_child_limit;
+        let shown = children
+            .iter()
+            .enumerate()
+            .filter(|&(index, child)| index < limit || principal == Some(child.mov));
+        let mut shown_count = 0_usize;
+        for (_, child) in shown {
+            shown_count = checked::add_usize(shown_count, 1_usize, "AnalysisSession::tree::shown");
+            let child_pn = child.summary.pn();
+            let child_dn = child.summary.dn();
+            println!(
+                "{marker} ({row:2}, {column:2})  pn={pn_text:>6}  dn={dn_text:>6}  胜利步数={win_len:>3}  {status}",
+                marker = if principal == Some(child.mov) {
+                    '*'
+                } else {
+                    ' '
+                },
+                row = child.mov.0,
+                column = child.mov.1,
+                pn_text = format_proof_number(child_pn),
+                dn_text = format_proof_number(child_dn),
+                win_len = format_proof_number(child.summary.win_len()),
+                status = proof_status(child_pn, child_dn)
+            );
+        }
+        let hidden = children.len().saturating_sub(shown_count);
+        if hidden > 0 {
+            println!("  ... 另有 {hidden} 个子节点未显示，使用 'tree <N>' 调整显示数量。");
+        }
+        let deferred = node.pending_deferred();
+        if deferred > 0 {
+            println!("  ... 另有 {deferred} 个延迟着法尚未生成子节点。");
+        }
+    }
+    fn down(&mut self, coord: Coord) {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            println!("尚未搜索，没有可浏览的证明树。请先执行 'search'。");
+            return;
+        };
+        let mut path = self.cursor.clone();
+        path.push(coord);
+        if Self::cursor_node(snapshot, &path).is_none() {
+            println!(
+                "光标节点没有着法 ({row}, {column}) 对应的子节点。",
+                row = coord.0,
+                column = coord.1
+            );
+            return;
+        }
+        self.cursor = path;
+        self.tree();
+    }
+}
@@ -0,0 +1,127 @@
+use super::{
+    PROGRAM_PLAYER, attacker_board, input::read_position, print_board, progress::format_number,
+};
+use crate::{
+    config::Config,
+    game_state::Coord,
+    interrupt::CancellationToken,
+    pns::{ChildSummary, EngineRun, ParallelSolver, SearchOutcome, SearchParams},
+};
+const SEQUENTIAL_THREADS: usize = 1;
+pub(super) fn run(exit_token: &CancellationToken, config: &Config) {
+    let Some(position) = read_position(exit_token, config) else {
+        return;
+    };
+    if position.winner().is_some() {
+        println!("该局面已分胜负，无需仲裁。");
+        return;
+    }
+    print_board(position.grid());
+    let side = if position.side_to_move() == PROGRAM_PLAYER {
+        "X"
+    } else {
+        "O"
+    };
+    let params = |num_threads| SearchParams {
+        board_size: position.board_size(),
+        win_len: position.win_len(),
+        num_threads,
+        ..config.search_params()
+    };
+    println!(
+        "正在分别以 {parallel} 线程与 {SEQUENTIAL_THREADS} 线程求解 {side} 的必胜着法...",
+        parallel = config.num_threads
+    );
+    let arbitration = ParallelSolver::arbitrate(
+        &attacker_board(&position, config.root_player),
+        params(config.num_threads),
+        params(SEQUENTIAL_THREADS),
+        exit_token,
+    );
+    if exit_token.is_cancelled() {
+        println!("仲裁已取消。");
+        return;
+    }
+    print_engine("引擎 A", &arbitration.primary);
+    print_engine("引擎 B", &arbitration.secondary);
+    println!("仲裁结果: {}", arbitration.verdict.label());
+    let Some(divergence) = arbitration.divergence else {
+        return;
+    };
+    let path_text = if divergence.path.is_empty() {
+        String::from("根")
+    } else {
+        format_line(&divergence.path)
+    };
+    println!("分歧节点 {path_text}:");
+    for diff in &divergence.children {
+        println!(
+            "  ({row:2}, {column:2})  A: {primary}  B: {secondary}",
+            row = diff.mov.0,
+            column = diff.mov.1,
+            primary = format_summary(diff.primary),
+            secondary = format_summary(diff.secondary)
+        );
+    }
+}
+fn format_line(line: &[Coord]) -> String {
+    line.iter()
+        .map(|&(row, column)| format!("({row},{column})"))
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+fn format_summary(summary: Option<ChildSummary>) -> String {
+    summary.map_or_else(
+        || String::from("未生成"),
+        |child| {
+            format!(
+                "pn={pn:>6} dn={dn:>6} 胜利步数={win_len:>3}",
+                pn = format_number(child.pn),
+                dn = format_number(child.dn),
+                win_len = format_number(child.win_len)
+            )
+        },
+    )
+}
+fn format_outcome(outcome: SearchOutcome) -> String {
+    match outcome {
+        SearchOutcome::Proven { best_move, win_len } => {
+            let best_move_text = best_move.map_or_else(
+                || String::from("-"),
+                |(row, column)| format!("({row}, {column})"),
+            );
+            format!("已证明，最佳首步 {best_move_text}，胜利步数 {win_len}")
+        }
+        SearchOutcome::Disproven => String::from("已证伪"),
+        SearchOutcome::Aborted { reason } => format!("已中止（{}）", reason.label()),
+        SearchOutcome::Unresolved {
+            root_pn,
+            root_dn,
+            limit_reached,
+            heuristic,
+        } => {
+            let limit_text = limit_reached
+                .map(|limit| format!("，{}预算耗尽", limit.label()))
+                .unwrap_or_default();
+            format!(
+                "未定 PN={pn} DN={dn}{limit_text}，启发式胜率估计 {percent:.1}%",
+                pn = format_number(root_pn),
+                dn = format_number(root_dn),
+                percent = heuristic.probability * 100.0_f64
+            )
+        }
+    }
+}
+fn print_engine(name: &str, run: &EngineRun) {
+    println!(
+        "{name}（{threads} 线程）: {outcome}，深度 D={depth}，节点 {nodes}，耗时 {elapsed:.3}s",
+        threads = run.num_threads,
+        outcome = format_outcome(run.outcome),
+        depth = run.depth,
+        nodes = run.nodes,
+        elapsed = run.elapsed_secs
+    );
+    if !run.line.is_empty() {
+        println!("  胜利路线: {}", format_line(&run.line));
+    }
+}
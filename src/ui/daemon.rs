@@ -0,0 +1,707 @@
+use super::{attacker_board, metrics::OpenMetrics, progress::format_number};
+use crate::{
+    alloc_stats, checked,
+    config::Config,
+    game_state::{Coord, Position},
+    interrupt::CancellationToken,
+    pns::{
+        ParallelSolver, ProgressCallback, ProofSnapshot, SearchOutcome, SearchParams,
+        SearchProgress, SearchReport, TranspositionTable, TreeStatsSnapshot, to_f64,
+    },
+    utils::available_memory_bytes,
+};
+use alloc::sync::Arc;
+use core::{cmp::Reverse, time::Duration};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+};
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+const FINISHED_HISTORY: usize = 100;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+const QUEUE_DIR: &str = "queue";
+const RUNNING_DIR: &str = "running";
+const DONE_DIR: &str = "done";
+const CHECKPOINT_DIR: &str = "checkpoints";
+const STATUS_FILE: &str = "status.json";
+const METRICS_FILE: &str = "metrics.prom";
+#[derive(Clone, Deserialize, Serialize)]
+struct JobSpec {
+    position: String,
+    #[serde(default)]
+    priority: i64,
+    #[serde(default)]
+    win_len: Option<usize>,
+    #[serde(default)]
+    nodes: Option<u64>,
+    #[serde(default)]
+    time_secs: Option<f64>,
+    #[serde(default)]
+    depth: Option<usize>,
+    #[serde(default)]
+    memory_mb: Option<u64>,
+    #[serde(default)]
+    threads: Option<usize>,
+}
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct JobProgress {
+    depth: Option<usize>,
+    root_pn: u64,
+    root_dn: u64,
+    nodes: u64,
+    elapsed_secs: f64,
+    #[serde(skip)]
+    stats: TreeStatsSnapshot,
+}
+impl From<&SearchProgress> for JobProgress {
+    fn from(progress: &SearchProgress) -> Self {
+        Self {
+            depth: progress.depth,
+            root_pn: progress.root_pn,
+            root_dn: progress.root_dn,
+            nodes: progress.nodes,
+            elapsed_secs: progress.elapsed.as_secs_f64(),
+            stats: progress.stats,
+        }
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum JobStatus {
+    Proven,
+    Disproven,
+    Unresolved,
+    Failed,
+}
+impl JobStatus {
+    const ALL: [Self; 4] = [
+        Self::Proven,
+        Self::Disproven,
+        Self::Unresolved,
+        Self::Failed,
+    ];
+    const fn metric_label(self) -> &'static str {
+        match self {
+            Self::Proven => "proven",
+            Self::Disproven => "disproven",
+            Self::Unresolved => "unresolved",
+            Self::Failed => "failed",
+        }
+    }
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Proven => "必胜",
+            Self::Disproven => "无必胜",
+            Self::Unresolved => "未解决",
+            Self::Failed => "失败",
+        }
+    }
+}
+#[derive(Serialize)]
+struct JobResult {
+    id: String,
+    priority: i64,
+    position: String,
+    status: JobStatus,
+    best_move: Option<Coord>,
+    restored_proofs: usize,
+    error: Option<String>,
+    report: Option<SearchReport>,
+}
+#[derive(Clone, Serialize, Deserialize)]
+struct QueuedJob {
+    id: String,
+    priority: i64,
+}
+#[derive(Clone, Serialize, Deserialize)]
+struct RunningJob {
+    id: String,
+    priority: i64,
+    progress: Option<JobProgress>,
+}
+#[derive(Clone, Serialize, Deserialize)]
+struct FinishedJob {
+    id: String,
+    priority: i64,
+    status: JobStatus,
+    best_move: Option<Coord>,
+    elapsed_secs: Option<f64>,
+}
+#[derive(Default, Serialize, Deserialize)]
+struct DaemonStatus {
+    workers: usize,
+    queued: Vec<QueuedJob>,
+    running: Vec<RunningJob>,
+    finished: Vec<FinishedJob>,
+}
+enum JobEnd {
+    Completed(Box<JobResult>),
+    Interrupted,
+}
+struct JobOutcome {
+    id: String,
+    end: JobEnd,
+}
+type ProgressBoard = Arc<Mutex<HashMap<String, JobProgress>>>;
+type JobGauge = (&'static str, &'static str, fn(&JobProgress) -> f64);
+struct Daemon<'config> {
+    config: &'config Config,
+    dir: PathBuf,
+    running: HashMap<String, i64>,
+    finished: Vec<FinishedJob>,
+    completed: HashMap<&'static str, u64>,
+    progress: ProgressBoard,
+}
+fn subdir(dir: &Path, name: &str) -> PathBuf {
+    dir.join(name)
+}
+fn job_file(dir: &Path, name: &str, id: &str) -> PathBuf {
+    subdir(dir, name).join(format!("{id}.json"))
+}
+fn checkpoint_file(dir: &Path, id: &str, kind: &str) -> PathBuf {
+    subdir(dir, CHECKPOINT_DIR).join(format!("{id}.{kind}.json"))
+}
+fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let staging = path.with_extension("json.tmp");
+    let text = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    fs::write(&staging, text)?;
+    fs::rename(&staging, path)
+}
+fn job_ids(dir: &Path, name: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(subdir(dir, name)) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .filter_map(|path| path.file_stem()?.to_str().map(String::from))
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+fn read_spec(path: &Path) -> Result<JobSpec, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("读取任务文件失败: {err}"))?;
+    serde_json::from_str(&text).map_err(|err| format!("解析任务文件失败: {err}"))
+}
+fn remove_checkpoints(dir: &Path, id: &str) {
+    for kind in ["progress", "proofs"] {
+        let path = checkpoint_file(dir, id, kind);
+        if path.exists()
+            && let Err(err) = fs::remove_file(&path)
+        {
+            eprintln!("删除检查点 {path} 失败: {err}", path = path.display());
+        }
+    }
+}
+fn job_params(
+    config: &Config,
+    spec: &JobSpec,
+    position: &Position,
+) -> Result<SearchParams, String> {
+    let mut budget = config.budget();
+    if let Some(nodes) = spec.nodes {
+        budget.nodes = Some(nodes);
+    }
+    if let Some(seconds) = spec.time_secs {
+        budget.time = Some(
+            Duration::try_from_secs_f64(seconds)
+                .map_err(|_err| String::from("time_secs 必须为非负有限数"))?,
+        );
+    }
+    if let Some(depth) = spec.depth {
+        budget.depth = Some(depth);
+    }
+    if let Some(megabytes) = spec.memory_mb {
+        budget.memory_bytes = Some(megabytes.saturating_mul(BYTES_PER_MB));
+    }
+    let num_threads = spec.threads.unwrap_or(config.num_threads);
+    if num_threads == 0 {
+        return Err(String::from("threads 必须大于 0"));
+    }
+    Ok(SearchParams {
+        board_size: position.board_size(),
+        win_len: position.win_len(),
+        num_threads,
+        ..config.search_params()
+    }
+    .with_budget(budget))
+}
+pub(super) fn check_checkpoints(dir: &Path) -> (usize, Vec<String>) {
+    let Ok(entries) = fs::read_dir(subdir(dir, CHECKPOINT_DIR)) else {
+        return (0, Vec::new());
+    };
+    let mut checked = 0_usize;
+    let mut errors = Vec::new();
+    for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+        if !path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(".proofs.json"))
+        {
+            continue;
+        }
+        checked = checked.saturating_add(1);
+        if let Err(err) = ProofSnapshot::load(&path) {
+            errors.push(format!("{path}: {err}", path = path.display()));
+        }
+    }
+    (checked, errors)
+}
+fn restore_proofs(dir: &Path, id: &str, tt: &TranspositionTable, board_size: usize) -> usize {
+    let path = checkpoint_file(dir, id, "proofs");
+    if !path.exists() {
+        return 0;
+    }
+    match ProofSnapshot::load(&path) {
+        Ok(snapshot) => tt.restore_proofs(&snapshot, board_size).unwrap_or_else(|| {
+            eprintln!("任务 {id} 的证明检查点棋盘大小不匹配，已忽略。");
+            0
+        }),
+        Err(err) => {
+            eprintln!("读取任务 {id} 的证明检查点失败: {err}");
+            0
+        }
+    }
+}
+fn progress_callback(dir: &Path, id: &str, board: &ProgressBoard) -> ProgressCallback {
+    let path = checkpoint_file(dir, id, "progress");
+    let job_id = String::from(id);
+    let progress_board = Arc::clone(board);
+    let last_checkpoint = Mutex::new(None::<Duration>);
+    Arc::new(move |progress: &SearchProgress| {
+        let snapshot = JobProgress::from(progress);
+        progress_board.lock().insert(job_id.clone(), snapshot);
+        let mut last = last_checkpoint.lock();
+        if last
+            .is_some_and(|previous| progress.elapsed.saturating_sub(previous) < CHECKPOINT_INTERVAL)
+        {
+            return;
+        }
+        *last = Some(progress.elapsed);
+        drop(last);
+        if let Err(err) = write_json(&path, &snapshot) {
+            eprintln!("写入任务 {job_id} 的进度检查点失败: {err}");
+        }
+    })
+}
+fn run_job(
+    config: &Config,
+    dir: &Path,
+    id: &str,
+    spec: &JobSpec,
+    board: &ProgressBoard,
+    cancel: &CancellationToken,
+) -> JobEnd {
+    let failed = |error: String| {
+        JobEnd::Completed(Box::new(JobResult {
+            id: String::from(id),
+            priority: spec.priority,
+            position: spec.position.clone(),
+            status: JobStatus::Failed,
+            best_move: None,
+            restored_proofs: 0,
+            error: Some(error),
+            report: None,
+        }))
+    };
+    let position = match Position::parse(&spec.position, spec.win_len.unwrap_or(config.win_len)) {
+        Ok(position) => position,
+        Err(err) => return failed(format!("局面无效: {err}")),
+    };
+    if position.winner().is_some() {
+        return failed(String::from("局面已出现胜负"));
+    }
+    let params = match job_params(config, spec, &position) {
+        Ok(params) => params.with_progress(progress_callback(dir, id, board)),
+        Err(err) => return failed(err),
+    };
+    let tt = TranspositionTable::default();
+    let restored_proofs = restore_proofs(dir, id, &tt, position.board_size());
+    let result = ParallelSolver::search(
+        attacker_board(&position, params.root_player),
+        params,
+        false,
+        cancel,
+        Some(tt),
+        None,
+    );
+    let status = match result.report.outcome {
+        SearchOutcome::Proven { .. } => JobStatus::Proven,
+        SearchOutcome::Disproven => JobStatus::Disproven,
+        SearchOutcome::Aborted { .. } => {
+            let snapshot = result
+                .transposition_table
+                .proof_snapshot(position.board_size());
+            if let Err(err) = snapshot.save(&checkpoint_file(dir, id, "proofs")) {
+                eprintln!("写入任务 {id} 的证明检查点失败: {err}");
+            }
+            return JobEnd::Interrupted;
+        }
+        SearchOutcome::Unresolved { .. } => JobStatus::Unresolved,
+    };
+    JobEnd::Completed(Box::new(JobResult {
+        id: String::from(id),
+        priority: spec.priority,
+        position: spec.position.clone(),
+        status,
+        best_move: result.best_move,
+        restored_proofs,
+        error: None,
+        report: Some(result.report),
+    }))
+}
+impl<'config> Daemon<'config> {
+    fn new(config: &'config Config, dir: &Path) -> io::Result<Self> {
+        for name in [QUEUE_DIR, RUNNING_DIR, DONE_DIR, CHECKPOINT_DIR] {
+            fs::create_dir_all(subdir(dir, name))?;
+        }
+        let daemon = Self {
+            config,
+            dir: dir.to_path_buf(),
+            running: HashMap::new(),
+            finished: Vec::new(),
+            completed: HashMap::new(),
+            progress: Arc::new(Mutex::new(HashMap::new())),
+        };
+        for id in job_ids(dir, RUNNING_DIR) {
+            println!("任务 {id} 上次未完成，重新排队。");
+            daemon.requeue(&id);
+        }
+        Ok(daemon)
+    }
+    fn requeue(&self, id: &str) {
+        if let Err(err) = fs::rename(
+            job_file(&self.dir, RUNNING_DIR, id),
+            job_file(&self.dir, QUEUE_DIR, id),
+        ) {
+            eprintln!("任务 {id} 重新排队失败: {err}");
+        }
+    }
+    fn queued(&mut self) -> Vec<(QueuedJob, JobSpec)> {
+        let mut queued = Vec::new();
+        for id in job_ids(&self.dir, QUEUE_DIR) {
+            let path = job_file(&self.dir, QUEUE_DIR, &id);
+            match read_spec(&path) {
+                Ok(spec) => queued.push((
+                    QueuedJob {
+                        id,
+                        priority: spec.priority,
+                    },
+                    spec,
+                )),
+                Err(err) => {
+                    eprintln!("任务 {id} 无效: {err}");
+                    let result = JobResult {
+                        id: id.clone(),
+                        priority: 0,
+                        position: String::new(),
+                        status: JobStatus::Failed,
+                        best_move: None,
+                        restored_proofs: 0,
+                        error: Some(err),
+                        report: None,
+                    };
+                    self.store_result(&result);
+                    if let Err(remove_err) = fs::remove_file(&path) {
+                        eprintln!("删除无效任务 {id} 失败: {remove_err}");
+                    }
+                }
+            }
+        }
+        queued.sort_by_key(|entry| Reverse(entry.0.priority));
+        queued
+    }
+    fn store_result(&mut self, result: &JobResult) {
+        if let Err(err) = write_json(&job_file(&self.dir, DONE_DIR, &result.id), result) {
+            eprintln!("写入任务 {id} 的结果失败: {err}", id = result.id);
+        }
+        let elapsed_secs = result
+            .report
+            .as_ref()
+            .map(|report| report.totals.elapsed_secs);
+        let completed = self
+            .completed
+            .entry(result.status.metric_label())
+            .or_default();
+        *completed = completed.saturating_add(1);
+        if self.finished.len() >= FINISHED_HISTORY {
+            self.finished.remove(0);
+        }
+        self.finished.push(FinishedJob {
+            id: result.id.clone(),
+            priority: result.priority,
+            status: result.status,
+            best_move: result.best_move,
+            elapsed_secs,
+        });
+    }
+    fn complete(&mut self, outcome: JobOutcome) {
+        let id = outcome.id;
+        self.running.remove(&id);
+        self.progress.lock().remove(&id);
+        match outcome.end {
+            JobEnd::Completed(result) => {
+                println!("任务 {id} 结束: {status}", status = result.status.label());
+                self.store_result(&result);
+                if let Err(err) = fs::remove_file(job_file(&self.dir, RUNNING_DIR, &id)) {
+                    eprintln!("删除运行中任务 {id} 失败: {err}");
+                }
+                remove_checkpoints(&self.dir, &id);
+            }
+            JobEnd::Interrupted => {
+                println!("任务 {id} 被中断，已保存检查点并重新排队。");
+                self.requeue(&id);
+            }
+        }
+    }
+    fn write_status(&self, queued: &[(QueuedJob, JobSpec)]) {
+        let progress = self.progress.lock().clone();
+        let mut running: Vec<RunningJob> = self
+            .running
+            .iter()
+            .map(|(id, &priority)| RunningJob {
+                id: id.clone(),
+                priority,
+                progress: progress.get(id).copied(),
+            })
+            .collect();
+        running.sort_by(|left, right| left.id.cmp(&right.id));
+        if let Err(err) = self.write_metrics(queued.len(), &running) {
+            eprintln!("写入守护进程指标失败: {err}");
+        }
+        let status = DaemonStatus {
+            workers: self.config.daemon_workers,
+            queued: queued.iter().map(|entry| entry.0.clone()).collect(),
+            running,
+            finished: self.finished.clone(),
+        };
+        if let Err(err) = write_json(&subdir(&self.dir, STATUS_FILE), &status) {
+            eprintln!("写入守护进程状态失败: {err}");
+        }
+    }
+    fn write_metrics(&self, queued: usize, running: &[RunningJob]) -> io::Result<()> {
+        let mut metrics = OpenMetrics::new();
+        metrics.gauge(
+            "daemon_workers",
+            "并行任务上限",
+            to_f64(checked::usize_to_u64(
+                self.config.daemon_workers,
+                "Daemon::write_metrics::workers",
+            )),
+        );
+        metrics.gauge(
+            "daemon_queue_depth",
+            "排队任务数",
+            to_f64(checked::usize_to_u64(
+                queued,
+                "Daemon::write_metrics::queued",
+            )),
+        );
+        metrics.gauge(
+            "daemon_running_jobs",
+            "运行中任务数",
+            to_f64(checked::usize_to_u64(
+                running.len(),
+                "Daemon::write_metrics::running",
+            )),
+        );
+        metrics.family("daemon_finished_jobs", "counter", "已完成任务数");
+        for status in JobStatus::ALL {
+            let label = status.metric_label();
+            metrics.counter(
+                "daemon_finished_jobs",
+                &[("status", label)],
+                self.completed.get(label).copied().unwrap_or(0),
+            );
+        }
+        metrics.gauge(
+            "live_heap_bytes",
+            "当前堆内存占用（字节）",
+            to_f64(alloc_stats::live_bytes()),
+        );
+        metrics.gauge(
+            "peak_heap_bytes",
+            "峰值堆内存占用（字节）",
+            to_f64(alloc_stats::peak_live_bytes()),
+        );
+        if let Some(available) = available_memory_bytes() {
+            metrics.gauge(
+                "available_memory_bytes",
+                "系统可用内存（字节）",
+                to_f64(available),
+            );
+        }
+        let jobs: Vec<(&str, JobProgress)> = running
+            .iter()
+            .filter_map(|job| Some((job.id.as_str(), job.progress?)))
+            .collect();
+        let gauges: [JobGauge; 5] = [
+            ("job_depth", "任务当前搜索深度", |progress| {
+                progress.depth.map_or(0.0_f64, |depth| {
+                    to_f64(checked::usize_to_u64(depth, "Daemon::write_metrics::depth"))
+                })
+            }),
+            ("job_root_pn", "任务根节点证明数", |progress| {
+                to_f64(progress.root_pn)
+            }),
+            ("job_root_dn", "任务根节点反证数", |progress| {
+                to_f64(progress.root_dn)
+            }),
+            ("job_nodes", "任务节点数", |progress| {
+                to_f64(progress.nodes)
+            }),
+            (
+                "job_elapsed_seconds",
+                "任务已用时间（秒）",
+                |progress| progress.elapsed_secs,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            metrics.family(name, "gauge", help);
+            for &(id, progress) in &jobs {
+                metrics.sample(name, &[("job", id)], value(&progress));
+            }
+        }
+        let job_stats: Vec<_> = jobs
+            .iter()
+            .map(|&(_, progress)| progress.stats.metrics())
+            .collect();
+        if let Some(first) = job_stats.first() {
+            for (index, &(name, help, _)) in first.iter().enumerate() {
+                let family = format!("search_{name}");
+                metrics.family(&family, "counter", help);
+                for (&(id, _), stats) in jobs.iter().zip(&job_stats) {
+                    let value = stats.get(index).map_or(0, |&(_, _, value)| value);
+                    metrics.counter(&family, &[("job", id)], value);
+                }
+            }
+        }
+        metrics.write(&subdir(&self.dir, METRICS_FILE))
+    }
+}
+pub(super) fn run(exit_token: &CancellationToken, config: &Config, dir: &Path) {
+    let mut daemon = match Daemon::new(config, dir) {
+        Ok(daemon) => daemon,
+        Err(err) => {
+            eprintln!("无法初始化守护进程目录 {dir}: {err}", dir = dir.display());
+            return;
+        }
+    };
+    println!(
+        "守护进程已启动，任务目录: {queue}，并行任务上限: {workers}。按 Ctrl+C 退出。",
+        queue = subdir(dir, QUEUE_DIR).display(),
+        workers = config.daemon_workers
+    );
+    let (sender, receiver) = mpsc::channel::<JobOutcome>();
+    thread::scope(|scope| {
+        loop {
+            if let Ok(outcome) = receiver.recv_timeout(POLL_INTERVAL) {
+                daemon.complete(outcome);
+            }
+            while let Ok(outcome) = receiver.try_recv() {
+                daemon.complete(outcome);
+            }
+            let mut queued = daemon.queued();
+            if !exit_token.is_cancelled() {
+                while daemon.running.len() < config.daemon_workers && !queued.is_empty() {
+                    let (job, spec) = queued.remove(0);
+                    if let Err(err) = fs::rename(
+                        job_file(dir, QUEUE_DIR, &job.id),
+                        job_file(dir, RUNNING_DIR, &job.id),
+                    ) {
+                        eprintln!("无法启动任务 {id}: {err}", id = job.id);
+                        continue;
+                    }
+                    println!(
+                        "开始任务 {id} (优先级 {priority})。",
+                        id = job.id,
+                        priority = job.priority
+                    );
+                    daemon.running.insert(job.id.clone(), job.priority);
+                    let job_sender = sender.clone();
+                    let board = Arc::clone(&daemon.progress);
+                    let cancel = exit_token.child();
+                    scope.spawn(move || {
+                        let end = run_job(config, dir, &job.id, &spec, &board, &cancel);
+                        let _sent = job_sender.send(JobOutcome { id: job.id, end });
+                    });
+                }
+            }
+            daemon.write_status(&queued);
+            if exit_token.is_cancelled() && daemon.running.is_empty() {
+                break;
+            }
+        }
+    });
+    println!("守护进程已退出。");
+}
+pub(super) fn print_status(dir: &Path) {
+    let path = subdir(dir, STATUS_FILE);
+    let status: DaemonStatus = match fs::read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| serde_json::from_str(&text).map_err(|err| err.to_string()))
+    {
+        Ok(status) => status,
+        Err(err) => {
+            eprintln!("无法读取守护进程状态 {path}: {err}", path = path.display());
+            return;
+        }
+    };
+    println!("并行任务上限: {workers}", workers = status.workers);
+    println!("排队任务 ({count}):", count = status.queued.len());
+    for job in &status.queued {
+        println!(
+            "  {id}  优先级 {priority}",
+            id = job.id,
+            priority = job.priority
+        );
+    }
+    println!("运行中任务 ({count}):", count = status.running.len());
+    for job in &status.running {
+        let detail = job.progress.map_or_else(
+            || String::from("尚无进度"),
+            |progress| {
+                format!(
+                    "深度 D={depth}  PN={pn}  DN={dn}  节点 {nodes}  已用 {elapsed:.1}s",
+                    depth = progress
+                        .depth
+                        .map_or_else(|| String::from("-"), |depth| depth.to_string()),
+                    pn = format_number(progress.root_pn),
+                    dn = format_number(progress.root_dn),
+                    nodes = progress.nodes,
+                    elapsed = progress.elapsed_secs
+                )
+            },
+        );
+        println!(
+            "  {id}  优先级 {priority}  {detail}",
+            id = job.id,
+            priority = job.priority
+        );
+    }
+    println!("已完成任务 ({count}):", count = status.finished.len());
+    for job in &status.finished {
+        let best_move = job.best_move.map_or_else(
+            || String::from("-"),
+            |(row, column)| format!("({row}, {column})"),
+        );
+        let elapsed = job
+            .elapsed_secs
+            .map_or_else(|| String::from("-"), |seconds| format!("{seconds:.2}s"));
+        println!(
+            "  {id}  优先级 {priority}  {status}  最佳着法 {best_move}  用时 {elapsed}",
+            id = job.id,
+            priority = job.priority,
+            status = job.status.label()
+        );
+    }
+}
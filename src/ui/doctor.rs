@@ -0,0 +1,179 @@
+use crate::{
+    calibration::{CALIBRATION_FILE, HostProfile},
+    checked,
+    config::Config,
+    pns::LOG_FILE_NAME,
+    utils::available_memory_bytes,
+};
+use std::{
+    fs::{self, OpenOptions},
+    path::Path,
+    thread,
+};
+const CONFIG_FILE: &str = "config.yaml";
+const BYTES_PER_MB: u64 = 1024 * 1024;
+const WRITE_PROBE_FILE: &str = ".inevitable-doctor";
+#[derive(Default)]
+struct Checkup {
+    warnings: usize,
+    failures: usize,
+}
+impl Checkup {
+    fn pass(message: &str) {
+        println!("[通过] {message}");
+    }
+    fn warn(&mut self, message: &str, fix: &str) {
+        self.warnings = self.warnings.saturating_add(1);
+        println!("[警告] {message}");
+        println!("       建议: {fix}");
+    }
+    fn fail(&mut self, message: &str, fix: &str) {
+        self.failures = self.failures.saturating_add(1);
+        println!("[失败] {message}");
+        println!("       修复: {fix}");
+    }
+}
+pub(super) fn run(preset: Option<String>, daemon_dir: &Path) -> bool {
+    let mut checkup = Checkup::default();
+    println!("正在检查运行环境...");
+    check_log_path(&mut checkup);
+    match Config::load_file(Path::new(CONFIG_FILE), preset) {
+        Ok(config) => {
+            Checkup::pass(&format!(
+                "{CONFIG_FILE} 解析成功: {size}x{size} 棋盘，{win_len} 子连珠",
+                size = config.board_size,
+                win_len = config.win_len
+            ));
+            check_memory(&mut checkup, &config);
+            check_threads(&mut checkup, &config);
+            check_calibration(&mut checkup);
+            check_resume_file(&mut checkup, &config);
+        }
+        Err(err) => checkup.fail(
+            &err,
+            &format!("修正 {CONFIG_FILE} 中报错的字段，或删除该字段以使用默认值"),
+        ),
+    }
+    check_daemon_checkpoints(&mut checkup, daemon_dir);
+    println!(
+        "检查完成: {failures} 项失败，{warnings} 项警告。",
+        failures = checkup.failures,
+        warnings = checkup.warnings
+    );
+    checkup.failures == 0
+}
+fn check_log_path(checkup: &mut Checkup) {
+    let result = if Path::new(LOG_FILE_NAME).exists() {
+        OpenOptions::new()
+            .append(true)
+            .open(LOG_FILE_NAME)
+            .map(drop)
+    } else {
+        fs::write(WRITE_PROBE_FILE, b"").and_then(|()| fs::remove_file(WRITE_PROBE_FILE))
+    };
+    match result {
+        Ok(()) => Checkup::pass(&format!("日志文件 {LOG_FILE_NAME} 可写")),
+        Err(err) => checkup.fail(
+            &format!("日志文件 {LOG_FILE_NAME} 不可写: {err}"),
+            "在有写权限的目录中运行程序，或移除只读的 log.csv",
+        ),
+    }
+}
+fn check_memory(checkup: &mut Checkup, config: &Config) {
+    let required = config.min_available_memory_mb.saturating_mul(BYTES_PER_MB);
+    let Some(available) = available_memory_bytes() else {
+        checkup.warn(
+            "无法读取可用内存，内存看门狗将不会生效",
+            "确认 /proc/meminfo 可读（Linux），或自行监控内存占用",
+        );
+        return;
+    };
+    let available_mb = checked::div_u64(available, BYTES_PER_MB, "doctor::check_memory");
+    if available < required {
+        checkup.fail(
+            &format!(
+                "可用内存 {available_mb}MB 低于 min_available_memory_mb={min}MB，程序启动后会立即退出",
+                min = config.min_available_memory_mb
+            ),
+            &format!("关闭其他占用内存的程序，或将 min_available_memory_mb 调低至 {available_mb} 以下"),
+        );
+    } else {
+        Checkup::pass(&format!(
+            "可用内存 {available_mb}MB，高于下限 {min}MB",
+            min = config.min_available_memory_mb
+        ));
+    }
+}
+fn check_threads(checkup: &mut Checkup, config: &Config) {
+    let logical = thread::available_parallelism().map_or(1, core::num::NonZero::get);
+    if config.num_threads > logical {
+        checkup.warn(
+            &format!(
+                "num_threads={threads} 超过本机逻辑线程数 {logical}，线程争用会降低搜索速度",
+                threads = config.num_threads
+            ),
+            &format!("将 num_threads 设为 {logical} 以内，或设为 0 以使用校准推荐值"),
+        );
+    } else {
+        Checkup::pass(&format!(
+            "num_threads={threads}，本机逻辑线程数 {logical}",
+            threads = config.num_threads
+        ));
+    }
+}
+fn check_calibration(checkup: &mut Checkup) {
+    match HostProfile::load(Path::new(CALIBRATION_FILE)) {
+        Ok(Some(profile)) if profile.is_current() => {
+            Checkup::pass(&format!("校准文件 {CALIBRATION_FILE} 与本机匹配"));
+        }
+        Ok(Some(_)) => checkup.warn(
+            &format!("校准文件 {CALIBRATION_FILE} 来自另一台主机，将被忽略"),
+            "运行 `inevitable calibrate` 重新校准",
+        ),
+        Ok(None) => checkup.warn(
+            &format!("尚未生成校准文件 {CALIBRATION_FILE}，首次启动将自动校准"),
+            "运行 `inevitable calibrate` 预先完成校准",
+        ),
+        Err(err) => checkup.fail(
+            &format!("校准文件 {CALIBRATION_FILE} 无法读取: {err}"),
+            &format!("删除 {CALIBRATION_FILE} 后运行 `inevitable calibrate`"),
+        ),
+    }
+}
+fn check_resume_file(checkup: &mut Checkup, config: &Config) {
+    let Some(resume_file) = config.resume_file.as_deref() else {
+        return;
+    };
+    let path = Path::new(resume_file);
+    if !path.exists() {
+        return;
+    }
+    match super::resume::check(path, config) {
+        Ok(proofs) => Checkup::pass(&format!(
+            "未完成对局 {resume_file} 与当前配置匹配，含 {proofs} 条证明"
+        )),
+        Err(err) => checkup.fail(
+            &format!("未完成对局 {resume_file} 无法恢复: {err}"),
+            &format!("恢复原来的 board_size/win_len 设置，或删除 {resume_file}"),
+        ),
+    }
+}
+fn check_daemon_checkpoints(checkup: &mut Checkup, dir: &Path) {
+    let (checked, errors) = super::daemon::check_checkpoints(dir);
+    if checked == 0 {
+        return;
+    }
+    if errors.is_empty() {
+        Checkup::pass(&format!(
+            "守护进程检查点 {checked} 个均可读取 ({dir})",
+            dir = dir.display()
+        ));
+        return;
+    }
+    for err in errors {
+        checkup.fail(
+            &format!("守护进程证明检查点损坏: {err}"),
+            "删除该检查点文件，任务将从头重新搜索",
+        );
+    }
+}
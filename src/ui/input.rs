@@ -1,30 +1,57 @@
-use crate::utils::board_index;
-use core::{
-    sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
+use super::BENCHMARK_BOARD_7X7;
+use crate::{
+    checked,
+    config::Config,
+    game_state::{Board, Position},
+    interrupt::CancellationToken,
 };
-use std::{io, sync::mpsc, thread};
+use core::time::Duration;
+use parking_lot::Mutex;
+use std::{
+    io,
+    sync::{OnceLock, mpsc},
+    thread,
+    time::Instant,
+};
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const COUNTDOWN_STEP_SECS: u64 = 10;
+const COUNTDOWN_FINAL_SECS: u64 = 5;
+type LineReceiver = mpsc::Receiver<io::Result<String>>;
+static STDIN_LINES: OnceLock<Mutex<LineReceiver>> = OnceLock::new();
 pub(super) enum PlayerInput {
     Move((usize, usize)),
     TakeBack,
+    Hint,
+    Save(Option<String>),
+    Timeout,
 }
 pub(super) fn read_player_input(
-    board: &[u8],
-    board_size: usize,
-    exit_flag: &AtomicBool,
+    board: &Board,
+    exit_token: &CancellationToken,
+    deadline: Option<Instant>,
 ) -> Option<PlayerInput> {
+    let mut shown_secs =
+        deadline.map(|limit| whole_secs(limit.saturating_duration_since(Instant::now())));
     loop {
-        if exit_flag.load(Ordering::SeqCst) {
+        if exit_token.is_cancelled() {
             return None;
         }
-        print!("请输入您的落子位置 (行 列)，例如 '3 4'；输入 'tb' 悔棋: ");
+        print!(
+            "请输入您的落子位置 (行 列)，例如 '3 4'；输入 'tb' 悔棋，'hint' 获取提示，'save [文件]' 保存棋谱: "
+        );
         let mut stdout = io::stdout();
         if let Err(err) = io::Write::flush(&mut stdout) {
             eprintln!("刷新标准输出失败: {err}");
             return None;
         }
-        let raw_input = match read_line_with_exit(exit_flag) {
-            Ok(line) => line,
+        let raw_input = match read_line_until(exit_token, deadline, |remaining| {
+            show_countdown(remaining, &mut shown_secs);
+        }) {
+            Ok(Some(line)) => line,
+            Ok(None) => {
+                println!();
+                return Some(PlayerInput::Timeout);
+            }
             Err(InputError::Exit) => return None,
             Err(InputError::Io) => {
                 println!("读取输入失败。");
@@ -35,6 +62,18 @@ pub(super) fn read_player_input(
         if trimmed_input.eq_ignore_ascii_case("tb") {
             return Some(PlayerInput::TakeBack);
         }
+        if trimmed_input.eq_ignore_ascii_case("hint") {
+            return Some(PlayerInput::Hint);
+        }
+        let (command, argument) = trimmed_input
+            .split_once(char::is_whitespace)
+            .unwrap_or((trimmed_input, ""));
+        if command.eq_ignore_ascii_case("save") {
+            let path = argument.trim();
+            return Some(PlayerInput::Save(
+                (!path.is_empty()).then(|| String::from(path)),
+            ));
+        }
         let mut parts = trimmed_input.split_whitespace();
         let Some(row_text) = parts.next() else {
             println!("输入格式错误，请输入两个数字或 'tb'。");
@@ -52,16 +91,11 @@ pub(super) fn read_player_input(
         let column = column_text.parse::<usize>();
         match (row, column) {
             (Ok(row_index), Ok(column_index)) => {
-                if row_index >= board_size || column_index >= board_size {
+                let Some(cell) = board.get((row_index, column_index)) else {
                     println!("坐标超出范围。");
                     continue;
-                }
-                let board_position = board_index(board_size, row_index, column_index);
-                let Some(cell) = board.get(board_position) else {
-                    eprintln!("棋盘数据长度不足，无法读取位置 ({row_index}, {column_index})。");
-                    return None;
                 };
-                if *cell != 0 {
+                if cell != 0 {
                     println!("该位置已有棋子。");
                     continue;
                 }
@@ -73,29 +107,115 @@ pub(super) fn read_player_input(
         }
     }
 }
-enum InputError {
+pub(super) enum InputError {
     Exit,
     Io,
 }
-fn read_line_with_exit(exit_flag: &AtomicBool) -> Result<String, InputError> {
-    let (tx, rx) = mpsc::channel();
-    thread::spawn(move || {
-        let mut input = String::new();
-        let result = io::stdin().read_line(&mut input).map(|_| input);
-        if let Err(err) = tx.send(result) {
-            eprintln!("发送输入结果失败: {err}");
-        }
-    });
+pub(super) fn read_line_with_exit(exit_token: &CancellationToken) -> Result<String, InputError> {
+    read_line_until(exit_token, None, |_remaining| {}).map(Option::unwrap_or_default)
+}
+fn read_line_until<F: FnMut(Duration)>(
+    exit_token: &CancellationToken,
+    deadline: Option<Instant>,
+    mut on_tick: F,
+) -> Result<Option<String>, InputError> {
+    let lines = stdin_lines().lock();
     loop {
-        if exit_flag.load(core::sync::atomic::Ordering::SeqCst) {
+        if exit_token.is_cancelled() {
             return Err(InputError::Exit);
         }
-        match rx.recv_timeout(Duration::from_millis(100)) {
-            Ok(Ok(line)) => return Ok(line),
-            Ok(Err(_)) | Err(mpsc::RecvTimeoutError::Disconnected) => {
-                return Err(InputError::Io);
+        if let Some(limit) = deadline {
+            let remaining = limit.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
             }
+            on_tick(remaining);
+        }
+        match lines.recv_timeout(POLL_INTERVAL) {
+            Ok(Ok(line)) => return Ok(Some(line)),
+            Ok(Err(_)) => return Err(InputError::Io),
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(Some(String::new())),
             Err(mpsc::RecvTimeoutError::Timeout) => {}
         }
     }
 }
+fn stdin_lines() -> &'static Mutex<LineReceiver> {
+    STDIN_LINES.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            loop {
+                let mut input = String::new();
+                let result = io::stdin().read_line(&mut input).map(|_| input);
+                let at_end = !result.as_ref().is_ok_and(|line| !line.is_empty());
+                if let Err(err) = tx.send(result) {
+                    eprintln!("发送输入结果失败: {err}");
+                    return;
+                }
+                if at_end {
+                    return;
+                }
+            }
+        });
+        Mutex::new(rx)
+    })
+}
+fn whole_secs(duration: Duration) -> u64 {
+    checked::add_u64(
+        duration.as_secs(),
+        u64::from(duration.subsec_nanos() > 0),
+        "whole_secs",
+    )
+}
+fn show_countdown(remaining: Duration, shown_secs: &mut Option<u64>) {
+    let secs = whole_secs(remaining);
+    if *shown_secs == Some(secs)
+        || (secs > COUNTDOWN_FINAL_SECS
+            && checked::rem_u64(secs, COUNTDOWN_STEP_SECS, "show_countdown::step") != 0)
+    {
+        return;
+    }
+    *shown_secs = Some(secs);
+    print!("\n（剩余 {secs} 秒）> ");
+    if let Err(err) = io::Write::flush(&mut io::stdout()) {
+        eprintln!("刷新标准输出失败: {err}");
+    }
+}
+pub(super) fn read_position(exit_token: &CancellationToken, config: &Config) -> Option<Position> {
+    loop {
+        print!(
+            "请输入局面（各行以 '/' 分隔，可在末尾附加 X 或 O 指定行棋方；直接回车使用内置残局）: "
+        );
+        if let Err(err) = io::Write::flush(&mut io::stdout()) {
+            eprintln!("刷新标准输出失败: {err}");
+            return None;
+        }
+        let raw_line = match read_line_with_exit(exit_token) {
+            Ok(line) => line,
+            Err(InputError::Exit) => return None,
+            Err(InputError::Io) => {
+                println!("读取输入失败。");
+                return None;
+            }
+        };
+        if raw_line.is_empty() {
+            return None;
+        }
+        let text = raw_line.trim();
+        let parsed = if text.is_empty() {
+            if config.board_size != BENCHMARK_BOARD_7X7.len() {
+                println!(
+                    "内置残局仅支持 {size}x{size} 棋盘，请手动输入局面。",
+                    size = BENCHMARK_BOARD_7X7.len()
+                );
+                continue;
+            }
+            Position::parse(&BENCHMARK_BOARD_7X7.join("/"), config.win_len)
+        } else {
+            Position::parse(text, config.win_len)
+        };
+        match parsed {
+            Ok(position) => return Some(position),
+            Err(err) => println!("局面无效: {err}"),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{config::Config, interrupt::CancellationToken, known_positions::known_positions};
+pub(super) fn run(exit_token: &CancellationToken, config: &Config) -> bool {
+    let positions = known_positions();
+    println!("正在验证 {count} 个已知局面...", count = positions.len());
+    let mut failed = Vec::new();
+    for position in positions {
+        if exit_token.is_cancelled() {
+            println!("验证已中断。");
+            return false;
+        }
+        let check = position.verify(config, exit_token);
+        let status = if check.passed { "通过" } else { "失败" };
+        println!(
+            "[{status}] {name}: 期望 {expected}，实际 {observed}（深度 D={depth}，{nodes} 个节点，{elapsed:.2}s）",
+            name = check.name,
+            expected = check.expected,
+            observed = check.observed,
+            depth = check.depth,
+            nodes = check.nodes,
+            elapsed = check.elapsed_secs
+        );
+        if !check.passed {
+            failed.push(check.name);
+        }
+    }
+    if failed.is_empty() {
+        println!("全部 {count} 个已知局面验证通过。", count = positions.len());
+        true
+    } else {
+        println!(
+            "{count} 个已知局面验证失败: {names}",
+            count = failed.len(),
+            names = failed.join(", ")
+        );
+        false
+    }
+}
@@ -0,0 +1,55 @@
+use std::{fs, io, path::Path};
+const METRIC_PREFIX: &str = "inevitable_";
+pub(super) struct OpenMetrics {
+    lines: Vec<String>,
+}
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+fn format_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|&(key, value)| format!("{key}=\"{value}\"", value = escape_label(value)))
+        .collect();
+    format!("{{{pairs}}}", pairs = pairs.join(","))
+}
+impl OpenMetrics {
+    pub(super) const fn new() -> Self {
+        Self { lines: Vec::new() }
+    }
+    pub(super) fn family(&mut self, name: &str, kind: &str, help: &str) {
+        self.lines
+            .push(format!("# TYPE {METRIC_PREFIX}{name} {kind}"));
+        self.lines
+            .push(format!("# HELP {METRIC_PREFIX}{name} {help}"));
+    }
+    pub(super) fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.lines.push(format!(
+            "{METRIC_PREFIX}{name}{labels} {value}",
+            labels = format_labels(labels)
+        ));
+    }
+    pub(super) fn counter(&mut self, name: &str, labels: &[(&str, &str)], value: u64) {
+        self.lines.push(format!(
+            "{METRIC_PREFIX}{name}_total{labels} {value}",
+            labels = format_labels(labels)
+        ));
+    }
+    pub(super) fn gauge(&mut self, name: &str, help: &str, value: f64) {
+        self.family(name, "gauge", help);
+        self.sample(name, &[], value);
+    }
+    pub(super) fn write(mut self, path: &Path) -> io::Result<()> {
+        self.lines.push(String::from("# EOF"));
+        self.lines.push(String::new());
+        let staging = path.with_extension("prom.tmp");
+        fs::write(&staging, self.lines.join("\n"))?;
+        fs::rename(&staging, path)
+    }
+}
@@ -0,0 +1,98 @@
+use super::human_player;
+use crate::{
+    checked,
+    config::Config,
+    game_state::{Board, Coord, GameState, ZobristHasher},
+    interrupt::{CancelReason, CancellationToken},
+    pns::{ParallelSolver, SearchParams, ThreadLocalContext, TranspositionTable},
+};
+use alloc::sync::Arc;
+use core::ops::ControlFlow;
+use std::thread::{self, JoinHandle};
+pub(super) struct Ponder {
+    stop: CancellationToken,
+    handle: JoinHandle<Option<TranspositionTable>>,
+}
+impl Ponder {
+    pub(super) fn start(
+        config: &Config,
+        board: &Board,
+        tt: Option<TranspositionTable>,
+        exit_token: &CancellationToken,
+    ) -> Option<Self> {
+        if config.ponder_replies == 0 || !board.has_stones() {
+            return None;
+        }
+        let replies = likely_replies(config, board);
+        let reply_count = checked::usize_to_u64(replies.len(), "Ponder::start::reply_count");
+        let nodes_per_reply = config.ponder_node_budget.checked_div(reply_count)?;
+        if nodes_per_reply == 0 {
+            return None;
+        }
+        let mut params = config.search_params().with_quiet(true);
+        params.budget.nodes = Some(nodes_per_reply);
+        params.budget.time = None;
+        let base_board = board.as_slice().to_vec();
+        let stop = exit_token.child();
+        let worker_stop = stop.clone();
+        let handle =
+            thread::spawn(move || ponder_replies(&base_board, &replies, &params, &worker_stop, tt));
+        Some(Self { stop, handle })
+    }
+    pub(super) fn finish(self) -> Option<TranspositionTable> {
+        self.stop.cancel(CancelReason::User);
+        self.handle.join().unwrap_or_else(|_panic| {
+            eprintln!("后台预读线程异常退出。");
+            None
+        })
+    }
+}
+fn likely_replies(config: &Config, board: &Board) -> Vec<Coord> {
+    let game_state = GameState::new(
+        board.as_slice().to_vec(),
+        config.board_size,
+        Arc::new(ZobristHasher::new(config.board_size)),
+        human_player(config),
+        config.win_len,
+        config.evaluation,
+    );
+    let mut ctx = ThreadLocalContext::new(game_state, 0);
+    let mut replies = Vec::with_capacity(config.ponder_replies);
+    ctx.for_each_legal_move(human_player(config), |mov, _score| {
+        replies.push(mov);
+        if replies.len() < config.ponder_replies {
+            ControlFlow::Continue(())
+        } else {
+            ControlFlow::Break(())
+        }
+    });
+    replies
+}
+fn ponder_replies(
+    base_board: &[u8],
+    replies: &[Coord],
+    params: &SearchParams,
+    stop: &CancellationToken,
+    mut tt: Option<TranspositionTable>,
+) -> Option<TranspositionTable> {
+    let board_size = params.board_size;
+    let human = checked::opponent_player(params.root_player, "ponder_replies::human");
+    for &(row, column) in replies {
+        if stop.is_cancelled() {
+            break;
+        }
+        let mut board = base_board.to_vec();
+        let index = checked::add_usize(
+            checked::mul_usize(row, board_size, "ponder_replies::row"),
+            column,
+            "ponder_replies::index",
+        );
+        let Some(cell) = board.get_mut(index) else {
+            continue;
+        };
+        *cell = human;
+        let result = ParallelSolver::search(board, params.clone(), false, stop, tt.take(), None);
+        tt = Some(result.transposition_table);
+    }
+    tt
+}
@@ -0,0 +1,72 @@
+use crate::{
+    checked,
+    config::Config,
+    pns::{ProgressCallback, SearchProgress},
+};
+use alloc::sync::Arc;
+use core::time::Duration;
+use std::io::{self, IsTerminal as _, Write as _};
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+const SPINNER_FRAME_MS: usize = 100;
+pub(super) fn enabled(verbose: bool) -> bool {
+    !verbose && io::stdout().is_terminal()
+}
+pub(super) fn format_number(value: u64) -> String {
+    if value == u64::MAX {
+        String::from("∞")
+    } else {
+        value.to_string()
+    }
+}
+pub(super) fn renderer(config: &Config) -> ProgressCallback {
+    let calibration = config.calibration;
+    let node_budget = config.budget().nodes;
+    let threads = config.num_threads;
+    Arc::new(move |progress: &SearchProgress| {
+        let eta = calibration
+            .zip(node_budget)
+            .and_then(|(host, budget)| host.eta(budget.saturating_sub(progress.nodes), threads));
+        render(progress, eta);
+    })
+}
+fn render(progress: &SearchProgress, eta: Option<Duration>) {
+    let elapsed_ms = usize::try_from(progress.elapsed.as_millis()).unwrap_or(usize::MAX);
+    let frame_count = checked::div_usize(
+        elapsed_ms,
+        SPINNER_FRAME_MS,
+        "ui::progress::render::frame_count",
+    );
+    let frame = SPINNER_FRAMES
+        .get(checked::rem_usize(
+            frame_count,
+            SPINNER_FRAMES.len(),
+            "ui::progress::render::frame",
+        ))
+        .copied()
+        .unwrap_or(' ');
+    let depth = progress
+        .depth
+        .map_or_else(|| String::from("-"), |depth| depth.to_string());
+    let mut stdout = io::stdout().lock();
+    let _ignored = write!(
+        stdout,
+        "\r\x1b[2K{frame} 深度 D={depth}  PN={pn}  DN={dn}  节点 {nodes}  已用 {elapsed:.1}s",
+        pn = format_number(progress.root_pn),
+        dn = format_number(progress.root_dn),
+        nodes = progress.nodes,
+        elapsed = progress.elapsed.as_secs_f64()
+    );
+    if let Some(remaining) = eta {
+        let _ignored_eta = write!(
+            stdout,
+            "  预计剩余 {remaining:.1}s",
+            remaining = remaining.as_secs_f64()
+        );
+    }
+    let _flushed = stdout.flush();
+}
+pub(super) fn clear() {
+    let mut stdout = io::stdout().lock();
+    let _ignored = write!(stdout, "\r\x1b[2K");
+    let _flushed = stdout.flush();
+}
@@ -0,0 +1,370 @@
+use super::input::{InputError, read_line_with_exit};
+use crate::{
+    about::engine_info,
+    checked,
+    config::Config,
+    game_state::{Board, Coord, GameSession, GameState, RENJU_WIN_LEN, Rule, ZobristHasher},
+    interrupt::{CancelReason, CancellationToken},
+    pns::{ParallelSolver, TimeControl, TranspositionTable},
+};
+use alloc::{collections::VecDeque, sync::Arc};
+use core::time::Duration;
+use std::thread;
+const OWN_STONE: u8 = 1;
+const OPPONENT_STONE: u8 = 2;
+const TURN_RESERVE_DIVISOR: u32 = 10;
+const FREESTYLE_RULE: u64 = 0;
+const RENJU_RULE: u64 = 4;
+const STOP_COMMANDS: [&str; 2] = ["STOP", "YXSTOP"];
+enum Command {
+    Start(usize),
+    RectStart,
+    Restart,
+    Turn(Coord),
+    Begin,
+    Board,
+    Info(String, String),
+    TakeBack(Coord),
+    About,
+    End,
+}
+#[derive(Default)]
+struct TimeLimits {
+    turn: Option<Duration>,
+    match_total: Option<Duration>,
+    left: Option<Duration>,
+    memory_bytes: Option<u64>,
+}
+struct ProtocolSession<'config> {
+    config: &'config Config,
+    board: Option<Board>,
+    limits: TimeLimits,
+    rule: Rule,
+    tt: Option<TranspositionTable>,
+    pending: VecDeque<String>,
+}
+pub(super) fn run(exit_token: &CancellationToken, config: &Config) {
+    let mut session = ProtocolSession {
+        config,
+        board: None,
+        limits: TimeLimits::default(),
+        rule: config.rule,
+        tt: None,
+        pending: VecDeque::new(),
+    };
+    while let Some(line) = session.next_line(exit_token) {
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let command = match parse_command(text) {
+            Ok(command) => command,
+            Err(err) => {
+                println!("{err}");
+                continue;
+            }
+        };
+        if matches!(command, Command::End) {
+            return;
+        }
+        session.execute(command, exit_token);
+    }
+}
+impl ProtocolSession<'_> {
+    fn next_line(&mut self, exit_token: &CancellationToken) -> Option<String> {
+        if let Some(line) = self.pending.pop_front() {
+            return (!line.is_empty()).then_some(line);
+        }
+        match read_line_with_exit(exit_token) {
+            Ok(line) => (!line.is_empty()).then_some(line),
+            Err(InputError::Exit) => None,
+            Err(InputError::Io) => {
+                eprintln!("读取协议输入失败。");
+                None
+            }
+        }
+    }
+    fn execute(&mut self, command: Command, exit_token: &CancellationToken) {
+        match command {
+            Command::Start(board_size) => self.start(board_size),
+            Command::RectStart => println!("ERROR 不支持矩形棋盘"),
+            Command::Restart => {
+                if let Some(board) = self.board.as_mut() {
+                    board.clear();
+                    println!("OK");
+                } else {
+                    println!("ERROR 尚未收到 START");
+                }
+            }
+            Command::Turn(coord) => {
+                if self.place(coord, OPPONENT_STONE) {
+                    self.respond(exit_token);
+                }
+            }
+            Command::Begin => self.respond(exit_token),
+            Command::Board => {
+                if self.read_board(exit_token) {
+                    self.respond(exit_token);
+                }
+            }
+            Command::Info(key, value) => self.info(&key, &value),
+            Command::TakeBack(coord) => {
+                if self.place(coord, 0) {
+                    println!("OK");
+                }
+            }
+            Command::About => {
+                let info = engine_info();
+                println!(
+                    "name=\"{name}\", version=\"{version}\"",
+                    name = info.name,
+                    version = info.version
+                );
+            }
+            Command::End => {}
+        }
+    }
+    fn start(&mut self, board_size: usize) {
+        if self.config.points_to_win.is_some() {
+            println!("ERROR 协议模式仅支持标准连珠规则");
+            return;
+        }
+        if let Err(err) = GameState::validate_shape(board_size, self.config.win_len) {
+            println!("ERROR {err}");
+            return;
+        }
+        if self
+            .board
+            .as_ref()
+            .is_none_or(|board| board.rows() != board_size)
+        {
+            self.tt = None;
+        }
+        self.board = Some(Board::new(board_size));
+        println!("OK");
+    }
+    fn info(&mut self, key: &str, value: &str) {
+        let Ok(number) = value.parse::<u64>() else {
+            return;
+        };
+        let positive = (number > 0).then_some(number);
+        match key.to_ascii_lowercase().as_str() {
+            "timeout_turn" => self.limits.turn = Some(Duration::from_millis(number)),
+            "timeout_match" => self.limits.match_total = positive.map(Duration::from_millis),
+            "time_left" => self.limits.left = Some(Duration::from_millis(number)),
+            "max_memory" => self.limits.memory_bytes = positive,
+            "rule" => self.set_rule(number),
+            _ => {}
+        }
+    }
+    fn set_rule(&mut self, number: u64) {
+        let rule = match number {
+            FREESTYLE_RULE => Rule::Freestyle,
+            RENJU_RULE if self.config.win_len == RENJU_WIN_LEN => Rule::Renju,
+            _ => {
+                println!(
+                    "MESSAGE 不支持的规则 rule={number}，沿用{name}",
+                    name = self.rule.name()
+                );
+                return;
+            }
+        };
+        if rule != self.rule {
+            self.rule = rule;
+            self.tt = None;
+        }
+    }
+    fn place(&mut self, (row, column): Coord, stone: u8) -> bool {
+        let Some(board) = self.board.as_mut() else {
+            println!("ERROR 尚未收到 START");
+            return false;
+        };
+        let Some(cell) = board.get((row, column)) else {
+            println!("ERROR 坐标 {column},{row} 超出棋盘");
+            return false;
+        };
+        if (stone == 0) == (cell == 0) {
+            println!("ERROR 坐标 {column},{row} 状态与指令不符");
+            return false;
+        }
+        board.set((row, column), stone).is_some()
+    }
+    fn read_board(&mut self, exit_token: &CancellationToken) -> bool {
+        let Some(board_size) = self.board.as_ref().map(Board::rows) else {
+            println!("ERROR 尚未收到 START");
+            return false;
+        };
+        let mut board = Board::new(board_size);
+        let mut valid = true;
+        while let Some(line) = self.next_line(exit_token) {
+            let text = line.trim();
+            if text.eq_ignore_ascii_case("DONE") {
+                if valid {
+                    self.board = Some(board);
+                } else {
+                    println!("ERROR BOARD 指令含有无效的落子");
+                }
+                return valid;
+            }
+            let parsed = parse_board_entry(text);
+            valid &= parsed.is_some_and(|(coord, stone)| {
+                board.get(coord) == Some(0) && board.set(coord, stone).is_some()
+            });
+        }
+        false
+    }
+    fn respond(&mut self, exit_token: &CancellationToken) {
+        let Some(coord) = self.think(exit_token) else {
+            if !exit_token.is_cancelled() {
+                println!("ERROR 没有可落子的位置");
+            }
+            return;
+        };
+        if self.place(coord, OWN_STONE) {
+            let (row, column) = coord;
+            println!("{column},{row}");
+        }
+    }
+    fn think(&mut self, exit_token: &CancellationToken) -> Option<Coord> {
+        let board = self.board.as_ref()?;
+        let board_size = board.rows();
+        if !board.has_stones() {
+            let center = checked::div_usize(board_size, 2, "protocol::think::center");
+            return Some((center, center));
+        }
+        let mut params = self
+            .config
+            .search_params()
+            .with_quiet(true)
+            .with_rule(self.rule)
+            .with_root_player(OWN_STONE);
+        params.board_size = board_size;
+        if let Some(turn) = self.limits.turn {
+            let reserve = turn.checked_div(TURN_RESERVE_DIVISOR).unwrap_or_default();
+            let allowed = turn.saturating_sub(reserve);
+            params.budget.time = Some(params.budget.time.map_or(allowed, |time| time.min(allowed)));
+        }
+        if let Some(bytes) = self.limits.memory_bytes {
+            params.budget.memory_bytes = Some(
+                params
+                    .budget
+                    .memory_bytes
+                    .map_or(bytes, |limit| limit.min(bytes)),
+            );
+        }
+        if self.limits.match_total.is_some()
+            && let Some(remaining) = self.limits.left
+        {
+            params = params.with_time_control(TimeControl {
+                remaining,
+                increment: Duration::ZERO,
+                moves_to_go: None,
+            });
+        }
+        let stop = exit_token.child();
+        let done = CancellationToken::new();
+        let initial_board = board.as_slice().to_vec();
+        let existing_tt = self.tt.take();
+        let (best_move, transposition_table, deferred) = thread::scope(|scope| {
+            let monitor = scope.spawn(|| watch_for_stop(&stop, &done));
+            let (best_move, _outcome, transposition_table, _node_table) =
+                ParallelSolver::find_best_move_with_tt_and_stop(
+                    initial_board,
+                    params,
+                    false,
+                    &stop,
+                    existing_tt,
+                    None,
+                );
+            done.cancel(CancelReason::User);
+            let deferred = monitor.join().unwrap_or_else(|_panic| {
+                eprintln!("协议输入监视线程异常退出。");
+                Vec::new()
+            });
+            (best_move, transposition_table, deferred)
+        });
+        self.pending.extend(deferred);
+        self.tt = Some(transposition_table);
+        if exit_token.is_cancelled() {
+            return None;
+        }
+        best_move.or_else(|| self.fallback_move())
+    }
+    fn fallback_move(&self) -> Option<Coord> {
+        let board = self.board.as_ref()?;
+        let board_size = board.rows();
+        let session = GameSession::new(
+            GameState::new(
+                board.as_slice().to_vec(),
+                board_size,
+                Arc::new(ZobristHasher::new(board_size)),
+                OWN_STONE,
+                self.config.win_len,
+                self.config.evaluation,
+            ),
+            OWN_STONE,
+        );
+        session.fixed_horizon_move()
+    }
+}
+fn watch_for_stop(stop: &CancellationToken, done: &CancellationToken) -> Vec<String> {
+    let mut deferred = Vec::new();
+    while let Ok(line) = read_line_with_exit(done) {
+        let text = line.trim();
+        if line.is_empty() || text.eq_ignore_ascii_case("END") {
+            stop.cancel(CancelReason::User);
+            deferred.push(line);
+            break;
+        }
+        if STOP_COMMANDS
+            .iter()
+            .any(|command| text.eq_ignore_ascii_case(command))
+        {
+            stop.cancel(CancelReason::User);
+        } else {
+            deferred.push(line);
+        }
+    }
+    deferred
+}
+fn parse_point(text: &str) -> Result<Coord, String> {
+    let invalid = || format!("ERROR 无法解析坐标 {text}");
+    let (column_text, row_text) = text.split_once(',').ok_or_else(invalid)?;
+    let row = row_text.trim().parse().map_err(|_err| invalid())?;
+    let column = column_text.trim().parse().map_err(|_err| invalid())?;
+    Ok((row, column))
+}
+fn parse_board_entry(text: &str) -> Option<(Coord, u8)> {
+    let (point, stone_text) = text.rsplit_once(',')?;
+    let coord = parse_point(point).ok()?;
+    match stone_text.trim().parse() {
+        Ok(stone @ (OWN_STONE | OPPONENT_STONE)) => Some((coord, stone)),
+        _ => None,
+    }
+}
+fn parse_command(text: &str) -> Result<Command, String> {
+    let (keyword, argument) = text
+        .split_once(char::is_whitespace)
+        .map_or((text, ""), |(keyword, rest)| (keyword, rest.trim()));
+    match keyword.to_ascii_uppercase().as_str() {
+        "START" => argument
+            .parse()
+            .map(Command::Start)
+            .map_err(|_err| format!("ERROR 无法解析棋盘大小 {argument}")),
+        "RECTSTART" => Ok(Command::RectStart),
+        "RESTART" => Ok(Command::Restart),
+        "TURN" => parse_point(argument).map(Command::Turn),
+        "BEGIN" => Ok(Command::Begin),
+        "BOARD" => Ok(Command::Board),
+        "INFO" => {
+            let (key, value) = argument
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("ERROR 无法解析 INFO {argument}"))?;
+            Ok(Command::Info(String::from(key), String::from(value.trim())))
+        }
+        "TAKEBACK" => parse_point(argument).map(Command::TakeBack),
+        "ABOUT" => Ok(Command::About),
+        "END" => Ok(Command::End),
+        _ => Err(format!("UNKNOWN {keyword}")),
+    }
+}
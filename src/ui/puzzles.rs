@@ -0,0 +1,120 @@
+use super::{attacker_board, input::read_position, trainer};
+use crate::{
+    checked,
+    config::Config,
+    game_state::{Coord, Position},
+    interrupt::CancellationToken,
+    pns::{Budget, BudgetLimit, ParallelSolver, RootMoveFilter, SearchParams},
+    puzzles::PuzzleSet,
+};
+use core::ops::RangeInclusive;
+use std::path::Path;
+const PUZZLE_LIMIT: usize = 100;
+const VERIFY_NODES: u64 = 200_000;
+pub(super) fn run(
+    exit_token: &CancellationToken,
+    config: &Config,
+    output: &Path,
+    win_len_range: &RangeInclusive<u64>,
+) {
+    let Some(position) = read_position(exit_token, config) else {
+        return;
+    };
+    if position.winner().is_some() {
+        println!("该局面已分胜负，无法用于出题。");
+        return;
+    }
+    println!("正在证明局面...");
+    let Some(root) = trainer::prove(config, &position, exit_token) else {
+        if !exit_token.is_cancelled() {
+            println!("无法证明行棋方在该局面必胜，无法出题。");
+        }
+        return;
+    };
+    println!("正在从证明树中挑选唯一解局面...");
+    let mined = crate::puzzles::mine(
+        &root,
+        &position,
+        win_len_range,
+        PUZZLE_LIMIT,
+        &config.move_selector(),
+        |candidate, solution, win_len| {
+            !exit_token.is_cancelled()
+                && verify_unique(config, candidate, solution, win_len, exit_token)
+        },
+    );
+    if mined.is_empty() {
+        println!(
+            "证明树中没有胜利步数在 {min}-{max} 之间且必胜着法唯一的局面。",
+            min = win_len_range.start(),
+            max = win_len_range.end()
+        );
+        return;
+    }
+    let mut set = if output.exists() {
+        match PuzzleSet::load(output) {
+            Ok(set)
+                if set.board_size == position.board_size() && set.win_len == position.win_len() =>
+            {
+                set
+            }
+            Ok(_) => {
+                eprintln!(
+                    "题库 {path} 的棋盘规格与当前局面不符，未写入。",
+                    path = output.display()
+                );
+                return;
+            }
+            Err(err) => {
+                eprintln!("读取题库 {path} 失败: {err}", path = output.display());
+                return;
+            }
+        }
+    } else {
+        PuzzleSet::new(position.board_size(), position.win_len())
+    };
+    let found = mined.len();
+    let added = set.merge(mined);
+    if let Err(err) = set.save(output) {
+        eprintln!("写入题库 {path} 失败: {err}", path = output.display());
+        return;
+    }
+    println!(
+        "找到 {found} 道题目，新增 {added} 道，题库 {path} 共 {total} 道。",
+        path = output.display(),
+        total = set.puzzles.len()
+    );
+}
+fn verify_unique(
+    config: &Config,
+    position: &Position,
+    solution: Coord,
+    win_len: u64,
+    exit_token: &CancellationToken,
+) -> bool {
+    let mut params = SearchParams {
+        board_size: position.board_size(),
+        win_len: position.win_len(),
+        ..config.search_params()
+    }
+    .with_budget(Budget {
+        depth: Some(checked::u64_to_usize(
+            win_len,
+            "puzzles::verify_unique::depth",
+        )),
+        nodes: Some(VERIFY_NODES),
+        ..Budget::unlimited()
+    })
+    .with_root_move_filter(RootMoveFilter::deny([solution]));
+    params.overlay_stream = None;
+    let result = ParallelSolver::search(
+        attacker_board(position, config.root_player),
+        params,
+        false,
+        exit_token,
+        None,
+        None,
+    );
+    result.best_move.is_none()
+        && (result.root_dn == 0 || result.limit_reached == Some(BudgetLimit::Depth))
+}
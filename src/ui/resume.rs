@@ -0,0 +1,173 @@
+use super::{
+    HUMAN_PLAYER, PROGRAM_PLAYER, PlayedMove, check_win,
+    input::{InputError, read_line_with_exit},
+};
+use crate::{
+    config::Config,
+    game_state::Board,
+    interrupt::CancellationToken,
+    pns::{ProofSnapshot, TranspositionTable},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write as _},
+    path::Path,
+};
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    board_size: usize,
+    win_len: usize,
+    side_to_move: u8,
+    moves: Vec<PlayedMove>,
+    proofs: ProofSnapshot,
+}
+pub(super) struct ResumedGame {
+    pub(super) board: Board,
+    pub(super) moves: Vec<PlayedMove>,
+    pub(super) side_to_move: u8,
+    pub(super) tt: Option<TranspositionTable>,
+}
+pub(super) fn save(
+    path: &Path,
+    config: &Config,
+    moves: &[PlayedMove],
+    side_to_move: u8,
+    tt: Option<&TranspositionTable>,
+) {
+    let saved = SavedGame {
+        board_size: config.board_size,
+        win_len: config.win_len,
+        side_to_move,
+        moves: moves.to_vec(),
+        proofs: tt.map_or_else(ProofSnapshot::default, |table| {
+            table.proof_snapshot(config.board_size)
+        }),
+    };
+    let written = File::create(path).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, &saved).map_err(io::Error::other)?;
+        writer.flush()
+    });
+    match written {
+        Ok(()) => println!(
+            "未完成的对局已保存至 {path}，下次启动时可继续。",
+            path = path.display()
+        ),
+        Err(err) => eprintln!("保存未完成对局失败: {err}"),
+    }
+}
+fn load(path: &Path) -> io::Result<SavedGame> {
+    let reader = BufReader::new(File::open(path)?);
+    serde_json::from_reader(reader).map_err(io::Error::other)
+}
+fn replay_board(saved: &SavedGame, config: &Config) -> Result<Board, String> {
+    if saved.board_size != config.board_size || saved.win_len != config.win_len {
+        return Err(format!(
+            "对局规格为 {size}x{size}、{win_len} 子连珠，与当前配置不符",
+            size = saved.board_size,
+            win_len = saved.win_len
+        ));
+    }
+    let mut board = Board::new(config.board_size);
+    let mut expected_player = PROGRAM_PLAYER;
+    for played_move in &saved.moves {
+        let (row, column) = played_move.coord;
+        if played_move.player != expected_player {
+            return Err(format!("着法 ({row}, {column}) 的行棋方顺序错误"));
+        }
+        match board.set(played_move.coord, played_move.player) {
+            None => return Err(format!("着法 ({row}, {column}) 超出棋盘")),
+            Some(0) => {}
+            Some(_) => return Err(format!("着法 ({row}, {column}) 落在已有棋子上")),
+        }
+        if !config.terminal_condition().continues_after_line()
+            && check_win(
+                board.as_slice(),
+                config.board_size,
+                config.win_len,
+                played_move.player,
+            )
+        {
+            return Err(String::from("对局已分胜负"));
+        }
+        expected_player = if expected_player == PROGRAM_PLAYER {
+            HUMAN_PLAYER
+        } else {
+            PROGRAM_PLAYER
+        };
+    }
+    if saved.side_to_move != expected_player {
+        return Err(String::from("行棋方与着法记录不一致"));
+    }
+    Ok(board)
+}
+fn confirm(exit_token: &CancellationToken, move_count: usize) -> bool {
+    loop {
+        print!("检测到未完成的对局（已落 {move_count} 手），是否继续？(y/n): ");
+        if let Err(err) = io::Write::flush(&mut io::stdout()) {
+            eprintln!("刷新标准输出失败: {err}");
+            return false;
+        }
+        let answer = match read_line_with_exit(exit_token) {
+            Ok(line) => line,
+            Err(InputError::Exit | InputError::Io) => return false,
+        };
+        match answer.trim() {
+            "y" | "Y" => return true,
+            "n" | "N" => return false,
+            _ => println!("请输入 y 或 n。"),
+        }
+    }
+}
+pub(super) fn check(path: &Path, config: &Config) -> Result<usize, String> {
+    let saved = load(path).map_err(|err| format!("读取失败: {err}"))?;
+    replay_board(&saved, config)?;
+    if !saved.proofs.proofs.is_empty() && saved.proofs.board_size != config.board_size {
+        return Err(format!(
+            "证明快照棋盘大小为 {size}，与当前配置 {expected} 不符",
+            size = saved.proofs.board_size,
+            expected = config.board_size
+        ));
+    }
+    Ok(saved.proofs.proofs.len())
+}
+pub(super) fn offer(
+    path: &Path,
+    config: &Config,
+    exit_token: &CancellationToken,
+) -> Option<ResumedGame> {
+    if !path.exists() {
+        return None;
+    }
+    let resumed = match load(path) {
+        Ok(saved) => match replay_board(&saved, config) {
+            Ok(board) if confirm(exit_token, saved.moves.len()) => {
+                let tt = TranspositionTable::default();
+                let restored = tt
+                    .restore_proofs(&saved.proofs, config.board_size)
+                    .unwrap_or_default();
+                println!("已恢复对局，载入 {restored} 条证明。");
+                Some(ResumedGame {
+                    board,
+                    moves: saved.moves,
+                    side_to_move: saved.side_to_move,
+                    tt: (restored > 0).then_some(tt),
+                })
+            }
+            Ok(_) => None,
+            Err(err) => {
+                eprintln!("未完成对局 {path} 无效: {err}", path = path.display());
+                None
+            }
+        },
+        Err(err) => {
+            eprintln!("读取未完成对局 {path} 失败: {err}", path = path.display());
+            None
+        }
+    };
+    if let Err(err) = fs::remove_file(path) {
+        eprintln!("删除未完成对局 {path} 失败: {err}", path = path.display());
+    }
+    resumed
+}
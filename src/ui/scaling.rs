@@ -0,0 +1,107 @@
+use super::{BENCHMARK_RUNS, fixed_benchmark_board};
+use crate::{
+    checked,
+    config::Config,
+    interrupt::CancellationToken,
+    pns::{ParallelSolver, SearchParams, to_f64},
+};
+const KNEE_MARGINAL_EFFICIENCY: f64 = 0.5;
+struct ScalingSample {
+    threads: usize,
+    elapsed_secs: f64,
+}
+fn thread_counts(max_threads: usize) -> Vec<usize> {
+    let mut counts = Vec::new();
+    let mut threads = 1_usize;
+    while threads < max_threads {
+        counts.push(threads);
+        threads = threads.saturating_mul(2);
+    }
+    counts.push(max_threads.max(1));
+    counts
+}
+fn thread_ratio(numerator: usize, denominator: usize) -> f64 {
+    to_f64(checked::usize_to_u64(
+        numerator,
+        "scaling::thread_ratio::numerator",
+    )) / to_f64(checked::usize_to_u64(
+        denominator,
+        "scaling::thread_ratio::denominator",
+    ))
+}
+fn knee_point(samples: &[ScalingSample]) -> Option<usize> {
+    let baseline = samples.first()?;
+    let mut knee = baseline.threads;
+    for (previous, current) in samples.iter().zip(samples.iter().skip(1)) {
+        let ideal_gain = thread_ratio(current.threads, previous.threads) - 1.0_f64;
+        let actual_gain = previous.elapsed_secs / current.elapsed_secs - 1.0_f64;
+        if actual_gain < ideal_gain * KNEE_MARGINAL_EFFICIENCY {
+            break;
+        }
+        knee = current.threads;
+    }
+    Some(knee)
+}
+pub(super) fn run(exit_token: &CancellationToken, config: &Config) {
+    let Some(board) = fixed_benchmark_board(config) else {
+        return;
+    };
+    let counts = thread_counts(config.num_threads);
+    let counts_text = counts
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!("开始线程扩展性基准测试：线程数 {counts_text}，每档循环 {BENCHMARK_RUNS} 次。");
+    let mut samples = Vec::with_capacity(counts.len());
+    for threads in counts {
+        let params = SearchParams::new(
+            config.board_size,
+            config.win_len,
+            threads,
+            config.evaluation,
+        );
+        let Some(result) =
+            ParallelSolver::benchmark_next_move(&board, &params, BENCHMARK_RUNS, exit_token)
+        else {
+            println!("扩展性基准测试已被中断。");
+            return;
+        };
+        println!(
+            "线程数 {threads}: 平均耗时 {elapsed:.6}s",
+            elapsed = result.elapsed_secs
+        );
+        samples.push(ScalingSample {
+            threads,
+            elapsed_secs: result.elapsed_secs,
+        });
+    }
+    let Some(baseline) = samples.first() else {
+        return;
+    };
+    println!(
+        "{:>6} {:>12} {:>8} {:>8}",
+        "线程", "耗时(s)", "加速比", "效率"
+    );
+    for sample in &samples {
+        let speedup = baseline.elapsed_secs / sample.elapsed_secs;
+        let efficiency = speedup / thread_ratio(sample.threads, 1);
+        println!(
+            "{threads:>8} {elapsed:>14.6} {speedup:>10.2}x {percent:>9.1}%",
+            threads = sample.threads,
+            elapsed = sample.elapsed_secs,
+            percent = efficiency * 100.0_f64
+        );
+    }
+    let threshold = KNEE_MARGINAL_EFFICIENCY * 100.0_f64;
+    let Some(knee) = knee_point(&samples) else {
+        return;
+    };
+    if samples.last().is_some_and(|last| last.threads == knee) {
+        println!(
+            "未检测到扩展拐点：直到 {knee} 线程，每次增加线程的增益均不低于理想值的 {threshold:.0}%。"
+        );
+    } else {
+        println!("扩展拐点: {knee} 线程（继续增加线程时，增益不足理想值的 {threshold:.0}%）。");
+    }
+}
@@ -0,0 +1,223 @@
+use super::{PROGRAM_PLAYER, adjudication::Adjudicator, attacker_board};
+use crate::{
+    checked,
+    config::Config,
+    game_state::{Coord, Position},
+    gamedb::{GameDatabase, GameRecorder, GameResult, PlayerInfo, result_from_winner},
+    interrupt::CancellationToken,
+    pns::{ParallelSolver, SearchOutcome, TieBreakPolicy},
+    rating::{MatchRecord, RatingsBook},
+};
+use std::{path::Path, time::Instant};
+const BASELINE_NAME: &str = "config.yaml";
+const DEFAULT_MOVE_NODES: u64 = 20_000;
+const SPRT_ELO0: f64 = 0.0;
+const SPRT_ELO1: f64 = 10.0;
+pub(super) fn run(
+    exit_token: &CancellationToken,
+    baseline: &Config,
+    challenger_path: &Path,
+    ratings_path: &Path,
+) {
+    let challenger = match Config::load_file(challenger_path, None) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
+    };
+    if challenger.board_size != baseline.board_size || challenger.win_len != baseline.win_len {
+        eprintln!(
+            "挑战者配置为 {size}x{size}、{win_len} 子连珠，与当前配置不符。",
+            size = challenger.board_size,
+            win_len = challenger.win_len
+        );
+        return;
+    }
+    let challenger_name = challenger_path.display().to_string();
+    let label = format!("{challenger_name} vs {BASELINE_NAME}");
+    let mut book = RatingsBook::load(ratings_path).unwrap_or_else(|err| {
+        eprintln!(
+            "读取战绩文件 {path} 失败，将从零开始记录: {err}",
+            path = ratings_path.display()
+        );
+        RatingsBook::default()
+    });
+    println!(
+        "自对弈 {label}，共 {games} 局，挑战者与基准轮流执先。",
+        games = baseline.self_play_games
+    );
+    let rules = baseline.adjudication_rules();
+    if rules.is_enabled() {
+        println!("对局裁定: {rules}", rules = rules.describe());
+    }
+    for game_index in 0..baseline.self_play_games {
+        let challenger_first = game_index.is_multiple_of(2);
+        let (players, names) = if challenger_first {
+            (
+                [&challenger, baseline],
+                [challenger_name.as_str(), BASELINE_NAME],
+            )
+        } else {
+            (
+                [baseline, &challenger],
+                [BASELINE_NAME, challenger_name.as_str()],
+            )
+        };
+        let mut recorder = GameRecorder::new(
+            baseline.board_size,
+            baseline.win_len,
+            names.map(|name| PlayerInfo {
+                name: String::from(name),
+                ..PlayerInfo::engine()
+            }),
+        );
+        let seed = checked::usize_to_u64(game_index, "selfplay::run::seed");
+        let mut adjudicator = Adjudicator::new(rules);
+        let Some(result) = play_one(exit_token, players, seed, &mut adjudicator, &mut recorder)
+        else {
+            println!("自对弈已中断。");
+            break;
+        };
+        let record = book.record_mut(&label);
+        match (result, challenger_first) {
+            (GameResult::FirstPlayerWin, true) | (GameResult::SecondPlayerWin, false) => {
+                record.add_win();
+            }
+            (GameResult::FirstPlayerWin, false) | (GameResult::SecondPlayerWin, true) => {
+                record.add_loss();
+            }
+            (GameResult::Draw | GameResult::Unfinished, _) => record.add_draw(),
+        }
+        let side = if challenger_first { "先" } else { "后" };
+        let reason = recorder
+            .adjudication()
+            .map_or_else(String::new, |adjudication| {
+                format!("（{reason}）", reason = adjudication.label())
+            });
+        println!(
+            "第 {number} 局（挑战者执{side}）: {result}{reason}",
+            number = game_index.saturating_add(1),
+            result = result.label()
+        );
+        if let Some(path) = baseline.game_database.as_deref()
+            && let Err(err) = GameDatabase::open(path).append(&recorder.finish(result))
+        {
+            eprintln!("保存自对弈对局失败: {err}");
+        }
+        if let Err(err) = book.save(ratings_path) {
+            eprintln!(
+                "写入战绩文件 {path} 失败: {err}",
+                path = ratings_path.display()
+            );
+        }
+    }
+    if let Some(&record) = book.matches.get(&label) {
+        print_summary(&label, record);
+    }
+}
+fn play_one(
+    exit_token: &CancellationToken,
+    players: [&Config; 2],
+    seed: u64,
+    adjudicator: &mut Adjudicator,
+    recorder: &mut GameRecorder,
+) -> Option<GameResult> {
+    let [first, second] = players;
+    let mut position = Position::new(first.board_size, first.win_len);
+    loop {
+        if let Some(winner) = position.winner() {
+            return result_from_winner(Some(winner));
+        }
+        if position.is_full() {
+            return Some(GameResult::Draw);
+        }
+        let mover = position.side_to_move();
+        let config = if mover == PROGRAM_PLAYER {
+            first
+        } else {
+            second
+        };
+        let turn_start = Instant::now();
+        let (mov, outcome) = choose_move(exit_token, config, &position, seed)?;
+        if let Err(err) = position.play(mov) {
+            eprintln!("自对弈着法无效: {err}");
+            return None;
+        }
+        recorder.push(mov, mover, turn_start.elapsed());
+        if position.winner().is_none()
+            && !position.is_full()
+            && let Some(adjudication) = adjudicator.observe(recorder.len(), mover, outcome.as_ref())
+        {
+            recorder.adjudicate(adjudication);
+            return Some(adjudication.result());
+        }
+    }
+}
+fn choose_move(
+    exit_token: &CancellationToken,
+    config: &Config,
+    position: &Position,
+    seed: u64,
+) -> Option<(Coord, Option<SearchOutcome>)> {
+    if !position.grid().has_stones() {
+        let center = checked::div_usize(position.board_size(), 2, "selfplay::choose_move::center");
+        return Some(((center, center), None));
+    }
+    let mut params = config
+        .search_params()
+        .with_tie_breaks(TieBreakPolicy::Random)
+        .with_tie_break_seed(Some(seed))
+        .with_quiet(true);
+    if config.node_budget.is_none() && config.time_budget_secs.is_none() {
+        params.budget.nodes = Some(DEFAULT_MOVE_NODES);
+    }
+    let result = ParallelSolver::search(
+        attacker_board(position, config.root_player),
+        params,
+        false,
+        exit_token,
+        None,
+        None,
+    );
+    if exit_token.is_cancelled() {
+        return None;
+    }
+    let mov = result
+        .best_move
+        .or(result.most_proving_move)
+        .or_else(|| position.legal_moves().first().copied())?;
+    Some((mov, Some(result.report.outcome)))
+}
+fn print_summary(label: &str, record: MatchRecord) {
+    println!(
+        "{label}: 胜 {wins} 和 {draws} 负 {losses}，共 {games} 局",
+        wins = record.wins,
+        draws = record.draws,
+        losses = record.losses,
+        games = record.games()
+    );
+    if let Some(estimate) = record.elo() {
+        println!(
+            "挑战者 Elo 差: {elo:+.1}（95% 置信区间 {lower:+.1} ~ {upper:+.1}）",
+            elo = estimate.elo,
+            lower = estimate.lower,
+            upper = estimate.upper
+        );
+    }
+    if let Some(sprt) = record.sprt(SPRT_ELO0, SPRT_ELO1) {
+        let verdict = if sprt.accepts_h1() {
+            "接受 H1，挑战者更强"
+        } else if sprt.accepts_h0() {
+            "接受 H0，挑战者未见提升"
+        } else {
+            "尚无定论，需要更多对局"
+        };
+        println!(
+            "SPRT [{SPRT_ELO0}, {SPRT_ELO1}]: LLR {llr:.3}（界限 {lower:.3} ~ {upper:.3}），{verdict}",
+            llr = sprt.llr,
+            lower = sprt.lower_bound,
+            upper = sprt.upper_bound
+        );
+    }
+}
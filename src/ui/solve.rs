@@ -0,0 +1,59 @@
+use super::{attacker_board, stone_glyph};
+use crate::{
+    config::Config,
+    game_state::Position,
+    interrupt::CancellationToken,
+    pns::{ParallelSolver, SearchOutcome, SearchParams},
+};
+pub(super) fn run(exit_token: &CancellationToken, config: &Config, text: &str) -> bool {
+    let position = match Position::parse(text, config.win_len) {
+        Ok(position) => position,
+        Err(err) => {
+            eprintln!("局面无效: {err}");
+            return false;
+        }
+    };
+    let side = stone_glyph(position.side_to_move());
+    println!("局面: {position}");
+    if let Some(winner) = position.winner() {
+        println!("结果: 已分胜负，{glyph} 获胜", glyph = stone_glyph(winner));
+        return true;
+    }
+    if position.is_full() {
+        println!("结果: 棋盘已满，和棋");
+        return true;
+    }
+    let params = SearchParams {
+        board_size: position.board_size(),
+        win_len: position.win_len(),
+        ..config.search_params()
+    }
+    .with_quiet(true);
+    let result = ParallelSolver::search(
+        attacker_board(&position, params.root_player),
+        params,
+        false,
+        exit_token,
+        None,
+        None,
+    );
+    match result.report.outcome {
+        SearchOutcome::Proven { best_move, win_len } => {
+            println!("结果: {side} 方必胜");
+            println!(
+                "最佳着法: {mov}",
+                mov = best_move.map_or_else(
+                    || String::from("无"),
+                    |(row, column)| format!("({row}, {column})")
+                )
+            );
+            println!("证明长度: {win_len} 手");
+        }
+        SearchOutcome::Disproven => println!("结果: {side} 方无必胜（已证伪）"),
+        SearchOutcome::Unresolved { .. } => println!("结果: 未知（搜索预算内未能证明）"),
+        SearchOutcome::Aborted { reason } => {
+            println!("结果: 未知（搜索已中止: {label}）", label = reason.label());
+        }
+    }
+    true
+}
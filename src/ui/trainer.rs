@@ -0,0 +1,299 @@
+use super::{
+    PROGRAM_PLAYER, attacker_board,
+    input::{PlayerInput, read_player_input, read_position},
+    print_board, progress,
+};
+use crate::{
+    config::Config,
+    game_state::{Coord, Position},
+    interrupt::CancellationToken,
+    pns::{NodeRef, ParallelSolver, SearchParams, SharedTree, ThreadLocalContext},
+    puzzles::PuzzleSet,
+};
+use alloc::sync::Arc;
+use core::{cmp::Reverse, ops::ControlFlow};
+use std::path::Path;
+struct TrainerStep {
+    position: Position,
+    node: NodeRef,
+}
+struct Trainer<'config> {
+    config: &'config Config,
+    attacker: u8,
+    position: Position,
+    node: NodeRef,
+    history: Vec<TrainerStep>,
+}
+pub(super) fn run(exit_token: &CancellationToken, config: &Config, puzzles: Option<&Path>) {
+    if let Some(path) = puzzles {
+        run_puzzle_set(exit_token, config, path);
+        return;
+    }
+    let Some(position) = read_position(exit_token, config) else {
+        return;
+    };
+    train(exit_token, config, position);
+}
+fn run_puzzle_set(exit_token: &CancellationToken, config: &Config, path: &Path) {
+    let set = match PuzzleSet::load(path) {
+        Ok(set) => set,
+        Err(err) => {
+            eprintln!("读取题库 {path} 失败: {err}", path = path.display());
+            return;
+        }
+    };
+    let total = set.puzzles.len();
+    println!("已载入题库 {path}: {total} 道题目。", path = path.display());
+    for (index, puzzle) in set.puzzles.iter().enumerate() {
+        if exit_token.is_cancelled() {
+            return;
+        }
+        println!(
+            "\n第 {number}/{total} 题: {win_len} 步内取胜",
+            number = index.saturating_add(1),
+            win_len = puzzle.win_len
+        );
+        match set.position(puzzle) {
+            Ok(position) => train(exit_token, config, position),
+            Err(err) => println!("题目无效，已跳过: {err}"),
+        }
+    }
+}
+fn train(exit_token: &CancellationToken, config: &Config, position: Position) {
+    if position.winner().is_some() {
+        println!("该局面已分胜负，无法用于训练。");
+        return;
+    }
+    let attacker = position.side_to_move();
+    println!("正在证明训练局面...");
+    let Some(node) = prove(config, &position, exit_token) else {
+        if !exit_token.is_cancelled() {
+            println!(
+                "无法证明 {side} 在该局面必胜，无法用于训练。",
+                side = stone_label(attacker)
+            );
+        }
+        return;
+    };
+    println!(
+        "训练开始: 您执 {side}，请找出必胜着法序列，程序将按证明树给出最顽强的防守。",
+        side = stone_label(attacker)
+    );
+    let mut trainer = Trainer {
+        config,
+        attacker,
+        position,
+        node,
+        history: Vec::new(),
+    };
+    trainer.run(exit_token);
+}
+const fn stone_label(player: u8) -> &'static str {
+    if player == PROGRAM_PLAYER { "X" } else { "O" }
+}
+fn format_coords<I>(moves: I) -> String
+where
+    I: IntoIterator<Item = Coord>,
+{
+    moves
+        .into_iter()
+        .map(|(row, column)| format!("({row}, {column})"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+pub(super) fn prove(
+    config: &Config,
+    position: &Position,
+    exit_token: &CancellationToken,
+) -> Option<NodeRef> {
+    let board = attacker_board(position, config.root_player);
+    let root_pos_hash = Position::from_board(
+        board.clone(),
+        position.board_size(),
+        position.win_len(),
+        config.root_player,
+    )
+    .ok()?
+    .hash();
+    let show_progress = progress::enabled(config.verbose);
+    let mut params = SearchParams {
+        board_size: position.board_size(),
+        win_len: position.win_len(),
+        ..config.search_params()
+    };
+    if show_progress {
+        params = params.with_progress(progress::renderer(config));
+    }
+    let result = ParallelSolver::search(board, params, config.verbose, exit_token, None, None);
+    if show_progress {
+        progress::clear();
+    }
+    result.best_move?;
+    result.node_table.get(&(root_pos_hash, 0))
+}
+impl Trainer<'_> {
+    fn run(&mut self, exit_token: &CancellationToken) {
+        loop {
+            if exit_token.is_cancelled() {
+                return;
+            }
+            println!("\n当前棋盘:");
+            print_board(self.position.grid());
+            println!(
+                "轮到您 ({side}) 落子，请找出必胜着法。",
+                side = stone_label(self.attacker)
+            );
+            let Some(player_input) = read_player_input(self.position.grid(), exit_token, None)
+            else {
+                return;
+            };
+            let mov = match player_input {
+                PlayerInput::Move(mov) => mov,
+                PlayerInput::TakeBack => {
+                    self.take_back();
+                    continue;
+                }
+                PlayerInput::Hint => {
+                    self.hint();
+                    continue;
+                }
+                PlayerInput::Save(_path) => {
+                    println!("训练模式不支持保存棋谱。");
+                    continue;
+                }
+                PlayerInput::Timeout => continue,
+            };
+            if self.attack(mov, exit_token).is_break() {
+                return;
+            }
+        }
+    }
+    fn take_back(&mut self) {
+        let Some(step) = self.history.pop() else {
+            println!("当前没有可悔棋步。");
+            return;
+        };
+        self.position = step.position;
+        self.node = step.node;
+        println!("已悔棋，回到您上一手落子前。");
+    }
+    fn hint(&self) {
+        match SharedTree::proven_moves(&self.node).first() {
+            Some(&((row, column), _)) => println!("提示：可落子于 ({row}, {column})。"),
+            None => println!("证明树在此处未展开，无法给出提示。"),
+        }
+    }
+    fn attack(&mut self, mov: Coord, exit_token: &CancellationToken) -> ControlFlow<()> {
+        let node = Arc::clone(&self.node);
+        let Some(mut children) = node.expanded_children() else {
+            println!("证明树在此处未展开，无法判定着法。");
+            return ControlFlow::Break(());
+        };
+        let winning = SharedTree::proven_moves(&node);
+        let Some(defender_node) = children
+            .find(|child_ref| child_ref.mov.coord() == mov && child_ref.node.get_pn() == 0)
+            .map(|child_ref| Arc::clone(&child_ref.node))
+        else {
+            println!(
+                "({row}, {column}) 不是必胜着法，请重试。",
+                row = mov.0,
+                column = mov.1
+            );
+            println!(
+                "可行的必胜着法: {moves}",
+                moves = format_coords(winning.iter().map(|&(winning_move, _)| winning_move))
+            );
+            return ControlFlow::Continue(());
+        };
+        let fastest = winning
+            .iter()
+            .map(|&(_, win_len)| win_len)
+            .min()
+            .unwrap_or(u64::MAX);
+        if defender_node.get_win_len() > fastest {
+            let faster = winning
+                .iter()
+                .filter(|&&(_, win_len)| win_len == fastest)
+                .map(|&(winning_move, _)| winning_move);
+            println!(
+                "正确，但存在更快的胜法: {moves}",
+                moves = format_coords(faster)
+            );
+        } else {
+            println!("正确！");
+        }
+        self.history.push(TrainerStep {
+            position: self.position.clone(),
+            node: Arc::clone(&self.node),
+        });
+        if let Err(err) = self.position.play(mov) {
+            println!("落子失败: {err}");
+            return ControlFlow::Break(());
+        }
+        if self.position.winner() == Some(self.attacker) {
+            println!("\n最终棋盘:");
+            print_board(self.position.grid());
+            println!("恭喜，您完成了必胜序列！");
+            return ControlFlow::Break(());
+        }
+        self.defend(&defender_node, exit_token)
+    }
+    fn defend(
+        &mut self,
+        defender_node: &NodeRef,
+        exit_token: &CancellationToken,
+    ) -> ControlFlow<()> {
+        let from_tree = defender_node.expanded_children().and_then(|children| {
+            children
+                .filter(|child_ref| child_ref.node.get_pn() == 0)
+                .max_by_key(|child_ref| (child_ref.node.get_win_len(), Reverse(child_ref.mov)))
+                .map(|child_ref| (child_ref.mov.coord(), Arc::clone(&child_ref.node)))
+        });
+        let Some((defense, next_node)) = from_tree
+            .map(|(defense, next_node)| (defense, Some(next_node)))
+            .or_else(|| self.ranked_defense().map(|defense| (defense, None)))
+        else {
+            println!("棋盘已满，训练结束。");
+            return ControlFlow::Break(());
+        };
+        if let Err(err) = self.position.play(defense) {
+            println!("防守落子失败: {err}");
+            return ControlFlow::Break(());
+        }
+        println!(
+            "程序防守于: ({row}, {column})",
+            row = defense.0,
+            column = defense.1
+        );
+        if self.position.winner().is_some() {
+            println!("\n最终棋盘:");
+            print_board(self.position.grid());
+            println!("防守方获胜，训练失败。");
+            return ControlFlow::Break(());
+        }
+        if let Some(node) = next_node.filter(|node| node.children.get().is_some()) {
+            self.node = node;
+            return ControlFlow::Continue(());
+        }
+        println!("证明树在此处未展开，正在重新证明当前局面...");
+        let Some(node) = prove(self.config, &self.position, exit_token) else {
+            if !exit_token.is_cancelled() {
+                println!("重新证明失败，训练结束。");
+            }
+            return ControlFlow::Break(());
+        };
+        self.node = node;
+        ControlFlow::Continue(())
+    }
+    fn ranked_defense(&self) -> Option<Coord> {
+        let defender = self.position.side_to_move();
+        let mut ctx =
+            ThreadLocalContext::new(self.position.to_game_state(self.config.evaluation), 0);
+        let mut defense = None;
+        ctx.for_each_legal_move(defender, |mov, _| {
+            defense = Some(mov);
+            ControlFlow::Break(())
+        });
+        defense
+    }
+}
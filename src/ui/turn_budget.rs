@@ -0,0 +1,27 @@
+use crate::pns::{SearchOutcome, SearchReport, to_f64};
+const BYTES_PER_MB: u64 = 1024 * 1024;
+pub(super) fn summary(report: &SearchReport, live_bytes_before: u64) -> String {
+    let totals = &report.totals;
+    let depth = totals
+        .depth
+        .map_or_else(|| String::from("-"), |depth| depth.to_string());
+    let status = match report.outcome {
+        SearchOutcome::Proven { .. } => "已证明",
+        SearchOutcome::Disproven => "已证伪",
+        SearchOutcome::Unresolved { .. } => "未定",
+        SearchOutcome::Aborted { .. } => "已中止",
+    };
+    let live_bytes_after = report.alloc.live_bytes;
+    let (sign, delta_bytes) = if live_bytes_after >= live_bytes_before {
+        ('+', live_bytes_after.saturating_sub(live_bytes_before))
+    } else {
+        ('-', live_bytes_before.saturating_sub(live_bytes_after))
+    };
+    format!(
+        "本手用量: 深度 D={depth}（{status}） | 节点 {nodes} | 用时 {elapsed:.2}s | 置换表复用 {reuse:.1}% | 内存 {sign}{delta_mb:.1}MB",
+        nodes = totals.stats.nodes_created,
+        elapsed = totals.elapsed_secs,
+        reuse = totals.tt_hit_rate,
+        delta_mb = to_f64(delta_bytes) / to_f64(BYTES_PER_MB)
+    )
+}
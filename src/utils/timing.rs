@@ -0,0 +1,95 @@
+use crate::checked;
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+use std::time::Instant;
+#[inline]
+#[must_use]
+pub(crate) fn duration_to_ns(duration: Duration) -> u64 {
+    match u64::try_from(duration.as_nanos()) {
+        Ok(value) => value,
+        Err(err) => {
+            eprintln!(
+                "Duration 转换为纳秒失败: secs={}, subsec_nanos={}, 错误: {err}",
+                duration.as_secs(),
+                duration.subsec_nanos()
+            );
+            panic!("Duration 转换为纳秒失败");
+        }
+    }
+}
+#[inline]
+#[must_use]
+pub(crate) fn elapsed_ns(start: Instant) -> u64 {
+    duration_to_ns(start.elapsed())
+}
+#[inline]
+pub(crate) fn time_it<R, F: FnOnce() -> R>(operation: F) -> (R, u64) {
+    let start = Instant::now();
+    let result = operation();
+    (result, elapsed_ns(start))
+}
+#[inline]
+pub(crate) fn record_duration_ns<R, F: FnOnce() -> R>(field: &mut u64, operation: F) -> R {
+    let (result, elapsed) = time_it(operation);
+    *field = elapsed;
+    result
+}
+#[inline]
+pub(crate) fn record_duration_add_ns<R, F: FnOnce() -> R>(field: &mut u64, operation: F) -> R {
+    let start = Instant::now();
+    let result = operation();
+    add_elapsed_ns(field, start, "utils::timing::record_duration_add_ns");
+    result
+}
+#[inline]
+pub(crate) fn add_elapsed_ns(field: &mut u64, start: Instant, context: &str) {
+    *field = checked::add_u64(*field, elapsed_ns(start), context);
+}
+#[inline]
+pub(crate) fn add_elapsed_ns_atomic(counter: &AtomicU64, start: Instant) {
+    counter.fetch_add(elapsed_ns(start), Ordering::Relaxed);
+}
+#[must_use]
+pub(crate) struct ScopedTimer<'target> {
+    start: Instant,
+    target: &'target mut u64,
+    context: &'static str,
+}
+impl<'target> ScopedTimer<'target> {
+    #[inline]
+    pub(crate) fn new(target: &'target mut u64, context: &'static str) -> Self {
+        Self {
+            start: Instant::now(),
+            target,
+            context,
+        }
+    }
+}
+impl Drop for ScopedTimer<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        add_elapsed_ns(self.target, self.start, self.context);
+    }
+}
+#[must_use]
+pub(crate) struct AtomicScopedTimer<'target> {
+    start: Instant,
+    target: &'target AtomicU64,
+}
+impl<'target> AtomicScopedTimer<'target> {
+    #[inline]
+    pub(crate) fn new(target: &'target AtomicU64) -> Self {
+        Self {
+            start: Instant::now(),
+            target,
+        }
+    }
+}
+impl Drop for AtomicScopedTimer<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        add_elapsed_ns_atomic(self.target, self.start);
+    }
+}
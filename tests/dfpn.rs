@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use inevitable::{
+        config::Config,
+        game_state::Position,
+        interrupt::CancellationToken,
+        pns::{ParallelSolver, SearchOutcome},
+    };
+    use std::path::Path;
+    const BOARD_SIZE: usize = 6;
+    const WIN_LEN: usize = 4;
+    const KNOWN_WIN: &str = "6/6/2x3/3o2/6/6 x";
+    const KNOWN_WIN_LEN: u64 = 7;
+    const EPSILON: f64 = 0.25;
+    fn proof(epsilon: Option<f64>, num_threads: usize) -> Option<u64> {
+        let config = Config::load_file(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("config.yaml"),
+            None,
+        )
+        .unwrap_or_else(|err| panic!("无法加载配置: {err}"));
+        let position =
+            Position::parse(KNOWN_WIN, WIN_LEN).unwrap_or_else(|err| panic!("局面无效: {err}"));
+        let mut params = config
+            .search_params()
+            .with_dfpn_epsilon(epsilon)
+            .with_quiet(true);
+        params.board_size = BOARD_SIZE;
+        params.win_len = WIN_LEN;
+        params.num_threads = num_threads;
+        let result = ParallelSolver::search(
+            position.board().to_vec(),
+            params,
+            false,
+            &CancellationToken::new(),
+            None,
+            None,
+        );
+        match result.report.outcome {
+            SearchOutcome::Proven { win_len, .. } => Some(win_len),
+            SearchOutcome::Disproven
+            | SearchOutcome::Unresolved { .. }
+            | SearchOutcome::Aborted { .. } => None,
+        }
+    }
+    #[test]
+    fn dfpn_epsilon_keeps_proof_result() {
+        for num_threads in [1_usize, 2_usize] {
+            let plain = proof(None, num_threads);
+            let thresholded = proof(Some(EPSILON), num_threads);
+            assert_eq!(
+                plain,
+                Some(KNOWN_WIN_LEN),
+                "未启用 df-pn 阈值时应证明已知胜局: {num_threads} 线程"
+            );
+            assert_eq!(
+                thresholded, plain,
+                "启用 df-pn 阈值后证明结果不应改变: {num_threads} 线程"
+            );
+        }
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use inevitable::{config::Config, interrupt::CancellationToken, pns::ParallelSolver};
+    use std::{env, fs, path::Path, process::Command};
+    const CHILD_ENV: &str = "INEVITABLE_QUIET_CHILD";
+    const CHILD_TEST: &str = "tests::quiet_search_child";
+    const BEGIN_MARKER: &str = "<<<quiet-search-begin>>>";
+    const END_MARKER: &str = "<<<quiet-search-end>>>";
+    const NODE_BUDGET: u64 = 20_000;
+    #[test]
+    #[ignore = "由 quiet_search_has_no_side_effects 在子进程中运行"]
+    fn quiet_search_child() {
+        if env::var_os(CHILD_ENV).is_none() {
+            return;
+        }
+        let config = Config::load_file(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("config.yaml"),
+            None,
+        )
+        .unwrap_or_else(|err| panic!("无法加载配置: {err}"));
+        let mut params = config.search_params().with_quiet(true);
+        params.num_threads = 1;
+        params.budget.nodes = Some(NODE_BUDGET);
+        let board = vec![0_u8; config.board_size.saturating_mul(config.board_size)];
+        println!("{BEGIN_MARKER}");
+        let _result =
+            ParallelSolver::search(board, params, true, &CancellationToken::new(), None, None);
+        println!("{END_MARKER}");
+    }
+    #[test]
+    fn quiet_search_has_no_side_effects() {
+        let workdir = env::temp_dir().join(format!("inevitable-quiet-{}", std::process::id()));
+        fs::create_dir_all(&workdir)
+            .unwrap_or_else(|err| panic!("无法创建临时目录 {}: {err}", workdir.display()));
+        let executable = env::current_exe().unwrap_or_else(|err| panic!("无法定位测试程序: {err}"));
+        let output = Command::new(executable)
+            .args([
+                CHILD_TEST,
+                "--exact",
+                "--ignored",
+                "--nocapture",
+                "--test-threads=1",
+            ])
+            .env(CHILD_ENV, "1")
+            .current_dir(&workdir)
+            .output()
+            .unwrap_or_else(|err| panic!("无法启动子进程: {err}"));
+        let leftovers: Vec<_> = fs::read_dir(&workdir)
+            .unwrap_or_else(|err| panic!("无法读取临时目录 {}: {err}", workdir.display()))
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name())
+            .collect();
+        if let Err(err) = fs::remove_dir_all(&workdir) {
+            eprintln!("无法清理临时目录 {}: {err}", workdir.display());
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "子进程搜索失败: {stdout}");
+        let captured = stdout
+            .split_once(BEGIN_MARKER)
+            .and_then(|(_, rest)| rest.split_once(END_MARKER))
+            .map(|(inside, _)| inside.trim());
+        assert_eq!(captured, Some(""), "静默搜索不应向标准输出打印内容");
+        assert!(leftovers.is_empty(), "静默搜索不应写入文件: {leftovers:?}");
+    }
+}
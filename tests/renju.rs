@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use inevitable::{
+        config::Config,
+        game_state::{GameState, Position, Rule},
+        interrupt::CancellationToken,
+        pns::ParallelSolver,
+        reference::{SlowRules, slow_legal_moves},
+    };
+    use std::path::Path;
+    const BOARD_SIZE: usize = 15;
+    const WIN_LEN: usize = 5;
+    const BLACK: u8 = 1;
+    const WHITE: u8 = 2;
+    const OVERLINE_SQUARE: (usize, usize) = (7, 7);
+    const NODE_BUDGET: u64 = 2_000;
+    const OVERLINE_FOUR: &str = "o13o/15/15/15/15/15/15/2oxxxx1x6/15/15/15/15/15/15/o13o x";
+    fn load_config() -> Config {
+        Config::load_file(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("config.yaml"),
+            None,
+        )
+        .unwrap_or_else(|err| panic!("无法加载配置: {err}"))
+    }
+    fn overline_four() -> Position {
+        Position::parse(OVERLINE_FOUR, WIN_LEN).unwrap_or_else(|err| panic!("局面无效: {err}"))
+    }
+    fn renju_state(position: &Position) -> GameState {
+        let mut game_state = position.to_game_state(load_config().evaluation);
+        game_state.set_rule(Rule::Renju, BLACK);
+        game_state
+    }
+    #[test]
+    fn overline_completion_is_not_a_threat() {
+        let position = overline_four();
+        let forcing = renju_state(&position).find_forcing_moves(WHITE);
+        assert!(
+            forcing.blocks.is_empty(),
+            "黑方长连不构成冲四，白方无需防守: {:?}",
+            forcing.blocks
+        );
+        let rules = SlowRules {
+            region: None,
+            forbidden_player: Some(BLACK),
+        };
+        let replies = slow_legal_moves(position.grid(), WIN_LEN, WHITE, &rules);
+        assert!(
+            replies.len() > 1 && replies.contains(&OVERLINE_SQUARE),
+            "参考实现不应把白方限制在长连点: {replies:?}"
+        );
+    }
+    #[test]
+    fn cross_check_accepts_overline_fours() {
+        let config = load_config();
+        let position = overline_four();
+        let mut params = config
+            .search_params()
+            .with_rule(Rule::Renju)
+            .with_cross_check(1)
+            .with_quiet(true);
+        params.board_size = BOARD_SIZE;
+        params.win_len = WIN_LEN;
+        params.num_threads = 1;
+        params.budget.nodes = Some(NODE_BUDGET);
+        let result = ParallelSolver::search(
+            position.board().to_vec(),
+            params,
+            false,
+            &CancellationToken::new(),
+            None,
+            None,
+        );
+        assert_ne!(
+            result.best_move,
+            Some(OVERLINE_SQUARE),
+            "黑方不得以长连取胜"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use inevitable::{
+        config::Config,
+        game_state::{Coord, GameSession, Position, PositionKey},
+    };
+    use std::path::Path;
+    const MOVES: [Coord; 4] = [(3, 3), (3, 4), (2, 2), (4, 4)];
+    fn new_session() -> GameSession {
+        let config = Config::load_file(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("config.yaml"),
+            None,
+        )
+        .unwrap_or_else(|err| panic!("无法加载配置: {err}"));
+        let position = Position::new(7, 5);
+        GameSession::new(position.to_game_state(config.evaluation), 1)
+    }
+    fn play_all(session: &mut GameSession) -> Vec<PositionKey> {
+        let mut keys = Vec::new();
+        for mov in MOVES {
+            session.play(mov, session.side_to_move());
+            keys.push(session.current_key());
+        }
+        keys
+    }
+    #[test]
+    fn undo_restores_visit_counts() {
+        let mut session = new_session();
+        let initial_key = session.current_key();
+        let initial_board = session.board().to_vec();
+        let keys = play_all(&mut session);
+        while session.undo().is_some() {}
+        assert_eq!(session.board(), initial_board, "悔棋后棋盘应复原");
+        assert_eq!(
+            session.hash_history(),
+            [initial_key],
+            "悔棋后哈希历史应只剩初始局面"
+        );
+        assert_eq!(session.side_to_move(), 1, "悔棋后应轮到先手");
+        assert_eq!(session.scores(), [0, 0], "悔棋后得分应清零");
+        for &key in &keys {
+            assert_eq!(
+                session.visit_count(key),
+                0,
+                "悔棋后不应保留已撤销局面的访问次数"
+            );
+        }
+        assert_eq!(
+            session.visited_positions().collect::<Vec<_>>(),
+            [initial_key],
+            "悔棋后只应记录初始局面"
+        );
+        let replayed = play_all(&mut session);
+        assert_eq!(replayed, keys, "重下相同着法应得到相同局面");
+        for &key in &replayed {
+            assert_eq!(session.visit_count(key), 1, "重下后每个局面应只访问一次");
+        }
+        assert!(!session.is_revisit(), "重下后不应判为重复局面");
+    }
+}